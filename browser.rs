@@ -0,0 +1,71 @@
+use crate::i18n::Text;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const PARENT: &str = "../";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Op {
+    Create,
+    Mkdir,
+    Rename(String),
+    Delete(String),
+}
+
+impl Op {
+    pub fn prompt(&self) -> Text {
+        match self {
+            Op::Create => Text::BrowserCreate,
+            Op::Mkdir => Text::BrowserMkdir,
+            Op::Rename(_) => Text::BrowserRename,
+            Op::Delete(_) => Text::BrowserDelete,
+        }
+    }
+}
+
+pub fn list(dir: &Path) -> io::Result<Vec<String>> {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.path().is_dir() {
+            dirs.push(format!("{}/", name));
+        } else {
+            files.push(name);
+        }
+    }
+    dirs.sort();
+    files.sort();
+    Ok(std::iter::once(PARENT.to_string()).chain(dirs).chain(files).collect())
+}
+
+pub fn entry(dir: &Path, line: &str) -> Option<PathBuf> {
+    match line {
+        PARENT => dir.parent().map(Path::to_path_buf),
+        "" => None,
+        name => Some(dir.join(name.trim_end_matches('/'))),
+    }
+}
+
+pub fn apply(dir: &Path, op: &Op, input: &str) -> io::Result<Option<PathBuf>> {
+    let target = dir.join(input.trim());
+    match op {
+        Op::Create => {
+            fs::OpenOptions::new().write(true).create_new(true).open(&target)?;
+            Ok(Some(target))
+        }
+        Op::Mkdir => fs::create_dir(&target).map(|_| None),
+        Op::Rename(_) if fs::symlink_metadata(&target).is_ok() => Err(io::ErrorKind::AlreadyExists.into()),
+        Op::Rename(name) => fs::rename(dir.join(name), &target).map(|_| None),
+        Op::Delete(name) => {
+            let path = dir.join(name.trim_end_matches('/'));
+            if path.is_dir() {
+                fs::remove_dir(path).map(|_| None)
+            } else {
+                fs::remove_file(path).map(|_| None)
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crate::term;
+use crossterm::event::KeyEvent;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+pub struct Cast {
+    started: Instant,
+    timestamp: u64,
+    size: Option<(u16, u16)>,
+    current: (u16, u16),
+    events: Vec<(f64, &'static str, String)>,
+}
+
+impl Cast {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            size: None,
+            current: (0, 0),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn output(&mut self, bytes: &[u8], width: u16, height: u16) {
+        if self.size.is_none() {
+            self.size = Some((width, height));
+        } else if self.current != (width, height) {
+            self.push("r", format!("{}x{}", width, height));
+        }
+        self.current = (width, height);
+        self.push("o", String::from_utf8_lossy(bytes).into_owned());
+    }
+
+    pub fn input(&mut self, key: &KeyEvent) {
+        if let Some(bytes) = term::key_bytes(key) {
+            self.push("i", String::from_utf8_lossy(&bytes).into_owned());
+        }
+    }
+
+    fn push(&mut self, kind: &'static str, data: String) {
+        self.events.push((self.started.elapsed().as_secs_f64(), kind, data));
+    }
+
+    pub fn export(&self, path: &str) -> io::Result<()> {
+        let (width, height) = self.size.unwrap_or((80, 24));
+        let mut out = format!(
+            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}}}\n",
+            width, height, self.timestamp
+        );
+        for (time, kind, data) in &self.events {
+            let _ = writeln!(out, "[{:.6}, \"{}\", {}]", time, kind, json_string(data));
+        }
+        fs::write(path, out)
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\x7f' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,98 @@
+use crate::remote::Url;
+use std::path::Path;
+
+pub const USAGE: &str = "\
+usage: aon [options] [+N | + | +/pattern] [file[:line[:column]]...]
+       aon --merge LOCAL BASE REMOTE MERGED
+
+  +N              start at line N of the first file (+ alone: last line)
+  +/pattern       start at the first match of pattern
+  -R              open the files read-only
+  --cmd COMMAND   run an ex command after loading the config (repeatable)
+  --stdout        write the buffer to stdout on exit
+  --record FILE   record input events to FILE
+  --replay FILE   replay input events from FILE
+  -h, --help      show this help";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Start {
+    Line(usize),
+    Position(usize, usize),
+    Last,
+    Pattern(String),
+}
+
+#[derive(Default, Debug)]
+pub struct Args {
+    pub files: Vec<(String, Option<Start>)>,
+    pub start: Option<Start>,
+    pub readonly: bool,
+    pub commands: Vec<String>,
+    pub write_stdout: bool,
+    pub record: Option<String>,
+    pub replay: Option<String>,
+    pub merge: Option<(String, String, String)>,
+    pub help: bool,
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    let mut files_only = false;
+    while let Some(arg) = args.next() {
+        if files_only {
+            parsed.files.push((arg, None));
+            continue;
+        }
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs an argument\n\n{}", name, USAGE));
+        match arg.as_str() {
+            "--" => files_only = true,
+            "-h" | "--help" => parsed.help = true,
+            "-R" => parsed.readonly = true,
+            "--stdout" => parsed.write_stdout = true,
+            "--record" => parsed.record = Some(value("--record")?),
+            "--replay" => parsed.replay = Some(value("--replay")?),
+            "--cmd" => parsed.commands.push(value("--cmd")?),
+            "--merge" => {
+                let paths: Vec<String> = args.by_ref().take(4).collect();
+                let [local, base, remote, merged] = <[String; 4]>::try_from(paths).map_err(|_| USAGE.to_string())?;
+                parsed.files.insert(0, (merged, None));
+                parsed.merge = Some((local, base, remote));
+            }
+            "+" => parsed.start = Some(Start::Last),
+            _ if arg.starts_with("+/") => parsed.start = Some(Start::Pattern(arg[2..].to_string())),
+            _ if arg.starts_with('+') => match arg[1..].parse() {
+                Ok(line) => parsed.start = Some(Start::Line(line)),
+                Err(_) => return Err(format!("invalid line number: {}\n\n{}", arg, USAGE)),
+            },
+            _ if arg.starts_with('-') && arg.len() > 1 => return Err(format!("unknown option: {}\n\n{}", arg, USAGE)),
+            _ => parsed.files.push(split_position(&arg)),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Splits the `:line` or `:line:column` suffix that compilers and grep
+/// print after a path, unless a file with the full name exists or the
+/// argument is a URL, whose port looks the same.
+pub fn split_position(arg: &str) -> (String, Option<Start>) {
+    let unchanged = (arg.to_string(), None);
+    if Path::new(arg).exists() || Url::parse(arg).is_some() {
+        return unchanged;
+    }
+    let trimmed = arg.strip_suffix(':').unwrap_or(arg);
+    let Some((rest, last)) = trimmed.rsplit_once(':') else {
+        return unchanged;
+    };
+    let Ok(last) = last.parse() else {
+        return unchanged;
+    };
+    match rest.rsplit_once(':') {
+        Some((path, line)) if !path.is_empty() => match line.parse() {
+            Ok(line) => (path.to_string(), Some(Start::Position(line, last))),
+            Err(_) => (rest.to_string(), Some(Start::Line(last))),
+        },
+        _ if rest.is_empty() => unchanged,
+        _ => (rest.to_string(), Some(Start::Line(last))),
+    }
+}
@@ -0,0 +1,389 @@
+use crate::i18n::Text;
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub help: Text,
+    pub range: bool,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "w",
+        usage: "w [file]",
+        help: Text::CmdWrite,
+        range: false,
+    },
+    CommandSpec {
+        name: "w!!",
+        usage: "w!!",
+        help: Text::CmdSudoWrite,
+        range: false,
+    },
+    CommandSpec {
+        name: "wa",
+        usage: "wa",
+        help: Text::CmdWriteAll,
+        range: false,
+    },
+    CommandSpec {
+        name: "q",
+        usage: "q",
+        help: Text::CmdQuit,
+        range: false,
+    },
+    CommandSpec {
+        name: "wq",
+        usage: "wq",
+        help: Text::CmdWriteQuit,
+        range: false,
+    },
+    CommandSpec {
+        name: "cq",
+        usage: "cq",
+        help: Text::CmdAbort,
+        range: false,
+    },
+    CommandSpec {
+        name: "e",
+        usage: "e <dosya>",
+        help: Text::CmdEdit,
+        range: false,
+    },
+    CommandSpec {
+        name: "r",
+        usage: "r file | r !command",
+        help: Text::CmdRead,
+        range: false,
+    },
+    CommandSpec {
+        name: "delete",
+        usage: "[range]delete [register]",
+        help: Text::CmdDelete,
+        range: true,
+    },
+    CommandSpec {
+        name: "s",
+        usage: "[range]s/pattern/replacement/[gciI]",
+        help: Text::CmdSubstitute,
+        range: true,
+    },
+    CommandSpec {
+        name: "g",
+        usage: "[range]g/pattern/command",
+        help: Text::CmdGlobal,
+        range: true,
+    },
+    CommandSpec {
+        name: "v",
+        usage: "[range]v/pattern/command",
+        help: Text::CmdGlobal,
+        range: true,
+    },
+    CommandSpec {
+        name: "sall",
+        usage: "sall/pattern/replacement/[giI]",
+        help: Text::CmdReplaceAll,
+        range: false,
+    },
+    CommandSpec {
+        name: "sproject",
+        usage: "sproject/pattern/replacement/[giI]",
+        help: Text::CmdReplaceProject,
+        range: false,
+    },
+    CommandSpec {
+        name: "sort",
+        usage: "[range]sort[!] [i][n][u]",
+        help: Text::CmdSort,
+        range: true,
+    },
+    CommandSpec {
+        name: "align",
+        usage: "[range]align [*]delimiter",
+        help: Text::CmdAlign,
+        range: true,
+    },
+    CommandSpec {
+        name: "format",
+        usage: "format",
+        help: Text::CmdFormat,
+        range: false,
+    },
+    CommandSpec {
+        name: "lint",
+        usage: "lint",
+        help: Text::CmdLint,
+        range: false,
+    },
+    CommandSpec {
+        name: "diagnostics",
+        usage: "diagnostics",
+        help: Text::CmdDiagnostics,
+        range: false,
+    },
+    CommandSpec {
+        name: "make",
+        usage: "make [args]",
+        help: Text::CmdMake,
+        range: false,
+    },
+    CommandSpec {
+        name: "output",
+        usage: "output",
+        help: Text::CmdOutput,
+        range: false,
+    },
+    CommandSpec {
+        name: "tasks",
+        usage: "tasks",
+        help: Text::CmdTasks,
+        range: false,
+    },
+    CommandSpec {
+        name: "task",
+        usage: "task",
+        help: Text::CmdTask,
+        range: false,
+    },
+    CommandSpec {
+        name: "tag",
+        usage: "tag [name]",
+        help: Text::CmdTag,
+        range: false,
+    },
+    CommandSpec {
+        name: "pop",
+        usage: "pop",
+        help: Text::CmdPop,
+        range: false,
+    },
+    CommandSpec {
+        name: "outline",
+        usage: "outline",
+        help: Text::CmdOutline,
+        range: false,
+    },
+    CommandSpec {
+        name: "patch",
+        usage: "patch [file]",
+        help: Text::CmdPatch,
+        range: false,
+    },
+    CommandSpec {
+        name: "pull",
+        usage: "pull local|base|remote",
+        help: Text::CmdPull,
+        range: false,
+    },
+    CommandSpec {
+        name: "rename",
+        usage: "rename new_name",
+        help: Text::CmdRename,
+        range: false,
+    },
+    CommandSpec {
+        name: "resolve",
+        usage: "resolve ours|theirs|both",
+        help: Text::CmdResolve,
+        range: false,
+    },
+    CommandSpec {
+        name: "noh",
+        usage: "noh",
+        help: Text::CmdNohlsearch,
+        range: false,
+    },
+    CommandSpec {
+        name: "ascii",
+        usage: "ascii",
+        help: Text::CmdAscii,
+        range: false,
+    },
+    CommandSpec {
+        name: "term",
+        usage: "term [command]",
+        help: Text::CmdTerm,
+        range: false,
+    },
+    CommandSpec {
+        name: "reflow",
+        usage: "[range]reflow",
+        help: Text::CmdReflow,
+        range: true,
+    },
+    CommandSpec {
+        name: "!",
+        usage: "[range]!command [&]",
+        help: Text::CmdFilter,
+        range: true,
+    },
+    CommandSpec {
+        name: "b",
+        usage: "b <n>",
+        help: Text::CmdBuffer,
+        range: false,
+    },
+    CommandSpec {
+        name: "bn",
+        usage: "bn",
+        help: Text::CmdNextBuffer,
+        range: false,
+    },
+    CommandSpec {
+        name: "bp",
+        usage: "bp",
+        help: Text::CmdPrevBuffer,
+        range: false,
+    },
+    CommandSpec {
+        name: "bd",
+        usage: "bd",
+        help: Text::CmdDeleteBuffer,
+        range: false,
+    },
+    CommandSpec {
+        name: "hex",
+        usage: "hex",
+        help: Text::CmdHex,
+        range: false,
+    },
+    CommandSpec {
+        name: "cursor",
+        usage: "cursor block|bar|underline",
+        help: Text::CmdCursor,
+        range: false,
+    },
+    CommandSpec {
+        name: "set",
+        usage: "set [name[=value|!|?] ...]",
+        help: Text::CmdSet,
+        range: false,
+    },
+    CommandSpec {
+        name: "command",
+        usage: "command [Name [definition]]",
+        help: Text::CmdCommand,
+        range: false,
+    },
+    CommandSpec {
+        name: "source",
+        usage: "source file",
+        help: Text::CmdSource,
+        range: false,
+    },
+    CommandSpec {
+        name: "map",
+        usage: "map key action",
+        help: Text::CmdMap,
+        range: false,
+    },
+    CommandSpec {
+        name: "registers",
+        usage: "registers",
+        help: Text::CmdRegisters,
+        range: false,
+    },
+    CommandSpec {
+        name: "mark",
+        usage: "mark A-Z",
+        help: Text::CmdMark,
+        range: false,
+    },
+    CommandSpec {
+        name: "marks",
+        usage: "marks",
+        help: Text::CmdMarks,
+        range: false,
+    },
+    CommandSpec {
+        name: "macros",
+        usage: "macros",
+        help: Text::CmdMacros,
+        range: false,
+    },
+    CommandSpec {
+        name: "macrosave",
+        usage: "macrosave name [macro]",
+        help: Text::CmdMacroSave,
+        range: false,
+    },
+    CommandSpec {
+        name: "macrodelete",
+        usage: "macrodelete name",
+        help: Text::CmdMacroDelete,
+        range: false,
+    },
+    CommandSpec {
+        name: "@",
+        usage: "@name",
+        help: Text::CmdPlayMacro,
+        range: false,
+    },
+    CommandSpec {
+        name: "yank",
+        usage: "[range]yank [register]",
+        help: Text::CmdYank,
+        range: true,
+    },
+    CommandSpec {
+        name: "put",
+        usage: "put [register]",
+        help: Text::CmdPut,
+        range: false,
+    },
+    CommandSpec {
+        name: "killring",
+        usage: "killring",
+        help: Text::CmdKillRing,
+        range: false,
+    },
+    CommandSpec {
+        name: "center-view",
+        usage: "center-view",
+        help: Text::CmdCenterView,
+        range: false,
+    },
+    CommandSpec {
+        name: "top-view",
+        usage: "top-view",
+        help: Text::CmdTopView,
+        range: false,
+    },
+    CommandSpec {
+        name: "bottom-view",
+        usage: "bottom-view",
+        help: Text::CmdBottomView,
+        range: false,
+    },
+    CommandSpec {
+        name: "filetype",
+        usage: "filetype [name|detect]",
+        help: Text::CmdFiletype,
+        range: false,
+    },
+    CommandSpec {
+        name: "preview",
+        usage: "preview",
+        help: Text::CmdPreview,
+        range: false,
+    },
+    CommandSpec {
+        name: "cast",
+        usage: "cast [file.cast]",
+        help: Text::CmdCast,
+        range: false,
+    },
+    CommandSpec {
+        name: "messages",
+        usage: "messages",
+        help: Text::CmdMessages,
+        range: false,
+    },
+    CommandSpec {
+        name: "help",
+        usage: "help",
+        help: Text::CmdHelp,
+        range: false,
+    },
+];
@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::Path;
+
+pub fn paths(partial: &str) -> Vec<String> {
+    let (dir, prefix) = match partial.rfind('/') {
+        Some(i) => (&partial[..=i], &partial[i + 1..]),
+        None => ("", partial),
+    };
+    let expanded = match dir.strip_prefix("~/") {
+        Some(rest) => std::env::var("HOME").map_or(dir.to_string(), |home| format!("{}/{}", home, rest)),
+        None => dir.to_string(),
+    };
+    let Ok(entries) = fs::read_dir(if expanded.is_empty() { "." } else { &expanded }) else {
+        return Vec::new();
+    };
+    let mut matches: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let slash = if entry.path().is_dir() { "/" } else { "" };
+            Some(format!("{}{}{}", dir, name, slash))
+        })
+        .collect();
+    matches.sort();
+    matches
+}
+
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for other in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(other.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(other.len()), |((i, _), _)| i.min(len));
+    }
+    first[..len].to_string()
+}
+
+pub fn file_name(path: &str) -> &str {
+    let trimmed = path.trim_end_matches('/');
+    Path::new(trimmed)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map_or(path, |name| &path[trimmed.len() - name.len()..])
+}
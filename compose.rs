@@ -0,0 +1,152 @@
+use crate::unicode;
+
+const HANGUL_BASE: u32 = 0xac00;
+
+const DEAD_KEYS: &[(char, char)] = &[
+    ('\u{b4}', '\u{301}'),
+    ('\u{2c6}', '\u{302}'),
+    ('\u{2dc}', '\u{303}'),
+    ('\u{af}', '\u{304}'),
+    ('\u{2d8}', '\u{306}'),
+    ('\u{2d9}', '\u{307}'),
+    ('\u{a8}', '\u{308}'),
+    ('\u{2da}', '\u{30a}'),
+    ('\u{2dd}', '\u{30b}'),
+    ('\u{2c7}', '\u{30c}'),
+    ('\u{b8}', '\u{327}'),
+    ('\u{2db}', '\u{328}'),
+];
+
+const INITIALS: &str = "ㄱㄲㄴㄷㄸㄹㅁㅂㅃㅅㅆㅇㅈㅉㅊㅋㅌㅍㅎ";
+const FINALS: &str = "ㄱㄲㄳㄴㄵㄶㄷㄹㄺㄻㄼㄽㄾㄿㅀㅁㅂㅄㅅㅆㅇㅈㅊㅋㅌㅍㅎ";
+const VOWELS: &str = "ㅏㅐㅑㅒㅓㅔㅕㅖㅗㅘㅙㅚㅛㅜㅝㅞㅟㅠㅡㅢㅣ";
+
+const COMPOUND_VOWELS: &[(char, char, char)] = &[
+    ('ㅗ', 'ㅏ', 'ㅘ'),
+    ('ㅗ', 'ㅐ', 'ㅙ'),
+    ('ㅗ', 'ㅣ', 'ㅚ'),
+    ('ㅜ', 'ㅓ', 'ㅝ'),
+    ('ㅜ', 'ㅔ', 'ㅞ'),
+    ('ㅜ', 'ㅣ', 'ㅟ'),
+    ('ㅡ', 'ㅣ', 'ㅢ'),
+];
+
+const COMPOUND_FINALS: &[(char, char, char)] = &[
+    ('ㄱ', 'ㅅ', 'ㄳ'),
+    ('ㄴ', 'ㅈ', 'ㄵ'),
+    ('ㄴ', 'ㅎ', 'ㄶ'),
+    ('ㄹ', 'ㄱ', 'ㄺ'),
+    ('ㄹ', 'ㅁ', 'ㄻ'),
+    ('ㄹ', 'ㅂ', 'ㄼ'),
+    ('ㄹ', 'ㅅ', 'ㄽ'),
+    ('ㄹ', 'ㅌ', 'ㄾ'),
+    ('ㄹ', 'ㅍ', 'ㄿ'),
+    ('ㄹ', 'ㅎ', 'ㅀ'),
+    ('ㅂ', 'ㅅ', 'ㅄ'),
+];
+
+fn dead_key(c: char) -> Option<char> {
+    DEAD_KEYS.iter().find(|&&(key, _)| key == c).map(|&(_, mark)| mark)
+}
+
+fn is_jamo(c: char) -> bool {
+    ('\u{3131}'..='\u{3163}').contains(&c)
+}
+
+fn index(table: &str, c: char) -> Option<u32> {
+    table.chars().position(|t| t == c).map(|i| i as u32)
+}
+
+fn pair(table: &[(char, char, char)], keys: &[char]) -> (Option<char>, usize) {
+    match keys {
+        [a, b, ..] => match table.iter().find(|&&(x, y, _)| x == *a && y == *b) {
+            Some(&(_, _, c)) => (Some(c), 2),
+            None => (Some(*a), 1),
+        },
+        [a] => (Some(*a), 1),
+        [] => (None, 0),
+    }
+}
+
+fn syllable(keys: &[char]) -> Option<char> {
+    let initial = keys.first().and_then(|&c| index(INITIALS, c));
+    let rest = &keys[usize::from(initial.is_some())..];
+    let (vowel, used) = pair(COMPOUND_VOWELS, rest);
+    let vowel = match vowel.map(|v| index(VOWELS, v)) {
+        Some(Some(vowel)) => vowel,
+        _ => return keys.first().copied().filter(|_| keys.len() == 1),
+    };
+    let rest = &rest[used..];
+    let (last, used) = pair(COMPOUND_FINALS, rest);
+    let last = match last.map(|t| index(FINALS, t)) {
+        Some(Some(last)) if initial.is_some() && used == rest.len() => last + 1,
+        Some(_) => return None,
+        None => 0,
+    };
+    let Some(initial) = initial else {
+        return char::from_u32(0x314f + vowel).filter(|_| last == 0);
+    };
+    char::from_u32(HANGUL_BASE + (initial * 21 + vowel) * 28 + last)
+}
+
+#[derive(Default)]
+pub struct Composer {
+    keys: Vec<char>,
+}
+
+impl Composer {
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn preedit(&self) -> String {
+        match self.keys[..] {
+            [] => String::new(),
+            [key, ..] if dead_key(key).is_some() => key.to_string(),
+            _ => syllable(&self.keys).map(String::from).unwrap_or_default(),
+        }
+    }
+
+    pub fn take(&mut self) -> String {
+        let text = self.preedit();
+        self.keys.clear();
+        text
+    }
+
+    pub fn backspace(&mut self) {
+        self.keys.pop();
+    }
+
+    /// Feeds a typed character, returning text that is complete and
+    /// ready for the buffer, or None when `c` is not part of a
+    /// composition and should be handled as usual.
+    pub fn feed(&mut self, c: char) -> Option<String> {
+        if let Some((key, mark)) = self.keys.first().and_then(|&key| Some((key, dead_key(key)?))) {
+            self.keys.clear();
+            let composed = unicode::nfc(&format!("{}{}", c, mark));
+            return Some(match c {
+                ' ' => key.to_string(),
+                _ if composed.chars().count() == 1 => composed,
+                _ => format!("{}{}", key, self.feed(c).unwrap_or_else(|| c.to_string())),
+            });
+        }
+        if dead_key(c).is_some() {
+            let committed = self.take();
+            self.keys.push(c);
+            return Some(committed);
+        }
+        if !is_jamo(c) {
+            return None;
+        }
+        self.keys.push(c);
+        if syllable(&self.keys).is_some() {
+            return Some(String::new());
+        }
+        let keys = std::mem::take(&mut self.keys);
+        let before = &keys[..keys.len() - 1];
+        let carried = before.len() >= 2 && index(VOWELS, c).is_some() && index(INITIALS, before[before.len() - 1]).is_some();
+        let split = if carried && syllable(&before[..before.len() - 1]).is_some() { before.len() - 1 } else { before.len() };
+        self.keys = keys[split..].to_vec();
+        Some(syllable(&keys[..split]).map(String::from).unwrap_or_default())
+    }
+}
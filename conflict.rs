@@ -0,0 +1,106 @@
+use crossterm::style::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Conflict {
+    pub start: usize,
+    pub base: Option<usize>,
+    pub middle: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Region {
+    Marker,
+    Ours,
+    Base,
+    Theirs,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    Ours,
+    Theirs,
+    Both,
+}
+
+impl Region {
+    pub fn color(self) -> Color {
+        match self {
+            Region::Marker => Color::AnsiValue(239),
+            Region::Ours => Color::AnsiValue(22),
+            Region::Base => Color::AnsiValue(58),
+            Region::Theirs => Color::AnsiValue(24),
+        }
+    }
+}
+
+impl Side {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "ours" => Some(Side::Ours),
+            "theirs" => Some(Side::Theirs),
+            "both" => Some(Side::Both),
+            _ => None,
+        }
+    }
+}
+
+fn marker(line: &str, c: char) -> bool {
+    let rest = line.trim_start_matches(c);
+    line.len() - rest.len() == 7 && (rest.is_empty() || (c != '=' && rest.starts_with(' ')))
+}
+
+pub fn find(lines: &[String]) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let mut open: Option<(usize, Option<usize>, Option<usize>)> = None;
+    for (y, line) in lines.iter().enumerate() {
+        if marker(line, '<') {
+            open = Some((y, None, None));
+        } else if let Some((start, base, middle)) = open.as_mut() {
+            if marker(line, '|') && middle.is_none() {
+                *base = Some(y);
+            } else if marker(line, '=') && middle.is_none() {
+                *middle = Some(y);
+            } else if let (true, Some(middle)) = (marker(line, '>'), *middle) {
+                conflicts.push(Conflict {
+                    start: *start,
+                    base: *base,
+                    middle,
+                    end: y,
+                });
+                open = None;
+            }
+        }
+    }
+    conflicts
+}
+
+impl Conflict {
+    pub fn contains(&self, y: usize) -> bool {
+        (self.start..=self.end).contains(&y)
+    }
+
+    pub fn region(&self, y: usize) -> Option<Region> {
+        if !self.contains(y) {
+            None
+        } else if y == self.start || y == self.middle || y == self.end || Some(y) == self.base {
+            Some(Region::Marker)
+        } else if y < self.base.unwrap_or(self.middle) {
+            Some(Region::Ours)
+        } else if y < self.middle {
+            Some(Region::Base)
+        } else {
+            Some(Region::Theirs)
+        }
+    }
+
+    pub fn resolve(&self, lines: &[String], side: Side) -> Vec<String> {
+        let ours = &lines[self.start + 1..self.base.unwrap_or(self.middle)];
+        let theirs = &lines[self.middle + 1..self.end];
+        match side {
+            Side::Ours => ours.to_vec(),
+            Side::Theirs => theirs.to_vec(),
+            Side::Both => ours.iter().chain(theirs).cloned().collect(),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::i18n::Text;
+use crate::shell;
+use std::io;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cipher {
+    Gpg,
+    Age,
+}
+
+impl Cipher {
+    pub fn detect(filename: &str) -> Option<Self> {
+        if filename.ends_with(".gpg") {
+            Some(Cipher::Gpg)
+        } else if filename.ends_with(".age") {
+            Some(Cipher::Age)
+        } else {
+            None
+        }
+    }
+
+    pub fn program(self) -> &'static str {
+        match self {
+            Cipher::Gpg => "gpg",
+            Cipher::Age => "age",
+        }
+    }
+
+    pub fn prompt(self) -> Text {
+        match self {
+            Cipher::Gpg => Text::PassphrasePrompt,
+            Cipher::Age => Text::IdentityPrompt,
+        }
+    }
+
+    pub fn masked(self) -> bool {
+        self == Cipher::Gpg
+    }
+}
+
+const GPG: &str = "--batch --quiet --yes --pinentry-mode loopback --passphrase-fd 0";
+
+fn run(line: &str, input: Vec<u8>) -> io::Result<Vec<u8>> {
+    let output = shell::filter(line, input)?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(io::Error::other(shell::failure(&output)))
+    }
+}
+
+pub fn decrypt(cipher: Cipher, program: &str, path: &str, secret: &str) -> io::Result<Vec<u8>> {
+    match cipher {
+        Cipher::Gpg => run(&format!("{} {} --decrypt {}", program, GPG, shell::quote(path)), format!("{}\n", secret).into_bytes()),
+        Cipher::Age => run(&format!("{} --decrypt --identity {} {}", program, shell::quote(secret), shell::quote(path)), Vec::new()),
+    }
+}
+
+pub fn encrypt(cipher: Cipher, program: &str, path: &str, secret: &str, plaintext: &[u8]) -> io::Result<()> {
+    let line = match cipher {
+        Cipher::Gpg => format!("{} {} --symmetric --output {}", program, GPG, shell::quote(path)),
+        Cipher::Age => format!("{} --encrypt --identity {} --output {}", program, shell::quote(secret), shell::quote(path)),
+    };
+    let mut input = Vec::new();
+    if cipher == Cipher::Gpg {
+        input.extend(format!("{}\n", secret).bytes());
+    }
+    input.extend_from_slice(plaintext);
+    run(&line, input).map(|_| ())
+}
@@ -0,0 +1,89 @@
+use crate::message::Severity;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn summary(&self) -> String {
+        format!("{}:{}:{}: {}", self.file, self.line + 1, self.column + 1, self.message)
+    }
+}
+
+fn number(text: &str) -> Option<(usize, &str)> {
+    let end = text.find(|c: char| !c.is_ascii_digit()).unwrap_or(text.len());
+    Some((text[..end].parse().ok()?, &text[end..]))
+}
+
+fn location(text: &str) -> Option<(&str, usize, usize, &str)> {
+    let (file, rest) = text.trim().split_once(':')?;
+    if file.is_empty() || file.contains(char::is_whitespace) {
+        return None;
+    }
+    let (line, rest) = number(rest)?;
+    let (column, rest) = match rest.strip_prefix(':').and_then(number) {
+        Some((column, rest)) => (column, rest),
+        None => (1, rest),
+    };
+    if !rest.is_empty() && !rest.starts_with(':') {
+        return None;
+    }
+    Some((file, line.max(1) - 1, column.max(1) - 1, rest.trim_start_matches(':').trim()))
+}
+
+fn severity(text: &str) -> Option<(Severity, &str)> {
+    let (label, message) = text.split_once(':')?;
+    let severity = match label.split('[').next()?.trim() {
+        "error" | "fatal error" => Severity::Error,
+        "warning" => Severity::Warning,
+        "note" | "info" | "help" | "style" => Severity::Info,
+        _ => return None,
+    };
+    Some((severity, message.trim()))
+}
+
+pub fn parse(output: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut header: Option<(Severity, &str)> = None;
+    for line in output.lines() {
+        if let Some(arrow) = line.trim_start().strip_prefix("--> ") {
+            if let (Some((severity, message)), Some((file, line, column, _))) = (header.take(), location(arrow)) {
+                diagnostics.push(Diagnostic {
+                    file: file.to_string(),
+                    line,
+                    column,
+                    severity,
+                    message: message.to_string(),
+                });
+            }
+        } else if let Some((file, line, column, rest)) = location(line) {
+            let (severity, message) = severity(rest).unwrap_or((Severity::Error, rest));
+            diagnostics.push(Diagnostic {
+                file: file.to_string(),
+                line,
+                column,
+                severity,
+                message: message.to_string(),
+            });
+        } else if let Some(found) = severity(line) {
+            header = Some(found);
+        }
+    }
+    diagnostics
+}
+
+pub fn same_file(a: &str, b: &str) -> bool {
+    if Path::new(a) == Path::new(b) {
+        return true;
+    }
+    match (Path::new(a).canonicalize(), Path::new(b).canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
@@ -0,0 +1,147 @@
+use crate::unicode;
+
+const MARKS: &[(char, char)] = &[
+    ('\'', '\u{301}'),
+    ('!', '\u{300}'),
+    ('>', '\u{302}'),
+    ('?', '\u{303}'),
+    ('-', '\u{304}'),
+    ('(', '\u{306}'),
+    ('.', '\u{307}'),
+    (':', '\u{308}'),
+    ('0', '\u{30a}'),
+    ('"', '\u{30b}'),
+    ('<', '\u{30c}'),
+    (',', '\u{327}'),
+    (';', '\u{328}'),
+];
+
+const SYMBOLS: &[(&str, char)] = &[
+    ("ss", 'ß'),
+    ("ae", 'æ'),
+    ("AE", 'Æ'),
+    ("o/", 'ø'),
+    ("O/", 'Ø'),
+    ("oe", 'œ'),
+    ("OE", 'Œ'),
+    ("i.", 'ı'),
+    ("th", 'þ'),
+    ("TH", 'Þ'),
+    ("d/", 'đ'),
+    ("D/", 'Đ'),
+    ("Eu", '€'),
+    ("Pd", '£'),
+    ("Ye", '¥'),
+    ("Ct", '¢'),
+    ("Co", '©'),
+    ("Rg", '®'),
+    ("TM", '™'),
+    ("SE", '§'),
+    ("PI", '¶'),
+    ("NO", '¬'),
+    ("DG", '°'),
+    ("+-", '±'),
+    ("*X", '×'),
+    ("-:", '÷'),
+    ("<<", '«'),
+    (">>", '»'),
+    ("!I", '¡'),
+    ("?I", '¿'),
+    ("12", '½'),
+    ("14", '¼'),
+    ("34", '¾'),
+    ("My", 'µ'),
+    ("->", '→'),
+    ("<-", '←'),
+    ("-!", '↑'),
+    ("-v", '↓'),
+    ("=>", '⇒'),
+    ("!=", '≠'),
+    ("=<", '≤'),
+    (">=", '≥'),
+    ("00", '∞'),
+    ("OK", '✓'),
+    ("XX", '✗'),
+    ("..", '…'),
+    ("-N", '–'),
+    ("-M", '—'),
+    ("'6", '‘'),
+    ("'9", '’'),
+    ("\"6", '“'),
+    ("\"9", '”'),
+    ("NS", '\u{a0}'),
+];
+
+const GREEK: &str = "abgdezyhiklmncoprstufxqw";
+const GREEK_LETTERS: &str = "αβγδεζηθικλμνξοπρστυφχψω";
+
+pub fn lookup(first: char, second: char) -> Option<char> {
+    let pair = |a: char, b: char| {
+        let code: String = [a, b].into_iter().collect();
+        if let Some(&(_, c)) = SYMBOLS.iter().find(|&&(name, _)| name == code) {
+            return Some(c);
+        }
+        if b == '*' {
+            let index = GREEK.find(a.to_ascii_lowercase())?;
+            let letter = GREEK_LETTERS.chars().nth(index)?;
+            return if a.is_ascii_uppercase() { letter.to_uppercase().next() } else { Some(letter) };
+        }
+        let &(_, mark) = MARKS.iter().find(|&&(symbol, _)| symbol == b)?;
+        let composed = unicode::nfc(&format!("{}{}", a, mark));
+        let mut chars = composed.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if a.is_alphabetic() => Some(c),
+            _ => None,
+        }
+    };
+    pair(first, second).or_else(|| pair(second, first))
+}
+
+pub fn name(c: char) -> Option<String> {
+    let symbol = SYMBOLS.iter().find(|&&(_, symbol)| symbol == c).map(|&(name, _)| name.to_string());
+    symbol.or_else(|| {
+        let mut decomposed = Vec::new();
+        unicode::decompose_char(c, &mut decomposed);
+        let [base, mark] = decomposed[..] else {
+            return None;
+        };
+        MARKS.iter().find(|&&(_, m)| m == mark).map(|&(symbol, _)| format!("{}{}", base, symbol))
+    })
+}
+
+pub enum Literal {
+    Pending,
+    Done(char),
+    Invalid,
+}
+
+fn codepoint(input: &str) -> Option<(&str, usize)> {
+    let digits = match input.chars().next()? {
+        'u' => 4,
+        'U' => 8,
+        'x' => 2,
+        _ => return None,
+    };
+    let hex = &input[1..];
+    hex.chars().all(|c| c.is_ascii_hexdigit()).then_some((hex, digits))
+}
+
+pub fn feed(input: &str) -> Literal {
+    if let Some((hex, digits)) = codepoint(input) {
+        if hex.len() < digits {
+            return Literal::Pending;
+        }
+        return finish(input).map_or(Literal::Invalid, Literal::Done);
+    }
+    let mut chars = input.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (None, _, _) | (Some(_), None, _) => Literal::Pending,
+        (Some(a), Some(b), None) => lookup(a, b).map_or(Literal::Invalid, Literal::Done),
+        _ => Literal::Invalid,
+    }
+}
+
+pub fn finish(input: &str) -> Option<char> {
+    let (hex, _) = codepoint(input).filter(|(hex, _)| !hex.is_empty())?;
+    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+}
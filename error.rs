@@ -0,0 +1,49 @@
+use crate::i18n::{trf, Text};
+use std::fmt;
+use std::io;
+
+/// A failed file operation, kept with the path it was about so the
+/// message area can show both and the OS error text.
+#[derive(Debug)]
+pub enum Error {
+    Read(String, io::Error),
+    Write(String, io::Error),
+}
+
+impl Error {
+    pub fn read(path: impl fmt::Display, err: io::Error) -> Self {
+        Error::Read(path.to_string(), err)
+    }
+
+    pub fn write(path: impl fmt::Display, err: io::Error) -> Self {
+        Error::Write(path.to_string(), err)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Error::Read(path, err) => trf(Text::ReadFailed, &[path, err]),
+            Error::Write(path, err) if err.kind() == io::ErrorKind::PermissionDenied => trf(Text::WriteDenied, &[path, err]),
+            Error::Write(path, err) => trf(Text::WriteFailed, &[path, err]),
+        };
+        f.write_str(&text)
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Read(_, err) | Error::Write(_, err) => Some(err),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> Self {
+        let kind = match &err {
+            Error::Read(_, inner) | Error::Write(_, inner) => inner.kind(),
+        };
+        io::Error::new(kind, err.to_string())
+    }
+}
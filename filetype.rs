@@ -0,0 +1,242 @@
+use std::path::Path;
+
+const MODELINE_LINES: usize = 5;
+const WRAPPERS: &[&str] = &[".gpg", ".age", ".gz"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Indent {
+    Tabs,
+    Spaces(usize),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileType {
+    pub name: &'static str,
+    pub comment: Option<&'static str>,
+    pub indent: Indent,
+    extensions: &'static [&'static str],
+    filenames: &'static [&'static str],
+    interpreters: &'static [&'static str],
+}
+
+pub const FILETYPES: &[FileType] = &[
+    FileType {
+        name: "rust",
+        comment: Some("//"),
+        indent: Indent::Spaces(4),
+        extensions: &["rs"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "c",
+        comment: Some("//"),
+        indent: Indent::Spaces(4),
+        extensions: &["c", "h"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "cpp",
+        comment: Some("//"),
+        indent: Indent::Spaces(4),
+        extensions: &["cc", "cpp", "cxx", "hpp", "hh"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "go",
+        comment: Some("//"),
+        indent: Indent::Tabs,
+        extensions: &["go"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "python",
+        comment: Some("#"),
+        indent: Indent::Spaces(4),
+        extensions: &["py", "pyw"],
+        filenames: &[],
+        interpreters: &["python", "python2", "python3"],
+    },
+    FileType {
+        name: "javascript",
+        comment: Some("//"),
+        indent: Indent::Spaces(2),
+        extensions: &["js", "mjs", "cjs", "jsx"],
+        filenames: &[],
+        interpreters: &["node", "nodejs"],
+    },
+    FileType {
+        name: "typescript",
+        comment: Some("//"),
+        indent: Indent::Spaces(2),
+        extensions: &["ts", "tsx"],
+        filenames: &[],
+        interpreters: &["deno", "ts-node"],
+    },
+    FileType {
+        name: "sh",
+        comment: Some("#"),
+        indent: Indent::Spaces(2),
+        extensions: &["sh", "bash", "zsh"],
+        filenames: &[".bashrc", ".bash_profile", ".profile", ".zshrc"],
+        interpreters: &["sh", "bash", "zsh", "dash", "ksh"],
+    },
+    FileType {
+        name: "ruby",
+        comment: Some("#"),
+        indent: Indent::Spaces(2),
+        extensions: &["rb"],
+        filenames: &["Gemfile", "Rakefile"],
+        interpreters: &["ruby"],
+    },
+    FileType {
+        name: "perl",
+        comment: Some("#"),
+        indent: Indent::Spaces(4),
+        extensions: &["pl", "pm"],
+        filenames: &[],
+        interpreters: &["perl"],
+    },
+    FileType {
+        name: "lua",
+        comment: Some("--"),
+        indent: Indent::Spaces(2),
+        extensions: &["lua"],
+        filenames: &[],
+        interpreters: &["lua", "luajit"],
+    },
+    FileType {
+        name: "make",
+        comment: Some("#"),
+        indent: Indent::Tabs,
+        extensions: &["mk"],
+        filenames: &["Makefile", "makefile", "GNUmakefile"],
+        interpreters: &["make"],
+    },
+    FileType {
+        name: "dockerfile",
+        comment: Some("#"),
+        indent: Indent::Spaces(4),
+        extensions: &[],
+        filenames: &["Dockerfile", "Containerfile"],
+        interpreters: &[],
+    },
+    FileType {
+        name: "toml",
+        comment: Some("#"),
+        indent: Indent::Spaces(4),
+        extensions: &["toml"],
+        filenames: &["Cargo.lock"],
+        interpreters: &[],
+    },
+    FileType {
+        name: "yaml",
+        comment: Some("#"),
+        indent: Indent::Spaces(2),
+        extensions: &["yml", "yaml"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "json",
+        comment: None,
+        indent: Indent::Spaces(2),
+        extensions: &["json"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "html",
+        comment: None,
+        indent: Indent::Spaces(2),
+        extensions: &["html", "htm"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "css",
+        comment: None,
+        indent: Indent::Spaces(2),
+        extensions: &["css"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "markdown",
+        comment: None,
+        indent: Indent::Spaces(2),
+        extensions: &["md", "markdown"],
+        filenames: &[],
+        interpreters: &[],
+    },
+    FileType {
+        name: "text",
+        comment: None,
+        indent: Indent::Spaces(4),
+        extensions: &["txt"],
+        filenames: &[],
+        interpreters: &[],
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static FileType> {
+    FILETYPES
+        .iter()
+        .find(|filetype| filetype.name == name)
+        .or_else(|| FILETYPES.iter().find(|filetype| filetype.interpreters.contains(&name)))
+}
+
+pub fn detect(filename: Option<&str>, lines: &[String]) -> Option<&'static FileType> {
+    let head = lines.iter().take(MODELINE_LINES);
+    let tail = lines.iter().skip(MODELINE_LINES).rev().take(MODELINE_LINES);
+    if let Some(filetype) = head.chain(tail).find_map(|line| modeline(line)).and_then(find) {
+        return Some(filetype);
+    }
+    if let Some(filetype) = filename.and_then(from_path) {
+        return Some(filetype);
+    }
+    lines.first().and_then(|line| shebang(line))
+}
+
+fn from_path(filename: &str) -> Option<&'static FileType> {
+    let filename = WRAPPERS.iter().find_map(|suffix| filename.strip_suffix(suffix)).unwrap_or(filename);
+    let path = Path::new(filename);
+    let name = path.file_name()?.to_str()?;
+    if let Some(filetype) = FILETYPES.iter().find(|filetype| filetype.filenames.contains(&name)) {
+        return Some(filetype);
+    }
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    FILETYPES
+        .iter()
+        .find(|filetype| filetype.extensions.contains(&extension.as_str()))
+}
+
+fn shebang(line: &str) -> Option<&'static FileType> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let mut program = Path::new(words.next()?).file_name()?.to_str()?;
+    if program == "env" {
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    FILETYPES
+        .iter()
+        .find(|filetype| filetype.interpreters.contains(&program))
+}
+
+fn modeline(line: &str) -> Option<&str> {
+    let start = ["vim:", "vi:", "ex:", "aon:"]
+        .iter()
+        .flat_map(|marker| line.match_indices(marker))
+        .filter(|(i, _)| line[..*i].chars().next_back().is_none_or(char::is_whitespace))
+        .map(|(i, marker)| i + marker.len())
+        .min()?;
+    let rest = line[start..].trim_start();
+    let rest = rest.strip_prefix("set ").or_else(|| rest.strip_prefix("se ")).unwrap_or(rest);
+    rest.split(|c: char| c == ':' || c.is_whitespace())
+        .filter_map(|setting| setting.split_once('='))
+        .find(|(name, _)| matches!(*name, "ft" | "filetype" | "syntax" | "syn"))
+        .map(|(_, value)| value)
+}
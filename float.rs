@@ -0,0 +1,167 @@
+use crate::screen::{Screen, Style};
+use crate::Position;
+
+/// Panels on a higher layer are drawn over those on a lower one.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Layer {
+    Hint,
+    Hover,
+    Completion,
+    Picker,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Placement {
+    Center,
+    /// Above the status line, or below the message line if there is no room.
+    Status,
+    Cursor,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Dismiss {
+    Key,
+    /// Once the cursor moves away from this position.
+    Cursor(Position),
+}
+
+pub struct Frame {
+    pub top: u16,
+    pub rows: u16,
+    pub bottom: u16,
+    pub cursor: Option<(u16, u16)>,
+}
+
+#[derive(Clone)]
+pub struct Panel {
+    title: String,
+    lines: Vec<String>,
+    layer: Layer,
+    placement: Placement,
+    selected: Option<usize>,
+    scroll: usize,
+    full_width: bool,
+}
+
+impl Panel {
+    pub fn new(title: &str, lines: Vec<String>, layer: Layer, placement: Placement) -> Self {
+        Self {
+            title: title.to_string(),
+            lines,
+            layer,
+            placement,
+            selected: None,
+            scroll: 0,
+            full_width: false,
+        }
+    }
+
+    /// Highlights line `index`, with line `scroll` at the top.
+    pub fn select(mut self, index: usize, scroll: usize) -> Self {
+        self.selected = Some(index);
+        self.scroll = scroll;
+        self
+    }
+
+    pub fn full_width(mut self) -> Self {
+        self.full_width = true;
+        self
+    }
+
+    /// `(x, y, width, height)` including the border, if a line fits.
+    pub fn area(&self, screen: &Screen, frame: &Frame) -> Option<(u16, u16, u16, u16)> {
+        let title = self.title().chars().count();
+        let longest = self.lines.iter().map(|line| line.chars().count() + 2).chain([title]).max().unwrap_or(0);
+        let width = if self.full_width {
+            screen.width()
+        } else {
+            (longest as u16 + 2).min(screen.width().saturating_sub(2)).max(8)
+        };
+        let wanted = self.lines.len().max(1) as u16;
+        let (x, y, rows) = match self.placement {
+            Placement::Center => {
+                let rows = wanted.min(frame.rows.saturating_sub(2));
+                let y = frame.top + (frame.rows.saturating_sub(rows + 2)) / 2;
+                ((screen.width().saturating_sub(width)) / 2, y, rows)
+            }
+            Placement::Status => {
+                let above = (frame.bottom + 1).saturating_sub(frame.top);
+                let below = screen.height().saturating_sub(frame.bottom + 3);
+                let room = if above >= wanted + 2 || above >= below { above } else { below };
+                let rows = wanted.min(room.saturating_sub(2));
+                let y = if room == above { (frame.bottom + 1).saturating_sub(rows + 2) } else { frame.bottom + 3 };
+                (0, y, rows)
+            }
+            Placement::Cursor => {
+                let (x, y) = frame.cursor?;
+                let below = (frame.bottom + 1).saturating_sub(y + 1);
+                let above = y.saturating_sub(frame.top);
+                let room = if below >= wanted + 2 || below >= above { below } else { above };
+                let rows = wanted.min(room.saturating_sub(2));
+                let y = if room == below { y + 1 } else { y.saturating_sub(rows + 2) };
+                (x.min(screen.width().saturating_sub(width)), y, rows)
+            }
+        };
+        (rows > 0).then_some((x, y, width, rows + 2))
+    }
+
+    pub fn draw(&self, screen: &mut Screen, frame: &Frame) {
+        let Some((x, y, width, height)) = self.area(screen, frame) else {
+            return;
+        };
+        let inner = (width - 2) as usize;
+        let plain = Style::default();
+        let title: String = self.title().chars().take(inner).collect();
+        screen.print(x, y, &format!("┌{:─^1$}┐", title, inner), plain);
+        for row in 0..height - 2 {
+            let index = self.scroll + row as usize;
+            let line = self.lines.get(index).map_or("", String::as_str);
+            let text: String = line.chars().take(inner - 1).collect();
+            let style = Style {
+                reverse: self.selected == Some(index) && index < self.lines.len(),
+                ..plain
+            };
+            screen.print(x, y + 1 + row, "│", plain);
+            screen.print(x + 1, y + 1 + row, &format!(" {:<1$}", text, inner - 1), style);
+            screen.print(x + width - 1, y + 1 + row, "│", plain);
+        }
+        screen.print(x, y + height - 1, &format!("└{}┘", "─".repeat(inner)), plain);
+    }
+
+    fn title(&self) -> String {
+        if self.title.is_empty() {
+            String::new()
+        } else {
+            format!(" {} ", self.title)
+        }
+    }
+}
+
+/// Panels shown until dismissed, one per layer.
+#[derive(Default)]
+pub struct Overlay {
+    panels: Vec<(Panel, Dismiss)>,
+}
+
+impl Overlay {
+    pub fn show(&mut self, panel: Panel, dismiss: Dismiss) {
+        self.panels.retain(|(shown, _)| shown.layer != panel.layer);
+        self.panels.push((panel, dismiss));
+    }
+
+    pub fn key_pressed(&mut self) {
+        self.panels.retain(|(_, dismiss)| *dismiss != Dismiss::Key);
+    }
+
+    pub fn cursor_moved(&mut self, cursor: Position) {
+        self.panels.retain(|(_, dismiss)| !matches!(dismiss, Dismiss::Cursor(at) if *at != cursor));
+    }
+
+    pub fn draw(&self, screen: &mut Screen, frame: &Frame, transient: &[Panel]) {
+        let mut panels: Vec<&Panel> = self.panels.iter().map(|(panel, _)| panel).chain(transient).collect();
+        panels.sort_by_key(|panel| panel.layer);
+        for panel in panels {
+            panel.draw(screen, frame);
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::shell;
+use std::io;
+
+pub fn is_compressed(filename: &str) -> bool {
+    filename.ends_with(".gz")
+}
+
+fn run(line: &str, input: &[u8]) -> io::Result<Vec<u8>> {
+    let output = shell::filter(line, input)?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(io::Error::other(shell::failure(&output)))
+    }
+}
+
+pub fn decompress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    run("gzip -dc", bytes)
+}
+
+pub fn compress(bytes: &[u8]) -> io::Result<Vec<u8>> {
+    run("gzip -c", bytes)
+}
@@ -0,0 +1,150 @@
+use crate::utf8;
+use crossterm::event::KeyCode;
+use std::cell::Cell;
+use std::ops::Range;
+
+const ROW_BYTES: usize = 16;
+
+pub struct HexView {
+    pub bytes: Vec<u8>,
+    pub cursor: usize,
+    low_nibble: bool,
+    scroll: Cell<usize>,
+    undo_stack: Vec<(usize, Option<u8>)>,
+}
+
+impl HexView {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            cursor: 0,
+            low_nibble: false,
+            scroll: Cell::new(0),
+            undo_stack: Vec::new(),
+        }
+    }
+
+    pub fn is_binary(bytes: &[u8]) -> bool {
+        let control = bytes.iter().filter(|&&b| (b < 0x20 && !b"\t\n\r\x0c\x1b".contains(&b)) || b == 0x7f).count();
+        bytes.contains(&0) || control * 10 > bytes.len() || utf8::invalid_bytes(bytes) * 4 > bytes.len() || utf8::collides(bytes)
+    }
+
+    pub fn rows(&self) -> usize {
+        (self.bytes.len() / ROW_BYTES + 1).max(1)
+    }
+
+    pub fn top(&self) -> usize {
+        self.scroll.get()
+    }
+
+    pub fn cursor_row(&self) -> usize {
+        self.cursor / ROW_BYTES
+    }
+
+    pub fn cursor_column(&self) -> usize {
+        let col = self.cursor % ROW_BYTES;
+        10 + col * 3 + usize::from(col >= ROW_BYTES / 2) + usize::from(self.low_nibble)
+    }
+
+    pub fn visible_rows(&self, height: usize) -> Range<usize> {
+        let height = height.max(1);
+        let row = self.cursor_row();
+        let mut top = self.scroll.get();
+        if row < top {
+            top = row;
+        } else if row >= top + height {
+            top = row + 1 - height;
+        }
+        self.scroll.set(top);
+        top..(top + height).min(self.rows())
+    }
+
+    pub fn row_text(&self, row: usize) -> String {
+        let start = row * ROW_BYTES;
+        let end = (start + ROW_BYTES).min(self.bytes.len());
+        let chunk = if start < end { &self.bytes[start..end] } else { &[][..] };
+
+        let mut text = format!("{:08x}  ", start);
+        for i in 0..ROW_BYTES {
+            if i == ROW_BYTES / 2 {
+                text.push(' ');
+            }
+            match chunk.get(i) {
+                Some(b) => text.push_str(&format!("{:02x} ", b)),
+                None => text.push_str("   "),
+            }
+        }
+        text.push('|');
+        for &b in chunk {
+            text.push(if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' });
+        }
+        text.push('|');
+        text
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Left | KeyCode::Backspace => self.move_by(-1),
+            KeyCode::Right => self.move_by(1),
+            KeyCode::Up => self.move_by(-(ROW_BYTES as isize)),
+            KeyCode::Down => self.move_by(ROW_BYTES as isize),
+            KeyCode::Home => self.move_to(self.cursor - self.cursor % ROW_BYTES),
+            KeyCode::End => self.move_to(self.cursor - self.cursor % ROW_BYTES + ROW_BYTES - 1),
+            KeyCode::PageUp => self.move_by(-(16 * ROW_BYTES as isize)),
+            KeyCode::PageDown => self.move_by(16 * ROW_BYTES as isize),
+            KeyCode::Char(c) => return self.input(c),
+            _ => {}
+        }
+        false
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some((offset, Some(old))) => {
+                self.bytes[offset] = old;
+                self.move_to(offset);
+                true
+            }
+            Some((offset, None)) => {
+                self.bytes.truncate(offset);
+                self.move_to(offset);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let target = self.cursor as isize + delta;
+        if target >= 0 {
+            self.move_to(target as usize);
+        }
+    }
+
+    fn move_to(&mut self, offset: usize) {
+        self.cursor = offset.min(self.bytes.len());
+        self.low_nibble = false;
+    }
+
+    fn input(&mut self, c: char) -> bool {
+        let Some(digit) = c.to_digit(16) else {
+            return false;
+        };
+        if self.cursor == self.bytes.len() {
+            self.undo_stack.push((self.cursor, None));
+            self.bytes.push(0);
+        } else if !self.low_nibble {
+            self.undo_stack.push((self.cursor, Some(self.bytes[self.cursor])));
+        }
+
+        let byte = &mut self.bytes[self.cursor];
+        if self.low_nibble {
+            *byte = (*byte & 0xf0) | digit as u8;
+            self.move_to(self.cursor + 1);
+        } else {
+            *byte = (*byte & 0x0f) | ((digit as u8) << 4);
+            self.low_nibble = true;
+        }
+        true
+    }
+}
@@ -0,0 +1,397 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Locale {
+    En,
+    Tr,
+}
+
+impl Locale {
+    pub fn parse(name: &str) -> Option<Self> {
+        let lang = name.split(['_', '.', '-']).next().unwrap_or("").to_ascii_lowercase();
+        match lang.as_str() {
+            "en" | "c" | "posix" => Some(Locale::En),
+            "tr" => Some(Locale::Tr),
+            _ => None,
+        }
+    }
+
+    pub fn detect(configured: Option<&str>) -> Self {
+        configured
+            .and_then(Locale::parse)
+            .or_else(|| {
+                ["LC_ALL", "LC_MESSAGES", "LANG"]
+                    .iter()
+                    .filter_map(|var| std::env::var(var).ok())
+                    .find(|value| !value.is_empty())
+                    .and_then(|value| Locale::parse(&value))
+            })
+            .unwrap_or(Locale::En)
+    }
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::Tr,
+        _ => Locale::En,
+    }
+}
+
+macro_rules! catalog {
+    ($($key:ident => $en:expr, $tr:expr;)*) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum Text {
+            $($key,)*
+        }
+
+        impl Text {
+            fn lookup(self, locale: Locale) -> &'static str {
+                match (self, locale) {
+                    $(
+                        (Text::$key, Locale::En) => $en,
+                        (Text::$key, Locale::Tr) => $tr,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+catalog! {
+    Modified => "MODIFIED", "DEĞİŞTİ";
+    Saved => "SAVED", "KAYITLI";
+    Overwrite => "OVERWRITE", "ÜZERİNE YAZ";
+    PasteMode => "PASTE", "YAPIŞTIR";
+    ModeNormal => "NORMAL", "NORMAL";
+    ModeInsert => "INSERT", "EKLE";
+    Line => "Line", "Satır";
+    HexOffset => "HEX Offset", "HEX Ofset";
+    ViewAll => "All", "Hepsi";
+    ViewTop => "Top", "Başı";
+    ViewBottom => "Bot", "Sonu";
+    Untitled => "[No Name]", "[İsimsiz]";
+    ConfirmSave => "Save changes? (y/n)", "Kaydetmek ister misin? (y/n)";
+    FilenamePrompt => "File name: {}", "Dosya adı: {}";
+    MessagesTitle => "[Messages]", "[Mesajlar]";
+    HelpTitle => "[Help]", "[Yardım]";
+    SeverityInfo => "INFO", "BİLGİ";
+    SeverityWarning => "WARN", "UYARI";
+    SeverityError => "ERROR", "HATA";
+    ReadOnly => "Buffer is read-only", "Tampon salt okunur";
+    UnsavedChanges => "Buffer has unsaved changes", "Kaydedilmemiş değişiklikler var";
+    Saving => "saving {}%", "kaydediliyor %{}";
+    SymlinkFollow => "→ {}", "→ {}";
+    SymlinkReplace => "replaces link", "bağlantıyı değiştirir";
+    BrowserCreate => "New file: {}", "Yeni dosya: {}";
+    BrowserMkdir => "New directory: {}", "Yeni dizin: {}";
+    BrowserRename => "Rename to: {}", "Yeni ad: {}";
+    BrowserDelete => "Delete {}? (y/n)", "{} silinsin mi? (e/h)";
+    BrowserFailed => "Could not update {}: {}", "{} güncellenemedi: {}";
+    Locked => "locked", "kilitli";
+    Encrypted => "encrypted", "şifreli";
+    PassphrasePrompt => "Passphrase: {}", "Parola: {}";
+    IdentityPrompt => "Identity file: {}", "Kimlik dosyası: {}";
+    DecryptFailed => "\"{}\" could not be decrypted: {}", "\"{}\" çözülemedi: {}";
+    NoSecret => "no passphrase or identity is set for this buffer", "bu tampon için parola ya da kimlik ayarlanmadı";
+    RemoteFetching => "fetching", "indiriliyor";
+    RemoteOffline => "offline", "bağlantı yok";
+    RemoteFailed => "\"{}\" could not be fetched: {}", "\"{}\" indirilemedi: {}";
+    RemoteReadOnly => "web pages are read-only; save a copy with :w <file>", "web sayfaları salt okunurdur; kopya için :w <dosya> kullanın";
+    RemoteNotLoaded => "remote file was never loaded; reopen it with :e to retry", "uzak dosya hiç yüklenmedi; yeniden denemek için :e ile açın";
+    SaveInProgress => "File is being saved, try again when the write finishes", "Dosya kaydediliyor, yazma bitince tekrar deneyin";
+    Written => "\"{}\" written", "\"{}\" yazıldı";
+    ReadFailed => "\"{}\" could not be read: {}", "\"{}\" okunamadı: {}";
+    WriteFailed => "\"{}\" could not be saved: {}", "\"{}\" kaydedilemedi: {}";
+    WriteDenied => "\"{}\" could not be saved: {}; use :w!! to save with sudo", "\"{}\" kaydedilemedi: {}; sudo ile kaydetmek için :w!! kullanın";
+    SudoPrompt => "[sudo] password: {}", "[sudo] parola: {}";
+    SudoLocalOnly => "sudo can only save local, unencrypted files", "sudo yalnızca yerel, şifresiz dosyaları kaydedebilir";
+    InvalidBuffer => "Invalid buffer: {}", "Geçersiz tampon: {}";
+    UnknownOption => "Unknown option: {}", "Bilinmeyen seçenek: {}";
+    InvalidKey => "Invalid key: {}", "Geçersiz tuş: {}";
+    UnknownAction => "Unknown action: {}", "Bilinmeyen eylem: {}";
+    InvalidOptionValue => "Invalid value for {}: {}", "{} için geçersiz değer: {}";
+    UnknownFiletype => "Unknown filetype: {}", "Bilinmeyen dosya türü: {}";
+    NoFiletype => "none", "yok";
+    NotMarkdown => "Preview is only available for Markdown files", "Önizleme yalnızca Markdown dosyaları için kullanılabilir";
+    CastStarted => "Recording session to {}", "Oturum {} dosyasına kaydediliyor";
+    CastSaved => "Session saved to {}", "Oturum {} dosyasına kaydedildi";
+    CastNotRecording => "No session is being recorded", "Kaydedilen bir oturum yok";
+    KillRingTitle => " Paste history ", " Yapıştırma geçmişi ";
+    KillRingEmpty => "Paste history is empty", "Yapıştırma geçmişi boş";
+    NoPreviousPaste => "Previous command was not a paste", "Önceki komut yapıştırma değildi";
+    RegistersTitle => " Registers and paste history, Enter pastes ", " Yazmaçlar ve yapıştırma geçmişi, Enter yapıştırır ";
+    RegistersEmpty => "All registers are empty", "Tüm yazmaçlar boş";
+    MacrosTitle => "[Macros]", "[Makrolar]";
+    MacrosEmpty => "No macros have been recorded", "Kaydedilmiş makro yok";
+    Recording => "recording @{}", "kayıt @{}";
+    MacroRecorded => "Recorded macro @{}", "@{} makrosu kaydedildi";
+    MacroSaved => "Saved macro @{}", "@{} makrosu saklandı";
+    MacroDeleted => "Deleted macro @{}", "@{} makrosu silindi";
+    MacroSaveFailed => "Could not save macros: {}", "Makrolar saklanamadı: {}";
+    MacroNameRequired => "A macro name is required", "Makro adı gerekli";
+    InvalidMacroName => "Invalid macro name: {}", "Geçersiz makro adı: {}";
+    NoSuchMacro => "No macro named @{}", "@{} adında makro yok";
+    NoLastMacro => "No macro has been recorded or played yet", "Henüz kaydedilen ya da oynatılan makro yok";
+    MacroTooDeep => "Macro nesting is too deep", "Makrolar çok derin iç içe";
+    MarksTitle => " Marks, Enter jumps ", " İşaretler, Enter atlar ";
+    FiletypesTitle => " Filetypes, Enter sets ", " Dosya türleri, Enter ayarlar ";
+    StartOfBuffer => "Already at the start of the buffer", "Zaten arabelleğin başında";
+    NoMarks => "No marks are set", "Hiç işaret yok";
+    MarkNotSet => "Mark not set: {}", "İşaret yok: {}";
+    InvalidMark => "Marks are A-Z: {}", "İşaretler A-Z olmalı: {}";
+    InvalidRegister => "Invalid register: {}", "Geçersiz yazmaç: {}";
+    InvalidRange => "Invalid range: {}", "Geçersiz aralık: {}";
+    NoRange => "{} does not take a range", "{} aralık almaz";
+    InvalidPattern => "Invalid pattern: {}", "Geçersiz desen: {}";
+    PatternNotFound => "Pattern not found: {}", "Desen bulunamadı: {}";
+    NoPreviousPattern => "No previous pattern", "Önceki desen yok";
+    NestedGlobal => ":g cannot be nested", ":g iç içe kullanılamaz";
+    MatchesTitle => "[Matches]", "[Eşleşmeler]";
+    ReplaceTitle => " {} matches: Space toggles, Enter applies ", " {} eşleşme: Boşluk seçer, Enter uygular ";
+    Replaced => "{} replacements in {} buffers", "{} değişiklik, {} tamponda";
+    ClipboardTooLarge => "Not copied to the terminal clipboard: {} bytes exceeds osc52limit ({})", "Uçbirim panosuna kopyalanmadı: {} bayt osc52limit değerini ({}) aşıyor";
+    PatchTitle => " Patch: {} hunks in {} files, Space toggles, Enter applies ", " Yama: {} parça, {} dosya, Boşluk seçer, Enter uygular ";
+    NoPatch => "No diff hunks found", "Fark parçası bulunamadı";
+    Patched => "{} hunks applied in {} buffers", "{} parça uygulandı, {} tamponda";
+    SearchForward => "/{}", "/{}";
+    SearchBackward => "?{}", "?{}";
+    LiteralPrompt => "Literal: {}", "Karakter: {}";
+    UnknownLiteral => "No character for {}", "{} için karakter yok";
+    NoCharacter => "No character under the cursor", "İmleçte karakter yok";
+    CharInfo => "<{}> {}, Hex {}, Oct {}", "<{}> {}, Onaltılık {}, Sekizlik {}";
+    CharDigraph => ", Digraph {}", ", Kısaltma {}";
+    PatchRejected => "{} of {} hunks did not apply (context mismatch)", "{} / {} parça uygulanamadı (bağlam uyuşmuyor)";
+    SearchWrapped => "Search wrapped around the buffer", "Arama tamponun başına/sonuna döndü";
+    CmdNohlsearch => "Stop showing the match count of the last search", "Son aramanın eşleşme sayısını göstermeyi bırak";
+    ConfirmSubstitute => "Replace with {} (y/n/a/q/l)?", "{} ile değiştir (y/n/a/q/l)?";
+    Substituted => "{} substitutions on {} lines", "{} değişiklik, {} satırda";
+    OptionsTitle => "[Options]", "[Seçenekler]";
+    Usage => "Usage: :{}", "Kullanım: :{}";
+    UnknownCommand => "Unknown command: {}", "Bilinmeyen komut: {}";
+    UserCommandsTitle => "[Commands]", "[Komutlar]";
+    HelpUserCommands => "User commands", "Kullanıcı komutları";
+    BuiltinCommand => "Cannot redefine built-in command: {}", "Yerleşik komut yeniden tanımlanamaz: {}";
+    InvalidCommandName => "Invalid command name: {}", "Geçersiz komut adı: {}";
+    CommandRecursion => "Command nesting too deep: {}", "Komut iç içe geçmesi çok derin: {}";
+    SourceFailed => "Could not read {}: {}", "{} okunamadı: {}";
+    MapUsage => "Usage: map KEY ACTION (or none)", "Kullanım: map TUŞ EYLEM (ya da none)";
+    ShellStarted => "Running in background: {}", "Arka planda çalışıyor: {}";
+    ShellFailed => "Shell command failed: {}", "Kabuk komutu başarısız: {}";
+    FormatFailed => "Formatter failed, buffer left unchanged: {}", "Biçimlendirici başarısız, arabellek değiştirilmedi: {}";
+    LintFailed => "Linter failed: {}", "Denetleyici başarısız: {}";
+    LintClean => "Lint: no problems", "Denetim: sorun yok";
+    LintProblems => "Lint: {} problem(s), :diagnostics to list", "Denetim: {} sorun, listelemek için :diagnostics";
+    NoDiagnostics => "No diagnostics", "Tanı yok";
+    NoConflicts => "No merge conflicts", "Birleştirme çakışması yok";
+    ConflictPosition => "Conflict {} of {}", "Çakışma {} / {}";
+    NotInConflict => "The cursor is not inside a merge conflict", "İmleç bir birleştirme çakışmasının içinde değil";
+    MergeLocal => "Local", "Yerel";
+    MergeBase => "Base", "Taban";
+    MergeRemote => "Remote", "Uzak";
+    MergeOnly => "Not in merge mode (start with aon --merge)", "Birleştirme kipinde değil (aon --merge ile başlat)";
+    MergeNoBase => "No base version matches this conflict", "Bu çakışmaya uyan taban sürümü yok";
+    BinaryFile => "{} looks like a binary file", "{} ikili dosya gibi görünüyor";
+    BinaryTitle => " Binary file: open as ", " İkili dosya: şöyle aç ";
+    BinaryReadOnly => "Read-only text", "Salt okunur metin";
+    BinaryHex => "Hex view", "Onaltılık görünüm";
+    BinaryText => "Editable text (saved byte for byte)", "Düzenlenebilir metin (bayt bayt kaydedilir)";
+    DiagnosticsTitle => "Diagnostics", "Tanılar";
+    NoFilename => "Buffer has no file name", "Arabelleğin dosya adı yok";
+    MakeRunning => "{} (running)", "{} (çalışıyor)";
+    MakeDone => "{} (done)", "{} (bitti)";
+    MakeExited => "{} (exit {})", "{} (çıkış {})";
+    MakeSucceeded => "Build finished, {} location(s) found", "Derleme bitti, {} konum bulundu";
+    MakeFailed => "Build failed with exit {}, {} location(s), :diagnostics to list", "Derleme {} koduyla başarısız, {} konum, listelemek için :diagnostics";
+    NoOutput => "No build output yet, run :make", "Henüz derleme çıktısı yok, :make çalıştırın";
+    TerminalTitle => "terminal: {} (Ctrl+\\ switches focus)", "terminal: {} (Ctrl+\\ odağı değiştirir)";
+    TerminalExited => "Terminal closed: {} exited", "Terminal kapandı: {} sonlandı";
+    NoTerminal => "No terminal open, run :term", "Açık terminal yok, :term çalıştırın";
+    NoTasks => "No Makefile, Cargo.toml or package.json tasks found", "Makefile, Cargo.toml veya package.json görevi bulunamadı";
+    TasksTitle => "Tasks", "Görevler";
+    NoWordUnderCursor => "No identifier under the cursor", "İmlecin altında tanımlayıcı yok";
+    NoTagsFile => "No tags file found (run ctags -R)", "tags dosyası bulunamadı (ctags -R çalıştırın)";
+    TagNotFound => "Tag not found: {}", "Etiket bulunamadı: {}";
+    TagsTitle => "Tags: {}", "Etiketler: {}";
+    TagMoved => "Tag location not found, the file may have changed", "Etiket konumu bulunamadı, dosya değişmiş olabilir";
+    TagStackEmpty => "Tag stack is empty", "Etiket yığını boş";
+    OutlineTitle => "Outline", "Anahat";
+    NoLanguageServer => "No language server configured for '{}' (lsp.<filetype> in the config)", "'{}' için dil sunucusu yapılandırılmamış (yapılandırmada lsp.<dosyatürü>)";
+    Renaming => "Asking the language server to rename to {}...", "Dil sunucusundan {} olarak yeniden adlandırma isteniyor...";
+    RenameFailed => "Rename failed: {}", "Yeniden adlandırma başarısız: {}";
+    RenameSpansLines => "the language server sent edits across lines", "dil sunucusu birden çok satıra yayılan düzenlemeler gönderdi";
+    RenameOutdated => "Rename discarded: the buffer changed while waiting for the language server", "Yeniden adlandırma iptal edildi: dil sunucusu beklenirken arabellek değişti";
+    NothingToRename => "The language server returned no edits", "Dil sunucusu hiçbir düzenleme döndürmedi";
+    RenameTitle => " Rename: {} edits in {} files, Space toggles, Enter applies ", " Yeniden adlandır: {} düzenleme, {} dosya, Boşluk seçer, Enter uygular ";
+    NoFormatter => "No formatter configured for this filetype", "Bu dosya türü için biçimlendirici yapılandırılmamış";
+    ShellDone => "Shell command finished", "Kabuk komutu tamamlandı";
+    HelpHeading => "aon help", "aon yardım";
+    HelpKeys => "Keys (insert mode)", "Tuşlar (ekleme modu)";
+    HelpCommands => "Commands", "Komutlar";
+    HelpOptions => "Options", "Seçenekler";
+    HelpCharacter => "<character>", "<karakter>";
+    HelpInsertChar => "Insert at cursor", "İmlece ekle";
+    ActionCommandMode => "Enter command mode", "Komut moduna geç";
+    ActionCopy => "Copy line", "Satırı kopyala";
+    ActionPaste => "Paste", "Yapıştır";
+    ActionUndo => "Undo", "Geri al";
+    ActionNextBuffer => "Next buffer", "Sonraki tampon";
+    ActionPrevBuffer => "Previous buffer", "Önceki tampon";
+    ActionDeleteBack => "Delete character left of cursor", "Soldaki karakteri sil";
+    ActionNewline => "New line", "Yeni satır";
+    ActionMoveUp => "Up", "Yukarı";
+    ActionMoveDown => "Down", "Aşağı";
+    ActionMoveLeft => "Left", "Sola";
+    ActionMoveRight => "Right", "Sağa";
+    ActionLineStart => "Move to line start", "Satır başına git";
+    ActionLineEnd => "Move to line end", "Satır sonuna git";
+    ActionWordForward => "Move forward a word", "Bir kelime ileri git";
+    ActionWordBackward => "Move back a word", "Bir kelime geri git";
+    ActionKillLine => "Cut to end of line", "Satır sonuna kadar kes";
+    ActionYankPop => "Replace the last paste with an older entry", "Son yapıştırmayı daha eski bir kayıtla değiştir";
+    ActionPasteHistory => "Choose a paste from the history", "Geçmişten yapıştırılacak kaydı seç";
+    ActionBufferStart => "Go to the start of the buffer", "Tamponun başına git";
+    ActionBufferEnd => "Go to the end of the buffer", "Tamponun sonuna git";
+    ActionParagraphForward => "Move to the next paragraph", "Sonraki paragrafa git";
+    ActionParagraphBackward => "Move to the previous paragraph", "Önceki paragrafa git";
+    ActionBlockStart => "Move to the start of the indented block", "Girintili bloğun başına git";
+    ActionBlockEnd => "Move to the end of the indented block", "Girintili bloğun sonuna git";
+    ActionRecenter => "Center the view on the cursor, then top, then bottom", "Görünümü imlece ortala, sonra üste, sonra alta al";
+    ActionIncrement => "Increment the number at or after the cursor", "İmleçteki veya sonrasındaki sayıyı artır";
+    ActionNextDiagnostic => "Jump to the next diagnostic in this file", "Bu dosyadaki sonraki tanıya atla";
+    ActionPrevDiagnostic => "Jump to the previous diagnostic in this file", "Bu dosyadaki önceki tanıya atla";
+    ActionNextConflict => "Jump to the next merge conflict", "Sonraki birleştirme çakışmasına atla";
+    ActionPrevConflict => "Jump to the previous merge conflict", "Önceki birleştirme çakışmasına atla";
+    ActionOutputPageUp => "Scroll the output pane up", "Çıktı bölmesini yukarı kaydır";
+    ActionOutputPageDown => "Scroll the output pane down", "Çıktı bölmesini aşağı kaydır";
+    ActionRerunTask => "Run the last task again (or pick one)", "Son görevi yeniden çalıştır (veya birini seç)";
+    ActionJumpToTag => "Jump to the definition of the identifier under the cursor (tags file)", "İmleçteki tanımlayıcının tanımına atla (tags dosyası)";
+    ActionPopTag => "Return to where the last tag jump started", "Son etiket atlamasının başladığı yere dön";
+    ActionToggleOutline => "Show and focus the symbol outline, or hide it when focused", "Sembol anahatını göster ve odakla, odaktaysa gizle";
+    ActionToggleTerminal => "Move focus between the buffer and the terminal pane", "Odağı tampon ile terminal bölmesi arasında değiştir";
+    ActionReflow => "Rewrap the current paragraph to textwidth", "Geçerli paragrafı textwidth genişliğine yeniden sar";
+    ActionDecrement => "Decrement the number at or after the cursor", "İmleçteki veya sonrasındaki sayıyı azalt";
+    ActionSearch => "Search the buffer for a pattern", "Tamponda desen ara";
+    ActionSearchNext => "Jump to the next match of the last search", "Son aramanın sonraki eşleşmesine atla";
+    ActionSearchPrev => "Jump to the previous match of the last search", "Son aramanın önceki eşleşmesine atla";
+    ActionInsertLiteral => "Insert a character by code (u00e7, U0001f600, x41) or digraph (e', a:, Eu)", "Karakteri koduyla (u00e7, U0001f600, x41) veya iki harfli kısaltmayla (e', a:, Eu) ekle";
+    ActionRecordMacro => "Start or stop recording a macro", "Makro kaydını başlat ya da durdur";
+    ActionPlayMacro => "Play the last recorded macro", "Son kaydedilen makroyu oynat";
+    ActionToggleOverwrite => "Toggle overwrite mode", "Üzerine yazma modunu aç/kapat";
+    ActionTallerWindow => "Make the text window taller by shrinking the panes", "Panelleri küçülterek metin penceresini uzat";
+    ActionShorterWindow => "Make the text window shorter by growing the panes", "Panelleri büyüterek metin penceresini kısalt";
+    ActionWiderWindow => "Make the text window wider by narrowing the outline", "Anahatı daraltarak metin penceresini genişlet";
+    ActionNarrowerWindow => "Make the text window narrower by widening the outline", "Anahatı genişleterek metin penceresini daralt";
+    ActionQuit => "Quit without saving", "Kaydetmeden çık";
+    CmdWrite => "Save the buffer, optionally under a new name", "Tamponu kaydet, istenirse yeni bir adla";
+    CmdQuit => "Quit (asks about unsaved changes)", "Çık (kaydedilmemiş değişiklik varsa sorar)";
+    CmdWriteQuit => "Save and quit", "Kaydet ve çık";
+    CmdAbort => "Quit with an error code, without output", "Çıktı yazmadan hata koduyla çık";
+    CmdEdit => "Open a file in a new buffer", "Dosyayı yeni tamponda aç";
+    CmdRead => "Insert a file or command output below the cursor line", "Dosyayı veya komut çıktısını imleç satırının altına ekle";
+    CmdDelete => "Delete lines into a register", "Satırları yazmaca silerek al";
+    CmdSubstitute => "Replace pattern matches (c asks y/n/a/q/l for each)", "Desen eşleşmelerini değiştir (c her biri için y/n/a/q/l sorar)";
+    CmdGlobal => "Run a command on every matching line (g!/v: non-matching)", "Eşleşen her satırda komut çalıştır (g!/v: eşleşmeyenler)";
+    CmdReplaceAll => "Preview and replace in all open buffers", "Tüm açık tamponlarda önizleyip değiştir";
+    CmdReplaceProject => "Preview and replace in all project files", "Tüm proje dosyalarında önizleyip değiştir";
+    CmdSudoWrite => "Save the buffer with sudo", "Tamponu sudo ile kaydet";
+    CmdWriteAll => "Save all modified buffers", "Değişen tüm tamponları kaydet";
+    CmdAlign => "Line up a delimiter across lines (* for every occurrence; default: paragraph)", "Ayracı satırlar boyunca hizala (* tüm geçişler için; varsayılan: paragraf)";
+    CmdFormat => "Pipe the buffer through the filetype's formatter (format.<filetype> in the config)", "Arabelleği dosya türünün biçimlendiricisinden geçir (yapılandırmada format.<dosyatürü>)";
+    CmdLint => "Run the filetype's linter (lint.<filetype> in the config, % is the file)", "Dosya türünün denetleyicisini çalıştır (yapılandırmada lint.<dosyatürü>, % dosyadır)";
+    CmdDiagnostics => "List linter and build diagnostics and jump to one", "Denetleyici ve derleme tanılarını listele ve birine atla";
+    CmdMake => "Run the build (make.<filetype> or make in the config) into the output pane", "Derlemeyi çıktı bölmesinde çalıştır (yapılandırmada make.<dosyatürü> veya make)";
+    CmdOutput => "Show or hide the build output pane", "Derleme çıktı bölmesini göster veya gizle";
+    CmdTasks => "Pick a Makefile target, Cargo command or package.json script to run", "Çalıştırmak için Makefile hedefi, Cargo komutu veya package.json betiği seç";
+    CmdTask => "Run the last task again (or pick one)", "Son görevi yeniden çalıştır (veya birini seç)";
+    CmdTag => "Jump to a tag from the tags file (default: identifier under the cursor)", "tags dosyasındaki etikete atla (varsayılan: imleçteki tanımlayıcı)";
+    CmdPop => "Return to where the last tag jump started", "Son etiket atlamasının başladığı yere dön";
+    CmdOutline => "Show and focus the symbol outline (Enter jumps, Esc returns), or hide it", "Sembol anahatını göster ve odakla (Enter atlar, Esc döner) veya gizle";
+    CmdRename => "Rename the symbol under the cursor across the project via the language server", "İmleçteki sembolü dil sunucusuyla proje genelinde yeniden adlandır";
+    CmdResolve => "Keep our side, their side or both of the conflict at the cursor", "İmleçteki çakışmada bizim tarafı, onların tarafını ya da ikisini tut";
+    CmdPatch => "Apply a unified diff from a file (default: the current buffer), choosing hunks", "Bir dosyadaki (varsayılan: geçerli tampon) birleşik farkı parça seçerek uygula";
+    CmdPull => "Replace the conflict at the cursor with the local, base or remote version", "İmleçteki çakışmayı yerel, taban veya uzak sürümle değiştir";
+    CmdAscii => "Show the codepoint of the character under the cursor", "İmleçteki karakterin kod noktasını göster";
+    CmdTerm => "Open a terminal pane running the shell or a command", "Kabuğu veya bir komutu çalıştıran terminal bölmesi aç";
+    CmdReflow => "Rewrap lines to textwidth, keeping indentation and comment markers (default: paragraph)", "Satırları textwidth genişliğine yeniden sar, girinti ve yorum işaretlerini koru (varsayılan: paragraf)";
+    CmdSort => "Sort lines (! reverse, i ignore case, n numeric, u unique)", "Satırları sırala (! ters, i büyük/küçük harf yok say, n sayısal, u tekil)";
+    CmdFilter => "Run a shell command, or filter lines through it", "Kabuk komutu çalıştır veya satırları ondan geçir";
+    CmdBuffer => "Switch to buffer n", "n numaralı tampona geç";
+    CmdNextBuffer => "Next buffer", "Sonraki tampon";
+    CmdPrevBuffer => "Previous buffer", "Önceki tampon";
+    CmdDeleteBuffer => "Close the buffer", "Tamponu kapat";
+    CmdHex => "Toggle the hex view", "Hex görünümünü aç/kapat";
+    CmdCursor => "Change the cursor shape", "İmleç şeklini değiştir";
+    CmdSet => "Set, toggle (name!) or query (name?) options", "Seçenekleri ayarla, değiştir (ad!) veya sorgula (ad?)";
+    CmdCommand => "List or define user commands (w | q, !shell <args>)", "Kullanıcı komutlarını listele veya tanımla (w | q, !kabuk <args>)";
+    CmdSource => "Run the ex commands in a file, one per line", "Dosyadaki ex komutlarını satır satır çalıştır";
+    CmdMap => "Bind a key to an action", "Bir tuşu bir eyleme bağla";
+    CmdMark => "Set a global mark (A-Z) at the cursor", "İmleçte genel işaret (A-Z) koy";
+    CmdMarks => "List the global marks and jump to one", "Genel işaretleri listele ve birine atla";
+    CmdMacros => "List recorded and saved macros (* marks saved ones)", "Kaydedilen ve saklanan makroları listele (* saklananları gösterir)";
+    CmdMacroSave => "Save a recorded macro under a name for future sessions", "Kaydedilen makroyu sonraki oturumlar için bir adla sakla";
+    CmdMacroDelete => "Delete a macro", "Makroyu sil";
+    CmdPlayMacro => "Play a macro", "Makroyu oynat";
+    CmdRegisters => "Preview the registers and paste history, and paste one", "Yazmaçları ve yapıştırma geçmişini göster, birini yapıştır";
+    CmdYank => "Copy lines into a register (a-z, A-Z appends)", "Satırları yazmaca kopyala (a-z, A-Z ekler)";
+    CmdPut => "Paste from a register", "Yazmaçtan yapıştır";
+    CmdFiletype => "Show, set or re-detect the buffer filetype", "Tamponun dosya türünü göster, ayarla ya da yeniden algıla";
+    CmdPreview => "Show a rendered preview of the current Markdown buffer", "Geçerli Markdown tamponunun önizlemesini göster";
+    CmdCenterView => "Scroll so the cursor line is in the middle of the view", "İmleç satırı görünümün ortasında olacak şekilde kaydır";
+    CmdTopView => "Scroll so the cursor line is at the top of the view", "İmleç satırı görünümün üstünde olacak şekilde kaydır";
+    CmdBottomView => "Scroll so the cursor line is at the bottom of the view", "İmleç satırı görünümün altında olacak şekilde kaydır";
+    CmdCast => "Record the session to an asciicast file, or stop recording", "Oturumu asciicast dosyasına kaydet ya da kaydı durdur";
+    CmdKillRing => "Choose a paste from the history", "Geçmişten yapıştırılacak kaydı seç";
+    CmdMessages => "Show the message history", "Mesaj geçmişini göster";
+    CmdHelp => "Open this help screen", "Bu yardım ekranını aç";
+    KeyFileStart => "Go to the first line", "İlk satıra git";
+    KeyBlockStart => "Go to the start of the block", "Bloğun başına git";
+    KeyBlockEnd => "Go to the end of the block", "Bloğun sonuna git";
+    KeyPrevDiagnostic => "Previous diagnostic", "Önceki tanı";
+    KeyNextDiagnostic => "Next diagnostic", "Sonraki tanı";
+    KeyPrevConflict => "Previous conflict", "Önceki çakışma";
+    KeyNextConflict => "Next conflict", "Sonraki çakışma";
+    KeyRegister => "Use this register for the next command", "Sonraki komut için bu yazmacı kullan";
+    KeyAppendRegister => "Append to the register", "Yazmacın sonuna ekle";
+    KeyRecord => "Record a macro into the register", "Yazmaca makro kaydet";
+    KeyPlay => "Play the macro in the register", "Yazmaçtaki makroyu oynat";
+    KeyPlayLast => "Play the last macro again", "Son makroyu yeniden oynat";
+    KeyJumpMarkLine => "Jump to the line of a mark", "İşaretin satırına atla";
+    KeyJumpMark => "Jump to the position of a mark", "İşaretin konumuna atla";
+    KeyObjectWord => "Word", "Sözcük";
+    KeyObjectLine => "Line", "Satır";
+    KeyObjectTag => "Markup tag", "İşaretleme etiketi";
+    KeyObjectPair => "Brackets or quotes", "Ayraçlar veya tırnaklar";
+    KeyLines => "Whole lines", "Tüm satırlar";
+    KeyMotionChar => "Characters or lines around the cursor", "İmlecin çevresindeki karakterler veya satırlar";
+    KeyMotionWord => "Words", "Sözcükler";
+    KeyMotionLine => "To the start or end of the line", "Satırın başına veya sonuna kadar";
+    KeyMotionParagraph => "Paragraphs", "Paragraflar";
+    KeyMotionFile => "To the start or end of the file", "Dosyanın başına veya sonuna kadar";
+    KeyObject => "Inside or around a text object", "Metin nesnesinin içi veya çevresi";
+    KeySurround => "Surroundings (brackets, quotes)", "Çevreleyenler (ayraçlar, tırnaklar)";
+}
+
+pub fn tr(text: Text) -> &'static str {
+    text.lookup(locale())
+}
+
+pub fn trf(text: Text, args: &[&dyn std::fmt::Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut parts = tr(text).split("{}").peekable();
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some() {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string());
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,84 @@
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+
+pub struct Jobs<T> {
+    sender: Sender<T>,
+    receiver: Receiver<T>,
+    running: usize,
+}
+
+impl<T: Send + 'static> Jobs<T> {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver,
+            running: 0,
+        }
+    }
+
+    pub fn spawn(&mut self, work: impl FnOnce() -> T + Send + 'static) {
+        let sender = self.sender.clone();
+        self.running += 1;
+        thread::spawn(move || {
+            let _ = sender.send(work());
+        });
+    }
+
+    pub fn running(&self) -> usize {
+        self.running
+    }
+
+    pub fn try_recv(&mut self) -> Option<T> {
+        let result = self.receiver.try_recv().ok()?;
+        self.running -= 1;
+        Some(result)
+    }
+}
+
+pub struct Stream {
+    child: Child,
+    receiver: Receiver<String>,
+}
+
+impl Stream {
+    pub fn spawn(mut command: Command) -> io::Result<Self> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let (sender, receiver) = mpsc::channel();
+        let stdout = child.stdout.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>);
+        let stderr = child.stderr.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>);
+        for pipe in [stdout, stderr].into_iter().flatten() {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for line in BufReader::new(pipe).lines().map_while(io::Result::ok) {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        Ok(Self { child, receiver })
+    }
+
+    pub fn poll(&mut self) -> (Vec<String>, Option<io::Result<ExitStatus>>) {
+        let mut lines = Vec::new();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(line) => lines.push(line),
+                Err(TryRecvError::Empty) => return (lines, None),
+                Err(TryRecvError::Disconnected) => return (lines, Some(self.child.wait())),
+            }
+        }
+    }
+
+    pub fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
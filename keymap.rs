@@ -0,0 +1,539 @@
+use crate::i18n::{tr, Text};
+use crossterm::event::{KeyCode, KeyModifiers};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+    CommandMode,
+    Copy,
+    Paste,
+    Undo,
+    NextBuffer,
+    PrevBuffer,
+    DeleteBack,
+    Newline,
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    LineStart,
+    LineEnd,
+    BufferStart,
+    BufferEnd,
+    ParagraphForward,
+    ParagraphBackward,
+    BlockStart,
+    BlockEnd,
+    WordForward,
+    WordBackward,
+    KillLine,
+    YankPop,
+    PasteHistory,
+    ToggleOverwrite,
+    Recenter,
+    Increment,
+    Decrement,
+    InsertLiteral,
+    RecordMacro,
+    PlayMacro,
+    Search,
+    SearchNext,
+    SearchPrev,
+    Reflow,
+    NextDiagnostic,
+    PrevDiagnostic,
+    NextConflict,
+    PrevConflict,
+    OutputPageUp,
+    OutputPageDown,
+    ToggleTerminal,
+    RerunTask,
+    JumpToTag,
+    PopTag,
+    ToggleOutline,
+    TallerWindow,
+    ShorterWindow,
+    WiderWindow,
+    NarrowerWindow,
+    Quit,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::CommandMode,
+        Action::Copy,
+        Action::Paste,
+        Action::Undo,
+        Action::NextBuffer,
+        Action::PrevBuffer,
+        Action::DeleteBack,
+        Action::Newline,
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::LineStart,
+        Action::LineEnd,
+        Action::BufferStart,
+        Action::BufferEnd,
+        Action::ParagraphForward,
+        Action::ParagraphBackward,
+        Action::BlockStart,
+        Action::BlockEnd,
+        Action::WordForward,
+        Action::WordBackward,
+        Action::KillLine,
+        Action::YankPop,
+        Action::PasteHistory,
+        Action::ToggleOverwrite,
+        Action::Recenter,
+        Action::Increment,
+        Action::Decrement,
+        Action::InsertLiteral,
+        Action::RecordMacro,
+        Action::PlayMacro,
+        Action::Search,
+        Action::SearchNext,
+        Action::SearchPrev,
+        Action::Reflow,
+        Action::NextDiagnostic,
+        Action::PrevDiagnostic,
+        Action::NextConflict,
+        Action::PrevConflict,
+        Action::OutputPageUp,
+        Action::OutputPageDown,
+        Action::ToggleTerminal,
+        Action::RerunTask,
+        Action::JumpToTag,
+        Action::PopTag,
+        Action::ToggleOutline,
+        Action::TallerWindow,
+        Action::ShorterWindow,
+        Action::WiderWindow,
+        Action::NarrowerWindow,
+        Action::Quit,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|action| action.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::CommandMode => "command-mode",
+            Action::Copy => "copy",
+            Action::Paste => "paste",
+            Action::Undo => "undo",
+            Action::NextBuffer => "next-buffer",
+            Action::PrevBuffer => "prev-buffer",
+            Action::DeleteBack => "delete-back",
+            Action::Newline => "newline",
+            Action::MoveUp => "move-up",
+            Action::MoveDown => "move-down",
+            Action::MoveLeft => "move-left",
+            Action::MoveRight => "move-right",
+            Action::LineStart => "line-start",
+            Action::LineEnd => "line-end",
+            Action::BufferStart => "buffer-start",
+            Action::BufferEnd => "buffer-end",
+            Action::ParagraphForward => "paragraph-forward",
+            Action::ParagraphBackward => "paragraph-backward",
+            Action::BlockStart => "block-start",
+            Action::BlockEnd => "block-end",
+            Action::WordForward => "word-forward",
+            Action::WordBackward => "word-backward",
+            Action::KillLine => "kill-line",
+            Action::YankPop => "yank-pop",
+            Action::PasteHistory => "paste-history",
+            Action::ToggleOverwrite => "toggle-overwrite",
+            Action::Recenter => "recenter",
+            Action::Increment => "increment",
+            Action::Decrement => "decrement",
+            Action::InsertLiteral => "insert-literal",
+            Action::RecordMacro => "record-macro",
+            Action::PlayMacro => "play-macro",
+            Action::Search => "search",
+            Action::SearchNext => "search-next",
+            Action::SearchPrev => "search-prev",
+            Action::Reflow => "reflow",
+            Action::NextDiagnostic => "next-diagnostic",
+            Action::PrevDiagnostic => "prev-diagnostic",
+            Action::NextConflict => "next-conflict",
+            Action::PrevConflict => "prev-conflict",
+            Action::OutputPageUp => "output-page-up",
+            Action::OutputPageDown => "output-page-down",
+            Action::ToggleTerminal => "toggle-terminal",
+            Action::RerunTask => "rerun-task",
+            Action::JumpToTag => "jump-to-tag",
+            Action::PopTag => "pop-tag",
+            Action::ToggleOutline => "toggle-outline",
+            Action::TallerWindow => "taller-window",
+            Action::ShorterWindow => "shorter-window",
+            Action::WiderWindow => "wider-window",
+            Action::NarrowerWindow => "narrower-window",
+            Action::Quit => "quit",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        tr(match self {
+            Action::CommandMode => Text::ActionCommandMode,
+            Action::Copy => Text::ActionCopy,
+            Action::Paste => Text::ActionPaste,
+            Action::Undo => Text::ActionUndo,
+            Action::NextBuffer => Text::ActionNextBuffer,
+            Action::PrevBuffer => Text::ActionPrevBuffer,
+            Action::DeleteBack => Text::ActionDeleteBack,
+            Action::Newline => Text::ActionNewline,
+            Action::MoveUp => Text::ActionMoveUp,
+            Action::MoveDown => Text::ActionMoveDown,
+            Action::MoveLeft => Text::ActionMoveLeft,
+            Action::MoveRight => Text::ActionMoveRight,
+            Action::LineStart => Text::ActionLineStart,
+            Action::LineEnd => Text::ActionLineEnd,
+            Action::BufferStart => Text::ActionBufferStart,
+            Action::BufferEnd => Text::ActionBufferEnd,
+            Action::ParagraphForward => Text::ActionParagraphForward,
+            Action::ParagraphBackward => Text::ActionParagraphBackward,
+            Action::BlockStart => Text::ActionBlockStart,
+            Action::BlockEnd => Text::ActionBlockEnd,
+            Action::WordForward => Text::ActionWordForward,
+            Action::WordBackward => Text::ActionWordBackward,
+            Action::KillLine => Text::ActionKillLine,
+            Action::YankPop => Text::ActionYankPop,
+            Action::PasteHistory => Text::ActionPasteHistory,
+            Action::ToggleOverwrite => Text::ActionToggleOverwrite,
+            Action::Recenter => Text::ActionRecenter,
+            Action::Increment => Text::ActionIncrement,
+            Action::Decrement => Text::ActionDecrement,
+            Action::InsertLiteral => Text::ActionInsertLiteral,
+            Action::RecordMacro => Text::ActionRecordMacro,
+            Action::PlayMacro => Text::ActionPlayMacro,
+            Action::Search => Text::ActionSearch,
+            Action::SearchNext => Text::ActionSearchNext,
+            Action::SearchPrev => Text::ActionSearchPrev,
+            Action::Reflow => Text::ActionReflow,
+            Action::NextDiagnostic => Text::ActionNextDiagnostic,
+            Action::PrevDiagnostic => Text::ActionPrevDiagnostic,
+            Action::NextConflict => Text::ActionNextConflict,
+            Action::PrevConflict => Text::ActionPrevConflict,
+            Action::OutputPageUp => Text::ActionOutputPageUp,
+            Action::OutputPageDown => Text::ActionOutputPageDown,
+            Action::ToggleTerminal => Text::ActionToggleTerminal,
+            Action::RerunTask => Text::ActionRerunTask,
+            Action::JumpToTag => Text::ActionJumpToTag,
+            Action::PopTag => Text::ActionPopTag,
+            Action::ToggleOutline => Text::ActionToggleOutline,
+            Action::TallerWindow => Text::ActionTallerWindow,
+            Action::ShorterWindow => Text::ActionShorterWindow,
+            Action::WiderWindow => Text::ActionWiderWindow,
+            Action::NarrowerWindow => Text::ActionNarrowerWindow,
+            Action::Quit => Text::ActionQuit,
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers = match code {
+            KeyCode::Char(_) => modifiers.difference(KeyModifiers::SHIFT),
+            _ => modifiers,
+        };
+        Self { code, modifiers }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = name;
+        loop {
+            let (prefix, modifier) = match rest.split_once('+') {
+                Some((prefix, tail)) if !tail.is_empty() => (prefix, tail),
+                _ => break,
+            };
+            modifiers |= match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" | "c" => KeyModifiers::CONTROL,
+                "alt" | "m" => KeyModifiers::ALT,
+                "shift" | "s" => KeyModifiers::SHIFT,
+                _ => return None,
+            };
+            rest = modifier;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::CONTROL) => KeyCode::Char(c.to_ascii_lowercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+            },
+        };
+        Some(Self::new(code, modifiers))
+    }
+
+    /// Parses a space-separated key sequence such as `g g` or
+    /// `Ctrl+K Ctrl+S`. `<leader>` stands for `leader`, and what follows it
+    /// in the same word is one key name or else one key per character, so
+    /// `<leader>ff` is the leader then `f` twice.
+    pub fn parse_sequence(text: &str, leader: Key) -> Option<Vec<Self>> {
+        let mut keys = Vec::new();
+        for word in text.split_whitespace() {
+            let rest = match word.get(..8) {
+                Some(prefix) if prefix.eq_ignore_ascii_case("<leader>") => {
+                    keys.push(leader);
+                    &word[8..]
+                }
+                _ => word,
+            };
+            if rest.is_empty() {
+                continue;
+            }
+            match Self::parse(rest) {
+                Some(key) => keys.push(key),
+                None if rest.len() < word.len() => {
+                    keys.extend(rest.chars().map(|c| Self::new(KeyCode::Char(c), KeyModifiers::NONE)))
+                }
+                None => return None,
+            }
+        }
+        Some(keys).filter(|keys| !keys.is_empty())
+    }
+
+    pub fn sequence_name(keys: &[Self]) -> String {
+        keys.iter().map(Self::name).collect::<Vec<_>>().join(" ")
+    }
+
+    /// What a terminal without the keyboard enhancement protocol sends for
+    /// keys that only the protocol tells apart, such as Ctrl+I or Shift+Enter.
+    pub fn legacy(&self) -> Option<Self> {
+        let ctrl = self.modifiers == KeyModifiers::CONTROL;
+        let alt = self.modifiers.intersection(KeyModifiers::ALT);
+        match self.code {
+            KeyCode::Char('i') if ctrl => Some(Self::new(KeyCode::Tab, KeyModifiers::NONE)),
+            KeyCode::Char('m') if ctrl => Some(Self::new(KeyCode::Enter, KeyModifiers::NONE)),
+            KeyCode::Char('[') if ctrl => Some(Self::new(KeyCode::Esc, KeyModifiers::NONE)),
+            KeyCode::Tab if self.modifiers == KeyModifiers::SHIFT => Some(Self::new(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            KeyCode::Tab | KeyCode::Enter | KeyCode::Backspace | KeyCode::Esc if self.modifiers != alt => {
+                Some(Self::new(self.code, alt))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            name.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            name.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            name.push_str("Shift+");
+        }
+        match self.code {
+            KeyCode::Char(' ') => name.push_str("Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                name.push(c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => name.push(c),
+            KeyCode::F(n) => name.push_str(&format!("F{}", n)),
+            code => name.push_str(&format!("{:?}", code)),
+        }
+        name
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Binding {
+    pub keys: Vec<Key>,
+    pub action: Action,
+}
+
+/// What a sequence of keys means so far.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Resolved {
+    Unbound,
+    /// Longer bindings start with these keys; the action is what the keys
+    /// run on their own if no more keys follow.
+    Pending(Option<Action>),
+    Bound(Action),
+}
+
+pub struct Keymap {
+    bindings: Vec<Binding>,
+    overrides: Vec<(Vec<Key>, Option<Action>)>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        let mut keymap = Self {
+            bindings: Vec::new(),
+            overrides: Vec::new(),
+        };
+        keymap.set_preset("default");
+        keymap
+    }
+
+    pub fn set_preset(&mut self, preset: &str) {
+        let ctrl = KeyModifiers::CONTROL;
+        let alt = KeyModifiers::ALT;
+        let none = KeyModifiers::NONE;
+        let mut bindings = vec![
+            (KeyCode::Char(':'), none, Action::CommandMode),
+            (KeyCode::PageDown, ctrl, Action::NextBuffer),
+            (KeyCode::PageUp, ctrl, Action::PrevBuffer),
+            (KeyCode::Char('c'), ctrl, Action::Copy),
+            (KeyCode::Char('v'), ctrl, Action::Paste),
+            (KeyCode::Char('z'), ctrl, Action::Undo),
+            (KeyCode::Backspace, none, Action::DeleteBack),
+            (KeyCode::Enter, none, Action::Newline),
+            (KeyCode::Up, none, Action::MoveUp),
+            (KeyCode::Down, none, Action::MoveDown),
+            (KeyCode::Left, none, Action::MoveLeft),
+            (KeyCode::Right, none, Action::MoveRight),
+            (KeyCode::Char('y'), alt, Action::YankPop),
+            (KeyCode::Char('v'), ctrl | alt, Action::PasteHistory),
+            (KeyCode::Home, none, Action::LineStart),
+            (KeyCode::End, none, Action::LineEnd),
+            (KeyCode::Home, ctrl, Action::BufferStart),
+            (KeyCode::End, ctrl, Action::BufferEnd),
+            (KeyCode::Down, ctrl, Action::ParagraphForward),
+            (KeyCode::Up, ctrl, Action::ParagraphBackward),
+            (KeyCode::Up, ctrl | alt, Action::BlockStart),
+            (KeyCode::Down, ctrl | alt, Action::BlockEnd),
+            (KeyCode::Insert, none, Action::ToggleOverwrite),
+            (KeyCode::Char('q'), ctrl, Action::InsertLiteral),
+            (KeyCode::F(3), none, Action::SearchNext),
+            (KeyCode::F(3), KeyModifiers::SHIFT, Action::SearchPrev),
+            (KeyCode::F(8), none, Action::NextDiagnostic),
+            (KeyCode::F(8), KeyModifiers::SHIFT, Action::PrevDiagnostic),
+            (KeyCode::F(7), none, Action::NextConflict),
+            (KeyCode::F(7), KeyModifiers::SHIFT, Action::PrevConflict),
+            (KeyCode::PageUp, alt, Action::OutputPageUp),
+            (KeyCode::PageDown, alt, Action::OutputPageDown),
+            (KeyCode::Char('\\'), ctrl, Action::ToggleTerminal),
+            (KeyCode::Char('4'), ctrl, Action::ToggleTerminal),
+            (KeyCode::F(5), none, Action::RerunTask),
+            (KeyCode::F(6), none, Action::RecordMacro),
+            (KeyCode::F(6), KeyModifiers::SHIFT, Action::PlayMacro),
+            (KeyCode::F(12), none, Action::JumpToTag),
+            (KeyCode::F(12), KeyModifiers::SHIFT, Action::PopTag),
+            (KeyCode::F(9), none, Action::ToggleOutline),
+            (KeyCode::Esc, none, Action::Quit),
+        ];
+        if preset == "emacs" {
+            bindings.extend([
+                (KeyCode::Char('a'), ctrl, Action::LineStart),
+                (KeyCode::Char('e'), ctrl, Action::LineEnd),
+                (KeyCode::Char('k'), ctrl, Action::KillLine),
+                (KeyCode::Char('y'), ctrl, Action::Paste),
+                (KeyCode::Char('f'), alt, Action::WordForward),
+                (KeyCode::Char('b'), alt, Action::WordBackward),
+                (KeyCode::Char('l'), ctrl, Action::Recenter),
+                (KeyCode::Char('<'), alt, Action::BufferStart),
+                (KeyCode::Char('>'), alt, Action::BufferEnd),
+                (KeyCode::Char('}'), alt, Action::ParagraphForward),
+                (KeyCode::Char('{'), alt, Action::ParagraphBackward),
+                (KeyCode::Char('q'), alt, Action::Reflow),
+                (KeyCode::Char('s'), ctrl, Action::Search),
+                (KeyCode::Char('.'), alt, Action::JumpToTag),
+                (KeyCode::Char(','), alt, Action::PopTag),
+            ]);
+        } else {
+            bindings.extend([
+                (KeyCode::Char('a'), ctrl, Action::Increment),
+                (KeyCode::Char('x'), ctrl, Action::Decrement),
+                (KeyCode::Char('f'), ctrl, Action::Search),
+            ]);
+        }
+        if preset == "vim" {
+            bindings.push((KeyCode::Char('k'), ctrl, Action::InsertLiteral));
+        }
+        self.bindings = bindings
+            .into_iter()
+            .map(|(code, modifiers, action)| Binding {
+                keys: vec![Key::new(code, modifiers)],
+                action,
+            })
+            .collect();
+        let window = Key::new(KeyCode::Char('w'), ctrl);
+        for (c, action) in [
+            ('+', Action::TallerWindow),
+            ('-', Action::ShorterWindow),
+            ('>', Action::WiderWindow),
+            ('<', Action::NarrowerWindow),
+        ] {
+            self.bindings.push(Binding {
+                keys: vec![window, Key::new(KeyCode::Char(c), none)],
+                action,
+            });
+        }
+        for (keys, action) in self.overrides.clone() {
+            self.apply(keys, action);
+        }
+    }
+
+    pub fn bind(&mut self, keys: Vec<Key>, action: Option<Action>) {
+        self.overrides.retain(|(k, _)| *k != keys);
+        self.overrides.push((keys.clone(), action));
+        self.apply(keys, action);
+    }
+
+    fn apply(&mut self, keys: Vec<Key>, action: Option<Action>) {
+        self.bindings.retain(|b| b.keys != keys);
+        if let Some(action) = action {
+            self.bindings.push(Binding { keys, action });
+        }
+    }
+
+    pub fn lookup(&self, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        let key = Key::new(code, modifiers);
+        self.bindings.iter().find(|b| b.keys == [key]).map(|b| b.action)
+    }
+
+    pub fn resolve(&self, keys: &[Key]) -> Resolved {
+        let exact = self.bindings.iter().find(|b| b.keys == keys).map(|b| b.action);
+        let longer = self.bindings.iter().any(|b| b.keys.len() > keys.len() && b.keys.starts_with(keys));
+        match (exact, longer) {
+            (exact, true) => Resolved::Pending(exact),
+            (Some(action), false) => Resolved::Bound(action),
+            (None, false) => Resolved::Unbound,
+        }
+    }
+
+    /// The bindings that continue `prefix`, with the remaining keys.
+    pub fn continuations(&self, prefix: &[Key]) -> Vec<(String, Action)> {
+        let mut continuations: Vec<(String, Action)> = self
+            .bindings
+            .iter()
+            .filter(|b| b.keys.len() > prefix.len() && b.keys.starts_with(prefix))
+            .map(|b| (Key::sequence_name(&b.keys[prefix.len()..]), b.action))
+            .collect();
+        continuations.sort_by(|a, b| a.0.cmp(&b.0));
+        continuations
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+}
@@ -0,0 +1,97 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+
+pub const LAZY_THRESHOLD: u64 = 64 * 1024 * 1024;
+const CHUNK: usize = 1024 * 1024;
+
+pub struct LazyFile {
+    file: File,
+    len: u64,
+    offsets: Vec<u64>,
+    scanned: u64,
+    pub top: usize,
+}
+
+impl LazyFile {
+    pub fn open(path: &str) -> io::Result<Option<Self>> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        if len < LAZY_THRESHOLD {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            file,
+            len,
+            offsets: vec![0],
+            scanned: 0,
+            top: 0,
+        }))
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.scanned >= self.len
+    }
+
+    pub fn indexed_lines(&self) -> usize {
+        if self.is_indexed() && self.offsets.last() == Some(&self.len) && self.offsets.len() > 1 {
+            self.offsets.len() - 1
+        } else {
+            self.offsets.len()
+        }
+    }
+
+    pub fn index_to(&mut self, line: usize) -> io::Result<()> {
+        let mut chunk = vec![0u8; CHUNK];
+        while self.offsets.len() <= line + 1 && !self.is_indexed() {
+            self.file.seek(SeekFrom::Start(self.scanned))?;
+            let n = self.file.read(&mut chunk)?;
+            if n == 0 {
+                self.scanned = self.len;
+                break;
+            }
+            for (i, &b) in chunk[..n].iter().enumerate() {
+                if b == b'\n' {
+                    self.offsets.push(self.scanned + i as u64 + 1);
+                }
+            }
+            self.scanned += n as u64;
+        }
+        Ok(())
+    }
+
+    pub fn index_all(&mut self) -> io::Result<()> {
+        self.index_to(usize::MAX - 1)
+    }
+
+    pub fn line(&mut self, n: usize) -> io::Result<Option<String>> {
+        self.index_to(n)?;
+        if n >= self.indexed_lines() {
+            return Ok(None);
+        }
+        let start = self.offsets[n];
+        let end = self.offsets.get(n + 1).copied().unwrap_or(self.len);
+        let mut bytes = vec![0u8; (end - start) as usize];
+        self.file.seek(SeekFrom::Start(start))?;
+        self.file.read_exact(&mut bytes)?;
+        while matches!(bytes.last(), Some(b'\n') | Some(b'\r')) {
+            bytes.pop();
+        }
+        Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    pub fn read_all(&mut self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.len as usize);
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn follow(&mut self, line: usize, height: usize) {
+        let height = height.max(1);
+        if line < self.top {
+            self.top = line;
+        } else if line >= self.top + height {
+            self.top = line + 1 - height;
+        }
+    }
+}
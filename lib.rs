@@ -0,0 +1,5518 @@
+use crossterm::{
+    event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
+    style::Color,
+};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read};
+use std::io::Result;
+use std::time::{Duration, Instant};
+
+mod browser;
+pub mod cli;
+mod command;
+mod complete;
+mod compose;
+mod conflict;
+mod cast;
+pub mod config;
+mod crypt;
+mod diagnostic;
+mod digraph;
+mod error;
+pub mod filetype;
+mod float;
+mod gzip;
+mod hex;
+pub mod i18n;
+mod jobs;
+mod json;
+mod keymap;
+mod lazy;
+mod lsp;
+mod macros;
+mod markdown;
+mod merge;
+mod message;
+mod meta;
+mod motion;
+mod options;
+mod osc52;
+mod outline;
+mod output;
+mod patch;
+mod pattern;
+mod picker;
+mod profile;
+mod prompt;
+mod pty;
+mod range;
+mod registers;
+mod remote;
+mod replace;
+pub mod replay;
+mod save;
+mod session;
+mod statusline;
+pub mod screen;
+mod shell;
+mod tags;
+mod tasks;
+mod term;
+mod text;
+mod timer;
+mod unicode;
+mod utf8;
+mod vim;
+
+use cast::Cast;
+use command::COMMANDS;
+use config::Config;
+use crypt::Cipher;
+use diagnostic::Diagnostic;
+use error::Error;
+use filetype::FileType;
+use float::{Dismiss, Frame, Layer, Overlay, Panel, Placement};
+use hex::HexView;
+use i18n::{tr, trf, Text};
+use jobs::{Jobs, Stream};
+use keymap::{Action, Key, Keymap, Resolved};
+use lazy::LazyFile;
+use macros::Macros;
+use merge::Merge;
+use message::{Messages, Severity};
+use motion::Motion;
+use options::{Options, OPTIONS};
+use outline::{Outline, Symbol};
+use output::OutputPane;
+use pattern::Regex;
+use picker::{Picker, PickerEvent};
+use prompt::{Prompt, PromptEvent};
+use profile::Profile;
+use pty::Pty;
+use range::LineRange;
+use registers::{Register, Registers};
+use remote::Url;
+use replace::Replacement;
+use save::{Contents, Save};
+use session::Session;
+use text::Newlines;
+use screen::{CursorShape, Screen, Style};
+use term::Terminal;
+use timer::{Timer, Timers};
+
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+const JOB_POLL: Duration = Duration::from_millis(50);
+const ASYNC_SAVE_BYTES: usize = 1024 * 1024;
+const TINT: Color = Color::AnsiValue(236);
+const OVERLENGTH: Color = Color::AnsiValue(52);
+const CURRENT_MATCH: Color = Color::AnsiValue(94);
+const SELECTION: Color = Color::AnsiValue(239);
+const MAX_MATCH_COUNT: usize = 9999;
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(300);
+const BLINK_INTERVAL: Duration = Duration::from_millis(530);
+const BELL_DURATION: Duration = Duration::from_millis(150);
+const MAX_MACRO_DEPTH: usize = 20;
+const SHOWCMD_WIDTH: usize = 10;
+const MULTI_CLICK: Duration = Duration::from_millis(400);
+const MIN_PANE_ROWS: usize = 2;
+const MIN_TEXT_ROWS: usize = 2;
+const MIN_SIDEBAR_COLUMNS: u16 = 10;
+const MIN_TEXT_COLUMNS: u16 = 20;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Mode {
+    Normal,
+    Insert,
+    Command,
+}
+
+/// What an answer to the open prompt is for.
+#[derive(Clone, PartialEq)]
+enum PromptKind {
+    SaveOnQuit,
+    SaveAs { quit: bool },
+    Unlock,
+    Sudo,
+    Search { forward: bool },
+    Browse(browser::Op),
+}
+
+enum PickerKind {
+    KillRing,
+    Replace,
+    Patch,
+    Binary,
+    Diagnostics,
+    Tasks(Vec<String>),
+    Tags(Vec<tags::Tag>),
+    Registers(Vec<registers::Source>),
+    Marks(Vec<char>),
+    Filetypes,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Layout {
+    width: u16,
+    height: u16,
+    split: usize,
+    most_split: usize,
+    panes: usize,
+    panes_top: u16,
+    text_top: u16,
+    text_rows: u16,
+    sidebar: u16,
+    most_sidebar: u16,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Separator {
+    Panes,
+    Sidebar,
+}
+
+struct Confirmation {
+    regex: Regex,
+    replacement: String,
+    global: bool,
+    last_line: usize,
+    at: Position,
+    current: Option<pattern::Match>,
+    before: EditorState,
+    replaced: usize,
+    lines: Vec<usize>,
+}
+
+#[derive(Clone, PartialEq)]
+enum ReplaceTarget {
+    Buffer(usize),
+    File(String),
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+struct Position {
+    x: usize,
+    y: usize,
+}
+
+#[derive(Clone)]
+struct EditorState {
+    buffer: Vec<String>,
+    cursor: Position,
+    filename: Option<String>,
+    dirty: bool,
+}
+
+impl EditorState {
+    fn memory(&self) -> usize {
+        let lines = self.buffer.capacity() * std::mem::size_of::<String>();
+        std::mem::size_of::<Self>() + lines + self.buffer.iter().map(String::capacity).sum::<usize>()
+    }
+
+    fn name(&self, label: Option<&str>) -> String {
+        match (label, &self.filename) {
+            (Some(label), _) => label.to_string(),
+            (None, Some(name)) => std::path::Path::new(name)
+                .file_name()
+                .map_or(name.clone(), |n| n.to_string_lossy().into_owned()),
+            (None, None) => tr(Text::Untitled).to_string(),
+        }
+    }
+
+    fn title(&self, label: Option<&str>) -> String {
+        let name = self.name(label);
+        if self.dirty {
+            format!("{} +", name)
+        } else {
+            name
+        }
+    }
+}
+
+impl Default for EditorState {
+    fn default() -> Self {
+        Self {
+            buffer: vec![String::new()],
+            cursor: Position::default(),
+            filename: None,
+            dirty: false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Document {
+    state: EditorState,
+    undo_stack: Vec<EditorState>,
+    redo_stack: Vec<EditorState>,
+    hex: Option<HexView>,
+    lazy: Option<LazyFile>,
+    newlines: Newlines,
+    scroll: usize,
+    label: Option<String>,
+    readonly: bool,
+    filetype: Option<&'static FileType>,
+    remote: Option<(Url, remote::State)>,
+    crypt: Option<(Cipher, Option<String>)>,
+    metadata: Option<meta::Metadata>,
+    browser: Option<std::path::PathBuf>,
+}
+
+impl Document {
+    fn open(filename: Option<String>) -> (Self, Option<Error>) {
+        if let Some(dir) = filename.as_deref().filter(|file| std::path::Path::new(file).is_dir()) {
+            return (Self::browse(dir.as_ref()), None);
+        }
+        let remote = filename.as_deref().and_then(Url::parse);
+        let cipher = filename.as_deref().and_then(Cipher::detect);
+        let local = filename.as_deref().filter(|file| *file != "-" && remote.is_none());
+        let metadata = local.and_then(|file| meta::capture(file.as_ref()));
+        if remote.is_some() || cipher.is_some() {
+            let document = Self {
+                filetype: filetype::detect(filename.as_deref(), &[]),
+                state: EditorState {
+                    filename,
+                    ..EditorState::default()
+                },
+                readonly: true,
+                crypt: cipher.filter(|_| remote.is_none()).map(|cipher| (cipher, None)),
+                remote: remote.map(|url| (url, remote::State::Queued)),
+                metadata,
+                ..Self::default()
+            };
+            return (document, None);
+        }
+        let compressed = filename.as_deref().is_some_and(gzip::is_compressed);
+        let lazy = match filename.as_deref() {
+            Some(file) if file != "-" && !compressed => LazyFile::open(file).ok().flatten(),
+            _ => None,
+        };
+        let name = filename.clone().unwrap_or_default();
+        let mut error = None;
+        let mut bytes = match lazy {
+            Some(_) => Vec::new(),
+            None => Self::load_file(&filename).unwrap_or_else(|err| {
+                error = Some(Error::read(&name, err));
+                Vec::new()
+            }),
+        };
+        if compressed && !bytes.is_empty() {
+            match gzip::decompress(&bytes) {
+                Ok(plain) => bytes = plain,
+                Err(err) => error = Some(Error::read(&name, err)),
+            }
+        }
+        let readonly = error.is_some();
+        let filename = filename.filter(|f| f != "-");
+        let (buffer, newlines, hex) = Self::decode(bytes);
+        let filetype = if hex.is_some() { None } else { filetype::detect(filename.as_deref(), &buffer) };
+        let document = Self {
+            filetype,
+            state: EditorState {
+                buffer,
+                filename,
+                ..EditorState::default()
+            },
+            hex,
+            lazy,
+            newlines,
+            readonly,
+            metadata,
+            ..Self::default()
+        };
+        (document, error)
+    }
+
+    fn browse(dir: &std::path::Path) -> Self {
+        let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+        let buffer = browser::list(&dir).unwrap_or_else(|_| vec![browser::PARENT.to_string()]);
+        Self {
+            state: EditorState {
+                buffer,
+                ..EditorState::default()
+            },
+            label: Some(format!("{}/", dir.display()).replace("//", "/")),
+            readonly: true,
+            browser: Some(dir),
+            ..Self::default()
+        }
+    }
+
+    fn load_file(filename: &Option<String>) -> Result<Vec<u8>> {
+        match filename.as_deref() {
+            Some("-") => {
+                let mut bytes = Vec::new();
+                io::stdin().read_to_end(&mut bytes).map(|_| bytes)
+            }
+            Some(file) => match fs::read(file) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+                result => result,
+            },
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn decode(bytes: Vec<u8>) -> (Vec<String>, Newlines, Option<HexView>) {
+        if HexView::is_binary(&bytes) {
+            (vec![String::new()], Newlines::default(), Some(HexView::new(bytes)))
+        } else {
+            let (buffer, newlines) = Newlines::split(&utf8::decode(&bytes));
+            (buffer, newlines, None)
+        }
+    }
+
+    fn split_lines(text: &str) -> Vec<String> {
+        Newlines::split(text).0
+    }
+}
+
+pub struct Editor {
+    state: EditorState,
+    mode: Mode,
+    overwrite: bool,
+    command: String,
+    undo_stack: Vec<EditorState>,
+    redo_stack: Vec<EditorState>,
+    prompt: Option<(Prompt, PromptKind)>,
+    registers: Registers,
+    register: Option<char>,
+    macros: Macros,
+    playing: usize,
+    macro_aborted: bool,
+    sourcing: Option<(String, usize)>,
+    session: Session,
+    recall: session::Recall,
+    last_paste: Option<(Position, Position, usize)>,
+    last_pattern: Option<String>,
+    in_global: bool,
+    replace_plan: Vec<(ReplaceTarget, Replacement)>,
+    confirmation: Option<Confirmation>,
+    search: Option<(Regex, bool)>,
+    search_count: Option<(u64, Vec<Position>)>,
+    flash: Option<(Position, Position, bool)>,
+    bell: bool,
+    which_key: bool,
+    overlay: Overlay,
+    chord: Vec<Key>,
+    chord_count: Option<usize>,
+    patch_plan: Vec<(ReplaceTarget, patch::Hunk)>,
+    picker: Option<(Picker, PickerKind)>,
+    normal: vim::Normal,
+    write_stdout: bool,
+    abort: bool,
+    hex: Option<HexView>,
+    lazy: Option<LazyFile>,
+    newlines: Newlines,
+    scroll: usize,
+    hscroll: usize,
+    view_height: usize,
+    options: Options,
+    last_autosave: Instant,
+    timers: Timers,
+    jobs: Jobs<io::Result<std::process::Output>>,
+    cursor_visible: bool,
+    saves: Vec<Save>,
+    profile: Profile,
+    cast: Option<(Cast, String)>,
+    preview: Option<String>,
+    recentered: Option<vim::ViewAt>,
+    documents: Vec<Document>,
+    current: usize,
+    tab_spans: Vec<(u16, u16, usize)>,
+    text_origin: (u16, u16),
+    status_row: u16,
+    layout: Layout,
+    pane_size: Option<(usize, u16)>,
+    sidebar_size: Option<(u16, u16)>,
+    resizing: Option<Separator>,
+    status_spans: Vec<statusline::Span>,
+    text_rows: Vec<(usize, usize)>,
+    selection: Option<(Position, bool)>,
+    last_click: Option<(Instant, u16, u16, usize)>,
+    free_scroll: bool,
+    label: Option<String>,
+    readonly: bool,
+    filetype: Option<&'static FileType>,
+    remote: Option<(Url, remote::State)>,
+    remote_jobs: Jobs<(String, io::Result<Vec<u8>>)>,
+    crypt: Option<(Cipher, Option<String>)>,
+    literal: Option<String>,
+    composer: compose::Composer,
+    metadata: Option<meta::Metadata>,
+    browser: Option<std::path::PathBuf>,
+    messages: Messages,
+    keymap: Keymap,
+    user_commands: BTreeMap<String, String>,
+    formatters: BTreeMap<String, String>,
+    linters: BTreeMap<String, String>,
+    lint_jobs: Jobs<io::Result<std::process::Output>>,
+    language_servers: BTreeMap<String, String>,
+    rename_jobs: Jobs<(String, String, std::result::Result<lsp::WorkspaceEdit, String>)>,
+    diagnostics: Vec<Diagnostic>,
+    make_programs: BTreeMap<String, String>,
+    build: Option<(Stream, String)>,
+    output: Option<OutputPane>,
+    last_task: Option<(String, std::path::PathBuf)>,
+    tag_stack: Vec<(Option<String>, Position)>,
+    outline: Option<Outline>,
+    symbols: Option<(u64, Vec<Symbol>)>,
+    breadcrumbs: Option<(u64, usize, String)>,
+    terminal: Option<(Terminal, Option<Pty>, String)>,
+    terminal_focus: bool,
+    merge: Option<Merge>,
+    clipboard: Vec<u8>,
+}
+
+impl Editor {
+    pub fn new(filename: Option<String>) -> Self {
+        let mut editor = Self {
+            state: EditorState::default(),
+            mode: Mode::Insert,
+            overwrite: false,
+            command: String::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            prompt: None,
+            registers: Registers::default(),
+            register: None,
+            macros: Macros::default(),
+            playing: 0,
+            macro_aborted: false,
+            sourcing: None,
+            session: Session::default(),
+            recall: session::Recall::default(),
+            last_paste: None,
+            last_pattern: None,
+            in_global: false,
+            replace_plan: Vec::new(),
+            confirmation: None,
+            search: None,
+            search_count: None,
+            flash: None,
+            bell: false,
+            which_key: false,
+            overlay: Overlay::default(),
+            chord: Vec::new(),
+            chord_count: None,
+            patch_plan: Vec::new(),
+            picker: None,
+            normal: vim::Normal::default(),
+            write_stdout: false,
+            abort: false,
+            hex: None,
+            lazy: None,
+            newlines: Newlines::default(),
+            scroll: 0,
+            hscroll: 0,
+            view_height: 0,
+            options: Options::new(),
+            last_autosave: Instant::now(),
+            timers: Timers::default(),
+            jobs: Jobs::new(),
+            cursor_visible: true,
+            saves: Vec::new(),
+            profile: Profile::default(),
+            cast: None,
+            preview: None,
+            recentered: None,
+            documents: vec![Document::default()],
+            current: 0,
+            tab_spans: Vec::new(),
+            text_origin: (0, 0),
+            status_row: 0,
+            layout: Layout::default(),
+            pane_size: None,
+            sidebar_size: None,
+            resizing: None,
+            status_spans: Vec::new(),
+            text_rows: Vec::new(),
+            selection: None,
+            last_click: None,
+            free_scroll: false,
+            label: None,
+            readonly: false,
+            filetype: None,
+            remote: None,
+            remote_jobs: Jobs::new(),
+            crypt: None,
+            literal: None,
+            composer: compose::Composer::default(),
+            metadata: None,
+            browser: None,
+            messages: Messages::default(),
+            keymap: Keymap::new(),
+            user_commands: BTreeMap::new(),
+            formatters: BTreeMap::new(),
+            linters: BTreeMap::new(),
+            lint_jobs: Jobs::new(),
+            language_servers: BTreeMap::new(),
+            rename_jobs: Jobs::new(),
+            diagnostics: Vec::new(),
+            make_programs: BTreeMap::new(),
+            build: None,
+            output: None,
+            last_task: None,
+            tag_stack: Vec::new(),
+            outline: None,
+            symbols: None,
+            breadcrumbs: None,
+            terminal: None,
+            terminal_focus: false,
+            merge: None,
+            clipboard: Vec::new(),
+        };
+        editor.open_document(filename);
+        editor.prompt_secret();
+        editor.prompt_binary();
+        editor
+    }
+
+    pub fn from_text(text: &str) -> Self {
+        let mut editor = Self::new(None);
+        editor.state.buffer = text.split('\n').map(str::to_string).collect();
+        editor
+    }
+
+    pub fn buffer(&self) -> &[String] {
+        &self.state.buffer
+    }
+
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.state.cursor.y, self.state.cursor.x)
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn filename(&self) -> Option<&str> {
+        self.state.filename.as_deref()
+    }
+
+    pub fn filetype(&self) -> Option<&'static FileType> {
+        self.filetype
+    }
+
+    pub fn window_title(&self) -> Option<String> {
+        if !self.options.flag("title") {
+            return None;
+        }
+        let name = self.state.name(self.label.as_deref());
+        let modified = if self.state.dirty { " [+]" } else { "" };
+        Some(format!("{}{} \u{2014} aon", name, modified))
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.state.dirty
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.messages.current().map(|message| message.text.as_str())
+    }
+
+    pub fn render(&mut self, width: u16, height: u16) -> Screen {
+        let mut screen = Screen::default();
+        screen.resize(width, height);
+        let _ = self.draw(&mut screen);
+        screen
+    }
+
+    pub fn set_write_stdout(&mut self, enabled: bool) {
+        self.write_stdout = enabled;
+    }
+
+    /// Whether the front end should ask the terminal for enhanced key
+    /// reporting when it supports it.
+    pub fn enhanced_keys(&self) -> bool {
+        self.options.flag("enhancedkeys")
+    }
+
+    pub fn writes_stdout(&self) -> bool {
+        self.write_stdout
+    }
+
+    pub fn aborted(&self) -> bool {
+        self.abort
+    }
+
+    pub fn contents(&mut self) -> Vec<u8> {
+        self.materialize();
+        match &self.hex {
+            Some(hex) => hex.bytes.clone(),
+            None => utf8::encode(format!("{}\n", self.state.buffer.join("\n"))),
+        }
+    }
+
+    pub fn record_render(&mut self, elapsed: Duration) {
+        self.profile.render.record(elapsed);
+        self.profile.frames += 1;
+    }
+
+    pub fn record_event(&mut self, elapsed: Duration) {
+        self.profile.event.record(elapsed);
+    }
+
+    pub fn casting(&self) -> bool {
+        self.cast.is_some()
+    }
+
+    pub fn record_output(&mut self, bytes: &[u8], width: u16, height: u16) {
+        if let Some((cast, _)) = self.cast.as_mut() {
+            cast.output(bytes, width, height);
+        }
+    }
+
+    pub fn finish_cast(&mut self) -> Option<String> {
+        let (cast, path) = self.cast.take()?;
+        match cast.export(&path) {
+            Ok(()) => {
+                self.notify(Severity::Info, trf(Text::CastSaved, &[&path]));
+                None
+            }
+            Err(err) => Some(Error::write(&path, err).to_string()),
+        }
+    }
+
+    pub fn wait_for_saves(&mut self) -> Vec<String> {
+        let mut failures = Vec::new();
+        for save in self.saves.drain(..) {
+            let filename = save.filename.clone();
+            if let Err(err) = save.finish() {
+                failures.push(Error::write(&filename, err).to_string());
+            }
+        }
+        if !failures.is_empty() {
+            self.abort = true;
+        }
+        failures
+    }
+
+    pub fn start_merge(&mut self, local: &str, base: &str, remote: &str) -> Result<()> {
+        self.merge = Some(Merge::load(local, base, remote)?);
+        self.next_conflict(true);
+        Ok(())
+    }
+
+    pub fn apply_config(&mut self, config: &Config) {
+        for (name, definition) in config.prefixed("command.") {
+            self.define_command(&format!("{} {}", name, definition));
+        }
+        for (name, value) in config.prefixed("") {
+            if Options::is_option(name) {
+                self.set_option(&format!("{}={}", name, value));
+            }
+        }
+        for (key, action) in config.prefixed("map.") {
+            self.map_key(key, action);
+        }
+        for (filetype, command) in config.prefixed("format.") {
+            self.formatters.insert(filetype.to_string(), command.to_string());
+        }
+        for (filetype, command) in config.prefixed("lint.") {
+            self.linters.insert(filetype.to_string(), command.to_string());
+        }
+        for (filetype, command) in config.prefixed("lsp.") {
+            self.language_servers.insert(filetype.to_string(), command.to_string());
+        }
+        for (filetype, command) in config.prefixed("make.") {
+            self.make_programs.insert(filetype.to_string(), command.to_string());
+        }
+        if let Some(command) = config.get("make") {
+            self.make_programs.insert(String::new(), command.to_string());
+        }
+        self.mode = self.base_mode();
+    }
+
+    fn base_mode(&self) -> Mode {
+        if self.options.string("keymap") == "vim" {
+            Mode::Normal
+        } else {
+            Mode::Insert
+        }
+    }
+
+    fn stash(&mut self) -> Document {
+        Document {
+            state: std::mem::take(&mut self.state),
+            undo_stack: std::mem::take(&mut self.undo_stack),
+            redo_stack: std::mem::take(&mut self.redo_stack),
+            hex: self.hex.take(),
+            lazy: self.lazy.take(),
+            newlines: self.newlines,
+            scroll: self.scroll,
+            label: self.label.take(),
+            readonly: self.readonly,
+            filetype: self.filetype.take(),
+            remote: self.remote.take(),
+            crypt: self.crypt.take(),
+            metadata: self.metadata.take(),
+            browser: self.browser.take(),
+        }
+    }
+
+    fn restore(&mut self, document: Document) {
+        self.state = document.state;
+        self.undo_stack = document.undo_stack;
+        self.redo_stack = document.redo_stack;
+        self.hex = document.hex;
+        self.lazy = document.lazy;
+        self.newlines = document.newlines;
+        self.scroll = document.scroll;
+        self.hscroll = 0;
+        self.label = document.label;
+        self.readonly = document.readonly;
+        self.filetype = document.filetype;
+        self.remote = document.remote;
+        self.crypt = document.crypt;
+        self.metadata = document.metadata;
+        self.browser = document.browser;
+    }
+
+    fn switch_to(&mut self, index: usize) {
+        if index == self.current || index >= self.documents.len() {
+            return;
+        }
+        self.remember_position();
+        self.selection = None;
+        self.documents[self.current] = self.stash();
+        let document = std::mem::take(&mut self.documents[index]);
+        self.restore(document);
+        self.current = index;
+    }
+
+    fn cycle_document(&mut self, forward: bool) {
+        let count = self.documents.len();
+        let index = if forward {
+            (self.current + 1) % count
+        } else {
+            (self.current + count - 1) % count
+        };
+        self.switch_to(index);
+    }
+
+    fn document_index(&self, filename: &str) -> Option<usize> {
+        (0..self.documents.len()).find(|&i| {
+            let name = if i == self.current {
+                &self.state.filename
+            } else {
+                &self.documents[i].state.filename
+            };
+            name.as_deref() == Some(filename)
+        })
+    }
+
+    fn open_document(&mut self, filename: Option<String>) {
+        let (document, error) = Document::open(filename);
+        self.restore(document);
+        if let Some(err) = error {
+            self.report(err);
+        }
+    }
+
+    fn edit_file(&mut self, filename: String) {
+        match self.document_index(&filename) {
+            Some(index) => {
+                self.switch_to(index);
+                if let Some((_, state)) = self.remote.as_mut().filter(|_| self.readonly) {
+                    if matches!(state, remote::State::Offline(_)) {
+                        *state = remote::State::Queued;
+                    }
+                }
+            }
+            None => {
+                self.documents.push(Document::default());
+                let index = self.documents.len() - 1;
+                self.switch_to(index);
+                self.open_document(Some(filename));
+                self.prompt_binary();
+                self.restore_position();
+            }
+        }
+        self.prompt_secret();
+    }
+
+    pub fn load_session(&mut self, path: std::path::PathBuf) {
+        match Session::load(path.clone()) {
+            Ok(session) => self.session = session,
+            Err(err) => return self.report(Error::read(path.display(), err)),
+        }
+        for (name, text, linewise) in self.session.registers() {
+            let register = Register {
+                text: text.to_string(),
+                linewise,
+                secret: false,
+            };
+            self.registers.restore(name, register);
+        }
+        self.restore_position();
+    }
+
+    pub fn save_session(&mut self) -> Result<()> {
+        self.remember_position();
+        let named = self.registers.entries().into_iter().filter_map(|(source, register)| match source {
+            registers::Source::Named(name) if name != registers::DEFAULT && !register.secret => Some((name, register)),
+            _ => None,
+        });
+        for (name, register) in named {
+            self.session.set_register(name, &register.text, register.linewise);
+        }
+        if self.options.flag("restorecursor") {
+            for (i, document) in self.documents.iter().enumerate() {
+                let plain = i != self.current && document.hex.is_none() && document.lazy.is_none() && document.browser.is_none();
+                if let Some(file) = document.state.filename.as_deref().filter(|_| plain) {
+                    self.session.remember(file, document.state.cursor.y, document.state.cursor.x);
+                }
+            }
+        }
+        self.session.write()
+    }
+
+    fn set_mark(&mut self, name: char) {
+        if !name.is_ascii_uppercase() {
+            self.notify(Severity::Error, trf(Text::InvalidMark, &[&name]));
+            return;
+        }
+        let Some(file) = self.state.filename.clone() else {
+            self.notify(Severity::Error, tr(Text::NoFilename).to_string());
+            return;
+        };
+        if !self.session.set_mark(name, &file, self.state.cursor.y, self.state.cursor.x) {
+            self.notify(Severity::Error, tr(Text::NoFilename).to_string());
+        }
+    }
+
+    fn jump_to_mark(&mut self, name: char, exact: bool) {
+        let Some(mark) = self.session.marks().get(&name).cloned() else {
+            self.notify(Severity::Error, trf(Text::MarkNotSet, &[&name]));
+            return;
+        };
+        let open = (0..self.documents.len()).find(|&i| {
+            let filename = if i == self.current { &self.state.filename } else { &self.documents[i].state.filename };
+            filename.as_deref().and_then(session::key).as_ref() == Some(&mark.file)
+        });
+        match open {
+            Some(index) => self.switch_to(index),
+            None => self.edit_file(mark.file.clone()),
+        }
+        let y = mark.y.min(self.state.buffer.len() - 1);
+        let line = &self.state.buffer[y];
+        let x = if exact { text::snap(line, mark.x) } else { motion::first_non_blank(line) };
+        self.state.cursor = Position { x, y };
+        if self.mode == Mode::Normal {
+            self.clamp_normal();
+        }
+    }
+
+    fn show_marks(&mut self) {
+        let marks: Vec<(char, session::Mark)> = self.session.marks().iter().map(|(&name, mark)| (name, mark.clone())).collect();
+        if marks.is_empty() {
+            self.notify(Severity::Info, tr(Text::NoMarks).to_string());
+            return;
+        }
+        let items = marks
+            .iter()
+            .map(|(name, mark)| format!("{}  {}:{}:{}", name, mark.file, mark.y + 1, mark.x + 1))
+            .collect();
+        let names = marks.into_iter().map(|(name, _)| name).collect();
+        self.picker = Some((Picker::new(tr(Text::MarksTitle), items), PickerKind::Marks(names)));
+    }
+
+    fn remember_position(&mut self) {
+        if let (Some(file), true) = (&self.state.filename, self.options.flag("restorecursor")) {
+            if self.hex.is_none() && self.lazy.is_none() && self.browser.is_none() {
+                self.session.remember(file, self.state.cursor.y, self.state.cursor.x);
+            }
+        }
+    }
+
+    fn restore_position(&mut self) {
+        if !self.options.flag("restorecursor") || self.hex.is_some() || self.lazy.is_some() || self.browser.is_some() {
+            return;
+        }
+        let Some((y, x)) = self.state.filename.as_deref().and_then(|file| self.session.position(file)) else {
+            return;
+        };
+        let y = y.min(self.state.buffer.len() - 1);
+        self.state.cursor = Position {
+            x: text::snap(&self.state.buffer[y], x),
+            y,
+        };
+    }
+
+    fn browse(&mut self, dir: &std::path::Path, select: Option<&str>) {
+        let document = Document::browse(dir);
+        let y = select.and_then(|name| document.state.buffer.iter().position(|line| line.trim_end_matches('/') == name));
+        self.restore(document);
+        self.state.cursor = Position { x: 0, y: y.unwrap_or(0) };
+    }
+
+    fn browser_key(&mut self, code: KeyCode) -> bool {
+        let Some(dir) = self.browser.clone() else {
+            return false;
+        };
+        let line = self.state.buffer[self.state.cursor.y].clone();
+        let entry = line != browser::PARENT && !line.is_empty();
+        let name = dir.file_name().map(|name| name.to_string_lossy().into_owned());
+        match code {
+            KeyCode::Enter => match browser::entry(&dir, &line) {
+                Some(path) if path.is_dir() => self.browse(&path, name.as_deref().filter(|_| !entry)),
+                Some(path) => self.edit_file(path.to_string_lossy().into_owned()),
+                None => {}
+            },
+            KeyCode::Char('-') => {
+                if let Some(parent) = dir.parent() {
+                    self.browse(parent, name.as_deref());
+                }
+            }
+            KeyCode::Char('%') => self.ask_browser(browser::Op::Create),
+            KeyCode::Char('d') => self.ask_browser(browser::Op::Mkdir),
+            KeyCode::Char('R') if entry => self.ask_browser(browser::Op::Rename(line)),
+            KeyCode::Char('D') if entry => self.ask_browser(browser::Op::Delete(line)),
+            _ => return false,
+        }
+        true
+    }
+
+    fn ask_browser(&mut self, op: browser::Op) {
+        let prompt = match &op {
+            browser::Op::Rename(entry) => Prompt::text(op.prompt()).filled(entry.trim_end_matches('/')),
+            browser::Op::Delete(entry) => Prompt::yes_no(op.prompt()).about(entry),
+            _ => Prompt::text(op.prompt()),
+        };
+        self.ask(prompt, PromptKind::Browse(op));
+    }
+
+    fn finish_browser_input(&mut self, op: browser::Op, input: &str) {
+        let Some(dir) = self.browser.clone() else {
+            return;
+        };
+        let name = input.trim();
+        if name.is_empty() {
+            return;
+        }
+        let y = self.state.cursor.y;
+        match browser::apply(&dir, &op, name) {
+            Ok(created) => {
+                self.browse(&dir, Some(name));
+                if matches!(op, browser::Op::Delete(_)) {
+                    self.state.cursor.y = y.min(self.state.buffer.len() - 1);
+                }
+                if let Some(path) = created {
+                    self.edit_file(path.to_string_lossy().into_owned());
+                }
+            }
+            Err(err) => {
+                let subject = match &op {
+                    browser::Op::Rename(entry) | browser::Op::Delete(entry) => entry.trim_end_matches('/'),
+                    _ => name,
+                };
+                self.notify(Severity::Error, trf(Text::BrowserFailed, &[&subject, &err]));
+            }
+        }
+    }
+
+    fn open_scratch(&mut self, label: &str, lines: Vec<String>) {
+        let existing = (0..self.documents.len()).find(|&i| {
+            let name = if i == self.current {
+                &self.label
+            } else {
+                &self.documents[i].label
+            };
+            name.as_deref() == Some(label)
+        });
+        match existing {
+            Some(index) => self.switch_to(index),
+            None => {
+                self.documents.push(Document::default());
+                self.switch_to(self.documents.len() - 1);
+            }
+        }
+        self.restore(Document {
+            state: EditorState {
+                buffer: if lines.is_empty() { vec![String::new()] } else { lines },
+                ..EditorState::default()
+            },
+            label: Some(label.to_string()),
+            readonly: true,
+            ..Document::default()
+        });
+    }
+
+    fn notify(&mut self, severity: Severity, text: String) {
+        let text = match &self.sourcing {
+            Some((path, line)) if severity == Severity::Error => format!("{}:{}: {}", path, line, text),
+            _ => text,
+        };
+        self.messages.push(severity, text);
+        if severity == Severity::Error {
+            self.ring_bell();
+        }
+    }
+
+    fn report(&mut self, err: Error) {
+        self.notify(Severity::Error, err.to_string());
+    }
+
+    fn ring_bell(&mut self) {
+        if self.options.string("bell") != "none" {
+            self.bell = true;
+            self.timers.schedule(Timer::Bell, BELL_DURATION);
+        }
+    }
+
+    fn show_messages(&mut self) {
+        let lines = self
+            .messages
+            .history()
+            .iter()
+            .map(|m| format!("[{}] {}", m.severity.label(), m.text))
+            .collect();
+        self.open_scratch(tr(Text::MessagesTitle), lines);
+        self.state.cursor.y = self.state.buffer.len() - 1;
+    }
+
+    fn editable(&mut self) -> bool {
+        if self.is_saving() {
+            self.notify(Severity::Warning, tr(Text::SaveInProgress).to_string());
+            return false;
+        }
+        if self.readonly {
+            self.notify(Severity::Warning, tr(Text::ReadOnly).to_string());
+        }
+        !self.readonly
+    }
+
+    fn close_document(&mut self) {
+        if self.state.dirty {
+            self.notify(Severity::Warning, tr(Text::UnsavedChanges).to_string());
+            return;
+        }
+        if self.documents.len() < 2 {
+            return;
+        }
+        self.remember_position();
+        self.documents.remove(self.current);
+        let index = self.current.min(self.documents.len() - 1);
+        let document = std::mem::take(&mut self.documents[index]);
+        self.restore(document);
+        self.current = index;
+    }
+
+    fn next_dirty(&self) -> Option<usize> {
+        if self.state.dirty {
+            return Some(self.current);
+        }
+        (0..self.documents.len()).find(|&i| i != self.current && self.documents[i].state.dirty)
+    }
+
+    fn document_titles(&self) -> Vec<String> {
+        (0..self.documents.len())
+            .map(|i| {
+                if i == self.current {
+                    self.state.title(self.label.as_deref())
+                } else {
+                    let document = &self.documents[i];
+                    document.state.title(document.label.as_deref())
+                }
+            })
+            .collect()
+    }
+
+    fn tab_at(&self, column: u16) -> Option<usize> {
+        self.tab_spans
+            .iter()
+            .find(|(start, end, _)| (*start..*end).contains(&column))
+            .map(|&(_, _, index)| index)
+    }
+
+    fn materialize(&mut self) {
+        if let Some(mut lazy) = self.lazy.take() {
+            let bytes = lazy.read_all().unwrap_or_else(|err| {
+                self.readonly = true;
+                self.report(Error::read(self.state.filename.clone().unwrap_or_default(), err));
+                Vec::new()
+            });
+            let (buffer, newlines, hex) = Document::decode(bytes);
+            self.state.buffer = buffer;
+            self.newlines = newlines;
+            self.hex = hex;
+            self.clamp_cursor();
+        }
+    }
+
+    fn lazy_key(&mut self, code: KeyCode) -> bool {
+        let Some(lazy) = self.lazy.as_mut() else {
+            return false;
+        };
+        let height = self.view_height;
+        let y = self.state.cursor.y;
+        let target = match code {
+            KeyCode::Up => y.saturating_sub(1),
+            KeyCode::Down => y + 1,
+            KeyCode::PageUp => y.saturating_sub(height),
+            KeyCode::PageDown => y + height,
+            KeyCode::Home => 0,
+            KeyCode::End => usize::MAX,
+            KeyCode::Char(':') | KeyCode::Esc => return false,
+            _ => {
+                self.materialize();
+                return false;
+            }
+        };
+        let indexed = match target {
+            usize::MAX => lazy.index_all(),
+            _ => lazy.index_to(target),
+        };
+        if let Err(err) = indexed {
+            self.report(Error::read(self.state.filename.clone().unwrap_or_default(), err));
+        }
+        let Some(lazy) = self.lazy.as_mut() else {
+            return true;
+        };
+        self.state.cursor.y = target.min(lazy.indexed_lines().saturating_sub(1));
+        self.state.cursor.x = 0;
+        lazy.follow(self.state.cursor.y, height);
+        true
+    }
+
+    fn toggle_hex(&mut self) {
+        self.materialize();
+        match self.hex.take() {
+            Some(hex) if HexView::is_binary(&hex.bytes) => self.hex = Some(hex),
+            Some(hex) => {
+                (self.state.buffer, self.newlines) = Newlines::split(&utf8::decode(&hex.bytes));
+                self.clamp_cursor();
+            }
+            None => self.hex = Some(HexView::new(utf8::encode(self.newlines.join(&self.state.buffer)))),
+        }
+    }
+
+    fn hex_key(&mut self, code: KeyCode) {
+        if let Some(hex) = self.hex.as_mut() {
+            if hex.handle_key(code) {
+                self.state.dirty = true;
+            }
+        }
+    }
+
+    fn hex_undo(&mut self) {
+        if let Some(hex) = self.hex.as_mut() {
+            hex.undo();
+        }
+    }
+
+    fn clamp_cursor(&mut self) {
+        if self.state.buffer.is_empty() {
+            self.state.cursor = Position { x: 0, y: 0 };
+            return;
+        }
+        self.state.cursor.y =
+            self.state.cursor.y.min(self.state.buffer.len().saturating_sub(1));
+        self.state.cursor.x = text::snap(&self.state.buffer[self.state.cursor.y], self.state.cursor.x);
+    }
+
+    fn save_snapshot(&mut self) {
+        if self.in_global {
+            return;
+        }
+        let snapshot = self.state.clone();
+        self.push_undo(snapshot);
+    }
+
+    /// Does nothing inside `:g`, which pushes one step for all its edits.
+    fn push_undo(&mut self, snapshot: EditorState) {
+        if self.in_global {
+            return;
+        }
+        self.redo_stack.clear();
+        self.undo_stack.push(snapshot);
+        self.trim_undo();
+        self.state.dirty = true;
+        self.schedule_preview();
+    }
+
+    fn trim_undo(&mut self) {
+        let levels = self.options.int("undolevels");
+        if levels > 0 && self.undo_stack.len() > levels {
+            let excess = self.undo_stack.len() - levels;
+            self.undo_stack.drain(..excess);
+        }
+        let limit = self.options.int("undosize") * 1024 * 1024;
+        if limit == 0 {
+            return;
+        }
+        let mut used: usize = self.undo_stack.iter().chain(&self.redo_stack).map(EditorState::memory).sum();
+        let mut excess = 0;
+        while used > limit && excess + 1 < self.undo_stack.len() {
+            used -= self.undo_stack[excess].memory();
+            excess += 1;
+        }
+        self.undo_stack.drain(..excess);
+    }
+
+    fn undo(&mut self) {
+        if !self.editable() {
+            return;
+        }
+        if let Some(prev) = self.undo_stack.pop() {
+            self.redo_stack.push(self.state.clone());
+            self.state = prev;
+            self.clamp_cursor();
+            self.schedule_preview();
+        }
+    }
+
+    fn schedule_preview(&mut self) {
+        if self.preview.is_some() && self.preview == self.state.filename {
+            self.timers.schedule(Timer::Preview, PREVIEW_DEBOUNCE);
+        }
+    }
+
+    fn set_filetype(&mut self, name: &str) {
+        if name.is_empty() {
+            let current = self.filetype.map_or(tr(Text::NoFiletype), |filetype| filetype.name);
+            self.notify(Severity::Info, format!("filetype={}", current));
+        } else if name == "detect" {
+            self.filetype = filetype::detect(self.state.filename.as_deref(), &self.state.buffer);
+        } else {
+            match filetype::find(name) {
+                Some(filetype) => self.filetype = Some(filetype),
+                None => self.notify(Severity::Error, trf(Text::UnknownFiletype, &[&name])),
+            }
+        }
+    }
+
+    fn preview_label(source: &str) -> String {
+        format!("[preview] {}", complete::file_name(source))
+    }
+
+    fn open_preview(&mut self) {
+        let Some(source) = self.state.filename.clone().filter(|name| markdown::is_markdown(name)) else {
+            self.notify(Severity::Error, tr(Text::NotMarkdown).to_string());
+            return;
+        };
+        let lines = markdown::render(&self.state.buffer);
+        self.preview = Some(source.clone());
+        self.open_scratch(&Self::preview_label(&source), lines);
+    }
+
+    fn refresh_preview(&mut self) {
+        let Some(source) = self.preview.clone() else {
+            return;
+        };
+        let label = Self::preview_label(&source);
+        let (mut lines, mut target) = (None, None);
+        for index in 0..self.documents.len() {
+            let (state, doc_label) = if index == self.current {
+                (&self.state, &self.label)
+            } else {
+                (&self.documents[index].state, &self.documents[index].label)
+            };
+            if state.filename.as_deref() == Some(source.as_str()) && doc_label.is_none() {
+                lines = Some(markdown::render(&state.buffer));
+            } else if doc_label.as_deref() == Some(label.as_str()) {
+                target = Some(index);
+            }
+        }
+        let (Some(lines), Some(index)) = (lines, target) else {
+            self.preview = None;
+            return;
+        };
+        let lines = if lines.is_empty() { vec![String::new()] } else { lines };
+        if index == self.current {
+            self.state.buffer = lines;
+            self.clamp_cursor();
+        } else {
+            self.documents[index].state.buffer = lines;
+        }
+    }
+
+    fn insert(&mut self, c: char) {
+        if !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+        let line = &mut self.state.buffer[self.state.cursor.y];
+
+        if self.overwrite {
+            let end = text::next_boundary(line, self.state.cursor.x);
+            line.replace_range(self.state.cursor.x..end, "");
+            line.insert(self.state.cursor.x, c);
+            self.state.cursor.x += c.len_utf8();
+        } else if let Some(pair) = Self::matching_pair(c).filter(|_| self.options.flag("autopair") && !self.options.flag("paste")) {
+            line.insert(self.state.cursor.x, c);
+            line.insert(self.state.cursor.x + 1, pair);
+            self.state.cursor.x += 1;
+        } else {
+            line.insert(self.state.cursor.x, c);
+            self.state.cursor.x += c.len_utf8();
+        }
+        if self.options.flag("normalize") && !c.is_ascii() {
+            self.normalize_before_cursor();
+        }
+        self.clamp_cursor();
+    }
+
+    fn normalize_before_cursor(&mut self) {
+        let Position { x, y } = self.state.cursor;
+        let line = &mut self.state.buffer[y];
+        let mut start = x;
+        for (i, c) in line[..x].char_indices().rev() {
+            start = i;
+            if unicode::combining_class(c) == 0 {
+                break;
+            }
+        }
+        let composed = unicode::nfc(&line[start..x]);
+        if composed != line[start..x] {
+            line.replace_range(start..x, &composed);
+            self.state.cursor.x = start + composed.len();
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.state.cursor.x == 0 && self.state.cursor.y == 0 {
+            self.notify(Severity::Warning, tr(Text::StartOfBuffer).to_string());
+            self.ring_bell();
+            return;
+        }
+        if !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+
+        if self.state.cursor.x > 0 {
+            let line = &mut self.state.buffer[self.state.cursor.y];
+            let start = text::prev_boundary(line, self.state.cursor.x);
+            line.replace_range(start..self.state.cursor.x, "");
+            self.state.cursor.x = start;
+        } else {
+            let y = self.state.cursor.y;
+            let prev_len = self.state.buffer[y - 1].len();
+            let line = self.state.buffer.remove(y);
+            self.state.buffer[y - 1].push_str(&line);
+            self.state.cursor.y -= 1;
+            self.state.cursor.x = prev_len;
+        }
+    }
+
+    fn newline(&mut self) {
+        if !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+        let y = self.state.cursor.y;
+        let rest = self.state.buffer[y].split_off(self.state.cursor.x);
+        self.state.buffer.insert(y + 1, rest);
+        self.state.cursor.y += 1;
+        self.state.cursor.x = 0;
+    }
+
+    fn yank(&mut self, text: String, linewise: bool) {
+        let name = self.register.take();
+        let copy = match self.options.string("osc52") {
+            "always" => true,
+            "auto" => osc52::headless(),
+            _ => false,
+        };
+        let limit = self.options.int("osc52limit");
+        if copy && text.len() > limit {
+            self.notify(Severity::Warning, trf(Text::ClipboardTooLarge, &[&text.len(), &limit]));
+        } else if copy {
+            self.clipboard = osc52::sequence(&text);
+        }
+        self.registers.store(name, text, linewise, self.crypt.is_some());
+    }
+
+    fn flash_yank(&mut self, start: Position, end: Position, linewise: bool) {
+        let duration = self.options.int("yankflash") as u64;
+        if duration > 0 {
+            self.flash = Some((start, end, linewise));
+            self.timers.schedule(Timer::Flash, Duration::from_millis(duration));
+        }
+    }
+
+    fn take_register(&mut self) -> Option<Register> {
+        let name = self.register.take();
+        self.registers.get(name).cloned()
+    }
+
+    fn select_register(&mut self, name: &str) -> bool {
+        let mut chars = name.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => true,
+            (Some(c), None) if Registers::is_valid(c) => {
+                self.register = Some(c);
+                true
+            }
+            _ => {
+                self.notify(Severity::Error, trf(Text::InvalidRegister, &[&name]));
+                false
+            }
+        }
+    }
+
+    fn show_registers(&mut self) {
+        let entries = self.registers.entries();
+        if entries.is_empty() {
+            self.notify(Severity::Info, tr(Text::RegistersEmpty).to_string());
+            return;
+        }
+        let items = entries.iter().map(|(source, register)| format!("{:<3} {}", source.label(), register.preview())).collect();
+        let sources = entries.iter().map(|&(source, _)| source).collect();
+        self.picker = Some((Picker::new(tr(Text::RegistersTitle), items), PickerKind::Registers(sources)));
+    }
+
+    fn paste_from(&mut self, source: registers::Source) {
+        match source {
+            _ if self.mode == Mode::Normal => {
+                if let Some(register) = self.registers.source(source).cloned() {
+                    self.put_register(register, false, 1);
+                }
+            }
+            registers::Source::Named(name) => {
+                self.register = Some(name);
+                self.paste();
+            }
+            registers::Source::History(index) => self.paste_ring(index),
+        }
+    }
+
+    pub fn open_buffers(&mut self, files: &[(String, Option<cli::Start>)]) {
+        let current = self.current;
+        for (file, start) in files {
+            self.edit_file(file.clone());
+            if let Some(start) = start {
+                self.start_at(start);
+            }
+        }
+        self.switch_to(current);
+    }
+
+    pub fn set_readonly(&mut self) {
+        self.readonly = true;
+        for document in &mut self.documents {
+            document.readonly = true;
+        }
+    }
+
+    pub fn start_at(&mut self, start: &cli::Start) {
+        let last = self.state.buffer.len() - 1;
+        let y = match start {
+            cli::Start::Line(line) => line.saturating_sub(1).min(last),
+            cli::Start::Position(line, column) => {
+                let y = line.saturating_sub(1).min(last);
+                let text = &self.state.buffer[y];
+                let x = text.char_indices().nth(column.saturating_sub(1)).map_or(text.len(), |(x, _)| x);
+                self.state.cursor = Position { x, y };
+                return;
+            }
+            cli::Start::Last => last,
+            cli::Start::Pattern(pattern) => {
+                let Some(regex) = self.compile_pattern(pattern, self.options.flag("ignorecase")) else {
+                    return;
+                };
+                let first = self.search_matches(&regex).next();
+                match first {
+                    Some(position) => self.state.cursor = position,
+                    None => self.notify(Severity::Error, trf(Text::PatternNotFound, &[&pattern])),
+                }
+                self.set_search(Some((regex, true)));
+                return;
+            }
+        };
+        self.state.cursor = Position {
+            x: motion::first_non_blank(&self.state.buffer[y]),
+            y,
+        };
+    }
+
+    pub fn run_command(&mut self, command: &str) -> Result<bool> {
+        self.command = command.to_string();
+        self.process_command()
+    }
+
+    pub fn load_macros(&mut self, path: std::path::PathBuf) {
+        match Macros::load(path.clone()) {
+            Ok(macros) => self.macros = macros,
+            Err(err) => self.report(Error::read(path.display(), err)),
+        }
+    }
+
+    fn toggle_recording(&mut self, name: Option<char>) {
+        if let Some(name) = self.macros.stop() {
+            self.normal.recording = false;
+            self.notify(Severity::Info, trf(Text::MacroRecorded, &[&name]));
+        } else if let Some(name) = name {
+            self.macros.start(name.to_string());
+            self.normal.recording = true;
+        }
+    }
+
+    fn play_macro(&mut self, name: &str, count: usize) -> bool {
+        let name = match name {
+            "" | "@" => match self.macros.last() {
+                Some(last) => last.to_string(),
+                None => {
+                    self.notify(Severity::Warning, tr(Text::NoLastMacro).to_string());
+                    return false;
+                }
+            },
+            name => name.to_string(),
+        };
+        let Some(keys) = self.macros.get(&name) else {
+            self.notify(Severity::Error, trf(Text::NoSuchMacro, &[&name]));
+            return false;
+        };
+        if self.playing >= MAX_MACRO_DEPTH {
+            self.macro_aborted = true;
+            self.notify(Severity::Error, tr(Text::MacroTooDeep).to_string());
+            return false;
+        }
+        self.playing += 1;
+        let mut quit = false;
+        'play: for _ in 0..count {
+            for key in &keys {
+                match self.handle_key(KeyEvent::new(key.code, key.modifiers)) {
+                    Ok(false) if !self.macro_aborted => {}
+                    Ok(done) => {
+                        quit = done;
+                        break 'play;
+                    }
+                    Err(err) => {
+                        self.notify(Severity::Error, err.to_string());
+                        break 'play;
+                    }
+                }
+            }
+        }
+        self.playing -= 1;
+        if self.playing == 0 {
+            self.macro_aborted = false;
+        }
+        quit
+    }
+
+    fn save_macro(&mut self, arg: &str) {
+        let mut args = arg.split_whitespace();
+        let (Some(name), from) = (args.next(), args.next()) else {
+            self.notify(Severity::Error, tr(Text::MacroNameRequired).to_string());
+            return;
+        };
+        if !macros::is_valid_name(name) {
+            self.notify(Severity::Error, trf(Text::InvalidMacroName, &[&name]));
+            return;
+        }
+        let Some(from) = from.or(self.macros.last()).map(str::to_string) else {
+            self.notify(Severity::Warning, tr(Text::NoLastMacro).to_string());
+            return;
+        };
+        match self.macros.save(name, &from) {
+            Ok(true) => self.notify(Severity::Info, trf(Text::MacroSaved, &[&name])),
+            Ok(false) => self.notify(Severity::Error, trf(Text::NoSuchMacro, &[&from])),
+            Err(err) => self.notify(Severity::Error, trf(Text::MacroSaveFailed, &[&err])),
+        }
+    }
+
+    fn delete_macro(&mut self, name: &str) {
+        match self.macros.delete(name) {
+            Ok(true) => self.notify(Severity::Info, trf(Text::MacroDeleted, &[&name])),
+            Ok(false) => self.notify(Severity::Error, trf(Text::NoSuchMacro, &[&name])),
+            Err(err) => self.notify(Severity::Error, trf(Text::MacroSaveFailed, &[&err])),
+        }
+    }
+
+    fn show_macros(&mut self) {
+        let lines = self.macros.list();
+        if lines.is_empty() {
+            self.notify(Severity::Info, tr(Text::MacrosEmpty).to_string());
+            return;
+        }
+        self.open_scratch(tr(Text::MacrosTitle), lines);
+    }
+
+    fn copy_selection(&mut self) {
+        let y = self.state.cursor.y;
+        let line = self.state.buffer[y].clone();
+        self.yank(line, false);
+        self.flash_yank(Position { x: 0, y }, Position { x: 0, y }, true);
+    }
+
+    fn paste(&mut self) {
+        if !self.editable() {
+            return;
+        }
+        if let Some(register) = self.take_register() {
+            self.save_snapshot();
+            let start = self.state.cursor;
+            self.insert_text(&register.text);
+            self.last_paste = Some((start, self.state.cursor, 0));
+        }
+    }
+
+    fn paste_ring(&mut self, index: usize) {
+        let Some(register) = self.registers.ring().get(index).cloned() else {
+            return;
+        };
+        if self.editable() {
+            self.save_snapshot();
+            let start = self.state.cursor;
+            self.insert_text(&register.text);
+            self.last_paste = Some((start, self.state.cursor, index));
+        }
+    }
+
+    fn yank_pop(&mut self, last_paste: Option<(Position, Position, usize)>) {
+        let Some((start, end, index)) = last_paste.filter(|&(_, end, _)| end == self.state.cursor) else {
+            self.notify(Severity::Warning, tr(Text::NoPreviousPaste).to_string());
+            return;
+        };
+        if self.registers.ring().is_empty() || !self.editable() {
+            return;
+        }
+        let ring = self.registers.ring();
+        let index = (index + 1) % ring.len();
+        let text = ring[index].text.clone();
+        self.remove_between(start, end);
+        self.insert_text(&text);
+        self.last_paste = Some((start, self.state.cursor, index));
+    }
+
+    fn show_kill_ring(&mut self) {
+        let items: Vec<String> = self
+            .registers
+            .ring()
+            .iter()
+            .map(|r| r.text.replace('\n', "^J"))
+            .collect();
+        if items.is_empty() {
+            self.notify(Severity::Info, tr(Text::KillRingEmpty).to_string());
+            return;
+        }
+        self.picker = Some((Picker::new(tr(Text::KillRingTitle), items), PickerKind::KillRing));
+    }
+
+    fn kill_line(&mut self) {
+        let Position { x, y } = self.state.cursor;
+        let line_len = self.state.buffer[y].len();
+        if (x == line_len && y + 1 == self.state.buffer.len()) || !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+        let killed = if x < line_len {
+            self.state.buffer[y].split_off(x)
+        } else {
+            let next = self.state.buffer.remove(y + 1);
+            self.state.buffer[y].push_str(&next);
+            "\n".to_string()
+        };
+        self.yank(killed, false);
+    }
+
+    fn insert_text(&mut self, text: &str) {
+        let Position { x, y } = self.state.cursor;
+        let rest = self.state.buffer[y].split_off(x);
+        let mut lines = text.split('\n');
+        self.state.buffer[y].push_str(lines.next().unwrap_or(""));
+        let mut row = y;
+        for line in lines {
+            row += 1;
+            self.state.buffer.insert(row, line.to_string());
+        }
+        self.state.cursor = Position {
+            x: self.state.buffer[row].len(),
+            y: row,
+        };
+        self.state.buffer[row].push_str(&rest);
+    }
+
+    fn literal_key(&mut self, code: KeyCode) {
+        let Some(input) = self.literal.as_mut() else {
+            return;
+        };
+        let result = match code {
+            KeyCode::Char(c) => {
+                input.push(c);
+                digraph::feed(input)
+            }
+            KeyCode::Tab if input.is_empty() => digraph::Literal::Done('\t'),
+            KeyCode::Backspace => {
+                input.pop();
+                digraph::Literal::Pending
+            }
+            KeyCode::Enter if input.is_empty() => digraph::Literal::Invalid,
+            KeyCode::Enter => digraph::finish(input).map_or(digraph::Literal::Invalid, digraph::Literal::Done),
+            KeyCode::Esc => {
+                self.literal = None;
+                return;
+            }
+            _ => digraph::Literal::Pending,
+        };
+        match result {
+            digraph::Literal::Pending => {}
+            digraph::Literal::Done(c) => {
+                self.literal = None;
+                self.materialize();
+                if self.editable() {
+                    self.save_snapshot();
+                    self.insert_text(c.encode_utf8(&mut [0; 4]));
+                    self.clamp_cursor();
+                }
+            }
+            digraph::Literal::Invalid => {
+                let input = self.literal.take().unwrap_or_default();
+                if !input.is_empty() {
+                    self.notify(Severity::Warning, trf(Text::UnknownLiteral, &[&input]));
+                }
+            }
+        }
+    }
+
+    fn compose_key(&mut self, key: KeyEvent) -> bool {
+        let plain = !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char(c) if plain && self.options.flag("compose") => {
+                if let Some(text) = self.composer.feed(c) {
+                    for c in text.chars() {
+                        self.insert(c);
+                    }
+                    return true;
+                }
+            }
+            KeyCode::Backspace if !self.composer.is_empty() => {
+                self.composer.backspace();
+                return true;
+            }
+            _ => {}
+        }
+        self.commit_preedit();
+        false
+    }
+
+    fn commit_preedit(&mut self) {
+        for c in self.composer.take().chars() {
+            self.insert(c);
+        }
+    }
+
+    fn describe_char(&mut self) {
+        let line = &self.state.buffer[self.state.cursor.y];
+        let start = self.state.cursor.x.min(line.len());
+        let cluster = &line[start..text::next_boundary(line, start)];
+        if cluster.is_empty() {
+            self.notify(Severity::Info, tr(Text::NoCharacter).to_string());
+            return;
+        }
+        let info: Vec<String> = cluster
+            .chars()
+            .map(|c| {
+                let code = utf8::raw_byte(c).map_or(c as u32, u32::from);
+                let shown = match code {
+                    0..=0x1f | 0x7f => format!("^{}", char::from(code as u8 ^ 0x40)),
+                    _ if code != c as u32 => format!("{:02x}", code),
+                    _ => c.to_string(),
+                };
+                let mut text = trf(Text::CharInfo, &[&shown, &code, &format!("{:04x}", code), &format!("{:o}", code)]);
+                if let Some(name) = digraph::name(c) {
+                    text.push_str(&trf(Text::CharDigraph, &[&name]));
+                }
+                text
+            })
+            .collect();
+        self.notify(Severity::Info, info.join("; "));
+    }
+
+    fn paste_text(&mut self, text: &str) {
+        let mut text = text.replace("\r\n", "\n").replace('\r', "\n");
+        if self.options.flag("normalize") {
+            text = unicode::nfc(&text);
+        }
+        let first = text.split('\n').next().unwrap_or("");
+        if let Some((prompt, _)) = self.prompt.as_mut() {
+            prompt.paste(first);
+        } else if self.mode == Mode::Command {
+            self.command.push_str(first);
+        } else if self.picker.is_none() && self.hex.is_none() {
+            self.materialize();
+            if !self.editable() {
+                return;
+            }
+            self.save_snapshot();
+            self.insert_text(&text);
+            self.clamp_cursor();
+        }
+    }
+
+    fn text_between(&self, start: Position, end: Position) -> String {
+        if start.y == end.y {
+            return self.state.buffer[start.y][start.x..end.x].to_string();
+        }
+        let mut text = self.state.buffer[start.y][start.x..].to_string();
+        for line in &self.state.buffer[start.y + 1..end.y] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&self.state.buffer[end.y][..end.x]);
+        text
+    }
+
+    fn remove_between(&mut self, start: Position, end: Position) {
+        let tail = self.state.buffer[end.y][end.x..].to_string();
+        self.state.buffer.drain(start.y + 1..=end.y);
+        let line = &mut self.state.buffer[start.y];
+        line.truncate(start.x);
+        line.push_str(&tail);
+        self.state.cursor = start;
+    }
+
+    fn write(&mut self, filename: String) -> bool {
+        if self.saves.iter().any(|save| save.filename == filename) {
+            self.notify(Severity::Warning, tr(Text::SaveInProgress).to_string());
+            return false;
+        }
+        match self.save_to_file(filename.clone()) {
+            Ok(()) => {
+                self.notify(Severity::Info, trf(Text::Written, &[&filename]));
+                self.lint(&filename);
+                true
+            }
+            Err(err) => {
+                self.report(Error::write(&filename, err));
+                false
+            }
+        }
+    }
+
+    fn sudo_write(&mut self, password: Option<String>) {
+        let Some(filename) = self.state.filename.clone() else {
+            self.notify(Severity::Error, tr(Text::NoFilename).to_string());
+            return;
+        };
+        if Url::parse(&filename).is_some() || Cipher::detect(&filename).is_some() {
+            self.notify(Severity::Error, tr(Text::SudoLocalOnly).to_string());
+            return;
+        }
+        let program = self.options.string("sudo").to_string();
+        if password.is_none() && !shell::sudo_cached(&program) {
+            self.ask(Prompt::text(Text::SudoPrompt).masked(true), PromptKind::Sudo);
+            return;
+        }
+        let mut bytes = self.file_bytes();
+        if gzip::is_compressed(&filename) {
+            bytes = match gzip::compress(&bytes) {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    self.report(Error::write(&filename, err));
+                    return;
+                }
+            };
+        }
+        match shell::sudo_write(&program, &filename, password.as_deref(), &bytes) {
+            Ok(()) => {
+                self.state.dirty = false;
+                self.last_autosave = Instant::now();
+                self.notify(Severity::Info, trf(Text::Written, &[&filename]));
+            }
+            Err(err) => self.report(Error::write(&filename, err)),
+        }
+    }
+
+    fn format_buffer(&mut self, explicit: bool) {
+        let formatter = self.filetype.and_then(|filetype| self.formatters.get(filetype.name));
+        let Some(command) = formatter.cloned() else {
+            if explicit {
+                self.notify(Severity::Warning, tr(Text::NoFormatter).to_string());
+            }
+            return;
+        };
+        self.materialize();
+        if self.hex.is_some() || !self.editable() {
+            return;
+        }
+        let input = self.state.buffer.join("\n") + "\n";
+        match shell::filter(&command, &input) {
+            Ok(output) if output.status.success() => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let mut lines: Vec<String> = stdout.lines().map(str::to_string).collect();
+                if lines.is_empty() {
+                    lines.push(String::new());
+                }
+                if lines != self.state.buffer {
+                    self.save_snapshot();
+                    self.state.buffer = lines;
+                    self.clamp_cursor();
+                }
+            }
+            Ok(output) => self.notify(Severity::Error, trf(Text::FormatFailed, &[&shell::failure(&output)])),
+            Err(err) => self.notify(Severity::Error, trf(Text::FormatFailed, &[&err])),
+        }
+    }
+
+    fn lint(&mut self, filename: &str) {
+        let filetype = if self.state.filename.as_deref() == Some(filename) {
+            self.filetype
+        } else {
+            let document = self.documents.iter().find(|document| document.state.filename.as_deref() == Some(filename));
+            document.and_then(|document| document.filetype)
+        };
+        let Some(command) = filetype.and_then(|filetype| self.linters.get(filetype.name)) else {
+            return;
+        };
+        if Url::parse(filename).is_some() || Cipher::detect(filename).is_some() || gzip::is_compressed(filename) {
+            return;
+        }
+        let line = command.replace('%', &shell::quote(filename));
+        self.lint_jobs.spawn(move || shell::run(&line));
+    }
+
+    fn report_lint(&mut self, output: io::Result<std::process::Output>) {
+        let output = match output {
+            Ok(output) => output,
+            Err(err) => {
+                self.notify(Severity::Error, trf(Text::LintFailed, &[&err]));
+                return;
+            }
+        };
+        let text = String::from_utf8_lossy(&output.stdout).into_owned() + &String::from_utf8_lossy(&output.stderr);
+        self.diagnostics = diagnostic::parse(&text);
+        match self.diagnostics.len() {
+            0 if !output.status.success() => {
+                self.notify(Severity::Error, trf(Text::LintFailed, &[&shell::failure(&output)]))
+            }
+            0 => self.notify(Severity::Info, tr(Text::LintClean).to_string()),
+            count => self.notify(Severity::Warning, trf(Text::LintProblems, &[&count])),
+        }
+    }
+
+    fn current_diagnostics(&self) -> Vec<usize> {
+        let Some(filename) = self.state.filename.as_deref() else {
+            return Vec::new();
+        };
+        (0..self.diagnostics.len())
+            .filter(|&i| diagnostic::same_file(&self.diagnostics[i].file, filename))
+            .collect()
+    }
+
+    fn show_diagnostics(&mut self) {
+        if self.diagnostics.is_empty() {
+            self.notify(Severity::Info, tr(Text::NoDiagnostics).to_string());
+            return;
+        }
+        let items = self.diagnostics.iter().map(Diagnostic::summary).collect();
+        self.picker = Some((Picker::new(tr(Text::DiagnosticsTitle), items), PickerKind::Diagnostics));
+    }
+
+    fn jump_to_diagnostic(&mut self, index: usize) {
+        let Some(diagnostic) = self.diagnostics.get(index).cloned() else {
+            return;
+        };
+        let here = self.state.filename.as_deref();
+        if !here.is_some_and(|name| diagnostic::same_file(name, &diagnostic.file)) {
+            self.edit_file(diagnostic.file.clone());
+        }
+        let y = diagnostic.line.min(self.state.buffer.len() - 1);
+        let line = &self.state.buffer[y];
+        let x = line.char_indices().nth(diagnostic.column).map_or(line.len(), |(i, _)| i);
+        self.state.cursor = Position { x, y };
+        // Messages too long for the message line are shown in full next to
+        // the cursor as well.
+        let width = (self.layout.width as usize).saturating_sub(4);
+        if width > 0 && (diagnostic.message.contains('\n') || text::width(&diagnostic.message, 1) > width) {
+            let lines = diagnostic.message.lines().flat_map(|line| text::reflow(&[line.to_string()], width, None)).collect();
+            let panel = Panel::new("", lines, Layer::Hover, Placement::Cursor);
+            self.overlay.show(panel, Dismiss::Cursor(self.state.cursor));
+        }
+        self.notify(diagnostic.severity, diagnostic.message);
+    }
+
+    fn next_diagnostic(&mut self, forward: bool) {
+        let mut here: Vec<usize> = self.current_diagnostics();
+        here.sort_by_key(|&i| (self.diagnostics[i].line, self.diagnostics[i].column));
+        let cursor = self.state.cursor;
+        let column = self.state.buffer[cursor.y][..cursor.x].chars().count();
+        let at = |i: &usize| (self.diagnostics[*i].line, self.diagnostics[*i].column);
+        let target = if forward {
+            here.iter().find(|i| at(i) > (cursor.y, column)).or(here.first())
+        } else {
+            here.iter().rev().find(|i| at(i) < (cursor.y, column)).or(here.last())
+        };
+        match target.copied() {
+            Some(index) => self.jump_to_diagnostic(index),
+            None => self.notify(Severity::Info, tr(Text::NoDiagnostics).to_string()),
+        }
+    }
+
+    fn next_conflict(&mut self, forward: bool) {
+        let conflicts = conflict::find(&self.state.buffer);
+        let y = self.state.cursor.y;
+        let target = if forward {
+            conflicts.iter().position(|c| c.start > y).or((!conflicts.is_empty()).then_some(0))
+        } else {
+            conflicts.iter().rposition(|c| c.start < y).or(conflicts.len().checked_sub(1))
+        };
+        match target {
+            Some(index) => {
+                self.state.cursor = Position {
+                    x: 0,
+                    y: conflicts[index].start,
+                };
+                let position = trf(Text::ConflictPosition, &[&(index + 1), &conflicts.len()]);
+                self.notify(Severity::Info, position);
+            }
+            None => self.notify(Severity::Info, tr(Text::NoConflicts).to_string()),
+        }
+    }
+
+    fn resolve_conflict(&mut self, side: conflict::Side) {
+        let y = self.state.cursor.y;
+        let Some(conflict) = conflict::find(&self.state.buffer).into_iter().find(|c| c.contains(y)) else {
+            self.notify(Severity::Warning, tr(Text::NotInConflict).to_string());
+            return;
+        };
+        let lines = conflict.resolve(&self.state.buffer, side);
+        self.replace_conflict(conflict, lines);
+    }
+
+    fn pull_hunk(&mut self, source: merge::Source) {
+        let y = self.state.cursor.y;
+        let Some(merge) = &self.merge else {
+            self.notify(Severity::Warning, tr(Text::MergeOnly).to_string());
+            return;
+        };
+        let Some(conflict) = conflict::find(&self.state.buffer).into_iter().find(|c| c.contains(y)) else {
+            self.notify(Severity::Warning, tr(Text::NotInConflict).to_string());
+            return;
+        };
+        let lines = match (source, conflict.base) {
+            (merge::Source::Local, _) => conflict.resolve(&self.state.buffer, conflict::Side::Ours),
+            (merge::Source::Remote, _) => conflict.resolve(&self.state.buffer, conflict::Side::Theirs),
+            (merge::Source::Base, Some(base)) => self.state.buffer[base + 1..conflict.middle].to_vec(),
+            (merge::Source::Base, None) => {
+                let ours = conflict.resolve(&self.state.buffer, conflict::Side::Ours);
+                let theirs = conflict.resolve(&self.state.buffer, conflict::Side::Theirs);
+                match merge.find(&ours, &theirs) {
+                    Some(hunk) => merge.lines(hunk, merge::Source::Base).to_vec(),
+                    None => {
+                        self.notify(Severity::Warning, tr(Text::MergeNoBase).to_string());
+                        return;
+                    }
+                }
+            }
+        };
+        self.replace_conflict(conflict, lines);
+    }
+
+    fn replace_conflict(&mut self, conflict: conflict::Conflict, mut lines: Vec<String>) {
+        if !self.editable() {
+            return;
+        }
+        if lines.is_empty() && self.state.buffer.len() == conflict.end - conflict.start + 1 {
+            lines.push(String::new());
+        }
+        self.save_snapshot();
+        self.state.buffer.splice(conflict.start..=conflict.end, lines);
+        self.state.cursor = Position {
+            x: 0,
+            y: conflict.start.min(self.state.buffer.len() - 1),
+        };
+    }
+
+    fn word_under_cursor(&self) -> Option<String> {
+        let span = motion::select(&self.state.buffer, self.state.cursor, motion::Object::Word, false, None)?;
+        let word = self.text_between(span.start, span.end);
+        Some(word).filter(|word| word.chars().all(|c| c.is_alphanumeric() || c == '_') && !word.is_empty())
+    }
+
+    fn tag(&mut self, name: &str) {
+        let name = match name {
+            "" => match self.word_under_cursor() {
+                Some(word) => word,
+                None => return self.notify(Severity::Info, tr(Text::NoWordUnderCursor).to_string()),
+            },
+            name => name.to_string(),
+        };
+        let file = self.state.filename.as_deref().map(std::path::Path::new);
+        let dir = file.and_then(|file| file.parent()).filter(|dir| !dir.as_os_str().is_empty());
+        match tags::find(dir.unwrap_or(std::path::Path::new(".")), &name) {
+            None => self.notify(Severity::Error, tr(Text::NoTagsFile).to_string()),
+            Some(tags) if tags.is_empty() => self.notify(Severity::Error, trf(Text::TagNotFound, &[&name])),
+            Some(mut tags) if tags.len() == 1 => self.jump_to_tag(tags.remove(0)),
+            Some(tags) => {
+                let items = tags.iter().map(tags::Tag::summary).collect();
+                let picker = Picker::new(&trf(Text::TagsTitle, &[&name]), items);
+                self.picker = Some((picker, PickerKind::Tags(tags)));
+            }
+        }
+    }
+
+    fn jump_to_tag(&mut self, tag: tags::Tag) {
+        self.tag_stack.push((self.state.filename.clone(), self.state.cursor));
+        let here = self.state.filename.as_deref();
+        if !here.is_some_and(|name| diagnostic::same_file(name, &tag.file)) {
+            self.edit_file(tag.file.clone());
+        }
+        match tags::locate(&self.state.buffer, &tag.address) {
+            Some(y) => {
+                let x = self.state.buffer[y].len() - self.state.buffer[y].trim_start().len();
+                self.state.cursor = Position { x, y };
+            }
+            None => self.notify(Severity::Warning, tr(Text::TagMoved).to_string()),
+        }
+    }
+
+    fn pop_tag(&mut self) {
+        let Some((filename, cursor)) = self.tag_stack.pop() else {
+            return self.notify(Severity::Info, tr(Text::TagStackEmpty).to_string());
+        };
+        if let Some(filename) = filename.filter(|name| self.state.filename.as_ref() != Some(name)) {
+            self.edit_file(filename);
+        }
+        let y = cursor.y.min(self.state.buffer.len() - 1);
+        self.state.cursor = Position {
+            x: text::snap(&self.state.buffer[y], cursor.x),
+            y,
+        };
+    }
+
+    fn refresh_outline(&mut self) {
+        if self.outline.is_none() {
+            return;
+        }
+        let fingerprint = self.update_symbols();
+        if let (Some(outline), Some((_, symbols))) = (self.outline.as_mut(), &self.symbols) {
+            outline.update(fingerprint, symbols, self.state.cursor.y);
+        }
+    }
+
+    fn toggle_outline(&mut self) {
+        match &mut self.outline {
+            None => self.outline = Some(Outline::new()),
+            Some(outline) if !outline.focused => outline.focused = true,
+            Some(_) => self.outline = None,
+        }
+        self.refresh_outline();
+    }
+
+    fn outline_key(&mut self, code: KeyCode) {
+        let Some(y) = self.outline.as_mut().and_then(|outline| outline.handle_key(code)) else {
+            return;
+        };
+        let y = y.min(self.state.buffer.len() - 1);
+        let line = &self.state.buffer[y];
+        self.state.cursor = Position {
+            x: line.len() - line.trim_start().len(),
+            y,
+        };
+    }
+
+    fn make_program(&self) -> String {
+        let configured = self.filetype.and_then(|filetype| self.make_programs.get(filetype.name));
+        if let Some(command) = configured.or_else(|| self.make_programs.get("")) {
+            return command.clone();
+        }
+        let exists = |name: &str| std::path::Path::new(name).exists();
+        if exists("Cargo.toml") {
+            "cargo build".to_string()
+        } else if exists("package.json") && !exists("Makefile") {
+            "npm run build".to_string()
+        } else {
+            "make".to_string()
+        }
+    }
+
+    fn make(&mut self, arg: &str) {
+        let program = self.make_program();
+        let line = if arg.is_empty() { program } else { format!("{} {}", program, arg) };
+        self.run_in_output(shell::command(&line), line);
+    }
+
+    fn run_in_output(&mut self, command: std::process::Command, line: String) {
+        if let Some((mut stream, _)) = self.build.take() {
+            stream.kill();
+        }
+        match Stream::spawn(command) {
+            Ok(stream) => {
+                self.output = Some(OutputPane::new(trf(Text::MakeRunning, &[&line])));
+                self.build = Some((stream, line));
+            }
+            Err(err) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&err])),
+        }
+    }
+
+    fn poll_build(&mut self) {
+        let Some((stream, line)) = self.build.as_mut() else {
+            return;
+        };
+        let (lines, status) = stream.poll();
+        let line = line.clone();
+        if let Some(pane) = self.output.as_mut() {
+            for text in &lines {
+                pane.push(text);
+            }
+        }
+        let Some(status) = status else {
+            return;
+        };
+        self.build = None;
+        let Some(pane) = self.output.as_mut() else {
+            return;
+        };
+        self.diagnostics = diagnostic::parse(&pane.lines().join("\n"));
+        let count = self.diagnostics.len();
+        match status {
+            Ok(status) if status.success() => {
+                pane.set_title(trf(Text::MakeDone, &[&line]));
+                self.notify(Severity::Info, trf(Text::MakeSucceeded, &[&count]));
+            }
+            Ok(status) => {
+                let code = status.code().map_or_else(|| status.to_string(), |code| code.to_string());
+                pane.set_title(trf(Text::MakeExited, &[&line, &code]));
+                self.notify(Severity::Error, trf(Text::MakeFailed, &[&code, &count]));
+            }
+            Err(err) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&err])),
+        }
+    }
+
+    fn task_dir(&self) -> std::path::PathBuf {
+        let file = self.state.filename.as_deref().map(std::path::Path::new);
+        let dir = file.and_then(|file| file.parent()).filter(|dir| !dir.as_os_str().is_empty());
+        tasks::root(dir.unwrap_or(std::path::Path::new(".")))
+    }
+
+    fn show_tasks(&mut self) {
+        let mut items = tasks::detect(&self.task_dir());
+        if let Some((last, _)) = &self.last_task {
+            items.retain(|task| task != last);
+            items.insert(0, last.clone());
+        }
+        if items.is_empty() {
+            self.notify(Severity::Info, tr(Text::NoTasks).to_string());
+            return;
+        }
+        let picker = Picker::new(tr(Text::TasksTitle), items.clone());
+        self.picker = Some((picker, PickerKind::Tasks(items)));
+    }
+
+    fn run_task(&mut self, task: String) {
+        let dir = match &self.last_task {
+            Some((last, dir)) if *last == task => dir.clone(),
+            _ => self.task_dir(),
+        };
+        let mut command = shell::command(&task);
+        command.current_dir(&dir);
+        self.last_task = Some((task.clone(), dir));
+        self.run_in_output(command, task);
+    }
+
+    fn rerun_task(&mut self) {
+        match self.last_task.clone() {
+            Some((task, _)) => self.run_task(task),
+            None => self.show_tasks(),
+        }
+    }
+
+    fn toggle_output(&mut self) {
+        match self.output.as_mut() {
+            Some(pane) => pane.visible = !pane.visible,
+            None => self.notify(Severity::Info, tr(Text::NoOutput).to_string()),
+        }
+    }
+
+    fn open_terminal(&mut self, arg: &str) {
+        let (command, name) = if arg.is_empty() {
+            let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+            (std::process::Command::new(&shell), shell)
+        } else {
+            (shell::command(arg), arg.to_string())
+        };
+        let (cols, rows) = match &self.terminal {
+            Some((terminal, _, _)) => terminal.size(),
+            None => (80, 10),
+        };
+        self.terminal = None;
+        match Pty::spawn(command, cols, rows) {
+            Ok(pty) => {
+                self.terminal = Some((Terminal::new(cols, rows), Some(pty), name));
+                self.terminal_focus = true;
+            }
+            Err(err) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&err])),
+        }
+    }
+
+    fn toggle_terminal(&mut self) {
+        match &self.terminal {
+            Some((_, Some(_), _)) => self.terminal_focus = !self.terminal_focus,
+            Some((_, None, _)) => {
+                self.terminal = None;
+                self.terminal_focus = false;
+            }
+            None => self.notify(Severity::Info, tr(Text::NoTerminal).to_string()),
+        }
+    }
+
+    fn poll_terminal(&mut self) {
+        let Some((terminal, Some(pty), name)) = self.terminal.as_mut() else {
+            return;
+        };
+        let (bytes, exited) = pty.poll();
+        terminal.feed(&bytes);
+        if exited {
+            let text = trf(Text::TerminalExited, &[name]);
+            self.terminal.as_mut().unwrap().1 = None;
+            self.terminal_focus = false;
+            self.notify(Severity::Info, text);
+        }
+    }
+
+    fn write_background(&mut self, filename: String) {
+        self.materialize();
+        let size = match &self.hex {
+            Some(hex) => hex.bytes.len(),
+            None => self.state.buffer.iter().map(|line| line.len() + self.newlines.separator().len()).sum(),
+        };
+        let wrapped = Url::parse(&filename).is_some() || Cipher::detect(&filename).is_some() || gzip::is_compressed(&filename);
+        if size < ASYNC_SAVE_BYTES || wrapped {
+            self.write(filename);
+            return;
+        }
+        if self.saves.iter().any(|save| save.filename == filename) {
+            self.notify(Severity::Warning, tr(Text::SaveInProgress).to_string());
+            return;
+        }
+        let contents = match &self.hex {
+            Some(hex) => Contents::Bytes(hex.bytes.clone()),
+            None => Contents::Lines(self.state.buffer.clone(), self.newlines),
+        };
+        let metadata = self.metadata_for(&filename);
+        let replace = self.replaces_link(&filename);
+        self.saves.push(Save::start(filename.clone(), contents, metadata, replace));
+        self.state.filename = Some(filename);
+        self.state.dirty = false;
+        self.last_autosave = Instant::now();
+    }
+
+    fn is_saving(&self) -> bool {
+        self.state.filename.as_ref().is_some_and(|name| self.saves.iter().any(|save| &save.filename == name))
+    }
+
+    fn finish_saves(&mut self) {
+        let (done, pending): (Vec<Save>, Vec<Save>) = self.saves.drain(..).partition(Save::is_finished);
+        self.saves = pending;
+        for save in done {
+            let filename = save.filename.clone();
+            match save.finish() {
+                Ok(()) => {
+                    if let Some(index) = self.document_index(&filename) {
+                        self.saved(index, None);
+                    }
+                    self.notify(Severity::Info, trf(Text::Written, &[&filename]));
+                    self.lint(&filename);
+                }
+                Err(err) => {
+                    self.report(Error::write(&filename, err));
+                    for index in 0..self.documents.len() {
+                        let state = if index == self.current {
+                            &mut self.state
+                        } else {
+                            &mut self.documents[index].state
+                        };
+                        if state.filename.as_deref() == Some(filename.as_str()) {
+                            state.dirty = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn file_bytes(&mut self) -> Vec<u8> {
+        self.materialize();
+        match &self.hex {
+            Some(hex) => hex.bytes.clone(),
+            None => utf8::encode(self.newlines.join(&self.state.buffer)),
+        }
+    }
+
+    fn metadata_for(&self, filename: &str) -> Option<meta::Metadata> {
+        match &self.metadata {
+            Some(metadata) if self.state.filename.as_deref() == Some(filename) => Some(metadata.clone()),
+            _ => meta::capture(filename.as_ref()),
+        }
+    }
+
+    fn replaces_link(&self, filename: &str) -> bool {
+        let link = fs::symlink_metadata(filename).is_ok_and(|metadata| metadata.file_type().is_symlink());
+        link && self.options.string("symlinks") == "replace"
+    }
+
+    fn save_to_file(&mut self, filename: String) -> Result<()> {
+        let bytes = self.file_bytes();
+        let metadata = self.metadata_for(&filename);
+        // A link being replaced stays in place until the new file is complete.
+        let temp = self.replaces_link(&filename).then(|| save::sibling(&filename));
+        let stored = self
+            .store(&filename, temp.as_deref().unwrap_or(&filename), bytes)
+            .and_then(|()| temp.as_ref().map_or(Ok(()), |temp| fs::rename(temp, &filename)));
+        if let Err(err) = stored {
+            if let Some(temp) = &temp {
+                let _ = fs::remove_file(temp);
+            }
+            return Err(err);
+        }
+        self.state.filename = Some(filename);
+        self.state.dirty = false;
+        self.last_autosave = Instant::now();
+        self.saved(self.current, metadata);
+        Ok(())
+    }
+
+    /// Catches a document up with the file it was just written to, whether
+    /// the save ran here or in the background.
+    fn saved(&mut self, index: usize, metadata: Option<meta::Metadata>) {
+        let (state, remote, hex, kind, current) = if index == self.current {
+            (&self.state, &self.remote, &self.hex, &mut self.filetype, &mut self.metadata)
+        } else {
+            let document = &mut self.documents[index];
+            (&document.state, &document.remote, &document.hex, &mut document.filetype, &mut document.metadata)
+        };
+        let Some(filename) = state.filename.clone() else {
+            return;
+        };
+        if remote.is_none() {
+            if let Some(metadata) = &metadata {
+                meta::restore(filename.as_ref(), metadata);
+            }
+            *current = metadata.or_else(|| meta::capture(filename.as_ref()));
+        }
+        if kind.is_none() && hex.is_none() {
+            *kind = filetype::detect(Some(&filename), &state.buffer);
+        }
+        if self.preview.as_ref() == Some(&filename) {
+            self.timers.cancel(Timer::Preview);
+            self.refresh_preview();
+        }
+    }
+
+    fn store(&mut self, filename: &str, path: &str, bytes: Vec<u8>) -> Result<()> {
+        match (Url::parse(filename), Cipher::detect(filename)) {
+            (Some(url), _) => self.store_remote(filename, url, &bytes),
+            (None, Some(cipher)) => self.store_encrypted(path, cipher, &bytes),
+            (None, None) => {
+                let bytes = if gzip::is_compressed(filename) { gzip::compress(&bytes)? } else { bytes };
+                fs::write(path, bytes)?;
+                if self.remote.take().is_some() || self.crypt.take().is_some() {
+                    self.readonly = false;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn store_remote(&mut self, filename: &str, url: Url, bytes: &[u8]) -> Result<()> {
+        let current = self.state.filename.as_deref() == Some(filename);
+        if current && self.readonly && url.writable() && self.remote.is_some() {
+            return Err(io::Error::other(tr(Text::RemoteNotLoaded)));
+        }
+        let result = remote::store(self.options.string(url.program()), &url, bytes);
+        match &result {
+            Ok(()) => self.remote = Some((url, remote::State::Online)),
+            Err(err) if current && url.writable() => self.remote = Some((url, remote::State::Offline(err.to_string()))),
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn store_encrypted(&mut self, filename: &str, cipher: Cipher, bytes: &[u8]) -> Result<()> {
+        let Some((_, Some(secret))) = self.crypt.as_ref().filter(|(current, _)| *current == cipher) else {
+            return Err(io::Error::other(tr(Text::NoSecret)));
+        };
+        crypt::encrypt(cipher, self.options.string(cipher.program()), filename, secret, bytes)
+    }
+
+    fn ask(&mut self, prompt: Prompt, kind: PromptKind) {
+        self.prompt = Some((prompt, kind));
+    }
+
+    /// Acts on an answer to the open prompt; true when the editor should quit.
+    fn answer_prompt(&mut self, event: PromptEvent) -> bool {
+        if let PromptEvent::Pending = event {
+            return false;
+        }
+        let Some((_, kind)) = self.prompt.take() else {
+            return false;
+        };
+        match (kind, event) {
+            (PromptKind::SaveAs { quit }, PromptEvent::Submitted(name)) => return self.write(name) && quit && self.quit_next(),
+            (PromptKind::SaveOnQuit, PromptEvent::Chosen(0)) => match self.state.filename.clone() {
+                Some(name) => return self.write(name) && self.quit_next(),
+                None => self.ask(Prompt::text(Text::FilenamePrompt), PromptKind::SaveAs { quit: true }),
+            },
+            (PromptKind::SaveOnQuit, PromptEvent::Chosen(_)) => return true,
+            (PromptKind::Unlock, PromptEvent::Submitted(secret)) => self.unlock(secret),
+            (PromptKind::Sudo, PromptEvent::Submitted(password)) => self.sudo_write(Some(password)),
+            (PromptKind::Search { forward }, PromptEvent::Submitted(input)) => {
+                self.recall.reset();
+                self.finish_search(forward, input);
+            }
+            (PromptKind::Search { .. }, _) => self.recall.reset(),
+            (PromptKind::Browse(op), PromptEvent::Submitted(input)) => self.finish_browser_input(op, &input),
+            (PromptKind::Browse(browser::Op::Delete(entry)), PromptEvent::Chosen(0)) => {
+                let name = entry.trim_end_matches('/').to_string();
+                self.finish_browser_input(browser::Op::Delete(entry), &name);
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn ask_save(&mut self, index: usize) {
+        self.switch_to(index);
+        self.ask(Prompt::yes_no(Text::ConfirmSave), PromptKind::SaveOnQuit);
+    }
+
+    /// True when no modified buffer is left to ask about.
+    fn quit_next(&mut self) -> bool {
+        match self.next_dirty() {
+            Some(index) => {
+                self.ask_save(index);
+                false
+            }
+            None => true,
+        }
+    }
+
+    fn prompt_secret(&mut self) {
+        if let Some((cipher, None)) = self.crypt {
+            self.ask(Prompt::text(cipher.prompt()).masked(cipher.masked()), PromptKind::Unlock);
+        }
+    }
+
+    fn prompt_binary(&mut self) {
+        let Some(filename) = self.state.filename.clone().filter(|_| self.hex.is_some()) else {
+            return;
+        };
+        self.notify(Severity::Warning, trf(Text::BinaryFile, &[&filename]));
+        let items = [Text::BinaryReadOnly, Text::BinaryHex, Text::BinaryText].map(|text| tr(text).to_string());
+        self.picker = Some((Picker::new(tr(Text::BinaryTitle), items.to_vec()), PickerKind::Binary));
+    }
+
+    fn open_binary_as(&mut self, choice: usize) {
+        if choice == 1 {
+            return;
+        }
+        let Some(hex) = self.hex.take() else {
+            return;
+        };
+        (self.state.buffer, self.newlines) = Newlines::split(&utf8::decode(&hex.bytes));
+        self.readonly |= choice == 0 || utf8::collides(&hex.bytes);
+        self.clamp_cursor();
+    }
+
+    fn unlock(&mut self, secret: String) {
+        let (Some((cipher, _)), Some(filename)) = (self.crypt.clone(), self.state.filename.clone()) else {
+            return;
+        };
+        if std::path::Path::new(&filename).exists() {
+            match crypt::decrypt(cipher, self.options.string(cipher.program()), &filename, &secret) {
+                Ok(bytes) => {
+                    let (buffer, newlines, hex) = Document::decode(bytes);
+                    self.filetype = if hex.is_some() { None } else { filetype::detect(Some(&filename), &buffer) };
+                    self.state.buffer = buffer;
+                    self.newlines = newlines;
+                    self.state.cursor = Position::default();
+                    self.hex = hex;
+                }
+                Err(err) => {
+                    self.notify(Severity::Error, trf(Text::DecryptFailed, &[&filename, &err]));
+                    self.prompt_secret();
+                    return;
+                }
+            }
+        }
+        self.crypt = Some((cipher, Some(secret)));
+        self.readonly = false;
+    }
+
+    fn fetch_remote(&mut self) {
+        let Some((url, state)) = self.remote.as_mut() else {
+            return;
+        };
+        if *state != remote::State::Queued {
+            return;
+        }
+        *state = remote::State::Fetching;
+        let (url, program) = (url.clone(), self.options.string(url.program()).to_string());
+        let filename = self.state.filename.clone().unwrap_or_default();
+        self.remote_jobs.spawn(move || {
+            let result = remote::fetch(&program, &url);
+            (filename, result)
+        });
+    }
+
+    fn finish_fetch(&mut self, filename: String, result: io::Result<Vec<u8>>) {
+        let Some(index) = self.document_index(&filename) else {
+            return;
+        };
+        let mut document = if index == self.current {
+            self.stash()
+        } else {
+            std::mem::take(&mut self.documents[index])
+        };
+        let state = match result {
+            Ok(bytes) => {
+                let (buffer, newlines, hex) = Document::decode(bytes);
+                document.newlines = newlines;
+                document.filetype = if hex.is_some() { None } else { filetype::detect(Some(&filename), &buffer) };
+                document.state = EditorState {
+                    buffer,
+                    filename: Some(filename),
+                    ..EditorState::default()
+                };
+                document.hex = hex;
+                document.undo_stack.clear();
+                document.redo_stack.clear();
+                document.scroll = 0;
+                document.readonly = document.remote.as_ref().is_some_and(|(url, _)| !url.writable());
+                remote::State::Online
+            }
+            Err(err) => {
+                self.notify(Severity::Error, trf(Text::RemoteFailed, &[&filename, &err]));
+                remote::State::Offline(err.to_string())
+            }
+        };
+        if let Some((_, current)) = document.remote.as_mut() {
+            *current = state;
+        }
+        if index == self.current {
+            self.restore(document);
+        } else {
+            self.documents[index] = document;
+        }
+    }
+
+    fn autosave(&mut self) {
+        let interval = self.options.int("autosave") as u64;
+        if interval == 0 || !self.state.dirty || self.readonly || self.last_autosave.elapsed().as_secs() < interval {
+            return;
+        }
+        self.last_autosave = Instant::now();
+        if let Some(name) = self.state.filename.clone() {
+            if self.is_saving() {
+                return;
+            }
+            self.write_background(name);
+        }
+    }
+
+    pub fn next_timeout(&self) -> Duration {
+        let timeout = self.timers.timeout(Instant::now()).unwrap_or(IDLE_TIMEOUT);
+        let terminal = matches!(self.terminal, Some((_, Some(_), _)));
+        let lsp = self.rename_jobs.running() > 0;
+        let remote = self.remote_jobs.running() > 0 || matches!(self.remote, Some((_, remote::State::Queued)));
+        let busy = self.jobs.running() > 0 || self.lint_jobs.running() > 0 || self.build.is_some() || terminal || lsp || remote;
+        if busy || !self.saves.is_empty() {
+            timeout.min(JOB_POLL)
+        } else {
+            timeout
+        }
+    }
+
+    pub fn wake(&mut self) {
+        self.cursor_visible = true;
+        self.timers.cancel(Timer::Blink);
+    }
+
+    pub fn tick(&mut self) {
+        for timer in self.timers.take_due(Instant::now()) {
+            match timer {
+                Timer::Autosave => self.autosave(),
+                Timer::Blink => self.cursor_visible = !self.cursor_visible,
+                Timer::Preview => self.refresh_preview(),
+                Timer::Flash => self.flash = None,
+                Timer::Bell => self.bell = false,
+                Timer::WhichKey => self.which_key = true,
+                Timer::Chord => {
+                    self.finish_chord();
+                }
+            }
+        }
+        while let Some(output) = self.jobs.try_recv() {
+            self.report_shell(output);
+        }
+        while let Some(output) = self.lint_jobs.try_recv() {
+            self.report_lint(output);
+        }
+        while let Some((filename, text, result)) = self.rename_jobs.try_recv() {
+            self.preview_rename(&filename, &text, result);
+        }
+        while let Some((filename, result)) = self.remote_jobs.try_recv() {
+            self.finish_fetch(filename, result);
+        }
+        self.fetch_remote();
+        self.poll_build();
+        self.poll_terminal();
+        self.finish_saves();
+
+        let interval = self.options.int("autosave") as u64;
+        if interval > 0 && self.state.dirty && !self.readonly {
+            self.timers.schedule_at(Timer::Autosave, self.last_autosave + Duration::from_secs(interval));
+        } else {
+            self.timers.cancel(Timer::Autosave);
+        }
+        if !self.options.flag("cursorblink") {
+            self.cursor_visible = true;
+            self.timers.cancel(Timer::Blink);
+        } else if !self.timers.is_scheduled(Timer::Blink) {
+            self.timers.schedule(Timer::Blink, BLINK_INTERVAL);
+        }
+    }
+
+    fn viewport(&self) -> (usize, usize) {
+        match (&self.lazy, &self.hex) {
+            (Some(lazy), _) => (lazy.top, lazy.indexed_lines()),
+            (None, Some(hex)) => (hex.top(), hex.rows()),
+            (None, None) => (self.scroll, self.state.buffer.len()),
+        }
+    }
+
+    fn position_label(&self) -> String {
+        let (top, total) = self.viewport();
+        if total <= self.view_height {
+            tr(Text::ViewAll).to_string()
+        } else if top == 0 {
+            tr(Text::ViewTop).to_string()
+        } else if top + self.view_height >= total {
+            tr(Text::ViewBottom).to_string()
+        } else {
+            format!("%{}", top * 100 / (total - self.view_height))
+        }
+    }
+
+    fn draw_scrollbar(&self, screen: &mut Screen, top: u16, rows: usize, right: u16) {
+        let (first, total) = self.viewport();
+        if rows == 0 || total <= rows {
+            return;
+        }
+        let x = screen.width().saturating_sub(1 + right);
+        let thumb = (rows * rows / total).max(1);
+        let start = (first * rows / total).min(rows - thumb);
+        for y in 0..rows {
+            let ch = if (start..start + thumb).contains(&y) { '█' } else { '│' };
+            screen.put(x, top + y as u16, ch, Style::default());
+        }
+    }
+
+    fn status_line(&self, width: usize) -> (String, Vec<statusline::Span>) {
+        let spec = self.options.string("statusline");
+        if !spec.is_empty() {
+            return statusline::format(spec, width, |name| self.status_segment(name));
+        }
+        let state = tr(if self.state.dirty { Text::Modified } else { Text::Saved });
+        let prefix = format!("[{}] {:?} | ", state, self.state.filename);
+        let line = match (&self.lazy, &self.hex) {
+            (Some(lazy), _) => format!(
+                "{} {}/{}{}",
+                tr(Text::Line),
+                self.state.cursor.y + 1,
+                lazy.indexed_lines(),
+                if lazy.is_indexed() { "" } else { "+" }
+            ),
+            (None, Some(hex)) => format!("{} {:08x}/{:08x}", tr(Text::HexOffset), hex.cursor, hex.bytes.len()),
+            (None, None) => format!("{} {}/{}", tr(Text::Line), self.state.cursor.y + 1, self.state.buffer.len()),
+        };
+        let start = text::width(&prefix, 1);
+        let mut spans = vec![(start, start + text::width(&line, 1), "position".to_string())];
+        let mut status = format!("{}{} | {}", prefix, line, self.position_label());
+        if self.lazy.is_none() && self.hex.is_none() {
+            self.mode_flags(&mut status, &mut spans);
+        }
+        (status, spans)
+    }
+
+    fn pending_keys(&self) -> Option<String> {
+        if !self.chord.is_empty() || self.chord_count.is_some() {
+            let count = self.chord_count.map(|count| count.to_string()).unwrap_or_default();
+            return Some(format!("{}{}", count, Key::sequence_name(&self.chord)));
+        }
+        self.normal.pending().filter(|_| self.mode == Mode::Normal).map(str::to_string)
+    }
+
+    fn status_segment(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "mode" => tr(if self.mode == Mode::Normal { Text::ModeNormal } else { Text::ModeInsert }).to_string(),
+            "file" => self.state.filename.clone().unwrap_or_else(|| self.state.name(self.label.as_deref())),
+            "modified" => if self.state.dirty { "[+]" } else { "" }.to_string(),
+            "branch" => statusline::branch(self.state.filename.as_deref()).unwrap_or_default(),
+            "position" => match &self.hex {
+                Some(hex) => format!("{:08x}", hex.cursor),
+                None => {
+                    let line = self.state.buffer.get(self.state.cursor.y).map_or("", String::as_str);
+                    let column = line.get(..self.state.cursor.x).map_or(self.state.cursor.x, |text| text.chars().count());
+                    format!("{}:{}", self.state.cursor.y + 1, column + 1)
+                }
+            },
+            "scroll" => self.position_label(),
+            "keys" => self.pending_keys().unwrap_or_default(),
+            "filetype" => self.filetype.map(|filetype| filetype.name.to_string()).unwrap_or_default(),
+            "encoding" => if self.hex.is_some() { "binary" } else { "utf-8" }.to_string(),
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    fn mode_flags(&self, flags: &mut String, spans: &mut Vec<statusline::Span>) {
+        if self.base_mode() == Mode::Normal {
+            let mode = if self.mode == Mode::Normal { Text::ModeNormal } else { Text::ModeInsert };
+            flags.push_str(&format!(" | {}", tr(mode)));
+        }
+        if self.overwrite {
+            flags.push_str(&format!(" | {}", tr(Text::Overwrite)));
+        }
+        if self.options.flag("paste") {
+            flags.push_str(&format!(" | {}", tr(Text::PasteMode)));
+        }
+        if let Some(name) = self.macros.recording() {
+            flags.push_str(&format!(" | {}", trf(Text::Recording, &[&name])));
+        }
+        if let (Some(_), Some((_, matches))) = (&self.search, &self.search_count) {
+            let cursor = (self.state.cursor.y, self.state.cursor.x);
+            let index = matches.partition_point(|position| (position.y, position.x) <= cursor);
+            match matches.len() {
+                0 => {}
+                total if total > MAX_MATCH_COUNT => flags.push_str(&format!(" | [{}/>{}]", index, MAX_MATCH_COUNT)),
+                total => flags.push_str(&format!(" | [{}/{}]", index, total)),
+            }
+        }
+        if let Some(filetype) = self.filetype {
+            flags.push_str(" | ");
+            let start = text::width(flags, 1);
+            spans.push((start, start + text::width(filetype.name, 1), "filetype".to_string()));
+            flags.push_str(filetype.name);
+            if let Some((_, _, scope)) = self.breadcrumbs.as_ref().filter(|(_, _, scope)| !scope.is_empty()) {
+                flags.push_str(&format!(" | {}", scope));
+            }
+        }
+        match &self.remote {
+            Some((_, remote::State::Queued | remote::State::Fetching)) => flags.push_str(&format!(" | {}", tr(Text::RemoteFetching))),
+            Some((_, remote::State::Offline(_))) => flags.push_str(&format!(" | {}", tr(Text::RemoteOffline))),
+            _ => {}
+        }
+        if let Some(target) = self.state.filename.as_deref().and_then(|name| fs::read_link(name).ok()) {
+            let link = match self.options.string("symlinks") {
+                "replace" => tr(Text::SymlinkReplace).to_string(),
+                _ => trf(Text::SymlinkFollow, &[&target.display()]),
+            };
+            flags.push_str(&format!(" | {}", link));
+        }
+        match &self.crypt {
+            Some((_, None)) => flags.push_str(&format!(" | {}", tr(Text::Locked))),
+            Some((_, Some(_))) => flags.push_str(&format!(" | {}", tr(Text::Encrypted))),
+            None => {}
+        }
+        for save in &self.saves {
+            if self.state.filename.as_ref() == Some(&save.filename) {
+                flags.push_str(&format!(" | {}", trf(Text::Saving, &[&save.percent()])));
+            }
+        }
+    }
+
+    fn line_rows(&self, y: usize, width: usize) -> usize {
+        if self.options.flag("wrap") {
+            text::width(&self.state.buffer[y], self.options.int("tabwidth")) / width + 1
+        } else {
+            1
+        }
+    }
+
+    fn place_view(&mut self, at: vim::ViewAt) {
+        let height = self.view_height.max(1);
+        let y = self.state.cursor.y;
+        let margin = self.options.int("scrolloff").min((height - 1) / 2);
+        self.scroll = match at {
+            vim::ViewAt::Center => y.saturating_sub(height / 2),
+            vim::ViewAt::Top => y.saturating_sub(margin),
+            vim::ViewAt::Bottom => (y + margin + 1).saturating_sub(height),
+        };
+    }
+
+    fn follow_cursor(&mut self, height: usize, width: usize) {
+        let height = height.max(1);
+        let Position { x, y } = self.state.cursor;
+        let column = text::column(&self.state.buffer[y], x, self.options.int("tabwidth"));
+        let last = self.state.buffer.len() - 1;
+        let margin = self.options.int("scrolloff").min((height - 1) / 2);
+        let below = margin.min(last - y);
+        if y < self.scroll + margin {
+            self.scroll = y.saturating_sub(margin);
+        } else if y + below >= self.scroll + height {
+            self.scroll = y + below + 1 - height;
+        }
+        if self.options.flag("wrap") {
+            self.hscroll = 0;
+            while self.scroll < y.saturating_sub(margin)
+                && (self.scroll..y).map(|i| self.line_rows(i, width)).sum::<usize>() + column / width + below >= height
+            {
+                self.scroll += 1;
+            }
+        } else if column < self.hscroll {
+            self.hscroll = column;
+        } else if column >= self.hscroll + width {
+            self.hscroll = column + 1 - width;
+        }
+    }
+
+    /// Rows for each pane: a third of the screen until resized.
+    fn pane_rows(&self, height: u16, top: u16, panes: usize) -> usize {
+        let height = height as usize;
+        let Some((rows, total)) = self.pane_size else {
+            return (height / 3).max(4);
+        };
+        (rows * height / (total as usize).max(1)).min(Self::most_pane_rows(height, top, panes)).max(MIN_PANE_ROWS)
+    }
+
+    fn most_pane_rows(height: usize, top: u16, panes: usize) -> usize {
+        height.saturating_sub(5 + top as usize + MIN_TEXT_ROWS) / panes.max(1)
+    }
+
+    /// Outline width: a quarter of the screen until resized.
+    fn sidebar_columns(&self, width: u16) -> u16 {
+        let Some((columns, total)) = self.sidebar_size else {
+            return (width / 4).clamp(16, 40).min(width / 2);
+        };
+        let scaled = (columns as usize * width as usize / (total as usize).max(1)) as u16;
+        scaled.min(Self::most_sidebar_columns(width)).max(MIN_SIDEBAR_COLUMNS).min(width / 2)
+    }
+
+    fn most_sidebar_columns(width: u16) -> u16 {
+        width.saturating_sub(MIN_TEXT_COLUMNS)
+    }
+
+    fn resize_panes(&mut self, rows: isize) {
+        let layout = self.layout;
+        if layout.split > 0 {
+            let split = layout.split.saturating_add_signed(rows).min(layout.most_split).max(MIN_PANE_ROWS);
+            self.pane_size = Some((split, layout.height));
+            self.layout.split = split;
+        }
+    }
+
+    fn resize_sidebar(&mut self, columns: i16) {
+        let layout = self.layout;
+        if layout.sidebar > 0 {
+            let sidebar = layout.sidebar.saturating_add_signed(columns).min(layout.most_sidebar).max(MIN_SIDEBAR_COLUMNS);
+            self.sidebar_size = Some((sidebar, layout.width));
+            self.layout.sidebar = sidebar;
+        }
+    }
+
+    fn separator_at(&self, column: u16, row: u16) -> Option<Separator> {
+        let layout = self.layout;
+        let pane_titles = (0..layout.panes).map(|n| layout.panes_top + (n * layout.split) as u16);
+        if layout.split > 0 && pane_titles.clone().any(|y| y == row) {
+            return Some(Separator::Panes);
+        }
+        let text = layout.text_top..layout.text_top + layout.text_rows;
+        (layout.sidebar > 0 && column == layout.width - layout.sidebar && text.contains(&row)).then_some(Separator::Sidebar)
+    }
+
+    fn drag_separator(&mut self, separator: Separator, column: u16, row: u16) {
+        let layout = self.layout;
+        match separator {
+            Separator::Panes => {
+                let end = layout.panes_top as usize + layout.panes * layout.split;
+                let rows = end.saturating_sub(row as usize) / layout.panes.max(1);
+                self.pane_size = Some((rows.max(MIN_PANE_ROWS), layout.height));
+            }
+            Separator::Sidebar => {
+                let columns = layout.width.saturating_sub(column);
+                self.sidebar_size = Some((columns.max(MIN_SIDEBAR_COLUMNS), layout.width));
+            }
+        }
+    }
+
+    fn draw_tabs(&mut self, screen: &mut Screen) {
+        self.tab_spans.clear();
+        let mut x = 0;
+        for (i, title) in self.document_titles().into_iter().enumerate() {
+            let style = Style {
+                reverse: i != self.current,
+                bold: i == self.current,
+                ..Style::default()
+            };
+            let end = screen.print(x, 0, &format!(" {} ", title), style);
+            self.tab_spans.push((x, end, i));
+            x = end + 1;
+        }
+    }
+
+    pub fn draw(&mut self, screen: &mut Screen) -> Result<()> {
+        screen.clear();
+        screen.cursor_shape = CursorShape::parse(self.options.string("cursorshape")).unwrap_or_default();
+        screen.passthrough.append(&mut self.clipboard);
+        let top = if self.documents.len() > 1 {
+            self.draw_tabs(screen);
+            1
+        } else {
+            self.tab_spans.clear();
+            0
+        };
+        let tabs = top;
+        let panes = usize::from(self.output.as_ref().is_some_and(|pane| pane.visible)) + usize::from(self.terminal.is_some());
+        let split = self.pane_rows(screen.height(), top, panes + usize::from(self.merge.is_some()));
+        let output_height = match &self.output {
+            Some(pane) if pane.visible => split,
+            _ => 0,
+        };
+        let terminal_height = if self.terminal.is_some() { split } else { 0 };
+        let top = match &mut self.merge {
+            Some(merge) => {
+                let y = self.state.cursor.y;
+                if let Some(conflict) = conflict::find(&self.state.buffer).into_iter().find(|c| c.contains(y)) {
+                    let ours = conflict.resolve(&self.state.buffer, conflict::Side::Ours);
+                    let theirs = conflict.resolve(&self.state.buffer, conflict::Side::Theirs);
+                    merge.current = merge.find(&ours, &theirs).or(merge.current);
+                }
+                merge.draw(screen, top, split as u16);
+                top + split as u16
+            }
+            None => top,
+        };
+        let pane_height = output_height + terminal_height;
+        self.view_height = (screen.height() as usize).saturating_sub(5 + top as usize + pane_height);
+        let height = self.view_height;
+        let outline_width = match &self.outline {
+            Some(_) if self.lazy.is_none() && self.hex.is_none() => self.sidebar_columns(screen.width()),
+            _ => 0,
+        };
+        self.layout = Layout {
+            width: screen.width(),
+            height: screen.height(),
+            split: if pane_height > 0 || self.merge.is_some() { split } else { 0 },
+            most_split: Self::most_pane_rows(screen.height() as usize, tabs, panes + usize::from(self.merge.is_some())),
+            panes,
+            panes_top: top + height as u16,
+            text_top: top,
+            text_rows: height as u16,
+            sidebar: outline_width,
+            most_sidebar: Self::most_sidebar_columns(screen.width()).min(screen.width() / 2),
+        };
+        let plain = Style::default();
+        let mut rows = 0;
+        let mut cursor = None;
+        self.text_rows.clear();
+
+        if let Some(lazy) = self.lazy.as_mut() {
+            for i in 0..height {
+                let Some(line) = lazy.line(lazy.top + i)? else {
+                    break;
+                };
+                if lazy.top + i == self.state.cursor.y {
+                    cursor = Some((0, top + rows as u16));
+                }
+                screen.print(0, top + rows as u16, &line, plain);
+                rows += 1;
+            }
+        } else if let Some(hex) = &self.hex {
+            for row in hex.visible_rows(height) {
+                if row == hex.cursor_row() {
+                    cursor = Some((hex.cursor_column() as u16, top + rows as u16));
+                }
+                screen.print(0, top + rows as u16, &hex.row_text(row), plain);
+                rows += 1;
+            }
+        } else {
+            let tabwidth = self.options.int("tabwidth");
+            let wrap = self.options.flag("wrap");
+            let mut marks = BTreeMap::new();
+            for i in self.current_diagnostics() {
+                let diagnostic = &self.diagnostics[i];
+                let mark = marks.entry(diagnostic.line).or_insert(diagnostic.severity);
+                if diagnostic.severity == Severity::Error || *mark == Severity::Info {
+                    *mark = diagnostic.severity;
+                }
+            }
+            let signs = usize::from(!marks.is_empty());
+            let gutter = signs
+                + if self.options.flag("number") {
+                    self.state.buffer.len().to_string().len() + 1
+                } else {
+                    0
+                };
+            let reserved = usize::from(self.options.flag("scrollbar")) + outline_width as usize;
+            let width = (screen.width() as usize).saturating_sub(gutter + reserved).max(1);
+            if !self.free_scroll {
+                self.follow_cursor(height, width);
+            }
+            self.text_origin = (top, gutter as u16);
+            let selection = self.selection_range();
+            let number = Style {
+                fg: Some(Color::DarkGrey),
+                ..Style::default()
+            };
+            let columns: Vec<usize> = self
+                .options
+                .string("colorcolumn")
+                .split(',')
+                .filter_map(|column| column.trim().parse::<usize>().ok())
+                .filter(|&column| column > 0)
+                .collect();
+            let conflicts = conflict::find(&self.state.buffer);
+            let limit = Some(self.options.int("textwidth")).filter(|_| self.options.flag("overlength"));
+            let flash_color = Color::AnsiValue(self.options.int("yankcolor").min(255) as u8);
+            let current_match = self.confirmation.as_ref().and_then(|confirmation| {
+                let m = confirmation.current.as_ref()?;
+                let line = &self.state.buffer[confirmation.at.y];
+                let start = text::column(line, m.start, tabwidth);
+                Some((confirmation.at.y, start..text::column(line, m.end, tabwidth).max(start + 1)))
+            });
+            for (i, line) in self.state.buffer.iter().enumerate().skip(self.scroll) {
+                if rows >= height {
+                    break;
+                }
+                let segments: Vec<Vec<char>> = if wrap {
+                    let cells = text::window(line, tabwidth, 0, (height - rows) * width);
+                    let rows = if cells.len() < (height - rows) * width { cells.len() / width + 1 } else { height - rows };
+                    (0..rows).map(|n| cells[n * width..((n + 1) * width).min(cells.len())].to_vec()).collect()
+                } else {
+                    vec![text::window(line, tabwidth, self.hscroll, width)]
+                };
+                if i == self.state.cursor.y {
+                    let column = text::column(line, self.state.cursor.x, tabwidth);
+                    let (row, col) = if wrap {
+                        (column / width, column % width)
+                    } else {
+                        (0, column - self.hscroll)
+                    };
+                    if rows + row < height {
+                        cursor = Some(((gutter + col) as u16, top + (rows + row) as u16));
+                    }
+                }
+                for (n, segment) in segments.into_iter().enumerate() {
+                    if rows >= height {
+                        break;
+                    }
+                    let y = top + rows as u16;
+                    if gutter > signs && n == 0 {
+                        screen.print(signs as u16, y, &format!("{:>1$} ", i + 1, gutter - signs - 1), number);
+                    }
+                    if let Some(severity) = marks.get(&i).filter(|_| n == 0) {
+                        screen.print(0, y, "●", severity.style());
+                    }
+                    screen.print(gutter as u16, y, &segment.iter().collect::<String>(), plain);
+                    if let Some(region) = conflicts.iter().find_map(|conflict| conflict.region(i)) {
+                        for x in gutter..gutter + width {
+                            screen.tint(x as u16, y, region.color());
+                        }
+                    }
+                    let offset = if wrap { n * width } else { self.hscroll };
+                    self.text_rows.push((i, offset));
+                    if let Some((_, columns)) = current_match.as_ref().filter(|(y, _)| *y == i) {
+                        for column in columns.clone().filter(|column| (offset..offset + width).contains(column)) {
+                            screen.tint((gutter + column - offset) as u16, y, CURRENT_MATCH);
+                        }
+                    }
+                    if let Some((start, end, linewise)) = self.flash.filter(|(start, end, _)| (start.y..=end.y).contains(&i)) {
+                        let from = if linewise || i > start.y { 0 } else { text::column(line, start.x, tabwidth) };
+                        let to = if linewise || i < end.y { usize::MAX } else { text::column(line, end.x, tabwidth) };
+                        for x in from.max(offset)..to.min(offset + segment.len()) {
+                            screen.tint((gutter + x - offset) as u16, y, flash_color);
+                        }
+                    }
+                    if let Some(motion::Span { start, end, linewise }) = selection.as_ref().filter(|span| (span.start.y..=span.end.y).contains(&i)) {
+                        let from = if *linewise || i > start.y { 0 } else { text::column(line, start.x, tabwidth) };
+                        let to = if *linewise || i < end.y { usize::MAX } else { text::column(line, end.x, tabwidth) };
+                        for x in from.max(offset)..to.min(offset + segment.len()) {
+                            screen.tint((gutter + x - offset) as u16, y, SELECTION);
+                        }
+                    }
+                    if let Some(limit) = limit {
+                        for x in limit.saturating_sub(offset)..segment.len() {
+                            screen.tint((gutter + x) as u16, y, OVERLENGTH);
+                        }
+                    }
+                    if i == self.state.cursor.y && self.options.flag("cursorline") {
+                        for x in gutter..gutter + width {
+                            screen.tint(x as u16, y, TINT);
+                        }
+                    }
+                    for &column in &columns {
+                        if (offset..offset + width).contains(&(column - 1)) {
+                            screen.tint((gutter + column - 1 - offset) as u16, y, TINT);
+                        }
+                    }
+                    rows += 1;
+                }
+            }
+            if let Some((x, _)) = cursor.filter(|_| self.options.flag("cursorcolumn")) {
+                for row in 0..rows {
+                    screen.tint(x, top + row as u16, TINT);
+                }
+            }
+            if let Some((x, y)) = cursor.filter(|_| !self.composer.is_empty()) {
+                let underline = Style {
+                    underline: true,
+                    ..plain
+                };
+                cursor = Some((screen.print(x, y, &self.composer.preedit(), underline), y));
+            }
+        }
+
+        let text_cursor = cursor;
+        self.draw_scrollbar(screen, top, rows, outline_width);
+        if outline_width > 0 {
+            self.refresh_outline();
+            if let Some(outline) = self.outline.as_mut() {
+                outline.draw(screen, screen.width() - outline_width, top, outline_width, height as u16);
+                if outline.focused {
+                    cursor = None;
+                }
+            }
+        }
+        let mut bottom = top + rows as u16;
+        if let Some(pane) = self.output.as_mut().filter(|pane| pane.visible) {
+            pane.draw(screen, top + height as u16, output_height as u16);
+        }
+        if let Some((terminal, pty, name)) = self.terminal.as_mut() {
+            let y = top + (height + output_height) as u16;
+            let size = (screen.width(), terminal_height as u16 - 1);
+            if terminal.size() != size {
+                terminal.resize(size.0, size.1);
+                if let Some(pty) = pty {
+                    pty.resize(size.0, size.1);
+                }
+            }
+            let title = match pty {
+                Some(_) => trf(Text::TerminalTitle, &[name]),
+                None => trf(Text::TerminalExited, &[name]),
+            };
+            let style = Style {
+                reverse: true,
+                ..plain
+            };
+            screen.print(0, y, &format!("{:<1$}", title, screen.width() as usize), style);
+            terminal.draw(screen, y + 1);
+            if self.terminal_focus {
+                let (x, row) = terminal.cursor();
+                cursor = Some((x, y + 1 + row));
+            }
+        }
+        if pane_height > 0 {
+            bottom = top + (height + pane_height) as u16;
+        }
+        let ringing = self.bell && self.options.string("bell") == "status";
+        let status = Style { reverse: ringing, ..plain };
+        self.count_matches();
+        self.update_breadcrumbs();
+        let (line, spans) = self.status_line(screen.width() as usize);
+        screen.print(0, bottom + 1, &format!("{:<1$}", line, screen.width() as usize), status);
+        self.status_row = bottom + 1;
+        self.status_spans = spans;
+        if self.mode == Mode::Command {
+            let end = screen.print(0, bottom + 2, &format!(":{}", self.command), plain);
+            cursor = Some((end, bottom + 2));
+            if let Some(message) = self.messages.current() {
+                screen.print(0, bottom + 3, &message.text, message.severity.style());
+            }
+        } else if let Some(message) = self.messages.current() {
+            screen.print(0, bottom + 2, &message.text, message.severity.style());
+        }
+        if let Some(keys) = self.pending_keys().filter(|_| self.options.flag("showcmd")) {
+            let shown: String = keys.chars().skip(keys.chars().count().saturating_sub(SHOWCMD_WIDTH)).collect();
+            let x = screen.width().saturating_sub(SHOWCMD_WIDTH as u16 + 1);
+            screen.print(x, bottom + 2, &format!("{:<1$}", shown, SHOWCMD_WIDTH), plain);
+        }
+        if let Some((prompt, _)) = &self.prompt {
+            let end = screen.print(0, bottom + 4, &prompt.line(), plain);
+            cursor = Some((end, bottom + 4));
+        }
+        if let Some(input) = &self.literal {
+            let end = screen.print(0, bottom + 4, &trf(Text::LiteralPrompt, &[input]), plain);
+            cursor = Some((end, bottom + 4));
+        }
+        if let Some(confirmation) = &self.confirmation {
+            let end = screen.print(0, bottom + 4, &trf(Text::ConfirmSubstitute, &[&confirmation.replacement]), plain);
+            cursor = Some((end, bottom + 4));
+        }
+
+        let frame = Frame {
+            top,
+            rows: self.view_height as u16,
+            bottom,
+            cursor: text_cursor,
+        };
+        let mut panels = Vec::new();
+        if self.which_key {
+            panels.extend(self.which_key_panel());
+        }
+        if let Some((picker, _)) = self.picker.as_mut() {
+            panels.push(picker.panel(screen, &frame));
+            cursor = None;
+        }
+        self.overlay.cursor_moved(self.state.cursor);
+        self.overlay.draw(screen, &frame, &panels);
+        if self.options.flag("profiling") {
+            self.draw_profile(screen, top);
+        }
+
+        if self.bell && self.options.string("bell") == "screen" {
+            screen.invert();
+        }
+        screen.cursor = cursor.filter(|_| self.cursor_visible);
+        Ok(())
+    }
+
+    fn which_key_entries(&self) -> Vec<(String, &'static str)> {
+        if !self.chord.is_empty() {
+            let continuations = self.keymap.continuations(&self.chord).into_iter();
+            return continuations.map(|(keys, action)| (keys, action.description())).collect();
+        }
+        if self.pending_keys().is_none() {
+            return Vec::new();
+        }
+        self.normal.continuations().into_iter().map(|(keys, text)| (keys, tr(text))).collect()
+    }
+
+    fn schedule_which_key(&mut self) {
+        self.which_key = false;
+        let delay = self.options.int("whichkey") as u64;
+        if delay > 0 && !self.which_key_entries().is_empty() {
+            self.timers.schedule(Timer::WhichKey, Duration::from_millis(delay));
+        } else {
+            self.timers.cancel(Timer::WhichKey);
+        }
+    }
+
+    fn which_key_panel(&self) -> Option<Panel> {
+        let entries = self.which_key_entries();
+        if entries.is_empty() {
+            return None;
+        }
+        let column = entries.iter().map(|(keys, _)| keys.chars().count()).max().unwrap_or(0);
+        let lines = entries.iter().map(|(keys, text)| format!("{:<2$}  {}", keys, text, column)).collect();
+        let title = self.pending_keys().unwrap_or_default();
+        Some(Panel::new(&title, lines, Layer::Hint, Placement::Status).full_width())
+    }
+
+    fn draw_profile(&self, screen: &mut Screen, top: u16) {
+        let undo_bytes = self.undo_stack.iter().chain(&self.redo_stack).map(EditorState::memory).sum();
+        let lines = self.profile.lines(undo_bytes);
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) as u16 + 2;
+        let x = screen.width().saturating_sub(width);
+        let style = Style {
+            reverse: true,
+            ..Style::default()
+        };
+        for (row, line) in lines.iter().enumerate() {
+            screen.print(x, top + row as u16, &format!(" {:<1$}", line, width as usize - 1), style);
+        }
+    }
+
+    fn complete_command(&mut self) {
+        let command = self.command.clone();
+        let Some((name, arg)) = command.split_once(' ') else {
+            return;
+        };
+        if !matches!(name, "e" | "r" | "w" | "wq") {
+            return;
+        }
+        let candidates = complete::paths(arg.trim_start());
+        let completion = match candidates.len() {
+            0 => return,
+            1 => candidates[0].clone(),
+            _ => {
+                let names = candidates.iter().map(|c| complete::file_name(c).to_string()).collect();
+                self.overlay.show(Panel::new("", names, Layer::Completion, Placement::Status), Dismiss::Key);
+                complete::common_prefix(&candidates)
+            }
+        };
+        self.command = format!("{} {}", name, completion);
+    }
+
+    fn read_file(&mut self, path: &str) {
+        if !self.editable() {
+            return;
+        }
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                self.report(Error::read(path, err));
+                return;
+            }
+        };
+        if utf8::collides(&bytes) {
+            return self.notify(Severity::Warning, trf(Text::BinaryFile, &[&path]));
+        }
+        let lines = Document::split_lines(&utf8::decode(&bytes));
+        self.insert_lines_below(lines);
+    }
+
+    fn read_command(&mut self, line: &str) {
+        if line.is_empty() || !self.editable() {
+            return;
+        }
+        match shell::run(line) {
+            Ok(output) if output.status.success() => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                if stdout.is_empty() {
+                    self.notify(Severity::Info, tr(Text::ShellDone).to_string());
+                } else {
+                    self.insert_lines_below(Document::split_lines(&stdout));
+                }
+            }
+            Ok(output) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&shell::failure(&output)])),
+            Err(err) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&err])),
+        }
+    }
+
+    fn insert_lines_below(&mut self, lines: Vec<String>) {
+        self.save_snapshot();
+        let row = self.state.cursor.y + 1;
+        self.state.buffer.splice(row..row, lines);
+        self.state.cursor = Position { x: 0, y: row };
+    }
+
+    fn process_command(&mut self) -> Result<bool> {
+        let cmd = self.command.trim().to_string();
+        self.command.clear();
+        self.mode = Mode::Insert;
+        let quit = self.execute_command(&cmd, 0)?;
+        self.mode = self.base_mode();
+        if self.mode == Mode::Normal {
+            self.clamp_normal();
+        }
+        Ok(quit)
+    }
+
+    fn execute_command(&mut self, cmd: &str, depth: usize) -> Result<bool> {
+        let last = self.state.buffer.len() - 1;
+        let (range, cmd) = match range::parse(cmd, self.state.cursor.y, last) {
+            Ok((range, rest)) => (range, rest.trim_start()),
+            Err(err) => {
+                self.notify(Severity::Error, err);
+                return Ok(false);
+            }
+        };
+        let expanded;
+        let cmd = if let Some(rest) = cmd.strip_prefix("r!") {
+            expanded = format!("r !{}", rest);
+            expanded.as_str()
+        } else if let Some(rest) = cmd.strip_prefix('!') {
+            expanded = format!("! {}", rest);
+            expanded.as_str()
+        } else if let Some(rest) = cmd.strip_prefix('@') {
+            expanded = format!("@ {}", rest);
+            expanded.as_str()
+        } else if let Some((name, rest)) = ["sproject", "sall", "g!", "s", "g", "v"].iter().find_map(|name| {
+            let rest = cmd.strip_prefix(name)?;
+            rest.starts_with(|c: char| !c.is_alphanumeric() && !c.is_whitespace() && c != '!')
+                .then_some((name, rest))
+        }) {
+            expanded = format!("{} {}", name, rest);
+            expanded.as_str()
+        } else {
+            cmd
+        };
+        let (name, arg) = match cmd.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (cmd, ""),
+        };
+        let name = match name {
+            "d" => "delete",
+            "y" => "yank",
+            "reg" => "registers",
+            "pu" => "put",
+            "ft" => "filetype",
+            name => name,
+        };
+        if range.is_some() && !name.is_empty() {
+            let base = name.trim_end_matches('!');
+            if !COMMANDS.iter().any(|spec| spec.range && (spec.name == base || spec.name == name)) {
+                self.notify(Severity::Error, trf(Text::NoRange, &[&name]));
+                return Ok(false);
+            }
+        }
+        let line = LineRange::line(self.state.cursor.y);
+        match name {
+            "w!!" => self.sudo_write(None),
+            "w" if !arg.is_empty() => {
+                self.format_buffer(false);
+                self.write_background(arg.to_string());
+            }
+            "w" => {
+                if let Some(name) = self.state.filename.clone() {
+                    self.format_buffer(false);
+                    self.write_background(name);
+                } else {
+                    self.ask(Prompt::text(Text::FilenamePrompt), PromptKind::SaveAs { quit: false });
+                }
+            }
+            "q" => match self.next_dirty() {
+                Some(index) if !(self.write_stdout && self.state.filename.is_none()) => self.ask_save(index),
+                _ => return Ok(true),
+            },
+            "cq" => {
+                self.abort = true;
+                return Ok(true);
+            }
+            "wq" => {
+                if let Some(name) = self.state.filename.clone() {
+                    self.format_buffer(false);
+                    if self.write(name) {
+                        return Ok(true);
+                    }
+                } else {
+                    self.ask(Prompt::text(Text::FilenamePrompt), PromptKind::SaveAs { quit: true });
+                }
+            }
+            "e" if !arg.is_empty() => self.edit_file(arg.to_string()),
+            "r" if arg.starts_with('!') => self.read_command(arg[1..].trim()),
+            "r" if !arg.is_empty() => self.read_file(arg),
+            "b" => match arg.parse::<usize>() {
+                Ok(n) if (1..=self.documents.len()).contains(&n) => self.switch_to(n - 1),
+                _ => self.notify(Severity::Error, trf(Text::InvalidBuffer, &[&arg])),
+            },
+            "bn" => self.cycle_document(true),
+            "bp" => self.cycle_document(false),
+            "bd" => self.close_document(),
+            "hex" => self.toggle_hex(),
+            "cursor" => self.set_option(&format!("cursorshape={}", arg)),
+            "set" => self.set_options(arg),
+            "messages" => self.show_messages(),
+            "registers" => self.show_registers(),
+            "macros" => self.show_macros(),
+            "marks" => self.show_marks(),
+            "mark" => match arg.chars().next() {
+                Some(name) if arg.len() == 1 => self.set_mark(name),
+                _ => self.notify(Severity::Error, trf(Text::InvalidMark, &[&arg])),
+            },
+            "macrosave" => self.save_macro(arg),
+            "macrodelete" => self.delete_macro(arg),
+            "@" => {
+                self.mode = self.base_mode();
+                return Ok(self.play_macro(arg, 1));
+            }
+            "killring" => self.show_kill_ring(),
+            "preview" => self.open_preview(),
+            "center-view" => self.place_view(vim::ViewAt::Center),
+            "top-view" => self.place_view(vim::ViewAt::Top),
+            "bottom-view" => self.place_view(vim::ViewAt::Bottom),
+            "filetype" => self.set_filetype(arg),
+            "cast" if arg.is_empty() => match self.finish_cast() {
+                Some(err) => self.notify(Severity::Error, err),
+                None => self.notify(Severity::Warning, tr(Text::CastNotRecording).to_string()),
+            },
+            "cast" => {
+                if let Some(err) = self.finish_cast() {
+                    self.notify(Severity::Error, err);
+                }
+                self.cast = Some((Cast::new(), arg.to_string()));
+                self.notify(Severity::Info, trf(Text::CastStarted, &[&arg]));
+            }
+            "delete" => {
+                if self.select_register(arg) {
+                    self.delete_lines(range.unwrap_or(line));
+                }
+            }
+            "yank" => {
+                if self.select_register(arg) {
+                    let range = range.unwrap_or(line);
+                    let lines = self.state.buffer[range.start..=range.end].join("\n");
+                    self.yank(lines, true);
+                    self.flash_yank(Position { x: 0, y: range.start }, Position { x: 0, y: range.end }, true);
+                }
+            }
+            "s" => self.substitute(range.unwrap_or(line), arg),
+            "sort" | "sort!" => {
+                let range = range.unwrap_or(LineRange { start: 0, end: last });
+                self.sort_lines(range, name.ends_with('!'), arg);
+            }
+            "align" if !arg.is_empty() => {
+                let range = range.unwrap_or_else(|| self.paragraph_range());
+                let (all, delimiter) = match arg.strip_prefix('*') {
+                    Some(rest) if !rest.is_empty() => (true, rest),
+                    _ => (false, arg),
+                };
+                if self.editable() {
+                    self.save_snapshot();
+                    text::align(&mut self.state.buffer[range.start..=range.end], delimiter, all);
+                    self.clamp_cursor();
+                }
+            }
+            "reflow" => {
+                let range = range.unwrap_or_else(|| self.paragraph_range());
+                self.reflow(range);
+            }
+            "format" => self.format_buffer(true),
+            "lint" => match self.state.filename.clone() {
+                Some(filename) => self.lint(&filename),
+                None => self.notify(Severity::Warning, tr(Text::NoFilename).to_string()),
+            },
+            "diagnostics" => self.show_diagnostics(),
+            "make" => self.make(arg),
+            "output" => self.toggle_output(),
+            "tasks" => self.show_tasks(),
+            "tag" => self.tag(arg),
+            "rename" if !arg.is_empty() => self.rename(arg),
+            "resolve" if conflict::Side::parse(arg).is_some() => {
+                if let Some(side) = conflict::Side::parse(arg) {
+                    self.resolve_conflict(side);
+                }
+            }
+            "patch" => self.patch(arg),
+            "ascii" => self.describe_char(),
+            "noh" => self.set_search(None),
+            "pull" if merge::Source::parse(arg).is_some() => {
+                if let Some(source) = merge::Source::parse(arg) {
+                    self.pull_hunk(source);
+                }
+            }
+            "outline" => self.toggle_outline(),
+            "pop" => self.pop_tag(),
+            "task" => self.rerun_task(),
+            "term" => self.open_terminal(arg),
+            "sall" => self.replace_everywhere(arg, false),
+            "sproject" => self.replace_everywhere(arg, true),
+            "wa" => self.write_all(),
+            "g" | "g!" | "v" => {
+                let range = range.unwrap_or(LineRange { start: 0, end: last });
+                return self.global(range, arg, name != "g", depth);
+            }
+            "!" => match range {
+                Some(range) => self.filter_lines(range, arg),
+                None => self.run_shell(arg),
+            },
+            "put" => {
+                if self.select_register(arg) {
+                    self.put(false, 1);
+                }
+            }
+            "help" => self.show_help(),
+            "command" => self.define_command(arg),
+            "source" if !arg.is_empty() => return self.source_file(arg, depth),
+            "map" => match arg.rsplit_once(char::is_whitespace) {
+                Some((key, action)) => self.map_key(key, action.trim()),
+                None => self.notify(Severity::Error, tr(Text::MapUsage).to_string()),
+            },
+            "" => {
+                if let Some(range) = range {
+                    self.state.cursor = Position {
+                        x: motion::first_non_blank(&self.state.buffer[range.end]),
+                        y: range.end,
+                    };
+                }
+            }
+            _ => match COMMANDS.iter().find(|spec| spec.name == name) {
+                Some(spec) => self.notify(Severity::Error, trf(Text::Usage, &[&spec.usage])),
+                None => return self.run_user_command(name, arg, depth),
+            },
+        }
+        Ok(false)
+    }
+
+    fn delete_lines(&mut self, range: LineRange) {
+        if !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+        let lines: Vec<String> = self.state.buffer.drain(range.start..=range.end).collect();
+        self.yank(lines.join("\n"), true);
+        if self.state.buffer.is_empty() {
+            self.state.buffer.push(String::new());
+        }
+        self.state.cursor.y = range.start.min(self.state.buffer.len() - 1);
+        self.state.cursor.x = motion::first_non_blank(&self.state.buffer[self.state.cursor.y]);
+    }
+
+    fn compile_pattern(&mut self, pattern: &str, ignore_case: bool) -> Option<Regex> {
+        let pattern = if pattern.is_empty() {
+            match self.last_pattern.clone() {
+                Some(pattern) => pattern,
+                None => {
+                    self.notify(Severity::Error, tr(Text::NoPreviousPattern).to_string());
+                    return None;
+                }
+            }
+        } else {
+            pattern.to_string()
+        };
+        let compiled = if self.options.flag("normalize") {
+            Regex::normalized(&pattern, ignore_case)
+        } else {
+            Regex::new(&pattern, ignore_case)
+        };
+        match compiled {
+            Ok(regex) => {
+                self.last_pattern = Some(pattern);
+                Some(regex)
+            }
+            Err(err) => {
+                self.notify(Severity::Error, err);
+                None
+            }
+        }
+    }
+
+    fn search_matches<'a>(&'a self, regex: &'a Regex) -> impl Iterator<Item = Position> + 'a {
+        self.state
+            .buffer
+            .iter()
+            .enumerate()
+            .flat_map(move |(y, line)| regex.find_iter(line).map(move |m| Position { x: m.start, y }))
+    }
+
+    fn set_search(&mut self, search: Option<(Regex, bool)>) {
+        self.search = search;
+        self.search_count = None;
+    }
+
+    /// Recounts the matches only when the buffer differs from the last count.
+    fn count_matches(&mut self) {
+        let Some((regex, _)) = &self.search else {
+            return;
+        };
+        let fingerprint = self.fingerprint();
+        if self.search_count.as_ref().is_some_and(|(seen, _)| *seen == fingerprint) {
+            return;
+        }
+        let matches = self.search_matches(regex).take(MAX_MATCH_COUNT + 1).collect();
+        self.search_count = Some((fingerprint, matches));
+    }
+
+    fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.filetype.map(|filetype| filetype.name).hash(&mut hasher);
+        self.state.buffer.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Rescans the symbols only when the buffer differs from the last scan,
+    /// returning the fingerprint they were taken from.
+    fn update_symbols(&mut self) -> u64 {
+        let fingerprint = self.fingerprint();
+        if self.symbols.as_ref().is_none_or(|(seen, _)| *seen != fingerprint) {
+            let symbols = outline::symbols(self.filetype.map(|filetype| filetype.name), &self.state.buffer);
+            self.symbols = Some((fingerprint, symbols));
+        }
+        fingerprint
+    }
+
+    fn update_breadcrumbs(&mut self) {
+        if self.filetype.is_none() || !self.options.flag("breadcrumbs") {
+            self.breadcrumbs = None;
+            return;
+        }
+        let fingerprint = self.update_symbols();
+        let line = self.state.cursor.y;
+        if self.breadcrumbs.as_ref().is_some_and(|&(seen, at, _)| seen == fingerprint && at == line) {
+            return;
+        }
+        let symbols = self.symbols.as_ref().map_or(&[][..], |(_, symbols)| symbols.as_slice());
+        let scope: Vec<&str> = outline::scope(symbols, line).iter().map(|symbol| symbol.name.as_str()).collect();
+        self.breadcrumbs = Some((fingerprint, line, scope.join(" > ")));
+    }
+
+    fn start_search(&mut self, forward: bool) {
+        let label = if forward { Text::SearchForward } else { Text::SearchBackward };
+        self.ask(Prompt::text(label).erase_cancels(), PromptKind::Search { forward });
+    }
+
+    fn finish_search(&mut self, forward: bool, input: String) {
+        if !input.is_empty() && self.crypt.is_none() {
+            self.session.add_search(&input);
+        }
+        let ignore_case = self.options.flag("ignorecase");
+        if let Some(regex) = self.compile_pattern(&input, ignore_case) {
+            self.set_search(Some((regex, forward)));
+            self.search_next(false);
+        }
+    }
+
+    fn search_next(&mut self, reverse: bool) {
+        let Some((regex, forward)) = &self.search else {
+            self.notify(Severity::Error, tr(Text::NoPreviousPattern).to_string());
+            return;
+        };
+        let cursor = (self.state.cursor.y, self.state.cursor.x);
+        let matches: Vec<Position> = self.search_matches(regex).collect();
+        let (target, wrapped) = if *forward != reverse {
+            match matches.iter().find(|p| (p.y, p.x) > cursor) {
+                Some(&p) => (Some(p), false),
+                None => (matches.first().copied(), true),
+            }
+        } else {
+            match matches.iter().rev().find(|p| (p.y, p.x) < cursor) {
+                Some(&p) => (Some(p), false),
+                None => (matches.last().copied(), true),
+            }
+        };
+        match target {
+            Some(position) => {
+                self.state.cursor = position;
+                if wrapped {
+                    self.notify(Severity::Info, tr(Text::SearchWrapped).to_string());
+                }
+            }
+            None => {
+                let pattern = self.last_pattern.clone().unwrap_or_default();
+                self.notify(Severity::Error, trf(Text::PatternNotFound, &[&pattern]));
+            }
+        }
+    }
+
+    fn parse_substitution(&mut self, arg: &str) -> Option<(Regex, String, String)> {
+        let mut chars = arg.chars();
+        let delimiter = chars.next()?;
+        let (pattern, rest) = take_pattern(chars.as_str(), delimiter);
+        let (replacement, flags) = take_pattern(rest, delimiter);
+        let mut ignore_case = self.options.flag("ignorecase");
+        for flag in flags.chars() {
+            match flag {
+                'i' => ignore_case = true,
+                'I' => ignore_case = false,
+                _ => {}
+            }
+        }
+        let regex = self.compile_pattern(&pattern, ignore_case)?;
+        Some((regex, replacement, flags.to_string()))
+    }
+
+    fn substitute(&mut self, range: LineRange, arg: &str) {
+        let Some((regex, replacement, flags)) = self.parse_substitution(arg) else {
+            return;
+        };
+        if !self.editable() {
+            return;
+        }
+        let global = flags.contains('g');
+        if flags.contains('c') && !self.in_global {
+            self.confirmation = Some(Confirmation {
+                regex,
+                replacement,
+                global,
+                last_line: range.end,
+                at: Position { x: 0, y: range.start },
+                current: None,
+                before: self.state.clone(),
+                replaced: 0,
+                lines: Vec::new(),
+            });
+            self.next_confirmation();
+            if self.confirmation.is_none() {
+                let pattern = self.last_pattern.clone().unwrap_or_default();
+                self.notify(Severity::Error, trf(Text::PatternNotFound, &[&pattern]));
+            }
+            return;
+        }
+        let (mut total, mut lines) = (0, 0);
+        for y in range.start..=range.end {
+            let (text, count) = regex.replace(&self.state.buffer[y], &replacement, global);
+            if count > 0 {
+                if total == 0 {
+                    self.save_snapshot();
+                }
+                self.state.buffer[y] = text;
+                self.state.cursor = Position {
+                    x: motion::first_non_blank(&self.state.buffer[y]),
+                    y,
+                };
+                total += count;
+                lines += 1;
+            }
+        }
+        if total == 0 {
+            let pattern = self.last_pattern.clone().unwrap_or_default();
+            self.notify(Severity::Error, trf(Text::PatternNotFound, &[&pattern]));
+            return;
+        }
+        if lines > 1 {
+            self.notify(Severity::Info, trf(Text::Substituted, &[&total, &lines]));
+        }
+    }
+
+    fn next_confirmation(&mut self) {
+        let Some(confirmation) = self.confirmation.as_mut() else {
+            return;
+        };
+        while confirmation.at.y <= confirmation.last_line {
+            let Position { x, y } = confirmation.at;
+            if let Some(m) = self.state.buffer.get(y).and_then(|line| confirmation.regex.find_at(line, x)) {
+                self.state.cursor = Position { x: m.start, y };
+                confirmation.current = Some(m);
+                return;
+            }
+            confirmation.at = Position { x: 0, y: y + 1 };
+        }
+        self.finish_confirmation();
+    }
+
+    fn confirm_replacement(&mut self, replace: bool) {
+        let Some(confirmation) = self.confirmation.as_mut() else {
+            return;
+        };
+        let Some(m) = confirmation.current.take() else {
+            return;
+        };
+        let y = confirmation.at.y;
+        let line = &mut self.state.buffer[y];
+        let mut end = m.end;
+        if replace {
+            let text = m.expand(&confirmation.replacement, line);
+            line.replace_range(m.start..m.end, &text);
+            end = m.start + text.len();
+            confirmation.replaced += 1;
+            if confirmation.lines.last() != Some(&y) {
+                confirmation.lines.push(y);
+            }
+        }
+        if end == m.start {
+            end += line[end..].chars().next().map_or(1, char::len_utf8);
+        }
+        confirmation.at = if confirmation.global && end <= line.len() { Position { x: end, y } } else { Position { x: 0, y: y + 1 } };
+        self.next_confirmation();
+    }
+
+    fn finish_confirmation(&mut self) {
+        let Some(confirmation) = self.confirmation.take() else {
+            return;
+        };
+        if let Some(&y) = confirmation.lines.last() {
+            self.state.cursor = Position {
+                x: motion::first_non_blank(&self.state.buffer[y]),
+                y,
+            };
+        }
+        self.clamp_cursor();
+        if confirmation.replaced > 0 {
+            self.push_undo(confirmation.before);
+            self.notify(Severity::Info, trf(Text::Substituted, &[&confirmation.replaced, &confirmation.lines.len()]));
+        }
+    }
+
+    fn confirmation_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('y') => self.confirm_replacement(true),
+            KeyCode::Char('n') => self.confirm_replacement(false),
+            KeyCode::Char('a') => {
+                while self.confirmation.as_ref().is_some_and(|confirmation| confirmation.current.is_some()) {
+                    self.confirm_replacement(true);
+                }
+            }
+            KeyCode::Char('l') => {
+                self.confirm_replacement(true);
+                self.finish_confirmation();
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.finish_confirmation(),
+            _ => {}
+        }
+    }
+
+    fn replace_everywhere(&mut self, arg: &str, project: bool) {
+        let Some((regex, replacement, flags)) = self.parse_substitution(arg) else {
+            return;
+        };
+        let global = flags.contains('g');
+        let mut plan = Vec::new();
+        let mut open_files = Vec::new();
+        for index in 0..self.documents.len() {
+            let document = if index == self.current {
+                None
+            } else {
+                Some(&self.documents[index])
+            };
+            let (state, readonly, special) = match document {
+                Some(d) => (&d.state, d.readonly, d.hex.is_some() || d.lazy.is_some()),
+                None => (&self.state, self.readonly, self.hex.is_some() || self.lazy.is_some()),
+            };
+            if let Some(name) = &state.filename {
+                open_files.push(fs::canonicalize(name).unwrap_or_else(|_| name.into()));
+            }
+            if readonly || special {
+                continue;
+            }
+            for found in replace::find(&regex, &replacement, &state.buffer, global) {
+                let preview = format!(
+                    "{}:{}: {}",
+                    state.title(None),
+                    found.line + 1,
+                    found.preview(&state.buffer[found.line]).trim()
+                );
+                plan.push((ReplaceTarget::Buffer(index), found, preview));
+            }
+        }
+        if project {
+            for path in replace::project_files(std::path::Path::new(".")) {
+                if open_files.contains(&fs::canonicalize(&path).unwrap_or_else(|_| path.clone())) {
+                    continue;
+                }
+                let Some(lines) = replace::read_text(&path) else {
+                    continue;
+                };
+                let name = path.strip_prefix(".").unwrap_or(&path).to_string_lossy().into_owned();
+                for found in replace::find(&regex, &replacement, &lines, global) {
+                    let preview = format!("{}:{}: {}", name, found.line + 1, found.preview(&lines[found.line]).trim());
+                    plan.push((ReplaceTarget::File(name.clone()), found, preview));
+                }
+            }
+        }
+        if plan.is_empty() {
+            let pattern = self.last_pattern.clone().unwrap_or_default();
+            self.notify(Severity::Error, trf(Text::PatternNotFound, &[&pattern]));
+            return;
+        }
+        let items = plan.iter().map(|(_, _, preview)| preview.clone()).collect();
+        let title = trf(Text::ReplaceTitle, &[&plan.len()]);
+        self.picker = Some((Picker::new(&title, items).with_checks(), PickerKind::Replace));
+        self.replace_plan = plan.into_iter().map(|(target, found, _)| (target, found)).collect();
+    }
+
+    fn rename(&mut self, new_name: &str) {
+        let Some(filename) = self.state.filename.clone() else {
+            return self.notify(Severity::Error, tr(Text::NoFilename).to_string());
+        };
+        let filetype = self.filetype.map_or("", |filetype| filetype.name);
+        let Some(server) = self.language_servers.get(filetype).cloned() else {
+            return self.notify(Severity::Error, trf(Text::NoLanguageServer, &[&filetype]));
+        };
+        let file = std::path::PathBuf::from(&filename);
+        let dir = file.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(std::path::Path::new("."));
+        let Position { x, y } = self.state.cursor;
+        let request = lsp::Rename {
+            server,
+            root: tasks::root(dir),
+            file,
+            language: if filetype == "sh" { "shellscript" } else { filetype }.to_string(),
+            text: self.state.buffer.join("\n"),
+            line: y,
+            character: lsp::utf16_column(&self.state.buffer[y], x),
+            new_name: new_name.to_string(),
+        };
+        self.notify(Severity::Info, trf(Text::Renaming, &[&new_name]));
+        let text = request.text.clone();
+        self.rename_jobs.spawn(move || (filename, text, lsp::rename(request)));
+    }
+
+    fn preview_rename(&mut self, filename: &str, text: &str, result: std::result::Result<lsp::WorkspaceEdit, String>) {
+        let files = match result {
+            Ok(files) => files,
+            Err(err) => return self.notify(Severity::Error, trf(Text::RenameFailed, &[&err])),
+        };
+        let changed = (0..self.documents.len()).any(|index| {
+            let state = if index == self.current { &self.state } else { &self.documents[index].state };
+            state.filename.as_deref() == Some(filename) && state.buffer.join("\n") != text
+        });
+        if changed {
+            return self.notify(Severity::Error, tr(Text::RenameOutdated).to_string());
+        }
+        let mut plan = Vec::new();
+        for (path, edits) in files {
+            let name = path.to_string_lossy().into_owned();
+            let open = (0..self.documents.len()).find(|&index| {
+                let state = if index == self.current { &self.state } else { &self.documents[index].state };
+                state.filename.as_deref().is_some_and(|filename| diagnostic::same_file(filename, &name))
+            });
+            let (target, lines, title) = match open {
+                Some(index) => {
+                    let (state, readonly, special) = if index == self.current {
+                        (&self.state, self.readonly, self.hex.is_some() || self.lazy.is_some())
+                    } else {
+                        let document = &self.documents[index];
+                        (&document.state, document.readonly, document.hex.is_some() || document.lazy.is_some())
+                    };
+                    if readonly || special {
+                        continue;
+                    }
+                    (ReplaceTarget::Buffer(index), state.buffer.clone(), state.title(None))
+                }
+                None => match replace::read_text(&path) {
+                    Some(lines) => {
+                        let cwd = std::env::current_dir().unwrap_or_default();
+                        let shown = path.strip_prefix(&cwd).unwrap_or(&path).to_string_lossy().into_owned();
+                        (ReplaceTarget::File(name.clone()), lines, shown)
+                    }
+                    None => continue,
+                },
+            };
+            for edit in edits {
+                let Some(line) = lines.get(edit.line) else {
+                    continue;
+                };
+                let found = Replacement {
+                    line: edit.line,
+                    start: lsp::byte_column(line, edit.start),
+                    end: lsp::byte_column(line, edit.end),
+                    text: edit.text,
+                };
+                let preview = format!("{}:{}: {}", title, found.line + 1, found.preview(line).trim());
+                plan.push((target.clone(), found, preview));
+            }
+        }
+        if plan.is_empty() {
+            return self.notify(Severity::Info, tr(Text::NothingToRename).to_string());
+        }
+        let files = {
+            let mut targets: Vec<&ReplaceTarget> = plan.iter().map(|(target, _, _)| target).collect();
+            targets.dedup();
+            targets.len()
+        };
+        let items = plan.iter().map(|(_, _, preview)| preview.clone()).collect();
+        let title = trf(Text::RenameTitle, &[&plan.len(), &files]);
+        self.replace_plan = plan.into_iter().map(|(target, found, _)| (target, found)).collect();
+        self.picker = Some((Picker::new(&title, items).with_checks(), PickerKind::Replace));
+    }
+
+    fn apply_replacements(&mut self, selected: &[usize]) {
+        let plan = std::mem::take(&mut self.replace_plan);
+        let original = self.current;
+        let mut targets: Vec<&ReplaceTarget> = Vec::new();
+        for &i in selected {
+            if !targets.contains(&&plan[i].0) {
+                targets.push(&plan[i].0);
+            }
+        }
+        let (mut files, mut total) = (0, 0);
+        for target in targets {
+            match target {
+                ReplaceTarget::Buffer(index) => self.switch_to(*index),
+                ReplaceTarget::File(name) => self.edit_file(name.clone()),
+            }
+            let chosen: Vec<&Replacement> = selected
+                .iter()
+                .filter(|&&i| plan[i].0 == *target)
+                .map(|&i| &plan[i].1)
+                .collect();
+            if !self.editable() {
+                continue;
+            }
+            self.save_snapshot();
+            replace::apply(&mut self.state.buffer, &chosen);
+            self.clamp_cursor();
+            files += 1;
+            total += chosen.len();
+        }
+        self.switch_to(original);
+        self.notify(Severity::Info, trf(Text::Replaced, &[&total, &files]));
+    }
+
+    fn patch(&mut self, path: &str) {
+        let lines = if path.is_empty() {
+            self.state.buffer.clone()
+        } else {
+            match fs::read(path) {
+                Ok(bytes) => Document::split_lines(&String::from_utf8_lossy(&bytes)),
+                Err(err) => return self.report(Error::read(path, err)),
+            }
+        };
+        let patches = patch::parse(&lines);
+        let mut plan = Vec::new();
+        for file in &patches {
+            let target = match self.document_index(&file.path) {
+                Some(index) => ReplaceTarget::Buffer(index),
+                None => ReplaceTarget::File(file.path.clone()),
+            };
+            for hunk in &file.hunks {
+                let preview = format!("{} {}", file.path, hunk.summary());
+                plan.push((target.clone(), hunk.clone(), preview));
+            }
+        }
+        if plan.is_empty() {
+            return self.notify(Severity::Info, tr(Text::NoPatch).to_string());
+        }
+        let items = plan.iter().map(|(_, _, preview)| preview.clone()).collect();
+        let title = trf(Text::PatchTitle, &[&plan.len(), &patches.len()]);
+        self.patch_plan = plan.into_iter().map(|(target, hunk, _)| (target, hunk)).collect();
+        self.picker = Some((Picker::new(&title, items).with_checks(), PickerKind::Patch));
+    }
+
+    fn apply_patch(&mut self, selected: &[usize]) {
+        let plan = std::mem::take(&mut self.patch_plan);
+        let original = self.current;
+        let mut targets: Vec<&ReplaceTarget> = Vec::new();
+        for &i in selected {
+            if !targets.contains(&&plan[i].0) {
+                targets.push(&plan[i].0);
+            }
+        }
+        let (mut files, mut rejected) = (0, 0);
+        for target in targets {
+            match target {
+                ReplaceTarget::Buffer(index) => self.switch_to(*index),
+                ReplaceTarget::File(name) => self.edit_file(name.clone()),
+            }
+            if !self.editable() {
+                continue;
+            }
+            let chosen: Vec<&patch::Hunk> = selected
+                .iter()
+                .filter(|&&i| plan[i].0 == *target)
+                .map(|&i| &plan[i].1)
+                .collect();
+            self.save_snapshot();
+            rejected += patch::apply(&mut self.state.buffer, &chosen);
+            self.clamp_cursor();
+            files += 1;
+        }
+        self.switch_to(original);
+        if rejected > 0 {
+            self.notify(Severity::Warning, trf(Text::PatchRejected, &[&rejected, &selected.len()]));
+        } else {
+            self.notify(Severity::Info, trf(Text::Patched, &[&selected.len(), &files]));
+        }
+    }
+
+    fn write_all(&mut self) {
+        let original = self.current;
+        for index in 0..self.documents.len() {
+            let dirty = if index == self.current {
+                self.state.dirty
+            } else {
+                self.documents[index].state.dirty
+            };
+            if !dirty {
+                continue;
+            }
+            self.switch_to(index);
+            if let Some(name) = self.state.filename.clone() {
+                self.write_background(name);
+            }
+        }
+        self.switch_to(original);
+    }
+
+    fn global(&mut self, range: LineRange, arg: &str, invert: bool, depth: usize) -> Result<bool> {
+        if self.in_global {
+            self.notify(Severity::Error, tr(Text::NestedGlobal).to_string());
+            return Ok(false);
+        }
+        let mut chars = arg.chars();
+        let Some(delimiter) = chars.next() else {
+            return Ok(false);
+        };
+        let (pattern, command) = take_pattern(chars.as_str(), delimiter);
+        let ignore_case = self.options.flag("ignorecase");
+        let Some(regex) = self.compile_pattern(&pattern, ignore_case) else {
+            return Ok(false);
+        };
+        let mut marked: Vec<Option<usize>> = (range.start..=range.end)
+            .filter(|&y| regex.find_at(&self.state.buffer[y], 0).is_some() != invert)
+            .map(Some)
+            .collect();
+        if marked.is_empty() {
+            self.notify(Severity::Error, trf(Text::PatternNotFound, &[&pattern]));
+            return Ok(false);
+        }
+        let command = command.trim();
+        if command.is_empty() {
+            let lines = marked
+                .iter()
+                .flatten()
+                .map(|&y| format!("{:>6}  {}", y + 1, self.state.buffer[y]))
+                .collect();
+            self.open_scratch(tr(Text::MatchesTitle), lines);
+            return Ok(false);
+        }
+
+        let before = self.state.clone();
+        let mut seen = before.buffer.clone();
+        self.in_global = true;
+        let mut quit = Ok(false);
+        for i in 0..marked.len() {
+            let Some(y) = marked[i] else {
+                continue;
+            };
+            self.state.cursor = Position { x: 0, y };
+            quit = self.execute_command(command, depth + 1);
+            if !matches!(quit, Ok(false)) {
+                break;
+            }
+            follow_edit(&mut seen, &self.state.buffer, &mut marked[i + 1..]);
+        }
+        self.in_global = false;
+        if self.state.buffer != before.buffer {
+            self.push_undo(before);
+        }
+        self.clamp_cursor();
+        quit
+    }
+
+    fn paragraph_range(&self) -> LineRange {
+        let buffer = &self.state.buffer;
+        let y = self.state.cursor.y;
+        let blank = |i: usize| buffer[i].trim().is_empty();
+        if blank(y) {
+            return LineRange::line(y);
+        }
+        let start = (0..y).rev().find(|&i| blank(i)).map_or(0, |i| i + 1);
+        let end = (y + 1..buffer.len()).find(|&i| blank(i)).map_or(buffer.len() - 1, |i| i - 1);
+        LineRange { start, end }
+    }
+
+    fn reflow(&mut self, range: LineRange) {
+        if !self.editable() {
+            return;
+        }
+        let width = match self.options.int("textwidth") {
+            0 => 79,
+            width => width,
+        };
+        let comment = self.filetype.and_then(|filetype| filetype.comment);
+        let lines = text::reflow(&self.state.buffer[range.start..=range.end], width, comment);
+        self.save_snapshot();
+        let end = range.start + lines.len() - 1;
+        self.state.buffer.splice(range.start..=range.end, lines);
+        self.state.cursor = Position {
+            x: motion::first_non_blank(&self.state.buffer[end]),
+            y: end,
+        };
+    }
+
+    fn sort_lines(&mut self, range: LineRange, reverse: bool, flags: &str) {
+        if !self.editable() {
+            return;
+        }
+        let numeric = flags.contains('n');
+        let ignore_case = flags.contains('i');
+        let unique = flags.contains('u');
+        let number = |line: &str| -> Option<i64> {
+            let start = line.find(|c: char| c.is_ascii_digit())?;
+            let negative = line[..start].ends_with('-');
+            let digits: String = line[start..].chars().take_while(char::is_ascii_digit).collect();
+            digits.parse::<i64>().ok().map(|n| if negative { -n } else { n })
+        };
+        let key = |line: &String| if ignore_case { line.to_lowercase() } else { line.clone() };
+        self.save_snapshot();
+        let lines = &mut self.state.buffer[range.start..=range.end];
+        if numeric {
+            lines.sort_by_key(|line| number(line));
+        } else {
+            lines.sort_by_key(key);
+        }
+        if reverse {
+            lines.reverse();
+        }
+        if unique {
+            let mut sorted: Vec<String> = self.state.buffer.drain(range.start..=range.end).collect();
+            sorted.dedup_by(|a, b| key(a) == key(b));
+            self.state.buffer.splice(range.start..range.start, sorted);
+        }
+        self.clamp_cursor();
+    }
+
+    fn filter_lines(&mut self, range: LineRange, line: &str) {
+        if line.is_empty() || !self.editable() {
+            return;
+        }
+        let input = self.state.buffer[range.start..=range.end].join("\n") + "\n";
+        match shell::filter(line, &input) {
+            Ok(output) if output.status.success() => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let lines: Vec<String> = stdout.lines().map(str::to_string).collect();
+                self.save_snapshot();
+                self.state.buffer.splice(range.start..=range.end, lines);
+                if self.state.buffer.is_empty() {
+                    self.state.buffer.push(String::new());
+                }
+                self.state.cursor = Position { x: 0, y: range.start };
+                self.clamp_cursor();
+            }
+            Ok(output) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&shell::failure(&output)])),
+            Err(err) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&err])),
+        }
+    }
+
+    fn set_options(&mut self, arg: &str) {
+        if arg.is_empty() {
+            let lines = self.options.list();
+            self.open_scratch(tr(Text::OptionsTitle), lines);
+            return;
+        }
+        for expr in Options::split(arg) {
+            self.set_option(&expr);
+        }
+    }
+
+    fn set_option(&mut self, expr: &str) {
+        match self.options.set(expr) {
+            Ok(Some(value)) => self.notify(Severity::Info, value),
+            Ok(None) => {
+                self.keymap.set_preset(self.options.string("keymap"));
+                self.trim_undo();
+            }
+            Err(err) => self.notify(Severity::Error, err),
+        }
+    }
+
+    fn map_key(&mut self, name: &str, action: &str) {
+        let leader = self.options.string("leader");
+        let Some(leader) = Key::parse(leader) else {
+            self.notify(Severity::Error, trf(Text::InvalidKey, &[&leader]));
+            return;
+        };
+        let Some(keys) = Key::parse_sequence(name, leader) else {
+            self.notify(Severity::Error, trf(Text::InvalidKey, &[&name]));
+            return;
+        };
+        match (action, Action::from_name(action)) {
+            ("none", _) => self.keymap.bind(keys, None),
+            (_, Some(action)) => self.keymap.bind(keys, Some(action)),
+            (_, None) => self.notify(Severity::Error, trf(Text::UnknownAction, &[&action])),
+        }
+    }
+
+    pub fn source(&mut self, path: &str) -> Result<bool> {
+        let quit = self.source_file(path, 0)?;
+        self.mode = self.base_mode();
+        Ok(quit)
+    }
+
+    fn source_file(&mut self, path: &str, depth: usize) -> Result<bool> {
+        if depth >= 16 {
+            self.notify(Severity::Error, trf(Text::CommandRecursion, &[&"source"]));
+            return Ok(false);
+        }
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) => {
+                self.notify(Severity::Error, trf(Text::SourceFailed, &[&path, &err]));
+                return Ok(false);
+            }
+        };
+        let outer = self.sourcing.take();
+        let mut quit = false;
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim().trim_start_matches(':');
+            if line.is_empty() || line.starts_with('"') || line.starts_with('#') {
+                continue;
+            }
+            self.sourcing = Some((path.to_string(), n + 1));
+            quit = self.execute_command(line, depth + 1)?;
+            if quit {
+                break;
+            }
+        }
+        self.sourcing = outer;
+        Ok(quit)
+    }
+
+    fn define_command(&mut self, arg: &str) {
+        if arg.is_empty() {
+            let lines = self
+                .user_commands
+                .iter()
+                .map(|(name, definition)| format!("{:<16}{}", name, definition))
+                .collect();
+            self.open_scratch(tr(Text::UserCommandsTitle), lines);
+            return;
+        }
+        let (name, definition) = match arg.split_once(char::is_whitespace) {
+            Some((name, definition)) => (name, definition.trim()),
+            None => (arg, ""),
+        };
+        if COMMANDS.iter().any(|spec| spec.name == name) {
+            self.notify(Severity::Error, trf(Text::BuiltinCommand, &[&name]));
+        } else if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+            self.notify(Severity::Error, trf(Text::InvalidCommandName, &[&name]));
+        } else if definition.is_empty() {
+            match self.user_commands.get(name) {
+                Some(definition) => self.notify(Severity::Info, format!("{} {}", name, definition)),
+                None => self.notify(Severity::Error, trf(Text::UnknownCommand, &[&name])),
+            }
+        } else {
+            self.user_commands.insert(name.to_string(), definition.to_string());
+        }
+    }
+
+    fn run_user_command(&mut self, name: &str, arg: &str, depth: usize) -> Result<bool> {
+        let Some(definition) = self.user_commands.get(name).cloned() else {
+            self.notify(Severity::Error, trf(Text::UnknownCommand, &[&name]));
+            return Ok(false);
+        };
+        if depth >= 16 {
+            self.notify(Severity::Error, trf(Text::CommandRecursion, &[&name]));
+            return Ok(false);
+        }
+        let definition = definition.replace("<args>", arg);
+        if let Some(line) = definition.strip_prefix('!') {
+            self.run_shell(line.trim());
+            return Ok(false);
+        }
+        for part in definition.split('|') {
+            if self.execute_command(part.trim(), depth + 1)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn run_shell(&mut self, line: &str) {
+        match line.strip_suffix('&') {
+            Some(background) => {
+                let line = background.trim_end().to_string();
+                self.notify(Severity::Info, trf(Text::ShellStarted, &[&line]));
+                self.jobs.spawn(move || shell::run(&line));
+            }
+            None => self.report_shell(shell::run(line)),
+        }
+    }
+
+    fn report_shell(&mut self, output: io::Result<std::process::Output>) {
+        match output {
+            Ok(output) if output.status.success() => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let text = match stdout.lines().rev().find(|l| !l.trim().is_empty()) {
+                    Some(last) => last.to_string(),
+                    None => tr(Text::ShellDone).to_string(),
+                };
+                self.notify(Severity::Info, text);
+            }
+            Ok(output) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&shell::failure(&output)])),
+            Err(err) => self.notify(Severity::Error, trf(Text::ShellFailed, &[&err])),
+        }
+    }
+
+    fn show_help(&mut self) {
+        let heading = tr(Text::HelpHeading);
+        let mut lines = vec![
+            heading.to_string(),
+            "=".repeat(heading.chars().count()),
+            String::new(),
+            tr(Text::HelpKeys).to_string(),
+        ];
+        for binding in self.keymap.bindings() {
+            lines.push(format!(
+                "  {:<16}{:<16}{}",
+                Key::sequence_name(&binding.keys),
+                binding.action.name(),
+                binding.action.description()
+            ));
+        }
+        lines.push(format!("  {:<32}{}", tr(Text::HelpCharacter), tr(Text::HelpInsertChar)));
+        lines.push(String::new());
+        lines.push(tr(Text::HelpCommands).to_string());
+        for spec in COMMANDS {
+            lines.push(format!("  :{:<27}{}", spec.usage, tr(spec.help)));
+        }
+        lines.push(String::new());
+        lines.push(tr(Text::HelpOptions).to_string());
+        for spec in OPTIONS {
+            let value = self.options.get(spec.name).map_or(String::new(), |v| v.to_string());
+            lines.push(format!("  {:<16}{:<16}{}", spec.name, spec.short, value));
+        }
+        if !self.user_commands.is_empty() {
+            lines.push(String::new());
+            lines.push(tr(Text::HelpUserCommands).to_string());
+            for (name, definition) in &self.user_commands {
+                lines.push(format!("  :{:<27}{}", name, definition));
+            }
+        }
+        self.open_scratch(tr(Text::HelpTitle), lines);
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
+        let key = self.legacy_fallback(key);
+        if self.playing == 0 {
+            self.macros.record(Key::new(key.code, key.modifiers));
+        }
+        self.process_key(key, true)
+    }
+
+    /// Keys that only enhanced key reporting distinguishes act like the key
+    /// a plain terminal would send unless something is bound to them.
+    fn legacy_fallback(&self, key: KeyEvent) -> KeyEvent {
+        let pressed = Key::new(key.code, key.modifiers);
+        let Some(legacy) = pressed.legacy() else {
+            return key;
+        };
+        let mut keys = self.chord.clone();
+        keys.push(pressed);
+        if self.keymap.resolve(&keys) != Resolved::Unbound || self.keymap.resolve(&[pressed]) != Resolved::Unbound {
+            return key;
+        }
+        KeyEvent::new(legacy.code, legacy.modifiers)
+    }
+
+    fn process_key(&mut self, key: KeyEvent, mapped: bool) -> Result<bool> {
+        self.messages.dismiss();
+        self.overlay.key_pressed();
+        self.flash = None;
+        self.free_scroll = false;
+        if self.selection.is_some() && self.selection_key(key) {
+            return Ok(false);
+        }
+        if self.terminal.is_some() && self.keymap.lookup(key.code, key.modifiers) == Some(Action::ToggleTerminal) {
+            self.toggle_terminal();
+            return Ok(false);
+        }
+        if self.outline.as_ref().is_some_and(|outline| outline.focused) {
+            match self.keymap.lookup(key.code, key.modifiers) {
+                Some(Action::ToggleOutline) => self.toggle_outline(),
+                _ => self.outline_key(key.code),
+            }
+            return Ok(false);
+        }
+        if self.terminal_focus {
+            if let (Some((_, Some(pty), _)), Some(bytes)) = (self.terminal.as_mut(), term::key_bytes(&key)) {
+                pty.write(&bytes);
+            }
+            return Ok(false);
+        }
+        if let Some((prompt, kind)) = self.prompt.as_mut() {
+            if let (PromptKind::Search { .. }, KeyCode::Up | KeyCode::Down) = (kind, key.code) {
+                self.recall.step(self.session.searches(), prompt.input_mut(), key.code == KeyCode::Up);
+                return Ok(false);
+            }
+            let event = prompt.handle_key(key.code);
+            return Ok(self.answer_prompt(event));
+        }
+        if self.literal.is_some() {
+            self.literal_key(key.code);
+            return Ok(false);
+        }
+        if self.confirmation.is_some() {
+            self.confirmation_key(key.code);
+            return Ok(false);
+        }
+
+        if let Some((picker, _)) = self.picker.as_mut() {
+            match picker.handle_key(key.code) {
+                PickerEvent::Pending => {}
+                PickerEvent::Cancelled => self.picker = None,
+                PickerEvent::Chosen(index) => {
+                    match self.picker.take() {
+                        Some((_, PickerKind::KillRing)) => self.paste_ring(index),
+                        Some((_, PickerKind::Diagnostics)) => self.jump_to_diagnostic(index),
+                        Some((_, PickerKind::Binary)) => self.open_binary_as(index),
+                        Some((_, PickerKind::Registers(sources))) => self.paste_from(sources[index]),
+                        Some((_, PickerKind::Marks(names))) => self.jump_to_mark(names[index], true),
+                        Some((_, PickerKind::Tasks(mut tasks))) => self.run_task(tasks.swap_remove(index)),
+                        Some((_, PickerKind::Tags(mut tags))) => self.jump_to_tag(tags.swap_remove(index)),
+                        Some((_, PickerKind::Filetypes)) => self.filetype = filetype::FILETYPES.get(index),
+                        _ => {}
+                    }
+                }
+                PickerEvent::Confirmed(selected) => match self.picker.take() {
+                    Some((_, PickerKind::Replace)) => self.apply_replacements(&selected),
+                    Some((_, PickerKind::Patch)) => self.apply_patch(&selected),
+                    _ => {}
+                },
+            }
+            return Ok(false);
+        }
+
+        if self.mode == Mode::Command {
+            match key.code {
+                KeyCode::Char(c) => self.command.push(c),
+                KeyCode::Backspace => {
+                    self.command.pop();
+                }
+                KeyCode::Up | KeyCode::Down => self.recall.step(self.session.commands(), &mut self.command, key.code == KeyCode::Up),
+                KeyCode::Enter => {
+                    self.recall.reset();
+                    if !self.command.trim().is_empty() && self.crypt.is_none() {
+                        self.session.add_command(self.command.trim());
+                    }
+                    return self.process_command();
+                }
+                KeyCode::Tab => self.complete_command(),
+                KeyCode::Esc => {
+                    self.recall.reset();
+                    self.command.clear();
+                    self.mode = self.base_mode();
+                }
+                _ => {}
+            }
+            return Ok(false);
+        }
+
+        let plain_key = !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if plain_key && self.browser.is_some() && self.browser_key(key.code) {
+            return Ok(false);
+        }
+        if mapped && self.lazy.is_none() && self.hex.is_none() {
+            if let Some(quit) = self.chord_key(key) {
+                return quit;
+            }
+        }
+
+        if self.mode == Mode::Normal && self.lazy.is_none() && self.hex.is_none() {
+            if key.modifiers.contains(KeyModifiers::ALT) {
+                if let Some(action) = self.keymap.lookup(key.code, key.modifiers) {
+                    return Ok(self.run_action(action));
+                }
+            }
+            let command = if key.modifiers.contains(KeyModifiers::CONTROL) {
+                self.normal.feed_ctrl(key.code)
+            } else {
+                self.normal.feed(key.code)
+            };
+            self.schedule_which_key();
+            return Ok(match command {
+                Some(vim::Command::Play(name, count)) => self.play_macro(&name.to_string(), count),
+                Some(command) => {
+                    self.run_normal(command);
+                    false
+                }
+                None => false,
+            });
+        }
+
+        if self.lazy.is_none() && self.hex.is_none() && self.compose_key(key) {
+            return Ok(false);
+        }
+
+        if self.mode == Mode::Insert && key.code == KeyCode::Esc && self.base_mode() == Mode::Normal {
+            self.mode = Mode::Normal;
+            self.clamp_normal();
+            return Ok(false);
+        }
+
+        if self.lazy.is_some() && self.lazy_key(key.code) {
+            return Ok(false);
+        }
+
+        let action = self.keymap.lookup(key.code, key.modifiers);
+        if self.hex.is_some() {
+            match action {
+                Some(Action::CommandMode) => self.mode = Mode::Command,
+                Some(Action::Undo) => self.hex_undo(),
+                Some(Action::Quit) => return Ok(true),
+                _ => self.hex_key(key.code),
+            }
+            return Ok(false);
+        }
+
+        let last_paste = self.last_paste.take();
+        let recentered = self.recentered.take();
+        let count = self.chord_count.take().unwrap_or(1);
+        match action {
+            Some(Action::YankPop) => self.yank_pop(last_paste),
+            Some(Action::Recenter) => {
+                let at = match recentered {
+                    Some(vim::ViewAt::Center) => vim::ViewAt::Top,
+                    Some(vim::ViewAt::Top) => vim::ViewAt::Bottom,
+                    _ => vim::ViewAt::Center,
+                };
+                self.place_view(at);
+                self.recentered = Some(at);
+            }
+            Some(action) => return Ok((0..count).any(|_| self.run_action(action))),
+            None => {
+                if let KeyCode::Char(c) = key.code {
+                    if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                        for _ in 0..count {
+                            self.insert(c);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// `None` when the key starts no sequence and is handled on its own.
+    fn chord_key(&mut self, key: KeyEvent) -> Option<Result<bool>> {
+        let pressed = Key::new(key.code, key.modifiers);
+        if (!self.chord.is_empty() || self.chord_count.is_some()) && pressed.code == KeyCode::Esc && pressed.modifiers.is_empty() {
+            self.cancel_chord();
+            return Some(Ok(false));
+        }
+        if self.chord.is_empty() {
+            if self.mode != Mode::Normal && pressed.modifiers == KeyModifiers::ALT {
+                let digit = match pressed.code {
+                    KeyCode::Char(c) => c.to_digit(10),
+                    _ => None,
+                };
+                if let Some(digit) = digit.filter(|_| self.keymap.resolve(&[pressed]) == Resolved::Unbound) {
+                    self.chord_count = Some(self.chord_count.unwrap_or(0).saturating_mul(10).saturating_add(digit as usize));
+                    return Some(Ok(false));
+                }
+            }
+            if !matches!(self.keymap.resolve(&[pressed]), Resolved::Pending(_)) {
+                return None;
+            }
+            if self.mode == Mode::Normal && self.normal.pending().is_some() {
+                self.chord_count = Some(self.normal.take_count()?);
+            }
+        }
+        self.chord.push(pressed);
+        let quit = match self.keymap.resolve(&self.chord) {
+            Resolved::Pending(_) => {
+                self.timers.schedule(Timer::Chord, Duration::from_millis(self.options.int("timeoutlen") as u64));
+                self.schedule_which_key();
+                false
+            }
+            Resolved::Bound(action) => {
+                self.chord.clear();
+                self.timers.cancel(Timer::Chord);
+                self.schedule_which_key();
+                self.run_counted(action)
+            }
+            Resolved::Unbound => {
+                self.chord.pop();
+                if self.finish_chord() {
+                    return Some(Ok(true));
+                }
+                return Some(self.process_key(key, true));
+            }
+        };
+        Some(Ok(quit))
+    }
+
+    fn finish_chord(&mut self) -> bool {
+        self.timers.cancel(Timer::Chord);
+        let keys = std::mem::take(&mut self.chord);
+        let quit = match self.keymap.resolve(&keys) {
+            Resolved::Pending(Some(action)) | Resolved::Bound(action) => self.run_counted(action),
+            _ => {
+                if let Some(count) = self.chord_count.take_if(|_| self.mode == Mode::Normal) {
+                    for digit in count.to_string().chars() {
+                        self.normal.feed(KeyCode::Char(digit));
+                    }
+                }
+                keys.into_iter().any(|key| matches!(self.process_key(KeyEvent::new(key.code, key.modifiers), false), Ok(true)))
+            }
+        };
+        self.schedule_which_key();
+        quit
+    }
+
+    fn cancel_chord(&mut self) {
+        self.chord.clear();
+        self.chord_count = None;
+        self.timers.cancel(Timer::Chord);
+        self.schedule_which_key();
+    }
+
+    fn run_counted(&mut self, action: Action) -> bool {
+        let count = self.chord_count.take().unwrap_or(1);
+        for _ in 0..count {
+            if self.run_action(action) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn run_action(&mut self, action: Action) -> bool {
+        match action {
+            Action::CommandMode => self.mode = Mode::Command,
+            Action::Copy => self.copy_selection(),
+            Action::Paste => self.paste(),
+            Action::Undo => self.undo(),
+            Action::NextBuffer => self.cycle_document(true),
+            Action::PrevBuffer => self.cycle_document(false),
+            Action::DeleteBack => self.delete(),
+            Action::Newline => self.newline(),
+            Action::MoveUp => self.move_up(),
+            Action::MoveDown => self.move_down(),
+            Action::MoveLeft => self.move_left(),
+            Action::MoveRight => self.move_right(),
+            Action::LineStart => self.state.cursor.x = 0,
+            Action::LineEnd => self.state.cursor.x = self.state.buffer[self.state.cursor.y].len(),
+            Action::BufferStart => self.state.cursor = Position { x: 0, y: 0 },
+            Action::ParagraphForward => self.apply_motion(Motion::ParagraphForward),
+            Action::ParagraphBackward => self.apply_motion(Motion::ParagraphBackward),
+            Action::BlockStart => self.apply_motion(Motion::BlockStart),
+            Action::BlockEnd => self.apply_motion(Motion::BlockEnd),
+            Action::BufferEnd => {
+                let y = self.state.buffer.len() - 1;
+                self.state.cursor = Position { x: self.state.buffer[y].len(), y };
+            }
+            Action::WordForward => self.state.cursor = motion::forward_word(&self.state.buffer, self.state.cursor),
+            Action::WordBackward => self.state.cursor = motion::backward_word(&self.state.buffer, self.state.cursor),
+            Action::KillLine => self.kill_line(),
+            Action::YankPop => self.yank_pop(None),
+            Action::PasteHistory => self.show_kill_ring(),
+            Action::ToggleOverwrite => self.overwrite = !self.overwrite,
+            Action::Recenter => self.place_view(vim::ViewAt::Center),
+            Action::Increment => self.increment(1),
+            Action::Decrement => self.increment(-1),
+            Action::InsertLiteral => self.literal = Some(String::new()),
+            Action::RecordMacro => self.toggle_recording(Some('q')),
+            Action::PlayMacro => return self.play_macro("@", 1),
+            Action::Search => self.start_search(true),
+            Action::SearchNext => self.search_next(false),
+            Action::SearchPrev => self.search_next(true),
+            Action::Reflow => self.reflow(self.paragraph_range()),
+            Action::OutputPageUp | Action::OutputPageDown => {
+                if let Some(pane) = self.output.as_mut() {
+                    pane.scroll_page(action == Action::OutputPageDown);
+                }
+            }
+            Action::ToggleTerminal => self.toggle_terminal(),
+            Action::RerunTask => self.rerun_task(),
+            Action::JumpToTag => self.tag(""),
+            Action::PopTag => self.pop_tag(),
+            Action::ToggleOutline => self.toggle_outline(),
+            Action::TallerWindow => self.resize_panes(-1),
+            Action::ShorterWindow => self.resize_panes(1),
+            Action::WiderWindow => self.resize_sidebar(-1),
+            Action::NarrowerWindow => self.resize_sidebar(1),
+            Action::NextDiagnostic => self.next_diagnostic(true),
+            Action::PrevDiagnostic => self.next_diagnostic(false),
+            Action::NextConflict => self.next_conflict(true),
+            Action::PrevConflict => self.next_conflict(false),
+            Action::Quit => return true,
+        }
+        false
+    }
+
+    fn apply_motion(&mut self, motion: Motion) {
+        self.state.cursor = motion::target(&self.state.buffer, self.state.cursor, motion, None);
+    }
+
+    fn clamp_normal(&mut self) {
+        let line = &self.state.buffer[self.state.cursor.y];
+        if self.state.cursor.x >= line.len() {
+            self.state.cursor.x = text::prev_boundary(line, line.len());
+        }
+    }
+
+    fn run_normal(&mut self, command: vim::Command) {
+        match command {
+            vim::Command::Move(motion, count) => {
+                self.state.cursor = motion::target(&self.state.buffer, self.state.cursor, motion, count);
+                self.clamp_normal();
+            }
+            vim::Command::Operate(operator, motion, count) => self.operate(operator, motion, count),
+            vim::Command::OperateObject(operator, object, around, count) => {
+                if let Some(span) = motion::select(&self.state.buffer, self.state.cursor, object, around, count) {
+                    self.apply_operator(operator, span);
+                }
+            }
+            vim::Command::Insert(at) => self.enter_insert(at),
+            vim::Command::DeleteChar(count) => {
+                let from = self.state.cursor;
+                let to = motion::target(&self.state.buffer, from, Motion::Right, Some(count));
+                let to = if to.x == from.x { motion::after(&self.state.buffer, from) } else { to };
+                if to.x > from.x && self.editable() {
+                    self.save_snapshot();
+                    let text = self.text_between(from, to);
+                    self.yank(text, false);
+                    self.remove_between(from, to);
+                    self.clamp_normal();
+                }
+            }
+            vim::Command::Paste { before, count } => self.put(before, count),
+            vim::Command::Undo => {
+                self.undo();
+                self.clamp_normal();
+            }
+            vim::Command::Surround(target, c) => {
+                if let Some(span) = self.target_span(target) {
+                    self.surround(span, c);
+                }
+            }
+            vim::Command::Reflow(target) => {
+                if let Some(span) = self.target_span(target) {
+                    self.reflow(LineRange {
+                        start: span.start.y,
+                        end: span.end.y,
+                    });
+                    self.clamp_normal();
+                }
+            }
+            vim::Command::Diagnostic(forward) => {
+                self.next_diagnostic(forward);
+                self.clamp_normal();
+            }
+            vim::Command::Conflict(forward) => {
+                self.next_conflict(forward);
+                self.clamp_normal();
+            }
+            vim::Command::Ascii => self.describe_char(),
+            vim::Command::Search(forward) => self.start_search(forward),
+            vim::Command::SearchNext(reverse) => {
+                self.search_next(reverse);
+                self.clamp_normal();
+            }
+            vim::Command::Tag => {
+                self.tag("");
+                self.clamp_normal();
+            }
+            vim::Command::PopTag => {
+                self.pop_tag();
+                self.clamp_normal();
+            }
+            vim::Command::Increment(delta) => {
+                self.increment(delta);
+                self.clamp_normal();
+            }
+            vim::Command::DeleteSurround(c) => self.change_surround(c, None),
+            vim::Command::ChangeSurround(old, new) => self.change_surround(old, Some(new)),
+            vim::Command::Register(name) => self.register = Some(name),
+            vim::Command::Record(name) => self.toggle_recording(name),
+            vim::Command::SetMark(name) => self.set_mark(name),
+            vim::Command::JumpMark(name, exact) => self.jump_to_mark(name, exact),
+            vim::Command::Play(..) => {}
+            vim::Command::View(at) => self.place_view(at),
+            vim::Command::Ex => self.mode = Mode::Command,
+        }
+    }
+
+    fn operate(&mut self, operator: vim::Operator, motion: Motion, count: Option<usize>) {
+        let span = self.motion_span(operator, motion, count);
+        self.apply_operator(operator, span);
+    }
+
+    fn target_span(&self, target: vim::Target) -> Option<motion::Span> {
+        match target {
+            vim::Target::Motion(motion, count) => Some(self.motion_span(vim::Operator::Yank, motion, count)),
+            vim::Target::Object(object, around, count) => {
+                motion::select(&self.state.buffer, self.state.cursor, object, around, count)
+            }
+        }
+    }
+
+    fn motion_span(&self, operator: vim::Operator, motion: Motion, count: Option<usize>) -> motion::Span {
+        let from = self.state.cursor;
+        let buffer = &self.state.buffer;
+        let motion = match (operator, motion) {
+            (vim::Operator::Change, Motion::WordForward) if motion::after(buffer, from) != from => {
+                Motion::WordEnd
+            }
+            _ => motion,
+        };
+        let mut to = motion::target(buffer, from, motion, count);
+        if motion == Motion::WordForward && to.y > from.y && to.x == 0 {
+            to = Position {
+                x: buffer[to.y - 1].len(),
+                y: to.y - 1,
+            };
+        }
+        let (start, mut end) = if (to.y, to.x) < (from.y, from.x) { (to, from) } else { (from, to) };
+        if motion.inclusive() {
+            end = motion::after(buffer, end);
+        }
+        motion::Span {
+            start,
+            end,
+            linewise: motion.linewise(),
+        }
+    }
+
+    fn apply_operator(&mut self, operator: vim::Operator, span: motion::Span) {
+        let motion::Span { start, end, linewise } = span;
+        if linewise {
+            let lines = self.state.buffer[start.y..=end.y].join("\n");
+            if operator != vim::Operator::Yank && !self.editable() {
+                return;
+            }
+            self.yank(lines, true);
+            match operator {
+                vim::Operator::Yank => {
+                    self.state.cursor.y = start.y;
+                    self.flash_yank(start, end, true);
+                }
+                vim::Operator::Delete => {
+                    self.save_snapshot();
+                    self.state.buffer.drain(start.y..=end.y);
+                    if self.state.buffer.is_empty() {
+                        self.state.buffer.push(String::new());
+                    }
+                    self.state.cursor.y = start.y.min(self.state.buffer.len() - 1);
+                    self.state.cursor.x = motion::first_non_blank(&self.state.buffer[self.state.cursor.y]);
+                }
+                vim::Operator::Change => {
+                    self.save_snapshot();
+                    self.state.buffer.drain(start.y + 1..=end.y);
+                    self.state.buffer[start.y].clear();
+                    self.state.cursor = Position { x: 0, y: start.y };
+                    self.mode = Mode::Insert;
+                    return;
+                }
+            }
+            self.clamp_normal();
+            return;
+        }
+
+        if operator != vim::Operator::Yank && !self.editable() {
+            return;
+        }
+        let text = self.text_between(start, end);
+        self.yank(text, false);
+        match operator {
+            vim::Operator::Yank => {
+                self.state.cursor = start;
+                self.clamp_normal();
+                self.flash_yank(start, end, false);
+            }
+            vim::Operator::Delete => {
+                self.save_snapshot();
+                self.remove_between(start, end);
+                self.clamp_normal();
+            }
+            vim::Operator::Change => {
+                self.save_snapshot();
+                self.remove_between(start, end);
+                self.mode = Mode::Insert;
+            }
+        }
+    }
+
+    fn increment(&mut self, delta: i64) {
+        let Position { x, y } = self.state.cursor;
+        let Some((line, x)) = text::increment(&self.state.buffer[y], x, delta) else {
+            return;
+        };
+        if !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+        self.state.buffer[y] = line;
+        self.state.cursor.x = x;
+    }
+
+    fn surround_pair(c: char) -> (String, String) {
+        match motion::pair(c) {
+            Some((open, close)) if open != close && c == open => (format!("{} ", open), format!(" {}", close)),
+            Some((open, close)) => (open.to_string(), close.to_string()),
+            None => (c.to_string(), c.to_string()),
+        }
+    }
+
+    fn surround(&mut self, span: motion::Span, c: char) {
+        let motion::Span { mut start, mut end, linewise } = span;
+        if linewise {
+            start.x = motion::first_non_blank(&self.state.buffer[start.y]);
+            end.x = self.state.buffer[end.y].len();
+        }
+        if !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+        let (open, close) = Self::surround_pair(c);
+        self.state.buffer[end.y].insert_str(end.x, &close);
+        self.state.buffer[start.y].insert_str(start.x, &open);
+        self.state.cursor = start;
+        self.clamp_normal();
+    }
+
+    fn change_surround(&mut self, old: char, new: Option<char>) {
+        let Some(object) = motion::Object::from_char(old) else {
+            return;
+        };
+        let Some([outer_start, start, end, outer_end]) = motion::bounds(&self.state.buffer, self.state.cursor, object, None)
+        else {
+            return;
+        };
+        if !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+        let (open, close) = new.map(Self::surround_pair).unwrap_or_default();
+        self.remove_between(end, outer_end);
+        self.insert_text(&close);
+        self.remove_between(outer_start, start);
+        self.insert_text(&open);
+        self.state.cursor = outer_start;
+        self.clamp_normal();
+    }
+
+    fn enter_insert(&mut self, at: vim::InsertAt) {
+        let y = self.state.cursor.y;
+        match at {
+            vim::InsertAt::Cursor => {}
+            vim::InsertAt::After => self.state.cursor = motion::after(&self.state.buffer, self.state.cursor),
+            vim::InsertAt::LineStart => self.state.cursor.x = motion::first_non_blank(&self.state.buffer[y]),
+            vim::InsertAt::LineEnd => self.state.cursor.x = self.state.buffer[y].len(),
+            vim::InsertAt::Below | vim::InsertAt::Above => {
+                if !self.editable() {
+                    return;
+                }
+                self.save_snapshot();
+                let row = if at == vim::InsertAt::Below { y + 1 } else { y };
+                self.state.buffer.insert(row, String::new());
+                self.state.cursor = Position { x: 0, y: row };
+            }
+        }
+        self.mode = Mode::Insert;
+    }
+
+    fn put(&mut self, before: bool, count: usize) {
+        if let Some(register) = self.take_register() {
+            self.put_register(register, before, count);
+        }
+    }
+
+    fn put_register(&mut self, register: Register, before: bool, count: usize) {
+        if !self.editable() {
+            return;
+        }
+        self.save_snapshot();
+        let text = vec![register.text.as_str(); count].join(if register.linewise { "\n" } else { "" });
+        if register.linewise {
+            let row = if before { self.state.cursor.y } else { self.state.cursor.y + 1 };
+            for (i, line) in text.split('\n').enumerate() {
+                self.state.buffer.insert(row + i, line.to_string());
+            }
+            self.state.cursor = Position {
+                x: motion::first_non_blank(&self.state.buffer[row]),
+                y: row,
+            };
+        } else {
+            if !before {
+                self.state.cursor = motion::after(&self.state.buffer, self.state.cursor);
+            }
+            self.insert_text(&text);
+            self.state.cursor = motion::target(&self.state.buffer, self.state.cursor, Motion::Left, None);
+        }
+    }
+
+    fn move_up(&mut self) {
+        if self.state.cursor.y > 0 {
+            self.state.cursor.y -= 1;
+            self.state.cursor.x = text::snap(&self.state.buffer[self.state.cursor.y], self.state.cursor.x);
+        }
+    }
+
+    fn move_down(&mut self) {
+        if self.state.cursor.y + 1 < self.state.buffer.len() {
+            self.state.cursor.y += 1;
+            self.state.cursor.x = text::snap(&self.state.buffer[self.state.cursor.y], self.state.cursor.x);
+        }
+    }
+
+    fn move_left(&mut self) {
+        if self.state.cursor.x > 0 {
+            self.state.cursor.x = text::prev_boundary(&self.state.buffer[self.state.cursor.y], self.state.cursor.x);
+        } else if self.state.cursor.y > 0 {
+            self.state.cursor.y -= 1;
+            self.state.cursor.x = self.state.buffer[self.state.cursor.y].len();
+        }
+    }
+
+    fn move_right(&mut self) {
+        if self.state.cursor.x < self.state.buffer[self.state.cursor.y].len() {
+            self.state.cursor.x = text::next_boundary(&self.state.buffer[self.state.cursor.y], self.state.cursor.x);
+        } else if self.state.cursor.y + 1 < self.state.buffer.len() {
+            self.state.cursor.y += 1;
+            self.state.cursor.x = 0;
+        }
+    }
+
+    pub fn dispatch(&mut self, event: Event) -> Result<bool> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Release => Ok(false),
+            Event::Key(key) => {
+                if let Some((cast, _)) = self.cast.as_mut() {
+                    cast.input(&key);
+                }
+                self.handle_key(key)
+            }
+            Event::Mouse(mouse) => {
+                self.commit_preedit();
+                self.handle_mouse(mouse);
+                Ok(false)
+            }
+            Event::Paste(text) => {
+                self.free_scroll = false;
+                self.commit_preedit();
+                self.paste_text(&text);
+                Ok(false)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if mouse.row == 0 && self.tab_at(mouse.column).is_some() => {
+                if let Some(index) = self.tab_at(mouse.column) {
+                    self.switch_to(index);
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) if mouse.row == self.status_row => self.click_status(mouse.column),
+            MouseEventKind::Down(MouseButton::Left) if self.separator_at(mouse.column, mouse.row).is_some() => {
+                self.resizing = self.separator_at(mouse.column, mouse.row);
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.resizing.is_some() => {
+                if let Some(separator) = self.resizing {
+                    self.drag_separator(separator, mouse.column, mouse.row);
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => self.resizing = None,
+            MouseEventKind::Down(MouseButton::Left) => {
+                if let Some(at) = self.position_at(mouse.column, mouse.row) {
+                    let now = Instant::now();
+                    let clicks = match self.last_click {
+                        Some((time, column, row, clicks))
+                            if (column, row) == (mouse.column, mouse.row) && now.duration_since(time) < MULTI_CLICK =>
+                        {
+                            clicks % 3 + 1
+                        }
+                        _ => 1,
+                    };
+                    self.last_click = Some((now, mouse.column, mouse.row, clicks));
+                    self.free_scroll = false;
+                    self.state.cursor = at;
+                    self.click(clicks);
+                }
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.selection.is_some() => {
+                if let Some(at) = self.position_at(mouse.column, mouse.row) {
+                    self.state.cursor = at;
+                }
+            }
+            MouseEventKind::ScrollDown => self.scroll_view(self.options.int("mousescroll") as isize),
+            MouseEventKind::ScrollUp => self.scroll_view(-(self.options.int("mousescroll") as isize)),
+            _ => {}
+        }
+    }
+
+    fn click_status(&mut self, column: u16) {
+        let column = column as usize;
+        let Some((_, _, name)) = self.status_spans.iter().find(|(start, end, _)| (*start..*end).contains(&column)) else {
+            return;
+        };
+        match name.as_str() {
+            "position" if self.picker.is_none() => {
+                self.command.clear();
+                self.mode = Mode::Command;
+            }
+            "filetype" if self.picker.is_none() => self.pick_filetype(),
+            _ => {}
+        }
+    }
+
+    fn pick_filetype(&mut self) {
+        let items = filetype::FILETYPES.iter().map(|filetype| filetype.name.to_string()).collect();
+        self.picker = Some((Picker::new(tr(Text::FiletypesTitle), items), PickerKind::Filetypes));
+    }
+
+    /// Starts a selection at the cursor: a single click selects nothing yet,
+    /// a double click the word under it and a triple click its line.
+    fn click(&mut self, clicks: usize) {
+        let word = motion::select(&self.state.buffer, self.state.cursor, motion::Object::Word, false, None);
+        match (clicks, word) {
+            (2, Some(span)) => {
+                self.state.cursor = span.end;
+                self.selection = Some((span.start, false));
+            }
+            (3, _) => self.selection = Some((self.state.cursor, true)),
+            _ => {
+                if self.mode == Mode::Normal {
+                    self.clamp_normal();
+                }
+                self.selection = Some((self.state.cursor, false));
+            }
+        }
+    }
+
+    /// The buffer position drawn at a screen cell, as laid out by the last
+    /// draw.
+    fn position_at(&self, column: u16, row: u16) -> Option<Position> {
+        let (top, gutter) = self.text_origin;
+        let &(y, offset) = self.text_rows.get(row.checked_sub(top)? as usize)?;
+        let column = offset + column.saturating_sub(gutter) as usize;
+        let x = text::index(&self.state.buffer[y], column, self.options.int("tabwidth"));
+        Some(Position { x, y })
+    }
+
+    /// Scrolls the view without moving the cursor; the view follows the
+    /// cursor again after the next key.
+    fn scroll_view(&mut self, lines: isize) {
+        if self.lazy.is_some() || self.hex.is_some() {
+            return;
+        }
+        self.scroll = self.scroll.saturating_add_signed(lines).min(self.state.buffer.len() - 1);
+        self.free_scroll = true;
+    }
+
+    /// The mouse selection in buffer order, if it covers anything.
+    fn selection_range(&self) -> Option<motion::Span> {
+        let (anchor, linewise) = self.selection?;
+        let cursor = self.state.cursor;
+        let (start, end) = if (anchor.y, anchor.x) <= (cursor.y, cursor.x) { (anchor, cursor) } else { (cursor, anchor) };
+        if linewise {
+            let start = Position { x: 0, y: start.y };
+            let end = Position {
+                x: self.state.buffer[end.y].len(),
+                y: end.y,
+            };
+            return Some(motion::Span { start, end, linewise });
+        }
+        Some(motion::Span { start, end, linewise }).filter(|_| start != end)
+    }
+
+    /// Copies, deletes or changes the mouse selection when `key` asks for it;
+    /// any other key drops the selection and is handled as usual.
+    fn selection_key(&mut self, key: KeyEvent) -> bool {
+        let Some(span) = self.selection_range() else {
+            self.selection = None;
+            return false;
+        };
+        self.selection = None;
+        let action = self.keymap.lookup(key.code, key.modifiers);
+        let vim = match key.code {
+            KeyCode::Char(c) if self.mode == Mode::Normal && key.modifiers == KeyModifiers::NONE => Some(c),
+            _ => None,
+        };
+        let operator = match (action, vim, key.code) {
+            (Some(Action::Copy), _, _) | (_, Some('y'), _) => vim::Operator::Yank,
+            (Some(Action::DeleteBack), _, _) | (_, Some('d' | 'x'), _) | (_, _, KeyCode::Delete) => vim::Operator::Delete,
+            (_, Some('c'), _) => vim::Operator::Change,
+            (_, _, KeyCode::Esc) => return true,
+            _ => {
+                if self.mode == Mode::Normal {
+                    self.clamp_normal();
+                }
+                return false;
+            }
+        };
+        let (start, linewise) = (span.start, span.linewise);
+        self.apply_operator(operator, span);
+        if self.mode == Mode::Insert {
+            self.state.cursor = if linewise { Position { x: 0, y: self.state.cursor.y } } else { start };
+        }
+        true
+    }
+
+    fn matching_pair(c: char) -> Option<char> {
+        match c {
+            '(' | '{' | '[' | '"' | '\'' => motion::pair(c).map(|(_, close)| close),
+            _ => None,
+        }
+    }
+}
+
+fn take_pattern(text: &str, delimiter: char) -> (String, &str) {
+    let mut pattern = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, next)) if next == delimiter => pattern.push(next),
+                Some((_, next)) => {
+                    pattern.push('\\');
+                    pattern.push(next);
+                }
+                None => pattern.push('\\'),
+            },
+            c if c == delimiter => return (pattern, &text[i + c.len_utf8()..]),
+            c => pattern.push(c),
+        }
+    }
+    (pattern, "")
+}
+
+/// Moves the `marked` lines of `seen` to where they are in `buffer` after
+/// one edit, dropping those the edit removed. The lines that changed are
+/// taken to be the ones left between the longest common suffix and then
+/// the longest common prefix, and `seen` is brought up to date with them.
+fn follow_edit(seen: &mut Vec<String>, buffer: &[String], marked: &mut [Option<usize>]) {
+    let shortest = seen.len().min(buffer.len());
+    let suffix = seen.iter().rev().zip(buffer.iter().rev()).take_while(|(a, b)| a == b).count();
+    let prefix = seen.iter().zip(buffer).take(shortest - suffix).take_while(|(a, b)| a == b).count();
+    let (old_end, new_end) = (seen.len() - suffix, buffer.len() - suffix);
+    for mark in marked.iter_mut() {
+        *mark = match *mark {
+            Some(y) if y >= old_end => Some(y - old_end + new_end),
+            Some(y) if y >= prefix && y - prefix >= new_end - prefix => None,
+            mark => mark,
+        };
+    }
+    seen.splice(prefix..old_end, buffer[prefix..new_end].iter().cloned());
+}
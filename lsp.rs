@@ -0,0 +1,260 @@
+use crate::i18n::{tr, Text};
+use crate::json::{self, object, Value};
+use crate::shell;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct Edit {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+pub type WorkspaceEdit = Vec<(PathBuf, Vec<Edit>)>;
+
+pub struct Rename {
+    pub server: String,
+    pub root: PathBuf,
+    pub file: PathBuf,
+    pub language: String,
+    pub text: String,
+    pub line: usize,
+    pub character: usize,
+    pub new_name: String,
+}
+
+pub fn utf16_column(line: &str, x: usize) -> usize {
+    line[..x.min(line.len())].encode_utf16().count()
+}
+
+pub fn byte_column(line: &str, column: usize) -> usize {
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= column {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
+pub fn file_uri(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => uri.push(byte as char),
+            byte => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+pub fn uri_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let hex = encoded.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (encoded[i], hex) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                bytes.push(byte);
+                i += 1;
+            }
+        }
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    receiver: Receiver<Value>,
+    next_id: usize,
+}
+
+impl Session {
+    fn start(server: &str, root: &Path) -> io::Result<Self> {
+        let mut child = shell::command(server)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("piped stdin");
+        let mut stdout = BufReader::new(child.stdout.take().expect("piped stdout"));
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            while let Some(message) = read_message(&mut stdout) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            receiver,
+            next_id: 1,
+        })
+    }
+
+    fn send(&mut self, message: Value) -> Result<(), String> {
+        let body = message.to_string();
+        write!(self.stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|err| err.to_string())
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> Result<(), String> {
+        self.send(object([("jsonrpc", "2.0".into()), ("method", method.into()), ("params", params)]))
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(object([("jsonrpc", "2.0".into()), ("id", id.into()), ("method", method.into()), ("params", params)]))?;
+        loop {
+            let message = self.receiver.recv_timeout(TIMEOUT).map_err(|_| format!("{} timed out", method))?;
+            match (message.get("id"), message.get("method")) {
+                (Some(reply), None) if reply.as_usize() == Some(id) => {
+                    if let Some(error) = message.get("error") {
+                        let text = error.get("message").and_then(Value::as_str).unwrap_or("request failed");
+                        return Err(text.to_string());
+                    }
+                    return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+                }
+                (Some(request), Some(_)) => {
+                    let reply = object([("jsonrpc", "2.0".into()), ("id", request.clone()), ("result", Value::Null)]);
+                    self.send(reply)?;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn read_message(reader: &mut impl BufRead) -> Option<Value> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let mut body = vec![0; length?];
+    reader.read_exact(&mut body).ok()?;
+    json::parse(&String::from_utf8_lossy(&body))
+}
+
+// Edits are applied within single lines, so a rename that needs more than
+// that is refused as a whole rather than applied in part.
+fn edits(value: &Value) -> Result<Vec<Edit>, String> {
+    let mut edits = Vec::new();
+    for edit in value.as_array().unwrap_or_default() {
+        let Some(range) = edit.get("range") else {
+            continue;
+        };
+        let (Some(start), Some(end)) = (range.get("start"), range.get("end")) else {
+            continue;
+        };
+        let (Some(line), Some(text)) = (start.get("line").and_then(Value::as_usize), edit.get("newText").and_then(Value::as_str)) else {
+            continue;
+        };
+        if end.get("line").and_then(Value::as_usize) != Some(line) || text.contains('\n') {
+            return Err(tr(Text::RenameSpansLines).to_string());
+        }
+        let (Some(from), Some(to)) = (start.get("character").and_then(Value::as_usize), end.get("character").and_then(Value::as_usize)) else {
+            continue;
+        };
+        edits.push(Edit {
+            line,
+            start: from,
+            end: to,
+            text: text.to_string(),
+        });
+    }
+    Ok(edits)
+}
+
+fn workspace_edit(result: &Value) -> Result<WorkspaceEdit, String> {
+    let mut files = Vec::new();
+    if let Some(changes) = result.get("changes").and_then(Value::as_object) {
+        for (uri, list) in changes {
+            if let Some(path) = uri_path(uri) {
+                files.push((path, edits(list)?));
+            }
+        }
+    }
+    for change in result.get("documentChanges").and_then(Value::as_array).unwrap_or_default() {
+        let uri = change.get("textDocument").and_then(|document| document.get("uri")).and_then(Value::as_str);
+        if let (Some(path), Some(list)) = (uri.and_then(uri_path), change.get("edits")) {
+            files.push((path, edits(list)?));
+        }
+    }
+    files.retain(|(_, edits)| !edits.is_empty());
+    Ok(files)
+}
+
+pub fn rename(request: Rename) -> Result<WorkspaceEdit, String> {
+    let mut session = Session::start(&request.server, &request.root).map_err(|err| err.to_string())?;
+    let root = file_uri(&request.root);
+    let capabilities = object([(
+        "workspace",
+        object([("workspaceEdit", object([("documentChanges", Value::Bool(true))]))]),
+    )]);
+    session.request(
+        "initialize",
+        object([
+            ("processId", Value::Number(std::process::id() as f64)),
+            ("rootUri", root.as_str().into()),
+            ("capabilities", capabilities),
+        ]),
+    )?;
+    session.notify("initialized", object([]))?;
+    let uri = file_uri(&request.file);
+    let document = object([
+        ("uri", uri.as_str().into()),
+        ("languageId", request.language.as_str().into()),
+        ("version", 1.into()),
+        ("text", request.text.as_str().into()),
+    ]);
+    session.notify("textDocument/didOpen", object([("textDocument", document)]))?;
+    let result = session.request(
+        "textDocument/rename",
+        object([
+            ("textDocument", object([("uri", uri.as_str().into())])),
+            ("position", object([("line", request.line.into()), ("character", request.character.into())])),
+            ("newName", request.new_name.as_str().into()),
+        ]),
+    )?;
+    let _ = session.notify("exit", Value::Null);
+    workspace_edit(&result)
+}
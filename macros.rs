@@ -0,0 +1,125 @@
+use crate::config::Config;
+use crate::keymap::Key;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Default)]
+pub struct Macros {
+    macros: BTreeMap<String, Vec<Key>>,
+    saved: BTreeSet<String>,
+    path: Option<PathBuf>,
+    recording: Option<(String, Vec<Key>)>,
+    last: Option<String>,
+}
+
+impl Macros {
+    pub fn load(path: PathBuf) -> io::Result<Self> {
+        let text = match fs::read_to_string(&path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            result => result?,
+        };
+        let macros: BTreeMap<String, Vec<Key>> = Config::parse(&text)
+            .prefixed("")
+            .filter(|(name, _)| is_valid_name(name))
+            .filter_map(|(name, keys)| Some((name.to_string(), parse(keys)?)))
+            .collect();
+        Ok(Self {
+            saved: macros.keys().cloned().collect(),
+            macros,
+            path: Some(path),
+            ..Self::default()
+        })
+    }
+
+    pub fn recording(&self) -> Option<&str> {
+        self.recording.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    pub fn start(&mut self, name: String) {
+        self.recording = Some((name, Vec::new()));
+    }
+
+    pub fn record(&mut self, key: Key) {
+        if let Some((_, keys)) = self.recording.as_mut() {
+            keys.push(key);
+        }
+    }
+
+    /// Stops recording, dropping the key that stopped it.
+    pub fn stop(&mut self) -> Option<String> {
+        let (name, mut keys) = self.recording.take()?;
+        keys.pop();
+        self.macros.insert(name.clone(), keys);
+        self.last = Some(name.clone());
+        Some(name)
+    }
+
+    pub fn last(&self) -> Option<&str> {
+        self.last.as_deref()
+    }
+
+    pub fn get(&mut self, name: &str) -> Option<Vec<Key>> {
+        let keys = self.macros.get(name)?.clone();
+        self.last = Some(name.to_string());
+        Some(keys)
+    }
+
+    pub fn save(&mut self, name: &str, from: &str) -> io::Result<bool> {
+        let Some(keys) = self.macros.get(from).cloned() else {
+            return Ok(false);
+        };
+        self.macros.insert(name.to_string(), keys);
+        self.saved.insert(name.to_string());
+        self.write()?;
+        Ok(true)
+    }
+
+    pub fn delete(&mut self, name: &str) -> io::Result<bool> {
+        if self.macros.remove(name).is_none() {
+            return Ok(false);
+        }
+        if self.saved.remove(name) {
+            self.write()?;
+        }
+        Ok(true)
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        self.macros
+            .iter()
+            .map(|(name, keys)| {
+                let marker = if self.saved.contains(name) { '*' } else { ' ' };
+                format!("{}@{:<12} {}", marker, name, format(keys))
+            })
+            .collect()
+    }
+
+    fn write(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text: String = self
+            .saved
+            .iter()
+            .filter_map(|name| Some(format!("{} = {}\n", name, format(self.macros.get(name)?))))
+            .collect();
+        fs::write(path, text)
+    }
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+pub fn format(keys: &[Key]) -> String {
+    keys.iter().map(Key::name).collect::<Vec<_>>().join(" ")
+}
+
+pub fn parse(text: &str) -> Option<Vec<Key>> {
+    text.split_whitespace().map(Key::parse).collect()
+}
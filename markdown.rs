@@ -0,0 +1,151 @@
+const RULE_WIDTH: usize = 40;
+
+pub fn is_markdown(filename: &str) -> bool {
+    let lower = filename.to_ascii_lowercase();
+    lower.ends_with(".md") || lower.ends_with(".markdown")
+}
+
+pub fn render(lines: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut fence: Option<String> = None;
+    for line in lines {
+        let trimmed = line.trim_start();
+        if let Some(marker) = &fence {
+            if trimmed.starts_with(marker.as_str()) {
+                fence = None;
+                out.push(String::new());
+            } else {
+                out.push(format!("    {}", line));
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(trimmed[..3].to_string());
+            continue;
+        }
+        if let Some((level, title)) = heading(trimmed) {
+            let title = inline(title);
+            let width = title.chars().count();
+            out.push(title);
+            match level {
+                1 => out.push("═".repeat(width)),
+                2 => out.push("─".repeat(width)),
+                _ => {}
+            }
+            continue;
+        }
+        if is_rule(trimmed) {
+            out.push("─".repeat(RULE_WIDTH));
+            continue;
+        }
+        let indent = &line[..line.len() - trimmed.len()];
+        if let Some(quote) = trimmed.strip_prefix('>') {
+            out.push(format!("{}│ {}", indent, inline(quote.trim_start())));
+        } else if let Some(item) = ["- ", "* ", "+ "].iter().find_map(|bullet| trimmed.strip_prefix(bullet)) {
+            out.push(format!("{}  • {}", indent, task(item)));
+        } else if let Some((number, item)) = ordered(trimmed) {
+            out.push(format!("{}  {}. {}", indent, number, task(item)));
+        } else {
+            out.push(inline(line));
+        }
+    }
+    out
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.is_empty() && !rest.starts_with(' ') {
+        return None;
+    }
+    Some((level, rest.trim().trim_end_matches('#').trim_end()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3 && ['-', '*', '_'].iter().any(|&c| compact.chars().all(|x| x == c))
+}
+
+fn ordered(line: &str) -> Option<(&str, &str)> {
+    let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let rest = line[digits..].strip_prefix(['.', ')'])?.strip_prefix(' ')?;
+    Some((&line[..digits], rest))
+}
+
+fn task(item: &str) -> String {
+    if let Some(rest) = item.strip_prefix("[ ] ") {
+        format!("☐ {}", inline(rest))
+    } else if let Some(rest) = item.strip_prefix("[x] ").or_else(|| item.strip_prefix("[X] ")) {
+        format!("☑ {}", inline(rest))
+    } else {
+        inline(item)
+    }
+}
+
+fn inline(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '\\' if rest.len() > 1 => {
+                let next = rest[1..].chars().next().unwrap_or('\\');
+                out.push(next);
+                rest = &rest[1 + next.len_utf8()..];
+            }
+            '`' => match rest[1..].find('`') {
+                Some(end) => {
+                    out.push_str(&rest[1..end + 1]);
+                    rest = &rest[end + 2..];
+                }
+                None => {
+                    out.push(c);
+                    rest = &rest[1..];
+                }
+            },
+            '!' | '[' => match link(rest) {
+                Some((label, url, len)) => {
+                    out.push_str(&inline(label));
+                    if !url.is_empty() {
+                        out.push_str(&format!(" <{}>", url));
+                    }
+                    rest = &rest[len..];
+                }
+                None => {
+                    out.push(c);
+                    rest = &rest[1..];
+                }
+            },
+            '*' | '_' => {
+                let run = rest.len() - rest.trim_start_matches(c).len();
+                let prev = out.chars().next_back();
+                let next = rest[run..].chars().next();
+                let space = |ch: Option<char>| ch.is_none_or(char::is_whitespace);
+                let word = |ch: Option<char>| ch.is_some_and(char::is_alphanumeric);
+                let intraword = c == '_' && word(prev) && word(next);
+                if space(prev) == space(next) || intraword {
+                    out.push_str(&rest[..run]);
+                }
+                rest = &rest[run..];
+            }
+            _ => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
+
+fn link(text: &str) -> Option<(&str, &str, usize)> {
+    let start = if text.starts_with("![") { 2 } else { 1 };
+    let close = start + text[start..].find(']')?;
+    let after = text[close + 1..].strip_prefix('(')?;
+    let end = after.find(')')?;
+    Some((&text[start..close], &after[..end], close + 2 + end + 1))
+}
@@ -0,0 +1,188 @@
+use crate::conflict::Region;
+use crate::i18n::{tr, Text};
+use crate::screen::{Screen, Style};
+use crate::text;
+use std::fs;
+use std::io;
+use std::ops::Range;
+
+const TABWIDTH: usize = 8;
+const MAX_CELLS: usize = 4_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    Local,
+    Base,
+    Remote,
+}
+
+impl Source {
+    pub const ALL: [Source; 3] = [Source::Local, Source::Base, Source::Remote];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "local" => Some(Source::Local),
+            "base" => Some(Source::Base),
+            "remote" => Some(Source::Remote),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> Text {
+        match self {
+            Source::Local => Text::MergeLocal,
+            Source::Base => Text::MergeBase,
+            Source::Remote => Text::MergeRemote,
+        }
+    }
+
+    fn region(self) -> Region {
+        match self {
+            Source::Local => Region::Ours,
+            Source::Base => Region::Base,
+            Source::Remote => Region::Theirs,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hunk {
+    ranges: [Range<usize>; 3],
+}
+
+pub struct Merge {
+    paths: [String; 3],
+    versions: [Vec<String>; 3],
+    hunks: Vec<Hunk>,
+    pub current: Option<usize>,
+}
+
+fn read_lines(path: &str) -> io::Result<Vec<String>> {
+    match fs::read(path) {
+        Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).lines().map(str::to_string).collect()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+fn matches(a: &[String], b: &[String]) -> Vec<(usize, usize)> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    let (n, m) = (a_mid.len(), b_mid.len());
+    if n > 0 && m > 0 && (n + 1) * (m + 1) <= MAX_CELLS {
+        let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lengths[i * (m + 1) + j] = if a_mid[i] == b_mid[j] {
+                    lengths[(i + 1) * (m + 1) + j + 1] + 1
+                } else {
+                    lengths[(i + 1) * (m + 1) + j].max(lengths[i * (m + 1) + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if a_mid[i] == b_mid[j] {
+                pairs.push((prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if lengths[(i + 1) * (m + 1) + j] >= lengths[i * (m + 1) + j + 1] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+    }
+    pairs.extend((0..suffix).map(|k| (a.len() - suffix + k, b.len() - suffix + k)));
+    pairs
+}
+
+fn diff3(local: &[String], base: &[String], remote: &[String]) -> Vec<Hunk> {
+    let mut to_local = vec![None; base.len()];
+    let mut to_remote = vec![None; base.len()];
+    for (b, l) in matches(base, local) {
+        to_local[b] = Some(l);
+    }
+    for (b, r) in matches(base, remote) {
+        to_remote[b] = Some(r);
+    }
+    let mut hunks = Vec::new();
+    let (mut l, mut b, mut r) = (0, 0, 0);
+    while b < base.len() || l < local.len() || r < remote.len() {
+        if b < base.len() && to_local[b] == Some(l) && to_remote[b] == Some(r) {
+            l += 1;
+            b += 1;
+            r += 1;
+            continue;
+        }
+        let next = (b..base.len()).find(|&k| to_local[k].is_some() && to_remote[k].is_some());
+        let (end_l, end_b, end_r) = match next {
+            Some(k) => (to_local[k].unwrap_or(l), k, to_remote[k].unwrap_or(r)),
+            None => (local.len(), base.len(), remote.len()),
+        };
+        hunks.push(Hunk {
+            ranges: [l..end_l, b..end_b, r..end_r],
+        });
+        (l, b, r) = (end_l, end_b, end_r);
+    }
+    hunks
+}
+
+impl Merge {
+    pub fn load(local: &str, base: &str, remote: &str) -> io::Result<Self> {
+        let versions = [read_lines(local)?, read_lines(base)?, read_lines(remote)?];
+        let hunks = diff3(&versions[0], &versions[1], &versions[2]);
+        Ok(Self {
+            paths: [local.to_string(), base.to_string(), remote.to_string()],
+            versions,
+            hunks,
+            current: None,
+        })
+    }
+
+    pub fn lines(&self, hunk: usize, source: Source) -> &[String] {
+        let index = source as usize;
+        &self.versions[index][self.hunks[hunk].ranges[index].clone()]
+    }
+
+    pub fn find(&self, ours: &[String], theirs: &[String]) -> Option<usize> {
+        (0..self.hunks.len()).find(|&hunk| self.lines(hunk, Source::Local) == ours && self.lines(hunk, Source::Remote) == theirs)
+    }
+
+    pub fn draw(&self, screen: &mut Screen, top: u16, height: u16) {
+        let title = Style {
+            reverse: true,
+            ..Style::default()
+        };
+        let width = (screen.width() as usize).saturating_sub(2) / 3;
+        let rows = (height as usize).saturating_sub(1);
+        for source in Source::ALL {
+            let index = source as usize;
+            let left = (index * (width + 1)) as u16;
+            let heading = format!(" {}: {}", tr(source.label()), self.paths[index]);
+            let heading: String = heading.chars().chain(std::iter::repeat(' ')).take(width).collect();
+            screen.print(left, top, &heading, title);
+            let scroll = match self.current {
+                Some(hunk) => self.hunks[hunk].ranges[index].start.saturating_sub(1),
+                None => 0,
+            };
+            for (row, line) in self.versions[index].iter().enumerate().skip(scroll).take(rows) {
+                let y = top + 1 + (row - scroll) as u16;
+                let cells: String = text::window(line, TABWIDTH, 0, width).into_iter().collect();
+                screen.print(left, y, &cells, Style::default());
+                if self.hunks.iter().any(|hunk| hunk.ranges[index].contains(&row)) {
+                    for x in left..left + width as u16 {
+                        screen.tint(x, y, source.region().color());
+                    }
+                }
+            }
+            if index < 2 {
+                for y in top..top + height {
+                    screen.put(left + width as u16, y, '│', Style::default());
+                }
+            }
+        }
+    }
+}
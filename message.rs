@@ -0,0 +1,71 @@
+use crate::i18n::{tr, Text};
+use crate::screen::Style;
+use crossterm::style::Color;
+
+const HISTORY_LIMIT: usize = 200;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        tr(match self {
+            Severity::Info => Text::SeverityInfo,
+            Severity::Warning => Text::SeverityWarning,
+            Severity::Error => Text::SeverityError,
+        })
+    }
+
+    pub fn style(self) -> Style {
+        match self {
+            Severity::Info => Style::default(),
+            Severity::Warning => Style {
+                fg: Some(Color::Yellow),
+                ..Style::default()
+            },
+            Severity::Error => Style {
+                fg: Some(Color::Red),
+                bold: true,
+                ..Style::default()
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Message {
+    pub severity: Severity,
+    pub text: String,
+}
+
+#[derive(Default)]
+pub struct Messages {
+    history: Vec<Message>,
+    visible: bool,
+}
+
+impl Messages {
+    pub fn push(&mut self, severity: Severity, text: String) {
+        self.history.push(Message { severity, text });
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+        self.visible = true;
+    }
+
+    pub fn current(&self) -> Option<&Message> {
+        self.history.last().filter(|_| self.visible)
+    }
+
+    pub fn dismiss(&mut self) {
+        self.visible = false;
+    }
+
+    pub fn history(&self) -> &[Message] {
+        &self.history
+    }
+}
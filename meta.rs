@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::Path;
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::os::raw::{c_char, c_int, c_void};
+
+    extern "C" {
+        pub fn listxattr(path: *const c_char, list: *mut c_char, size: usize) -> isize;
+        pub fn getxattr(path: *const c_char, name: *const c_char, value: *mut c_void, size: usize) -> isize;
+        pub fn setxattr(path: *const c_char, name: *const c_char, value: *const c_void, size: usize, flags: c_int) -> c_int;
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Metadata {
+    permissions: fs::Permissions,
+    #[cfg(unix)]
+    owner: (u32, u32),
+    #[cfg(target_os = "linux")]
+    xattrs: Vec<(std::ffi::CString, Vec<u8>)>,
+}
+
+pub fn capture(path: &Path) -> Option<Metadata> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+    Some(Metadata {
+        permissions: metadata.permissions(),
+        #[cfg(unix)]
+        owner: {
+            use std::os::unix::fs::MetadataExt;
+            (metadata.uid(), metadata.gid())
+        },
+        #[cfg(target_os = "linux")]
+        xattrs: xattrs(path),
+    })
+}
+
+pub fn restore(path: &Path, metadata: &Metadata) {
+    let _ = fs::set_permissions(path, metadata.permissions.clone());
+    #[cfg(unix)]
+    {
+        let (uid, gid) = metadata.owner;
+        if std::os::unix::fs::chown(path, Some(uid), Some(gid)).is_err() {
+            let _ = std::os::unix::fs::chown(path, None, Some(gid));
+        }
+    }
+    #[cfg(target_os = "linux")]
+    if let Some(path) = c_path(path) {
+        for (name, value) in &metadata.xattrs {
+            unsafe { sys::setxattr(path.as_ptr(), name.as_ptr(), value.as_ptr().cast(), value.len(), 0) };
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn c_path(path: &Path) -> Option<std::ffi::CString> {
+    use std::os::unix::ffi::OsStrExt;
+    std::ffi::CString::new(path.as_os_str().as_bytes()).ok()
+}
+
+#[cfg(target_os = "linux")]
+fn xattrs(path: &Path) -> Vec<(std::ffi::CString, Vec<u8>)> {
+    use std::ffi::CStr;
+    let Some(path) = c_path(path) else {
+        return Vec::new();
+    };
+    let size = unsafe { sys::listxattr(path.as_ptr(), std::ptr::null_mut(), 0) };
+    if size <= 0 {
+        return Vec::new();
+    }
+    let mut names = vec![0u8; size as usize];
+    let size = unsafe { sys::listxattr(path.as_ptr(), names.as_mut_ptr().cast(), names.len()) };
+    names.truncate(size.max(0) as usize);
+    let mut attrs = Vec::new();
+    for name in names.split_inclusive(|&byte| byte == 0) {
+        let Ok(name) = CStr::from_bytes_with_nul(name) else {
+            continue;
+        };
+        let size = unsafe { sys::getxattr(path.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0) };
+        if size < 0 {
+            continue;
+        }
+        let mut value = vec![0u8; size as usize];
+        let size = unsafe { sys::getxattr(path.as_ptr(), name.as_ptr(), value.as_mut_ptr().cast(), value.len()) };
+        if size >= 0 {
+            value.truncate(size as usize);
+            attrs.push((name.to_owned(), value));
+        }
+    }
+    attrs
+}
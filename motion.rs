@@ -0,0 +1,527 @@
+use crate::text;
+use crate::Position;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    WordForward,
+    WordBackward,
+    WordEnd,
+    FileStart,
+    FileEnd,
+    ParagraphForward,
+    ParagraphBackward,
+    BlockStart,
+    BlockEnd,
+    Lines,
+}
+
+impl Motion {
+    pub fn linewise(self) -> bool {
+        matches!(
+            self,
+            Motion::Up
+                | Motion::Down
+                | Motion::FileStart
+                | Motion::FileEnd
+                | Motion::BlockStart
+                | Motion::BlockEnd
+                | Motion::Lines
+        )
+    }
+
+    pub fn inclusive(self) -> bool {
+        self == Motion::WordEnd
+    }
+}
+
+fn char_at(buffer: &[String], pos: Position) -> char {
+    buffer[pos.y][pos.x..].chars().next().unwrap_or('\n')
+}
+
+fn class(c: char) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+fn next(buffer: &[String], pos: Position) -> Option<Position> {
+    let line = &buffer[pos.y];
+    if pos.x < line.len() {
+        Some(Position {
+            x: text::next_boundary(line, pos.x),
+            y: pos.y,
+        })
+    } else if pos.y + 1 < buffer.len() {
+        Some(Position { x: 0, y: pos.y + 1 })
+    } else {
+        None
+    }
+}
+
+fn prev(buffer: &[String], pos: Position) -> Option<Position> {
+    if pos.x > 0 {
+        Some(Position {
+            x: text::prev_boundary(&buffer[pos.y], pos.x),
+            y: pos.y,
+        })
+    } else if pos.y > 0 {
+        Some(Position {
+            x: buffer[pos.y - 1].len(),
+            y: pos.y - 1,
+        })
+    } else {
+        None
+    }
+}
+
+fn empty_line(buffer: &[String], pos: Position) -> bool {
+    buffer[pos.y].is_empty()
+}
+
+fn word_forward(buffer: &[String], mut pos: Position) -> Position {
+    let start = class(char_at(buffer, pos));
+    if start != 0 {
+        while let Some(n) = next(buffer, pos) {
+            pos = n;
+            if class(char_at(buffer, pos)) != start {
+                break;
+            }
+        }
+    }
+    while class(char_at(buffer, pos)) == 0 {
+        match next(buffer, pos) {
+            Some(n) => pos = n,
+            None => break,
+        }
+        if empty_line(buffer, pos) {
+            break;
+        }
+    }
+    pos
+}
+
+fn word_backward(buffer: &[String], mut pos: Position) -> Position {
+    let Some(p) = prev(buffer, pos) else {
+        return pos;
+    };
+    pos = p;
+    while class(char_at(buffer, pos)) == 0 && !empty_line(buffer, pos) {
+        match prev(buffer, pos) {
+            Some(p) => pos = p,
+            None => return pos,
+        }
+    }
+    let current = class(char_at(buffer, pos));
+    while let Some(p) = prev(buffer, pos) {
+        if current == 0 || class(char_at(buffer, p)) != current {
+            break;
+        }
+        pos = p;
+    }
+    pos
+}
+
+fn word_end(buffer: &[String], mut pos: Position) -> Position {
+    let Some(n) = next(buffer, pos) else {
+        return pos;
+    };
+    pos = n;
+    while class(char_at(buffer, pos)) == 0 {
+        match next(buffer, pos) {
+            Some(n) => pos = n,
+            None => return pos,
+        }
+    }
+    let current = class(char_at(buffer, pos));
+    while let Some(n) = next(buffer, pos) {
+        if class(char_at(buffer, n)) != current {
+            break;
+        }
+        pos = n;
+    }
+    pos
+}
+
+fn blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+fn paragraph_forward(buffer: &[String], mut y: usize) -> Position {
+    let last = buffer.len() - 1;
+    while y < last && blank(&buffer[y]) {
+        y += 1;
+    }
+    while y < last && !blank(&buffer[y]) {
+        y += 1;
+    }
+    let x = if blank(&buffer[y]) { 0 } else { buffer[y].len() };
+    Position { x, y }
+}
+
+fn paragraph_backward(buffer: &[String], mut y: usize) -> Position {
+    while y > 0 && blank(&buffer[y]) {
+        y -= 1;
+    }
+    while y > 0 && !blank(&buffer[y]) {
+        y -= 1;
+    }
+    Position { x: 0, y }
+}
+
+fn block_edge(buffer: &[String], y: usize, forward: bool) -> usize {
+    let Some(anchor) = (y..buffer.len()).find(|&i| !blank(&buffer[i])) else {
+        return y;
+    };
+    let level = first_non_blank(&buffer[anchor]);
+    let lines: Box<dyn Iterator<Item = usize>> = if forward {
+        Box::new(anchor + 1..buffer.len())
+    } else {
+        Box::new((0..y).rev())
+    };
+    let mut edge = anchor;
+    let mut outer = None;
+    for i in lines.filter(|&i| !blank(&buffer[i])) {
+        if first_non_blank(&buffer[i]) < level {
+            outer = Some(i);
+            break;
+        }
+        edge = i;
+    }
+    if edge == y {
+        outer.unwrap_or(y)
+    } else {
+        edge
+    }
+}
+
+pub fn first_non_blank(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+pub fn target(buffer: &[String], from: Position, motion: Motion, count: Option<usize>) -> Position {
+    let n = count.unwrap_or(1);
+    let last = buffer.len() - 1;
+    let column = |y: usize, x: usize| Position {
+        x: text::snap(&buffer[y], x),
+        y,
+    };
+    match motion {
+        Motion::Left => {
+            let mut pos = from;
+            for _ in 0..n {
+                match prev(buffer, pos).filter(|p| p.y == from.y) {
+                    Some(p) => pos = p,
+                    None => break,
+                }
+            }
+            pos
+        }
+        Motion::Right => {
+            let mut pos = from;
+            for _ in 0..n {
+                match next(buffer, pos).filter(|p| p.y == from.y) {
+                    Some(p) => pos = p,
+                    None => break,
+                }
+            }
+            pos
+        }
+        Motion::Up => column(from.y.saturating_sub(n), from.x),
+        Motion::Down => column((from.y + n).min(last), from.x),
+        Motion::LineStart => column(from.y, 0),
+        Motion::FirstNonBlank => column(from.y, first_non_blank(&buffer[from.y])),
+        Motion::LineEnd => {
+            let y = (from.y + n - 1).min(last);
+            column(y, buffer[y].len())
+        }
+        Motion::WordForward => (0..n).fold(from, |pos, _| word_forward(buffer, pos)),
+        Motion::WordBackward => (0..n).fold(from, |pos, _| word_backward(buffer, pos)),
+        Motion::WordEnd => (0..n).fold(from, |pos, _| word_end(buffer, pos)),
+        Motion::FileStart => {
+            let y = count.map_or(0, |n| n.saturating_sub(1)).min(last);
+            column(y, first_non_blank(&buffer[y]))
+        }
+        Motion::FileEnd => {
+            let y = count.map_or(last, |n| n.saturating_sub(1)).min(last);
+            column(y, first_non_blank(&buffer[y]))
+        }
+        Motion::ParagraphForward => (0..n).fold(from, |pos, _| paragraph_forward(buffer, pos.y)),
+        Motion::ParagraphBackward => (0..n).fold(from, |pos, _| paragraph_backward(buffer, pos.y)),
+        Motion::BlockStart | Motion::BlockEnd => {
+            let y = (0..n).fold(from.y, |y, _| block_edge(buffer, y, motion == Motion::BlockEnd));
+            column(y, first_non_blank(&buffer[y]))
+        }
+        Motion::Lines => column((from.y + n - 1).min(last), from.x),
+    }
+}
+
+pub fn after(buffer: &[String], pos: Position) -> Position {
+    next(buffer, pos).filter(|p| p.y == pos.y).unwrap_or(pos)
+}
+
+pub fn forward_word(buffer: &[String], mut pos: Position) -> Position {
+    while class(char_at(buffer, pos)) != 1 {
+        match next(buffer, pos) {
+            Some(n) => pos = n,
+            None => return pos,
+        }
+    }
+    while class(char_at(buffer, pos)) == 1 {
+        match next(buffer, pos) {
+            Some(n) => pos = n,
+            None => break,
+        }
+    }
+    pos
+}
+
+pub fn backward_word(buffer: &[String], mut pos: Position) -> Position {
+    while let Some(p) = prev(buffer, pos) {
+        if class(char_at(buffer, p)) == 1 {
+            break;
+        }
+        pos = p;
+    }
+    while let Some(p) = prev(buffer, pos) {
+        if class(char_at(buffer, p)) != 1 {
+            break;
+        }
+        pos = p;
+    }
+    pos
+}
+
+pub fn pair(c: char) -> Option<(char, char)> {
+    match c {
+        '(' | ')' | 'b' => Some(('(', ')')),
+        '[' | ']' | 'r' => Some(('[', ']')),
+        '{' | '}' | 'B' => Some(('{', '}')),
+        '<' | '>' | 'a' => Some(('<', '>')),
+        '"' | '\'' | '`' => Some((c, c)),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Object {
+    Word,
+    Quote(char),
+    Pair(char, char),
+    Tag,
+    Line,
+}
+
+impl Object {
+    pub fn from_char(c: char) -> Option<Object> {
+        match c {
+            'w' => Some(Object::Word),
+            't' => Some(Object::Tag),
+            'l' => Some(Object::Line),
+            _ => pair(c).map(|(open, close)| if open == close { Object::Quote(open) } else { Object::Pair(open, close) }),
+        }
+    }
+}
+
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+    pub linewise: bool,
+}
+
+/// The clusters around `x` of the same class, each classed by its base
+/// character so combining marks stay with it.
+fn run(line: &str, x: usize) -> (usize, usize) {
+    let class_at = |i: usize| class(line[i..].chars().next().unwrap_or(' '));
+    let current = class_at(x);
+    let mut start = x;
+    while start > 0 && class_at(text::prev_boundary(line, start)) == current {
+        start = text::prev_boundary(line, start);
+    }
+    let end = text::graphemes(&line[x..])
+        .find(|&(_, cluster)| class(cluster.chars().next().unwrap_or(' ')) != current)
+        .map_or(line.len(), |(i, _)| x + i);
+    (start, end)
+}
+
+fn word_object(line: &str, x: usize, around: bool) -> Option<(usize, usize)> {
+    if line.is_empty() {
+        return None;
+    }
+    let x = if x < line.len() { x } else { text::prev_boundary(line, line.len()) };
+    let (mut start, mut end) = run(line, x);
+    if around {
+        let on_space = |i: usize| line[i..].chars().next().is_some_and(char::is_whitespace);
+        if end < line.len() && (on_space(end) || on_space(x)) {
+            end = run(line, end).1;
+        } else if !on_space(x) {
+            if let Some((i, c)) = line[..start].char_indices().next_back() {
+                if c.is_whitespace() {
+                    start = run(line, i).0;
+                }
+            }
+        }
+    }
+    Some((start, end))
+}
+
+fn quote_bounds(buffer: &[String], pos: Position, quote: char) -> Option<[Position; 4]> {
+    let line = &buffer[pos.y];
+    let mut quotes = Vec::new();
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            quotes.push(i);
+        }
+    }
+    let (open, close) = quotes
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|&(_, close)| pos.x <= close)?;
+    let width = quote.len_utf8();
+    Some([open, open + width, close, close + width].map(|x| Position { x, y: pos.y }))
+}
+
+fn enclosing(buffer: &[String], pos: Position, open: char, close: char) -> Option<(Position, Position)> {
+    let mut depth = 0;
+    let mut at = if char_at(buffer, pos) == close { prev(buffer, pos)? } else { pos };
+    let start = loop {
+        let c = char_at(buffer, at);
+        if c == close {
+            depth += 1;
+        } else if c == open {
+            if depth == 0 {
+                break at;
+            }
+            depth -= 1;
+        }
+        at = prev(buffer, at)?;
+    };
+    at = start;
+    loop {
+        at = next(buffer, at)?;
+        let c = char_at(buffer, at);
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                return Some((start, at));
+            }
+            depth -= 1;
+        }
+    }
+}
+
+fn pair_bounds(buffer: &[String], pos: Position, open: char, close: char, count: Option<usize>) -> Option<[Position; 4]> {
+    let (mut start, mut end) = enclosing(buffer, pos, open, close)?;
+    for _ in 1..count.unwrap_or(1) {
+        match prev(buffer, start).and_then(|p| enclosing(buffer, p, open, close)) {
+            Some(outer) => (start, end) = outer,
+            None => break,
+        }
+    }
+    Some([start, after(buffer, start), end, after(buffer, end)])
+}
+
+fn position(buffer: &[String], mut offset: usize) -> Position {
+    for (y, line) in buffer.iter().enumerate() {
+        if offset <= line.len() {
+            return Position { x: offset, y };
+        }
+        offset -= line.len() + 1;
+    }
+    let y = buffer.len() - 1;
+    Position { x: buffer[y].len(), y }
+}
+
+fn tag_bounds(buffer: &[String], pos: Position) -> Option<[Position; 4]> {
+    let text = buffer.join("\n");
+    let offset = buffer[..pos.y].iter().map(|line| line.len() + 1).sum::<usize>() + pos.x;
+    let mut open: Vec<(&str, usize, usize)> = Vec::new();
+    let mut from = 0;
+    while let Some(i) = text[from..].find('<') {
+        let start = from + i;
+        let Some(j) = text[start..].find('>') else {
+            break;
+        };
+        let end = start + j + 1;
+        let tag = &text[start + 1..end - 1];
+        from = end;
+        if let Some(name) = tag.strip_prefix('/') {
+            let Some(k) = open.iter().rposition(|&(n, _, _)| n == name.trim()) else {
+                continue;
+            };
+            let (_, open_start, open_end) = open[k];
+            open.truncate(k);
+            if open_start <= offset && offset < end {
+                return Some([open_start, open_end, start, end].map(|o| position(buffer, o)));
+            }
+        } else if !tag.ends_with('/') && !tag.starts_with(['!', '?']) {
+            open.push((tag.split_whitespace().next().unwrap_or(""), start, end));
+        }
+    }
+    None
+}
+
+pub fn bounds(buffer: &[String], pos: Position, object: Object, count: Option<usize>) -> Option<[Position; 4]> {
+    match object {
+        Object::Quote(quote) => quote_bounds(buffer, pos, quote),
+        Object::Pair(open, close) => pair_bounds(buffer, pos, open, close, count),
+        Object::Tag => tag_bounds(buffer, pos),
+        Object::Word | Object::Line => None,
+    }
+}
+
+pub fn select(buffer: &[String], pos: Position, object: Object, around: bool, count: Option<usize>) -> Option<Span> {
+    let line = &buffer[pos.y];
+    let charwise = |start: Position, end: Position| Span {
+        start,
+        end,
+        linewise: false,
+    };
+    let on_line = |(start, end): (usize, usize)| charwise(Position { x: start, y: pos.y }, Position { x: end, y: pos.y });
+    match object {
+        Object::Word => word_object(line, pos.x, around).map(on_line),
+        Object::Line if around => Some(Span {
+            start: Position { x: 0, y: pos.y },
+            end: Position { x: line.len(), y: pos.y },
+            linewise: true,
+        }),
+        Object::Line => {
+            let indent = first_non_blank(line);
+            Some(on_line((indent, line.trim_end().len().max(indent))))
+        }
+        _ => {
+            let [outer_start, start, end, outer_end] = bounds(buffer, pos, object, count)?;
+            if around {
+                return Some(charwise(outer_start, outer_end));
+            }
+            let block = start.x == buffer[start.y].len() && end.y > start.y && blank(&buffer[end.y][..end.x]);
+            if block && end.y > start.y + 1 {
+                return Some(Span {
+                    start: Position { x: 0, y: start.y + 1 },
+                    end: Position {
+                        x: buffer[end.y - 1].len(),
+                        y: end.y - 1,
+                    },
+                    linewise: true,
+                });
+            }
+            Some(if block { charwise(end, end) } else { charwise(start, end) })
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use crate::i18n::{trf, Text};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Int(usize),
+    Str(String),
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Default {
+    Bool(bool),
+    Int(usize),
+    Str(&'static str),
+}
+
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub short: &'static str,
+    default: Default,
+    choices: &'static [&'static str],
+}
+
+impl OptionSpec {
+    fn default_value(&self) -> Value {
+        match self.default {
+            Default::Bool(b) => Value::Bool(b),
+            Default::Int(n) => Value::Int(n),
+            Default::Str(s) => Value::Str(s.to_string()),
+        }
+    }
+}
+
+pub const OPTIONS: &[OptionSpec] = &[
+    OptionSpec {
+        name: "number",
+        short: "nu",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "wrap",
+        short: "wrap",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "scrolloff",
+        short: "so",
+        default: Default::Int(0),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "tabwidth",
+        short: "ts",
+        default: Default::Int(4),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "textwidth",
+        short: "tw",
+        default: Default::Int(79),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "autosave",
+        short: "aw",
+        default: Default::Int(0),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "undolevels",
+        short: "ul",
+        default: Default::Int(50),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "undosize",
+        short: "us",
+        default: Default::Int(0),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "ignorecase",
+        short: "ic",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "autopair",
+        short: "ap",
+        default: Default::Bool(true),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "paste",
+        short: "paste",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "compose",
+        short: "compose",
+        default: Default::Bool(true),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "normalize",
+        short: "nfc",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "cursorline",
+        short: "cul",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "cursorcolumn",
+        short: "cuc",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "overlength",
+        short: "ol",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "colorcolumn",
+        short: "cc",
+        default: Default::Str(""),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "scrollbar",
+        short: "sb",
+        default: Default::Bool(true),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "breadcrumbs",
+        short: "bc",
+        default: Default::Bool(true),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "cursorshape",
+        short: "cs",
+        default: Default::Str("block"),
+        choices: &["block", "bar", "underline"],
+    },
+    OptionSpec {
+        name: "cursorblink",
+        short: "cb",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "profiling",
+        short: "prof",
+        default: Default::Bool(false),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "scp",
+        short: "scp",
+        default: Default::Str("scp"),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "curl",
+        short: "curl",
+        default: Default::Str("curl"),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "gpg",
+        short: "gpg",
+        default: Default::Str("gpg"),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "age",
+        short: "age",
+        default: Default::Str("age"),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "sudo",
+        short: "sudo",
+        default: Default::Str("sudo"),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "symlinks",
+        short: "sl",
+        default: Default::Str("follow"),
+        choices: &["follow", "replace"],
+    },
+    OptionSpec {
+        name: "osc52",
+        short: "osc52",
+        default: Default::Str("auto"),
+        choices: &["auto", "always", "never"],
+    },
+    OptionSpec {
+        name: "osc52limit",
+        short: "osc52limit",
+        default: Default::Int(100_000),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "yankflash",
+        short: "yf",
+        default: Default::Int(200),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "yankcolor",
+        short: "yc",
+        default: Default::Int(24),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "bell",
+        short: "vb",
+        default: Default::Str("status"),
+        choices: &["status", "screen", "none"],
+    },
+    OptionSpec {
+        name: "showcmd",
+        short: "sc",
+        default: Default::Bool(true),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "timeoutlen",
+        short: "tm",
+        default: Default::Int(1000),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "leader",
+        short: "ldr",
+        default: Default::Str("\\"),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "whichkey",
+        short: "wk",
+        default: Default::Int(500),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "statusline",
+        short: "stl",
+        default: Default::Str(""),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "title",
+        short: "title",
+        default: Default::Bool(true),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "restorecursor",
+        short: "rc",
+        default: Default::Bool(true),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "mousescroll",
+        short: "mscr",
+        default: Default::Int(3),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "enhancedkeys",
+        short: "ek",
+        default: Default::Bool(true),
+        choices: &[],
+    },
+    OptionSpec {
+        name: "keymap",
+        short: "km",
+        default: Default::Str("default"),
+        choices: &["default", "vim", "emacs"],
+    },
+];
+
+pub struct Options {
+    values: BTreeMap<&'static str, Value>,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self {
+            values: OPTIONS.iter().map(|spec| (spec.name, spec.default_value())).collect(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.values.get(name)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        matches!(self.get(name), Some(Value::Bool(true)))
+    }
+
+    pub fn int(&self, name: &str) -> usize {
+        match self.get(name) {
+            Some(Value::Int(n)) => *n,
+            _ => 0,
+        }
+    }
+
+    pub fn string(&self, name: &str) -> &str {
+        match self.get(name) {
+            Some(Value::Str(s)) => s,
+            _ => "",
+        }
+    }
+
+    pub fn is_option(name: &str) -> bool {
+        Self::spec(name).is_some()
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        OPTIONS
+            .iter()
+            .map(|spec| format!("{:<16}{}", spec.name, self.values[spec.name]))
+            .collect()
+    }
+
+    pub fn set(&mut self, expr: &str) -> Result<Option<String>, String> {
+        if let Some(name) = expr.strip_suffix('?') {
+            let spec = Self::spec(name).ok_or_else(|| trf(Text::UnknownOption, &[&name]))?;
+            return Ok(Some(format!("{}={}", spec.name, self.values[spec.name])));
+        }
+        if let Some(name) = expr.strip_suffix('!') {
+            let spec = Self::spec(name).ok_or_else(|| trf(Text::UnknownOption, &[&name]))?;
+            let toggled = !self.flag(spec.name);
+            return self.assign(spec, Value::Bool(toggled));
+        }
+        if let Some((name, raw)) = expr.split_once('=') {
+            let spec = Self::spec(name.trim()).ok_or_else(|| trf(Text::UnknownOption, &[&name]))?;
+            let value = Self::parse(spec, raw.trim())?;
+            return self.assign(spec, value);
+        }
+        if let Some(spec) = Self::spec(expr) {
+            return match spec.default {
+                Default::Bool(_) => self.assign(spec, Value::Bool(true)),
+                _ => Ok(Some(format!("{}={}", spec.name, self.values[spec.name]))),
+            };
+        }
+        match expr.strip_prefix("no").and_then(Self::spec) {
+            Some(spec) if matches!(spec.default, Default::Bool(_)) => self.assign(spec, Value::Bool(false)),
+            _ => Err(trf(Text::UnknownOption, &[&expr])),
+        }
+    }
+
+    /// Splits `:set` arguments on whitespace that is not escaped with `\`.
+    pub fn split(arg: &str) -> Vec<String> {
+        let mut exprs = Vec::new();
+        let mut current = String::new();
+        let mut chars = arg.chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some(next)) if next.is_whitespace() || next == '\\' => {
+                    current.push(next);
+                    chars.next();
+                }
+                _ if c.is_whitespace() => exprs.extend(Some(std::mem::take(&mut current)).filter(|expr| !expr.is_empty())),
+                _ => current.push(c),
+            }
+        }
+        exprs.extend(Some(current).filter(|expr| !expr.is_empty()));
+        exprs
+    }
+
+    fn assign(&mut self, spec: &OptionSpec, value: Value) -> Result<Option<String>, String> {
+        self.values.insert(spec.name, value);
+        Ok(None)
+    }
+
+    fn spec(name: &str) -> Option<&'static OptionSpec> {
+        OPTIONS.iter().find(|spec| spec.name == name || spec.short == name)
+    }
+
+    fn parse(spec: &OptionSpec, raw: &str) -> Result<Value, String> {
+        let invalid = || trf(Text::InvalidOptionValue, &[&spec.name, &raw]);
+        match spec.default {
+            Default::Bool(_) => match raw {
+                "true" | "on" | "yes" | "1" => Ok(Value::Bool(true)),
+                "false" | "off" | "no" | "0" => Ok(Value::Bool(false)),
+                _ => Err(invalid()),
+            },
+            Default::Int(_) => raw.parse().map(Value::Int).map_err(|_| invalid()),
+            Default::Str(_) => {
+                if spec.choices.is_empty() || spec.choices.contains(&raw) {
+                    Ok(Value::Str(raw.to_string()))
+                } else {
+                    Err(invalid())
+                }
+            }
+        }
+    }
+}
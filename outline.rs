@@ -0,0 +1,205 @@
+use crate::i18n::{tr, Text};
+use crate::pattern::Regex;
+use crate::screen::{Screen, Style};
+use crossterm::event::KeyCode;
+use crossterm::style::Color;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Symbol {
+    pub line: usize,
+    pub depth: usize,
+    pub kind: String,
+    pub name: String,
+}
+
+const RUST: &[(&str, &str, &str)] = &[
+    (r#"^\s*([\w()"]+\s+)*(fn|struct|enum|trait|union|type|mod)\s+(\w+)"#, r"\2", r"\3"),
+    (r"^\s*(unsafe\s+)?impl(<[^>]*>)?\s+([^{]*[^{\s])", "impl", r"\3"),
+    (r"^\s*macro_rules!\s*(\w+)", "macro", r"\1"),
+];
+const C: &[(&str, &str, &str)] = &[
+    (r"^(typedef\s+)?(struct|enum|union|class|namespace)\s+(\w+)", r"\2", r"\3"),
+    (r"^\w[\w\s\*&:<>,]*[\s\*&](~?[\w:]+)\s*\([^;]*$", "fn", r"\1"),
+];
+const GO: &[(&str, &str, &str)] = &[
+    (r"^func\s+(\([^)]*\)\s*)?(\w+)", "func", r"\2"),
+    (r"^type\s+(\w+)\s+(\w+)", r"\2", r"\1"),
+];
+const PYTHON: &[(&str, &str, &str)] = &[(r"^\s*(async\s+)?(def|class)\s+(\w+)", r"\2", r"\3")];
+const JAVASCRIPT: &[(&str, &str, &str)] = &[
+    (r"^\s*(export\s+)?(default\s+)?(async\s+)?(function|class|interface|enum|type)\*?\s+(\w+)", r"\4", r"\5"),
+    (r"^\s*(export\s+)?const\s+(\w+)\s*=\s*(async\s*)?(\([^)]*\)|\w+)\s*=>", "function", r"\2"),
+];
+const SH: &[(&str, &str, &str)] = &[(r"^\s*(function\s+)?([\w-]+)\s*\(\)|^\s*function\s+([\w-]+)", "function", r"\2\3")];
+const RUBY: &[(&str, &str, &str)] = &[(r"^\s*(def|class|module)\s+([\w.:?!]+)", r"\1", r"\2")];
+const PERL: &[(&str, &str, &str)] = &[(r"^\s*(sub|package)\s+([\w:]+)", r"\1", r"\2")];
+const LUA: &[(&str, &str, &str)] = &[(r"^\s*(local\s+)?function\s+([\w.:]+)", "function", r"\2")];
+const MAKE: &[(&str, &str, &str)] = &[(r"^([\w-][\w./-]*)\s*:([^=]|$)", "target", r"\1")];
+const TOML: &[(&str, &str, &str)] = &[(r"^\s*\[+([^\]]+)\]+", "table", r"\1")];
+const MARKDOWN: &[(&str, &str, &str)] = &[(r"^(#+)\s+(.*\S)", r"\1", r"\2")];
+
+fn rules(filetype: &str) -> &'static [(&'static str, &'static str, &'static str)] {
+    match filetype {
+        "rust" => RUST,
+        "c" | "cpp" => C,
+        "go" => GO,
+        "python" => PYTHON,
+        "javascript" | "typescript" => JAVASCRIPT,
+        "sh" => SH,
+        "ruby" => RUBY,
+        "perl" => PERL,
+        "lua" => LUA,
+        "make" => MAKE,
+        "toml" => TOML,
+        "markdown" => MARKDOWN,
+        _ => &[],
+    }
+}
+
+pub fn symbols(filetype: Option<&str>, lines: &[String]) -> Vec<Symbol> {
+    let rules: Vec<(Regex, &str, &str)> = rules(filetype.unwrap_or(""))
+        .iter()
+        .filter_map(|&(pattern, kind, name)| Some((Regex::new(pattern, false).ok()?, kind, name)))
+        .collect();
+    let markdown = filetype == Some("markdown");
+    let mut symbols = Vec::new();
+    let mut levels: Vec<usize> = Vec::new();
+    let mut fenced = false;
+    for (line, text) in lines.iter().enumerate() {
+        if markdown && text.trim_start().starts_with("```") {
+            fenced = !fenced;
+        }
+        if fenced {
+            continue;
+        }
+        let Some((m, kind, name)) = rules.iter().find_map(|(regex, kind, name)| Some((regex.find_at(text, 0)?, kind, name))) else {
+            continue;
+        };
+        let mut kind = m.expand(kind, text);
+        let level = if markdown {
+            let level = kind.len();
+            kind = "#".repeat(level);
+            level
+        } else {
+            text.len() - text.trim_start().len()
+        };
+        while levels.last().is_some_and(|&last| last >= level) {
+            levels.pop();
+        }
+        symbols.push(Symbol {
+            line,
+            depth: levels.len(),
+            kind,
+            name: m.expand(name, text),
+        });
+        levels.push(level);
+    }
+    symbols
+}
+
+pub fn enclosing(symbols: &[Symbol], line: usize) -> Option<usize> {
+    symbols.iter().rposition(|symbol| symbol.line <= line)
+}
+
+pub fn scope(symbols: &[Symbol], line: usize) -> Vec<&Symbol> {
+    let Some(mut index) = enclosing(symbols, line) else {
+        return Vec::new();
+    };
+    let mut chain = vec![&symbols[index]];
+    while let Some(parent) = symbols[..index].iter().rposition(|symbol| symbol.depth < symbols[index].depth) {
+        index = parent;
+        chain.push(&symbols[index]);
+    }
+    chain.reverse();
+    chain
+}
+
+pub struct Outline {
+    pub symbols: Vec<Symbol>,
+    pub selected: usize,
+    pub focused: bool,
+    scroll: usize,
+    fingerprint: Option<u64>,
+}
+
+impl Outline {
+    pub fn new() -> Self {
+        Self {
+            symbols: Vec::new(),
+            selected: 0,
+            focused: true,
+            scroll: 0,
+            fingerprint: None,
+        }
+    }
+
+    /// Takes a copy of `symbols` unless they are the ones already shown.
+    pub fn update(&mut self, fingerprint: u64, symbols: &[Symbol], line: usize) {
+        if self.fingerprint != Some(fingerprint) {
+            self.symbols = symbols.to_vec();
+            self.fingerprint = Some(fingerprint);
+        }
+        if !self.focused {
+            self.selected = enclosing(&self.symbols, line).unwrap_or(0);
+        }
+        self.selected = self.selected.min(self.symbols.len().saturating_sub(1));
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> Option<usize> {
+        let last = self.symbols.len().saturating_sub(1);
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(last),
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(10),
+            KeyCode::PageDown => self.selected = (self.selected + 10).min(last),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            KeyCode::Enter => {
+                self.focused = false;
+                return self.symbols.get(self.selected).map(|symbol| symbol.line);
+            }
+            KeyCode::Esc => self.focused = false,
+            _ => {}
+        }
+        None
+    }
+
+    pub fn draw(&mut self, screen: &mut Screen, x: u16, top: u16, width: u16, height: u16) {
+        let plain = Style::default();
+        let title = Style {
+            reverse: true,
+            bold: self.focused,
+            ..plain
+        };
+        let inner = width.saturating_sub(1) as usize;
+        let rows = (height as usize).saturating_sub(1);
+        for y in 0..height {
+            screen.print(x, top + y, "│", plain);
+        }
+        screen.print(x + 1, top, &format!(" {:<1$}", tr(Text::OutlineTitle), inner.saturating_sub(1)), title);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows.max(1);
+        }
+        let kind = Style {
+            fg: Some(Color::DarkGrey),
+            ..plain
+        };
+        for (row, (index, symbol)) in self.symbols.iter().enumerate().skip(self.scroll).take(rows).enumerate() {
+            let y = top + 1 + row as u16;
+            let selected = Style {
+                reverse: index == self.selected,
+                ..plain
+            };
+            let indent = " ".repeat(1 + 2 * symbol.depth);
+            let end = screen.print(x + 1, y, &format!("{}{} ", indent, symbol.kind), if index == self.selected { selected } else { kind });
+            let name: String = symbol.name.chars().take((x + width).saturating_sub(end) as usize).collect();
+            let end = screen.print(end, y, &name, selected);
+            if index == self.selected {
+                let fill = (x + width).saturating_sub(end) as usize;
+                screen.print(end, y, &" ".repeat(fill), selected);
+            }
+        }
+    }
+}
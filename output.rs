@@ -0,0 +1,65 @@
+use crate::screen::{Screen, Style};
+use crate::text;
+
+const TABWIDTH: usize = 8;
+
+pub struct OutputPane {
+    title: String,
+    lines: Vec<String>,
+    scroll: usize,
+    rows: usize,
+    follow: bool,
+    pub visible: bool,
+}
+
+impl OutputPane {
+    pub fn new(title: String) -> Self {
+        Self {
+            title,
+            lines: Vec::new(),
+            scroll: 0,
+            rows: 1,
+            follow: true,
+            visible: true,
+        }
+    }
+
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    pub fn push(&mut self, line: &str) {
+        self.lines.push(text::expand_tabs(line, TABWIDTH).into_iter().collect());
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    fn last_top(&self) -> usize {
+        self.lines.len().saturating_sub(self.rows)
+    }
+
+    pub fn scroll_page(&mut self, forward: bool) {
+        let step = (self.rows / 2).max(1);
+        self.scroll = if forward { self.scroll + step } else { self.scroll.saturating_sub(step) }.min(self.last_top());
+        self.follow = self.scroll == self.last_top();
+    }
+
+    pub fn draw(&mut self, screen: &mut Screen, top: u16, height: u16) {
+        let title = Style {
+            reverse: true,
+            ..Style::default()
+        };
+        let width = screen.width() as usize;
+        screen.print(0, top, &format!(" {:<1$}", self.title, width.saturating_sub(1)), title);
+        self.rows = (height as usize).saturating_sub(1).max(1);
+        if self.follow {
+            self.scroll = self.last_top();
+        }
+        self.scroll = self.scroll.min(self.last_top());
+        for (row, line) in self.lines.iter().skip(self.scroll).take(self.rows).enumerate() {
+            screen.print(0, top + 1 + row as u16, line, Style::default());
+        }
+    }
+}
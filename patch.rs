@@ -0,0 +1,125 @@
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hunk {
+    pub header: String,
+    pub start: usize,
+    pub old: Vec<String>,
+    pub new: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilePatch {
+    pub path: String,
+    pub hunks: Vec<Hunk>,
+}
+
+fn path(header: &str) -> Option<String> {
+    let path = header.split('\t').next()?.trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(path.strip_prefix("a/").or_else(|| path.strip_prefix("b/")).unwrap_or(path).to_string())
+}
+
+fn range(spec: &str) -> Option<(usize, usize)> {
+    match spec.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((spec.parse().ok()?, 1)),
+    }
+}
+
+fn hunk_header(line: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = line.strip_prefix("@@ ")?.split(' ');
+    let (start, old) = range(parts.next()?.strip_prefix('-')?)?;
+    let (_, new) = range(parts.next()?.strip_prefix('+')?)?;
+    Some((if old == 0 { start } else { start.saturating_sub(1) }, old, new))
+}
+
+pub fn parse(lines: &[String]) -> Vec<FilePatch> {
+    let mut patches: Vec<FilePatch> = Vec::new();
+    let mut old_path = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = &lines[i];
+        i += 1;
+        if let Some(rest) = line.strip_prefix("--- ") {
+            old_path = path(rest);
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            if let Some(path) = path(rest).or(old_path.take()) {
+                patches.push(FilePatch { path, hunks: Vec::new() });
+            }
+        } else if let (Some((start, mut old_left, mut new_left)), Some(patch)) = (hunk_header(line), patches.last_mut()) {
+            let mut hunk = Hunk {
+                header: line.clone(),
+                start,
+                old: Vec::new(),
+                new: Vec::new(),
+            };
+            while (old_left > 0 || new_left > 0) && i < lines.len() {
+                let body = &lines[i];
+                let (kind, text) = body.split_at(body.len().min(1));
+                match kind {
+                    "-" if old_left > 0 => {
+                        hunk.old.push(text.to_string());
+                        old_left -= 1;
+                    }
+                    "+" if new_left > 0 => {
+                        hunk.new.push(text.to_string());
+                        new_left -= 1;
+                    }
+                    " " | "" if old_left > 0 && new_left > 0 => {
+                        hunk.old.push(text.to_string());
+                        hunk.new.push(text.to_string());
+                        old_left -= 1;
+                        new_left -= 1;
+                    }
+                    "\\" => {}
+                    _ => break,
+                }
+                i += 1;
+            }
+            patch.hunks.push(hunk);
+        }
+    }
+    patches.retain(|patch| !patch.hunks.is_empty());
+    patches
+}
+
+impl Hunk {
+    pub fn summary(&self) -> String {
+        let removed = self.old.iter().find(|line| !self.new.contains(line)).map(|line| format!("-{}", line.trim()));
+        let added = self.new.iter().find(|line| !self.old.contains(line)).map(|line| format!("+{}", line.trim()));
+        format!("{} {}", self.header.split(" @@").next().unwrap_or_default(), added.or(removed).unwrap_or_default())
+    }
+
+    fn locate(&self, buffer: &[String]) -> Option<usize> {
+        let fits = |at: usize| buffer.get(at..at + self.old.len()).is_some_and(|lines| lines == self.old);
+        if self.old.is_empty() {
+            return Some(self.start.min(buffer.len()));
+        }
+        (0..=buffer.len())
+            .flat_map(|offset| [Some(self.start + offset), self.start.checked_sub(offset)])
+            .flatten()
+            .find(|&at| fits(at))
+    }
+}
+
+pub fn apply(buffer: &mut Vec<String>, hunks: &[&Hunk]) -> usize {
+    if buffer.len() == 1 && buffer[0].is_empty() {
+        buffer.clear();
+    }
+    let mut sorted = hunks.to_vec();
+    sorted.sort_by_key(|hunk| hunk.start);
+    let mut rejected = 0;
+    for hunk in sorted.into_iter().rev() {
+        match hunk.locate(buffer) {
+            Some(at) => {
+                buffer.splice(at..at + hunk.old.len(), hunk.new.iter().cloned());
+            }
+            None => rejected += 1,
+        }
+    }
+    if buffer.is_empty() {
+        buffer.push(String::new());
+    }
+    rejected
+}
@@ -0,0 +1,542 @@
+use crate::i18n::{trf, Text};
+use crate::unicode;
+
+#[derive(Clone, Debug)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Char(char),
+    Any,
+    Class(Vec<ClassItem>, bool),
+    Start,
+    End,
+    WordBoundary,
+    Group(Box<Node>, Option<usize>),
+    Alt(Vec<Node>),
+    Concat(Vec<Node>),
+    Repeat(Box<Node>, usize, Option<usize>, bool),
+}
+
+#[derive(Debug)]
+enum Inst {
+    Char(char),
+    Any,
+    Class(Vec<ClassItem>, bool),
+    Start,
+    End,
+    WordBoundary,
+    Save(usize),
+    Split(usize, usize),
+    Jump(usize),
+    Match,
+}
+
+enum Job {
+    Run(usize, usize),
+    Restore(usize, Option<usize>),
+}
+
+/// A line decoded once so that repeated searches over it stay linear.
+struct Input {
+    chars: Vec<char>,
+    offsets: Vec<usize>,
+}
+
+impl Input {
+    fn new(text: &str) -> Self {
+        let mut offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+        offsets.push(text.len());
+        Self {
+            chars: text.chars().collect(),
+            offsets,
+        }
+    }
+}
+
+// (instruction, position) pairs already tried; a pair that failed once fails
+// from any start, so the set is shared by all starts of one search.
+#[derive(Default)]
+struct Visited {
+    bits: Vec<u64>,
+    base: usize,
+    used: usize,
+}
+
+impl Visited {
+    fn reset(&mut self, base: usize) {
+        self.bits[..self.used].fill(0);
+        self.base = base;
+        self.used = 0;
+    }
+
+    fn insert(&mut self, pc: usize, i: usize, len: usize) -> bool {
+        let bit = (i - self.base) * len + pc;
+        let word = bit / 64;
+        if word >= self.bits.len() {
+            self.bits.resize((word + 1).max(self.bits.len() * 2), 0);
+        }
+        self.used = self.used.max(word + 1);
+        let mask = 1 << (bit % 64);
+        let fresh = self.bits[word] & mask == 0;
+        self.bits[word] |= mask;
+        fresh
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Match {
+    pub start: usize,
+    pub end: usize,
+    groups: Vec<Option<(usize, usize)>>,
+}
+
+impl Match {
+    pub fn group(&self, n: usize) -> Option<(usize, usize)> {
+        if n == 0 {
+            Some((self.start, self.end))
+        } else {
+            self.groups.get(n - 1).copied().flatten()
+        }
+    }
+
+    pub fn expand(&self, replacement: &str, text: &str) -> String {
+        let mut out = String::new();
+        let mut chars = replacement.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '&' => out.push_str(&text[self.start..self.end]),
+                '\\' => match chars.next() {
+                    Some(d @ '0'..='9') => {
+                        if let Some((a, b)) = self.group(d as usize - '0' as usize) {
+                            out.push_str(&text[a..b]);
+                        }
+                    }
+                    Some('t') => out.push('\t'),
+                    Some(other) => out.push(other),
+                    None => out.push('\\'),
+                },
+                c => out.push(c),
+            }
+        }
+        out
+    }
+}
+
+#[derive(Debug)]
+pub struct Regex {
+    program: Vec<Inst>,
+    groups: usize,
+    ignore_case: bool,
+    normalize: bool,
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    groups: usize,
+    pattern: &'a str,
+}
+
+impl Parser<'_> {
+    fn error(&self) -> String {
+        trf(Text::InvalidPattern, &[&self.pattern])
+    }
+
+    fn alternation(&mut self) -> Result<Node, String> {
+        let mut alternatives = vec![self.concat()?];
+        while self.chars.next_if_eq(&'|').is_some() {
+            alternatives.push(self.concat()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.remove(0)
+        } else {
+            Node::Alt(alternatives)
+        })
+    }
+
+    fn concat(&mut self) -> Result<Node, String> {
+        let mut nodes = Vec::new();
+        while let Some(&c) = self.chars.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(Node::Concat(nodes))
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.chars.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => return self.braces(atom),
+            _ => return Ok(atom),
+        };
+        self.chars.next();
+        if matches!(atom, Node::Start | Node::End | Node::WordBoundary) {
+            return Err(self.error());
+        }
+        let greedy = self.chars.next_if_eq(&'?').is_none();
+        Ok(Node::Repeat(Box::new(atom), min, max, greedy))
+    }
+
+    fn braces(&mut self, atom: Node) -> Result<Node, String> {
+        self.chars.next();
+        let mut spec = String::new();
+        for c in self.chars.by_ref() {
+            if c == '}' {
+                let (min, max) = match spec.split_once(',') {
+                    Some((min, "")) => (min.parse().ok(), None),
+                    Some((min, max)) => (min.parse().ok(), Some(max.parse().map_err(|_| self.error())?)),
+                    None => (spec.parse().ok(), spec.parse().ok()),
+                };
+                let min = min.ok_or_else(|| self.error())?;
+                let greedy = self.chars.next_if_eq(&'?').is_none();
+                return Ok(Node::Repeat(Box::new(atom), min, max, greedy));
+            }
+            spec.push(c);
+        }
+        Err(self.error())
+    }
+
+    fn atom(&mut self) -> Result<Node, String> {
+        let c = self.chars.next().ok_or_else(|| self.error())?;
+        Ok(match c {
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '(' => {
+                let index = if self.chars.next_if_eq(&'?').is_some() {
+                    if self.chars.next() != Some(':') {
+                        return Err(self.error());
+                    }
+                    None
+                } else {
+                    self.groups += 1;
+                    Some(self.groups - 1)
+                };
+                let inner = self.alternation()?;
+                if self.chars.next() != Some(')') {
+                    return Err(self.error());
+                }
+                Node::Group(Box::new(inner), index)
+            }
+            '[' => self.class()?,
+            '\\' => match self.chars.next().ok_or_else(|| self.error())? {
+                'b' => Node::WordBoundary,
+                c => match escape_class(c) {
+                    Some(item) => Node::Class(vec![item], false),
+                    None => Node::Char(escape_char(c)),
+                },
+            },
+            '*' | '+' | '?' | ')' => return Err(self.error()),
+            c => Node::Char(c),
+        })
+    }
+
+    fn class(&mut self) -> Result<Node, String> {
+        let negated = self.chars.next_if_eq(&'^').is_some();
+        let mut items = Vec::new();
+        let mut first = true;
+        loop {
+            let c = self.chars.next().ok_or_else(|| self.error())?;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let start = if c == '\\' {
+                let e = self.chars.next().ok_or_else(|| self.error())?;
+                if let Some(item) = escape_class(e) {
+                    items.push(item);
+                    continue;
+                }
+                escape_char(e)
+            } else {
+                c
+            };
+            if self.chars.peek() == Some(&'-') {
+                self.chars.next();
+                match self.chars.next() {
+                    Some(']') => {
+                        items.push(ClassItem::Range(start, start));
+                        items.push(ClassItem::Range('-', '-'));
+                        break;
+                    }
+                    Some(end) => items.push(ClassItem::Range(start, end)),
+                    None => return Err(self.error()),
+                }
+            } else {
+                items.push(ClassItem::Range(start, start));
+            }
+        }
+        Ok(Node::Class(items, negated))
+    }
+}
+
+fn escape_class(c: char) -> Option<ClassItem> {
+    match c {
+        'd' => Some(ClassItem::Digit(true)),
+        'D' => Some(ClassItem::Digit(false)),
+        'w' => Some(ClassItem::Word(true)),
+        'W' => Some(ClassItem::Word(false)),
+        's' => Some(ClassItem::Space(true)),
+        'S' => Some(ClassItem::Space(false)),
+        _ => None,
+    }
+}
+
+fn escape_char(c: char) -> char {
+    match c {
+        'n' => '\n',
+        't' => '\t',
+        c => c,
+    }
+}
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn compile(node: Node, program: &mut Vec<Inst>) {
+    match node {
+        Node::Char(c) => program.push(Inst::Char(c)),
+        Node::Any => program.push(Inst::Any),
+        Node::Class(items, negated) => program.push(Inst::Class(items, negated)),
+        Node::Start => program.push(Inst::Start),
+        Node::End => program.push(Inst::End),
+        Node::WordBoundary => program.push(Inst::WordBoundary),
+        Node::Group(inner, None) => compile(*inner, program),
+        Node::Group(inner, Some(index)) => {
+            program.push(Inst::Save(2 * index));
+            compile(*inner, program);
+            program.push(Inst::Save(2 * index + 1));
+        }
+        Node::Alt(alternatives) => {
+            let last = alternatives.len() - 1;
+            let mut jumps = Vec::new();
+            for (n, alt) in alternatives.into_iter().enumerate() {
+                if n == last {
+                    compile(alt, program);
+                    break;
+                }
+                let split = program.len();
+                program.push(Inst::Split(split + 1, 0));
+                compile(alt, program);
+                jumps.push(program.len());
+                program.push(Inst::Jump(0));
+                program[split] = Inst::Split(split + 1, program.len());
+            }
+            let end = program.len();
+            for jump in jumps {
+                program[jump] = Inst::Jump(end);
+            }
+        }
+        Node::Concat(nodes) => nodes.into_iter().for_each(|node| compile(node, program)),
+        Node::Repeat(inner, min, max, greedy) => {
+            for _ in 0..min {
+                compile((*inner).clone(), program);
+            }
+            let split = |program: &mut Vec<Inst>, at: usize, exit: usize| {
+                program[at] = if greedy { Inst::Split(at + 1, exit) } else { Inst::Split(exit, at + 1) };
+            };
+            match max {
+                None => {
+                    let start = program.len();
+                    program.push(Inst::Jump(0));
+                    compile(*inner, program);
+                    program.push(Inst::Jump(start));
+                    let exit = program.len();
+                    split(program, start, exit);
+                }
+                Some(max) => {
+                    let mut splits = Vec::new();
+                    for _ in min..max {
+                        splits.push(program.len());
+                        program.push(Inst::Jump(0));
+                        compile((*inner).clone(), program);
+                    }
+                    let exit = program.len();
+                    for at in splits {
+                        split(program, at, exit);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Regex {
+    pub fn new(pattern: &str, ignore_case: bool) -> Result<Self, String> {
+        let mut parser = Parser {
+            chars: pattern.chars().peekable(),
+            groups: 0,
+            pattern,
+        };
+        let node = parser.alternation()?;
+        if parser.chars.next().is_some() {
+            return Err(parser.error());
+        }
+        let mut program = Vec::new();
+        compile(node, &mut program);
+        program.push(Inst::Match);
+        Ok(Self {
+            program,
+            groups: parser.groups,
+            ignore_case,
+            normalize: false,
+        })
+    }
+
+    pub fn normalized(pattern: &str, ignore_case: bool) -> Result<Self, String> {
+        let mut regex = Self::new(&unicode::nfc(pattern), ignore_case)?;
+        regex.normalize = true;
+        Ok(regex)
+    }
+
+    pub fn find_at(&self, text: &str, start: usize) -> Option<Match> {
+        self.search(&Input::new(text), start, &mut Visited::default())
+    }
+
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let input = Input::new(text);
+        let mut visited = Visited::default();
+        let mut at = Some(0);
+        std::iter::from_fn(move || {
+            let m = self.search(&input, at?, &mut visited)?;
+            at = if m.end > m.start {
+                Some(m.end)
+            } else {
+                text[m.end..].chars().next().map(|c| m.end + c.len_utf8())
+            };
+            Some(m)
+        })
+    }
+
+    pub fn replace(&self, text: &str, replacement: &str, global: bool) -> (String, usize) {
+        let mut result = String::new();
+        let mut last = 0;
+        let mut count = 0;
+        for m in self.find_iter(text) {
+            result.push_str(&text[last..m.start]);
+            result.push_str(&m.expand(replacement, text));
+            last = m.end;
+            count += 1;
+            if !global {
+                break;
+            }
+        }
+        result.push_str(&text[last..]);
+        (result, count)
+    }
+
+    fn eq(&self, a: char, b: char) -> bool {
+        a == b || self.ignore_case && a.to_lowercase().eq(b.to_lowercase())
+    }
+
+    fn canonical_match(&self, c: char, s: &[char], i: usize) -> Option<usize> {
+        let mut expected = Vec::new();
+        unicode::decompose_char(c, &mut expected);
+        let mut found = Vec::new();
+        let mut j = i;
+        while found.len() < expected.len() {
+            unicode::decompose_char(*s.get(j)?, &mut found);
+            j += 1;
+        }
+        (found.len() == expected.len() && found.iter().zip(&expected).all(|(&a, &b)| self.eq(a, b))).then_some(j)
+    }
+
+    fn class_match(&self, items: &[ClassItem], c: char) -> bool {
+        items.iter().any(|item| match *item {
+            ClassItem::Range(a, b) => {
+                (a..=b).contains(&c)
+                    || self.ignore_case
+                        && c.to_lowercase().chain(c.to_uppercase()).any(|v| (a..=b).contains(&v))
+            }
+            ClassItem::Digit(yes) => c.is_ascii_digit() == yes,
+            ClassItem::Word(yes) => is_word(c) == yes,
+            ClassItem::Space(yes) => c.is_whitespace() == yes,
+        })
+    }
+
+    fn search(&self, input: &Input, start: usize, visited: &mut Visited) -> Option<Match> {
+        let first = input.offsets.partition_point(|&o| o < start);
+        if first == input.offsets.len() {
+            return None;
+        }
+        visited.reset(first);
+        let mut slots = vec![None; 2 * self.groups];
+        let mut jobs = Vec::new();
+        for i in first..input.offsets.len() {
+            jobs.push(Job::Run(0, i));
+            if let Some(end) = self.run(&input.chars, &mut jobs, &mut slots, visited) {
+                let offset = |slot: Option<usize>| slot.map(|i| input.offsets[i]);
+                return Some(Match {
+                    start: input.offsets[i],
+                    end: input.offsets[end],
+                    groups: slots.chunks(2).map(|pair| offset(pair[0]).zip(offset(pair[1]))).collect(),
+                });
+            }
+        }
+        None
+    }
+
+    fn run(&self, s: &[char], jobs: &mut Vec<Job>, slots: &mut [Option<usize>], visited: &mut Visited) -> Option<usize> {
+        while let Some(job) = jobs.pop() {
+            let (mut pc, mut i) = match job {
+                Job::Run(pc, i) => (pc, i),
+                Job::Restore(slot, old) => {
+                    slots[slot] = old;
+                    continue;
+                }
+            };
+            while visited.insert(pc, i, self.program.len()) {
+                let next = match &self.program[pc] {
+                    Inst::Char(c) if self.normalize => self.canonical_match(*c, s, i),
+                    Inst::Char(c) => (i < s.len() && self.eq(s[i], *c)).then_some(i + 1),
+                    Inst::Any => (i < s.len()).then_some(i + 1),
+                    Inst::Class(items, negated) => (i < s.len() && self.class_match(items, s[i]) != *negated).then_some(i + 1),
+                    Inst::Start => (i == 0).then_some(i),
+                    Inst::End => (i == s.len()).then_some(i),
+                    Inst::WordBoundary => {
+                        let before = i > 0 && is_word(s[i - 1]);
+                        let after = i < s.len() && is_word(s[i]);
+                        (before != after).then_some(i)
+                    }
+                    Inst::Save(slot) => {
+                        jobs.push(Job::Restore(*slot, slots[*slot]));
+                        slots[*slot] = Some(i);
+                        Some(i)
+                    }
+                    Inst::Split(first, second) => {
+                        jobs.push(Job::Run(*second, i));
+                        pc = *first;
+                        continue;
+                    }
+                    Inst::Jump(target) => {
+                        pc = *target;
+                        continue;
+                    }
+                    Inst::Match => {
+                        jobs.clear();
+                        return Some(i);
+                    }
+                };
+                match next {
+                    Some(j) => {
+                        pc += 1;
+                        i = j;
+                    }
+                    None => break,
+                }
+            }
+        }
+        None
+    }
+}
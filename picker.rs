@@ -0,0 +1,84 @@
+use crate::float::{Frame, Layer, Panel, Placement};
+use crate::screen::Screen;
+use crossterm::event::KeyCode;
+
+pub enum PickerEvent {
+    Pending,
+    Chosen(usize),
+    Confirmed(Vec<usize>),
+    Cancelled,
+}
+
+pub struct Picker {
+    title: String,
+    items: Vec<String>,
+    selected: usize,
+    scroll: usize,
+    checked: Option<Vec<bool>>,
+}
+
+impl Picker {
+    pub fn new(title: &str, items: Vec<String>) -> Self {
+        Self {
+            title: title.to_string(),
+            items,
+            selected: 0,
+            scroll: 0,
+            checked: None,
+        }
+    }
+
+    pub fn with_checks(mut self) -> Self {
+        self.checked = Some(vec![true; self.items.len()]);
+        self
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> PickerEvent {
+        let last = self.items.len().saturating_sub(1);
+        match code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => self.selected = (self.selected + 1).min(last),
+            KeyCode::PageUp => self.selected = self.selected.saturating_sub(10),
+            KeyCode::PageDown => self.selected = (self.selected + 10).min(last),
+            KeyCode::Home => self.selected = 0,
+            KeyCode::End => self.selected = last,
+            KeyCode::Char(' ') => {
+                if let Some(flag) = self.checked.as_mut().and_then(|checked| checked.get_mut(self.selected)) {
+                    *flag = !*flag;
+                    self.selected = (self.selected + 1).min(last);
+                }
+            }
+            KeyCode::Enter => {
+                if let Some(checked) = &self.checked {
+                    return PickerEvent::Confirmed((0..checked.len()).filter(|&i| checked[i]).collect());
+                }
+                if !self.items.is_empty() {
+                    return PickerEvent::Chosen(self.selected);
+                }
+            }
+            KeyCode::Esc => return PickerEvent::Cancelled,
+            _ => {}
+        }
+        PickerEvent::Pending
+    }
+
+    /// The picker as a panel for `frame`, scrolled to keep the selected
+    /// item in view.
+    pub fn panel(&mut self, screen: &Screen, frame: &Frame) -> Panel {
+        let items = self.items.iter().enumerate().map(|(index, item)| {
+            match self.checked.as_ref().and_then(|checked| checked.get(index)) {
+                Some(true) => format!("[x] {}", item),
+                Some(false) => format!("[ ] {}", item),
+                None => item.to_string(),
+            }
+        });
+        let panel = Panel::new(&self.title, items.collect(), Layer::Picker, Placement::Center);
+        let rows = panel.area(screen, frame).map_or(1, |(_, _, _, height)| height as usize - 2);
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+        panel.select(self.selected, self.scroll)
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+const SAMPLES: usize = 60;
+
+#[derive(Default)]
+pub struct Samples {
+    values: VecDeque<Duration>,
+}
+
+impl Samples {
+    pub fn record(&mut self, value: Duration) {
+        if self.values.len() == SAMPLES {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    pub fn last(&self) -> Duration {
+        self.values.back().copied().unwrap_or_default()
+    }
+
+    pub fn average(&self) -> Duration {
+        match self.values.len() {
+            0 => Duration::ZERO,
+            n => self.values.iter().sum::<Duration>() / n as u32,
+        }
+    }
+
+    pub fn max(&self) -> Duration {
+        self.values.iter().max().copied().unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+pub struct Profile {
+    pub render: Samples,
+    pub event: Samples,
+    pub frames: u64,
+}
+
+impl Profile {
+    pub fn lines(&self, undo_bytes: usize) -> Vec<String> {
+        let row = |label: &str, samples: &Samples| {
+            format!(
+                "{:<7}{:>8.2} {:>8.2} {:>8.2}",
+                label,
+                ms(samples.last()),
+                ms(samples.average()),
+                ms(samples.max())
+            )
+        };
+        vec![
+            format!("{:<7}{:>8} {:>8} {:>8}", "ms", "last", "avg", "max"),
+            row("render", &self.render),
+            row("event", &self.event),
+            format!("frames {:>8}", self.frames),
+            format!("undo   {:>8.1} KiB", undo_bytes as f64 / 1024.0),
+        ]
+    }
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
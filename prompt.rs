@@ -0,0 +1,116 @@
+use crate::i18n::{trf, Text};
+use crossterm::event::KeyCode;
+
+pub enum PromptEvent {
+    Pending,
+    Submitted(String),
+    Chosen(usize),
+    Cancelled,
+}
+
+enum Kind {
+    Text { masked: bool },
+    Choice(Vec<char>),
+}
+
+/// A question on the message line that holds the keyboard until answered.
+pub struct Prompt {
+    label: Text,
+    kind: Kind,
+    input: String,
+    subject: Option<String>,
+    erase_cancels: bool,
+}
+
+impl Prompt {
+    /// Asks for a line of text, shown in place of the `{}` in `label`.
+    pub fn text(label: Text) -> Self {
+        Self {
+            label,
+            kind: Kind::Text { masked: false },
+            input: String::new(),
+            subject: None,
+            erase_cancels: false,
+        }
+    }
+
+    /// Asks to pick one of `keys`, in either case; the answer is its index.
+    pub fn choice(label: Text, keys: &str) -> Self {
+        Self {
+            label,
+            kind: Kind::Choice(keys.chars().collect()),
+            input: String::new(),
+            subject: None,
+            erase_cancels: false,
+        }
+    }
+
+    pub fn yes_no(label: Text) -> Self {
+        Self::choice(label, "yn")
+    }
+
+    pub fn masked(mut self, masked: bool) -> Self {
+        if let Kind::Text { masked: current } = &mut self.kind {
+            *current = masked;
+        }
+        self
+    }
+
+    pub fn filled(mut self, input: &str) -> Self {
+        self.input = input.to_string();
+        self
+    }
+
+    /// Shows `subject` in the label instead of the answer.
+    pub fn about(mut self, subject: &str) -> Self {
+        self.subject = Some(subject.to_string());
+        self
+    }
+
+    pub fn erase_cancels(mut self) -> Self {
+        self.erase_cancels = true;
+        self
+    }
+
+    pub fn input_mut(&mut self) -> &mut String {
+        &mut self.input
+    }
+
+    pub fn handle_key(&mut self, code: KeyCode) -> PromptEvent {
+        match (&self.kind, code) {
+            (_, KeyCode::Esc) => PromptEvent::Cancelled,
+            (Kind::Text { .. }, KeyCode::Backspace) if self.input.is_empty() && self.erase_cancels => PromptEvent::Cancelled,
+            (Kind::Text { .. }, KeyCode::Char(c)) => {
+                self.input.push(c);
+                PromptEvent::Pending
+            }
+            (Kind::Text { .. }, KeyCode::Backspace) => {
+                self.input.pop();
+                PromptEvent::Pending
+            }
+            (Kind::Text { .. }, KeyCode::Enter) => PromptEvent::Submitted(std::mem::take(&mut self.input)),
+            (Kind::Choice(keys), KeyCode::Char(c)) => {
+                match keys.iter().position(|key| key.eq_ignore_ascii_case(&c)) {
+                    Some(index) => PromptEvent::Chosen(index),
+                    None => PromptEvent::Pending,
+                }
+            }
+            _ => PromptEvent::Pending,
+        }
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        if let Kind::Text { .. } = self.kind {
+            self.input.push_str(text);
+        }
+    }
+
+    pub fn line(&self) -> String {
+        let shown = match (&self.subject, &self.kind) {
+            (Some(subject), _) => subject.clone(),
+            (None, Kind::Text { masked: true }) => "*".repeat(self.input.chars().count()),
+            (None, _) => self.input.clone(),
+        };
+        trf(self.label, &[&shown])
+    }
+}
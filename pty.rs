@@ -0,0 +1,156 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process::{Child, Command};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+#[cfg(unix)]
+mod sys {
+    use std::os::raw::{c_char, c_int, c_ulong, c_ushort};
+
+    extern "C" {
+        pub fn posix_openpt(flags: c_int) -> c_int;
+        pub fn grantpt(fd: c_int) -> c_int;
+        pub fn unlockpt(fd: c_int) -> c_int;
+        pub fn ptsname(fd: c_int) -> *mut c_char;
+        pub fn setsid() -> c_int;
+        pub fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+        pub fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+    }
+
+    #[repr(C)]
+    pub struct Winsize {
+        pub rows: c_ushort,
+        pub cols: c_ushort,
+        pub xpixel: c_ushort,
+        pub ypixel: c_ushort,
+    }
+
+    pub const O_RDWR: c_int = 2;
+    pub const F_SETFD: c_int = 2;
+    pub const FD_CLOEXEC: c_int = 1;
+    #[cfg(target_os = "linux")]
+    pub const O_NOCTTY: c_int = 0o400;
+    #[cfg(not(target_os = "linux"))]
+    pub const O_NOCTTY: c_int = 0x20000;
+    #[cfg(target_os = "linux")]
+    pub const TIOCSCTTY: c_ulong = 0x540E;
+    #[cfg(not(target_os = "linux"))]
+    pub const TIOCSCTTY: c_ulong = 0x20007461;
+    #[cfg(target_os = "linux")]
+    pub const TIOCSWINSZ: c_ulong = 0x5414;
+    #[cfg(not(target_os = "linux"))]
+    pub const TIOCSWINSZ: c_ulong = 0x80087467;
+}
+
+pub struct Pty {
+    master: File,
+    child: Child,
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl Pty {
+    #[cfg(unix)]
+    pub fn spawn(mut command: Command, cols: u16, rows: u16) -> io::Result<Self> {
+        use std::ffi::CStr;
+        use std::os::unix::io::FromRawFd;
+        use std::os::unix::process::CommandExt;
+
+        let fd = unsafe { sys::posix_openpt(sys::O_RDWR | sys::O_NOCTTY) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let master = unsafe { File::from_raw_fd(fd) };
+        // Keep the master out of the shell and every later child process.
+        if unsafe { sys::fcntl(fd, sys::F_SETFD, sys::FD_CLOEXEC) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if unsafe { sys::grantpt(fd) } != 0 || unsafe { sys::unlockpt(fd) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let name = unsafe { sys::ptsname(fd) };
+        if name.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let path = unsafe { CStr::from_ptr(name) }.to_string_lossy().into_owned();
+        let slave = std::fs::OpenOptions::new().read(true).write(true).open(path)?;
+        command
+            .stdin(slave.try_clone()?)
+            .stdout(slave.try_clone()?)
+            .stderr(slave)
+            .env("TERM", "vt100");
+        unsafe {
+            command.pre_exec(|| {
+                if sys::setsid() < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                sys::ioctl(0, sys::TIOCSCTTY, 0);
+                Ok(())
+            });
+        }
+        let child = command.spawn()?;
+        drop(command);
+
+        let mut reader = master.try_clone()?;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            while let Ok(n @ 1..) = reader.read(&mut buf) {
+                if sender.send(buf[..n].to_vec()).is_err() {
+                    break;
+                }
+            }
+        });
+        let pty = Self { master, child, receiver };
+        pty.resize(cols, rows);
+        Ok(pty)
+    }
+
+    #[cfg(not(unix))]
+    pub fn spawn(_command: Command, _cols: u16, _rows: u16) -> io::Result<Self> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "terminal needs a unix pty"))
+    }
+
+    #[cfg(unix)]
+    pub fn resize(&self, cols: u16, rows: u16) {
+        use std::os::unix::io::AsRawFd;
+
+        let size = sys::Winsize {
+            rows,
+            cols,
+            xpixel: 0,
+            ypixel: 0,
+        };
+        unsafe {
+            sys::ioctl(self.master.as_raw_fd(), sys::TIOCSWINSZ, &size as *const sys::Winsize);
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn resize(&self, _cols: u16, _rows: u16) {}
+
+    pub fn write(&mut self, bytes: &[u8]) {
+        let _ = self.master.write_all(bytes);
+        let _ = self.master.flush();
+    }
+
+    pub fn poll(&mut self) -> (Vec<u8>, bool) {
+        let mut output = Vec::new();
+        loop {
+            match self.receiver.try_recv() {
+                Ok(bytes) => output.extend(bytes),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return (output, true),
+            }
+        }
+        let exited = matches!(self.child.try_wait(), Ok(Some(_)) | Err(_));
+        (output, exited)
+    }
+}
+
+impl Drop for Pty {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,72 @@
+use crate::i18n::{trf, Text};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    pub fn line(y: usize) -> Self {
+        Self { start: y, end: y }
+    }
+}
+
+fn digits(input: &str) -> usize {
+    input.len() - input.trim_start_matches(|c: char| c.is_ascii_digit()).len()
+}
+
+fn address(input: &str, current: usize, last: usize) -> (Option<isize>, &str) {
+    let mut rest = input;
+    let mut base = None;
+    if let Some(r) = rest.strip_prefix('.') {
+        base = Some(current as isize);
+        rest = r;
+    } else if let Some(r) = rest.strip_prefix('$') {
+        base = Some(last as isize);
+        rest = r;
+    } else {
+        let n = digits(rest);
+        if n > 0 {
+            base = rest[..n].parse::<isize>().ok().map(|line| (line - 1).max(0));
+            rest = &rest[n..];
+        }
+    }
+    loop {
+        let sign = match rest.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => break,
+        };
+        rest = &rest[1..];
+        let n = digits(rest);
+        let offset = if n > 0 { rest[..n].parse::<isize>().unwrap_or(0) } else { 1 };
+        rest = &rest[n..];
+        base = Some(base.unwrap_or(current as isize) + sign * offset);
+    }
+    (base, rest)
+}
+
+pub fn parse(input: &str, current: usize, last: usize) -> Result<(Option<LineRange>, &str), String> {
+    if let Some(rest) = input.strip_prefix('%') {
+        return Ok((Some(LineRange { start: 0, end: last }), rest));
+    }
+    let (first, rest) = address(input, current, last);
+    let (second, rest) = match rest.strip_prefix([',', ';']) {
+        Some(tail) => {
+            let (second, rest) = address(tail, current, last);
+            (Some(second.unwrap_or(current as isize)), rest)
+        }
+        None if first.is_none() => return Ok((None, input)),
+        None => (None, rest),
+    };
+    let start = first.unwrap_or(current as isize);
+    let end = second.unwrap_or(start);
+    let valid = |line: isize| (0..=last as isize).contains(&line);
+    if !valid(start) || !valid(end) {
+        let text = &input[..input.len() - rest.len()];
+        return Err(trf(Text::InvalidRange, &[&text]));
+    }
+    let (start, end) = (start.min(end) as usize, start.max(end) as usize);
+    Ok((Some(LineRange { start, end }), rest))
+}
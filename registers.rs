@@ -0,0 +1,116 @@
+use std::collections::{BTreeMap, VecDeque};
+
+pub const DEFAULT: char = '"';
+const RING_LIMIT: usize = 32;
+const PREVIEW_CHARS: usize = 60;
+
+#[derive(Clone, Default, Debug)]
+pub struct Register {
+    pub text: String,
+    pub linewise: bool,
+    /// Yanked from an encrypted buffer, so never written to the session.
+    pub secret: bool,
+}
+
+impl Register {
+    pub fn preview(&self) -> String {
+        let flat = self.text.replace('\n', "^J");
+        let mut preview: String = flat.chars().take(PREVIEW_CHARS).collect();
+        if preview.len() < flat.len() {
+            preview.push('…');
+        }
+        preview
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    Named(char),
+    History(usize),
+}
+
+impl Source {
+    pub fn label(self) -> String {
+        match self {
+            Source::Named(c) => format!("\"{}", c),
+            Source::History(i) => format!("#{}", i + 1),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Registers {
+    unnamed: Register,
+    named: BTreeMap<char, Register>,
+    ring: VecDeque<Register>,
+}
+
+impl Registers {
+    pub fn is_valid(name: char) -> bool {
+        name == DEFAULT || name.is_ascii_alphabetic()
+    }
+
+    pub fn store(&mut self, name: Option<char>, text: String, linewise: bool, secret: bool) {
+        if self.ring.front().is_none_or(|r| r.text != text) {
+            self.ring.push_front(Register {
+                text: text.clone(),
+                linewise,
+                secret,
+            });
+            self.ring.truncate(RING_LIMIT);
+        }
+        let register = match name.filter(|&c| c != DEFAULT) {
+            Some(c) if c.is_ascii_uppercase() => {
+                let entry = self.named.entry(c.to_ascii_lowercase()).or_default();
+                if entry.linewise || linewise {
+                    if !entry.text.is_empty() {
+                        entry.text.push('\n');
+                    }
+                    entry.linewise = true;
+                }
+                entry.text.push_str(&text);
+                entry.secret |= secret;
+                entry.clone()
+            }
+            Some(c) => {
+                let register = Register { text, linewise, secret };
+                self.named.insert(c, register.clone());
+                register
+            }
+            None => Register { text, linewise, secret },
+        };
+        self.unnamed = register;
+    }
+
+    pub fn restore(&mut self, name: char, register: Register) {
+        self.named.insert(name, register);
+    }
+
+    pub fn get(&self, name: Option<char>) -> Option<&Register> {
+        let register = match name.filter(|&c| c != DEFAULT) {
+            Some(c) => self.named.get(&c.to_ascii_lowercase())?,
+            None => &self.unnamed,
+        };
+        Some(register).filter(|r| !r.text.is_empty())
+    }
+
+    pub fn ring(&self) -> &VecDeque<Register> {
+        &self.ring
+    }
+
+    pub fn source(&self, source: Source) -> Option<&Register> {
+        match source {
+            Source::Named(c) => self.get(Some(c)),
+            Source::History(i) => self.ring.get(i),
+        }
+    }
+
+    pub fn entries(&self) -> Vec<(Source, &Register)> {
+        std::iter::once((DEFAULT, &self.unnamed))
+            .chain(self.named.iter().map(|(&c, r)| (c, r)))
+            .filter(|(_, r)| !r.text.is_empty())
+            .map(|(c, r)| (Source::Named(c), r))
+            .chain(self.ring.iter().enumerate().map(|(i, r)| (Source::History(i), r)))
+            .collect()
+    }
+}
@@ -0,0 +1,121 @@
+use crate::i18n::{tr, Text};
+use crate::shell;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Url {
+    Ssh { host: String, port: Option<u16>, path: String },
+    Http(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum State {
+    Queued,
+    Fetching,
+    Online,
+    Offline(String),
+}
+
+impl Url {
+    pub fn parse(text: &str) -> Option<Self> {
+        if ["http://", "https://"].iter().any(|scheme| text.len() > scheme.len() && text.starts_with(scheme)) {
+            return Some(Url::Http(text.to_string()));
+        }
+        let rest = text.strip_prefix("ssh://").or_else(|| text.strip_prefix("sftp://"))?;
+        let (authority, path) = rest.split_at(rest.find('/')?);
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port.parse().ok()?)),
+            None => (authority, None),
+        };
+        if host.is_empty() || host.starts_with('-') || host.ends_with('@') || path.len() < 2 {
+            return None;
+        }
+        let path = if path.starts_with("/~") { &path[1..] } else { path };
+        Some(Url::Ssh {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    pub fn program(&self) -> &'static str {
+        match self {
+            Url::Ssh { .. } => "scp",
+            Url::Http(_) => "curl",
+        }
+    }
+
+    pub fn writable(&self) -> bool {
+        matches!(self, Url::Ssh { .. })
+    }
+}
+
+// A new directory only this user can enter, so the copy inside can be
+// neither read nor swapped for a link by others sharing the temp dir.
+fn scratch() -> io::Result<PathBuf> {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNT.fetch_add(1, Ordering::Relaxed);
+    let dir = std::env::temp_dir().join(format!("aon-remote-{}-{}", std::process::id(), n));
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::DirBuilderExt;
+        builder.mode(0o700);
+    }
+    builder.create(&dir)?;
+    Ok(dir)
+}
+
+fn copy(program: &str, port: Option<u16>, from: &str, to: &str) -> io::Result<()> {
+    let control = std::env::temp_dir().join("aon-ssh-%C");
+    let mut line = format!(
+        "{} -q -o BatchMode=yes -o ControlMaster=auto -o ControlPersist=600 -o ControlPath={}",
+        program,
+        shell::quote(&control.to_string_lossy())
+    );
+    if let Some(port) = port {
+        line.push_str(&format!(" -P {}", port));
+    }
+    line.push_str(&format!(" -- {} {}", shell::quote(from), shell::quote(to)));
+    let output = shell::run(&line)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(shell::failure(&output)))
+    }
+}
+
+fn download(program: &str, url: &str) -> io::Result<Vec<u8>> {
+    let output = shell::run(&format!("{} -fsSL {}", program, shell::quote(url)))?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(io::Error::other(shell::failure(&output)))
+    }
+}
+
+pub fn fetch(program: &str, url: &Url) -> io::Result<Vec<u8>> {
+    let (host, port, path) = match url {
+        Url::Ssh { host, port, path } => (host, port, path),
+        Url::Http(url) => return download(program, url),
+    };
+    let dir = scratch()?;
+    let local = dir.join("file");
+    let result = copy(program, *port, &format!("{}:{}", host, path), &local.to_string_lossy()).and_then(|_| fs::read(&local));
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
+
+pub fn store(program: &str, url: &Url, bytes: &[u8]) -> io::Result<()> {
+    let Url::Ssh { host, port, path } = url else {
+        return Err(io::Error::new(io::ErrorKind::Unsupported, tr(Text::RemoteReadOnly)));
+    };
+    let dir = scratch()?;
+    let local = dir.join("file");
+    let result = fs::write(&local, bytes).and_then(|_| copy(program, *port, &local.to_string_lossy(), &format!("{}:{}", host, path)));
+    let _ = fs::remove_dir_all(&dir);
+    result
+}
@@ -0,0 +1,85 @@
+use crate::hex::HexView;
+use crate::pattern::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+#[derive(Clone, Debug)]
+pub struct Replacement {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+impl Replacement {
+    pub fn preview(&self, line: &str) -> String {
+        format!("{}{}{}", &line[..self.start], self.text, &line[self.end..])
+    }
+}
+
+pub fn find(regex: &Regex, replacement: &str, buffer: &[String], global: bool) -> Vec<Replacement> {
+    let mut found = Vec::new();
+    for (y, line) in buffer.iter().enumerate() {
+        for m in regex.find_iter(line) {
+            found.push(Replacement {
+                line: y,
+                start: m.start,
+                end: m.end,
+                text: m.expand(replacement, line),
+            });
+            if !global {
+                break;
+            }
+        }
+    }
+    found
+}
+
+pub fn apply(buffer: &mut [String], replacements: &[&Replacement]) {
+    let mut sorted = replacements.to_vec();
+    sorted.sort_by_key(|r| (r.line, r.start));
+    for r in sorted.into_iter().rev() {
+        if let Some(line) = buffer.get_mut(r.line) {
+            if r.end <= line.len() {
+                line.replace_range(r.start..r.end, &r.text);
+            }
+        }
+    }
+}
+
+pub fn project_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() && !SKIPPED_DIRS.contains(&name.as_str()) => pending.push(path),
+                Ok(kind) if kind.is_file() && entry.metadata().is_ok_and(|meta| meta.len() <= MAX_FILE_SIZE) => {
+                    files.push(path)
+                }
+                _ => {}
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+pub fn read_text(path: &Path) -> Option<Vec<String>> {
+    let bytes = fs::read(path).ok()?;
+    if HexView::is_binary(&bytes) {
+        return None;
+    }
+    Some(String::from_utf8(bytes).ok()?.lines().map(str::to_string).collect())
+}
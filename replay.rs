@@ -0,0 +1,136 @@
+use crate::keymap::Key;
+use crossterm::event::{Event, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use std::fs::{self, File};
+use std::io::{self, Write};
+
+pub struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    pub fn create(path: &str, width: u16, height: u16) -> io::Result<Self> {
+        let mut recorder = Self { file: File::create(path)? };
+        recorder.record(&Event::Resize(width, height))?;
+        Ok(recorder)
+    }
+
+    pub fn record(&mut self, event: &Event) -> io::Result<()> {
+        match format(event) {
+            Some(line) => writeln!(self.file, "{}", line),
+            None => Ok(()),
+        }
+    }
+}
+
+pub fn load(path: &str) -> io::Result<Vec<Event>> {
+    let text = fs::read_to_string(path)?;
+    let mut events = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let event = parse(line).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}:{}: {}", path, n + 1, line))
+        })?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+pub fn format(event: &Event) -> Option<String> {
+    match event {
+        Event::Key(key) if key.kind == KeyEventKind::Release => None,
+        Event::Key(key) => Some(format!("key {}", Key::new(key.code, key.modifiers).name())),
+        Event::Mouse(mouse) => {
+            let kind = match mouse.kind {
+                MouseEventKind::Down(button) => format!("down {}", button_name(button)),
+                MouseEventKind::Up(button) => format!("up {}", button_name(button)),
+                MouseEventKind::Drag(button) => format!("drag {}", button_name(button)),
+                MouseEventKind::Moved => "moved".to_string(),
+                MouseEventKind::ScrollDown => "scroll down".to_string(),
+                MouseEventKind::ScrollUp => "scroll up".to_string(),
+                _ => return None,
+            };
+            Some(format!("mouse {} {} {}", kind, mouse.column, mouse.row))
+        }
+        Event::Resize(width, height) => Some(format!("resize {} {}", width, height)),
+        Event::Paste(text) => Some(format!("paste {:?}", text)),
+        _ => None,
+    }
+}
+
+pub fn parse(line: &str) -> Option<Event> {
+    let (kind, rest) = line.split_once(' ')?;
+    match kind {
+        "key" => {
+            let key = Key::parse(rest)?;
+            Some(Event::Key(KeyEvent::new(key.code, key.modifiers)))
+        }
+        "mouse" => {
+            let words: Vec<&str> = rest.split_whitespace().collect();
+            let (kind, position) = match words.as_slice() {
+                ["down", button, position @ ..] => (MouseEventKind::Down(parse_button(button)?), position),
+                ["up", button, position @ ..] => (MouseEventKind::Up(parse_button(button)?), position),
+                ["drag", button, position @ ..] => (MouseEventKind::Drag(parse_button(button)?), position),
+                ["moved", position @ ..] => (MouseEventKind::Moved, position),
+                ["scroll", "down", position @ ..] => (MouseEventKind::ScrollDown, position),
+                ["scroll", "up", position @ ..] => (MouseEventKind::ScrollUp, position),
+                _ => return None,
+            };
+            let [column, row] = position else {
+                return None;
+            };
+            Some(Event::Mouse(MouseEvent {
+                kind,
+                column: column.parse().ok()?,
+                row: row.parse().ok()?,
+                modifiers: KeyModifiers::NONE,
+            }))
+        }
+        "resize" => {
+            let (width, height) = rest.split_once(' ')?;
+            Some(Event::Resize(width.parse().ok()?, height.parse().ok()?))
+        }
+        "paste" => {
+            let mut text = String::new();
+            let mut chars = rest.strip_prefix('"')?.strip_suffix('"')?.chars();
+            while let Some(c) = chars.next() {
+                if c != '\\' {
+                    text.push(c);
+                    continue;
+                }
+                match chars.next()? {
+                    'n' => text.push('\n'),
+                    'r' => text.push('\r'),
+                    't' => text.push('\t'),
+                    '0' => text.push('\0'),
+                    'u' => {
+                        let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                        text.push(char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                    }
+                    escaped => text.push(escaped),
+                }
+            }
+            Some(Event::Paste(text))
+        }
+        _ => None,
+    }
+}
+
+fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "left",
+        MouseButton::Right => "right",
+        MouseButton::Middle => "middle",
+    }
+}
+
+fn parse_button(name: &str) -> Option<MouseButton> {
+    match name {
+        "left" => Some(MouseButton::Left),
+        "right" => Some(MouseButton::Right),
+        "middle" => Some(MouseButton::Middle),
+        _ => None,
+    }
+}
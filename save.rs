@@ -0,0 +1,120 @@
+use crate::meta::{self, Metadata};
+use crate::text::Newlines;
+use crate::utf8;
+use std::fs::File;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+const CHUNK: usize = 64 * 1024;
+
+pub enum Contents {
+    Lines(Vec<String>, Newlines),
+    Bytes(Vec<u8>),
+}
+
+impl Contents {
+    fn len(&self) -> usize {
+        match self {
+            Contents::Lines(lines, newlines) => {
+                let breaks = lines.len().saturating_sub(1) + usize::from(newlines.last);
+                lines.iter().map(String::len).sum::<usize>() + breaks * newlines.separator().len()
+            }
+            Contents::Bytes(bytes) => bytes.len(),
+        }
+    }
+}
+
+pub struct Save {
+    pub filename: String,
+    written: Arc<AtomicUsize>,
+    total: usize,
+    handle: JoinHandle<io::Result<()>>,
+}
+
+impl Save {
+    /// Writes `contents` to `filename` in the background; with `replace`,
+    /// a symlink there is swapped for the file once it is fully written.
+    pub fn start(filename: String, contents: Contents, metadata: Option<Metadata>, replace: bool) -> Self {
+        let written = Arc::new(AtomicUsize::new(0));
+        let total = contents.len();
+        let path = filename.clone();
+        let progress = Arc::clone(&written);
+        let handle = thread::spawn(move || {
+            if replace {
+                let temp = sibling(&path);
+                if let Err(err) = write(&temp, contents, &progress).and_then(|()| fs::rename(&temp, &path)) {
+                    let _ = fs::remove_file(&temp);
+                    return Err(err);
+                }
+            } else {
+                write(&path, contents, &progress)?;
+            }
+            if let Some(metadata) = metadata {
+                meta::restore(path.as_ref(), &metadata);
+            }
+            Ok(())
+        });
+        Self {
+            filename,
+            written,
+            total,
+            handle,
+        }
+    }
+
+    pub fn percent(&self) -> usize {
+        match self.total {
+            0 => 100,
+            total => self.written.load(Ordering::Relaxed) * 100 / total,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        self.handle
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("save thread panicked")))
+    }
+}
+
+/// A hidden file next to `filename` to write to before renaming it over.
+pub fn sibling(filename: &str) -> String {
+    let path = Path::new(filename);
+    let name = path.file_name().map_or_else(Default::default, |name| name.to_string_lossy());
+    path.with_file_name(format!(".{}.aon-{}", name, std::process::id())).to_string_lossy().into_owned()
+}
+
+fn write(path: &str, contents: Contents, written: &AtomicUsize) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    match contents {
+        Contents::Lines(lines, newlines) => {
+            let separator = newlines.separator();
+            for (i, line) in lines.into_iter().enumerate() {
+                if i > 0 {
+                    out.write_all(separator.as_bytes())?;
+                }
+                let len = line.len();
+                out.write_all(&utf8::encode(line))?;
+                written.fetch_add(len + if i > 0 { separator.len() } else { 0 }, Ordering::Relaxed);
+            }
+            if newlines.last {
+                out.write_all(separator.as_bytes())?;
+                written.fetch_add(separator.len(), Ordering::Relaxed);
+            }
+        }
+        Contents::Bytes(bytes) => {
+            for chunk in bytes.chunks(CHUNK) {
+                out.write_all(chunk)?;
+                written.fetch_add(chunk.len(), Ordering::Relaxed);
+            }
+        }
+    }
+    out.flush()
+}
@@ -0,0 +1,203 @@
+use crossterm::{
+    cursor, queue,
+    style::{self, Attribute, Color},
+    terminal,
+};
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+    pub reverse: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum CursorShape {
+    #[default]
+    Block,
+    Bar,
+    Underline,
+}
+
+impl CursorShape {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "block" => Some(Self::Block),
+            "bar" => Some(Self::Bar),
+            "underline" => Some(Self::Underline),
+            _ => None,
+        }
+    }
+
+    fn command(self) -> cursor::SetCursorStyle {
+        match self {
+            Self::Block => cursor::SetCursorStyle::SteadyBlock,
+            Self::Bar => cursor::SetCursorStyle::SteadyBar,
+            Self::Underline => cursor::SetCursorStyle::SteadyUnderScore,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cell {
+    pub ch: char,
+    pub style: Style,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            style: Style::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Screen {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+    prev: Vec<Cell>,
+    pub cursor: Option<(u16, u16)>,
+    pub cursor_shape: CursorShape,
+    pub passthrough: Vec<u8>,
+    shown_shape: Option<CursorShape>,
+}
+
+impl Screen {
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn resize(&mut self, width: u16, height: u16) {
+        if (width, height) != (self.width, self.height) {
+            self.width = width;
+            self.height = height;
+            self.cells = vec![Cell::default(); width as usize * height as usize];
+            self.prev.clear();
+        }
+    }
+
+    pub fn invalidate(&mut self) {
+        self.prev.clear();
+        self.shown_shape = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::default());
+        self.cursor = None;
+    }
+
+    pub fn cell(&self, x: u16, y: u16) -> Option<Cell> {
+        (x < self.width && y < self.height).then(|| self.cells[y as usize * self.width as usize + x as usize])
+    }
+
+    pub fn row(&self, y: u16) -> String {
+        (0..self.width).filter_map(|x| self.cell(x, y)).map(|cell| cell.ch).collect()
+    }
+
+    pub fn put(&mut self, x: u16, y: u16, ch: char, style: Style) {
+        if x < self.width && y < self.height {
+            self.cells[y as usize * self.width as usize + x as usize] = Cell { ch, style };
+        }
+    }
+
+    pub fn invert(&mut self) {
+        for cell in &mut self.cells {
+            cell.style.reverse = !cell.style.reverse;
+        }
+    }
+
+    pub fn tint(&mut self, x: u16, y: u16, bg: Color) {
+        if x < self.width && y < self.height {
+            let cell = &mut self.cells[y as usize * self.width as usize + x as usize];
+            cell.style.bg = cell.style.bg.or(Some(bg));
+        }
+    }
+
+    pub fn print(&mut self, x: u16, y: u16, text: &str, style: Style) -> u16 {
+        let mut x = x;
+        for ch in text.chars() {
+            if x >= self.width {
+                break;
+            }
+            self.put(x, y, ch, style);
+            x += 1;
+        }
+        x
+    }
+
+    pub fn flush<W: Write>(&mut self, out: &mut W) -> io::Result<()> {
+        out.write_all(&self.passthrough)?;
+        self.passthrough.clear();
+        let full = self.prev.len() != self.cells.len();
+        if full {
+            queue!(out, terminal::Clear(terminal::ClearType::All))?;
+        }
+
+        let mut current: Option<Style> = None;
+        let mut next_pos: Option<(u16, u16)> = None;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y as usize * self.width as usize + x as usize;
+                let cell = self.cells[i];
+                if !full && self.prev[i] == cell {
+                    continue;
+                }
+                if next_pos != Some((x, y)) {
+                    queue!(out, cursor::MoveTo(x, y))?;
+                }
+                if current != Some(cell.style) {
+                    Self::apply_style(out, cell.style)?;
+                    current = Some(cell.style);
+                }
+                queue!(out, style::Print(cell.ch))?;
+                next_pos = Some((x + 1, y));
+            }
+        }
+        if current.is_some() {
+            queue!(out, style::SetAttribute(Attribute::Reset), style::ResetColor)?;
+        }
+        match self.cursor {
+            Some((x, y)) => {
+                if self.shown_shape != Some(self.cursor_shape) {
+                    queue!(out, self.cursor_shape.command())?;
+                    self.shown_shape = Some(self.cursor_shape);
+                }
+                queue!(out, cursor::MoveTo(x, y), cursor::Show)?;
+            }
+            None => queue!(out, cursor::Hide)?,
+        }
+        out.flush()?;
+        self.prev.clone_from(&self.cells);
+        Ok(())
+    }
+
+    fn apply_style<W: Write>(out: &mut W, style: Style) -> io::Result<()> {
+        queue!(out, style::SetAttribute(Attribute::Reset), style::ResetColor)?;
+        if let Some(fg) = style.fg {
+            queue!(out, style::SetForegroundColor(fg))?;
+        }
+        if let Some(bg) = style.bg {
+            queue!(out, style::SetBackgroundColor(bg))?;
+        }
+        if style.bold {
+            queue!(out, style::SetAttribute(Attribute::Bold))?;
+        }
+        if style.underline {
+            queue!(out, style::SetAttribute(Attribute::Underlined))?;
+        }
+        if style.reverse {
+            queue!(out, style::SetAttribute(Attribute::Reverse))?;
+        }
+        Ok(())
+    }
+}
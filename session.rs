@@ -0,0 +1,274 @@
+use crate::save;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+const MAX_POSITIONS: usize = 500;
+const MAX_HISTORY: usize = 200;
+const MAX_REGISTER: usize = 64 * 1024;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mark {
+    pub file: String,
+    pub y: usize,
+    pub x: usize,
+}
+
+#[derive(Clone)]
+enum Change {
+    Position(String, usize, usize),
+    Command(String),
+    Search(String),
+    Register(char, String, bool),
+    Mark(char, Mark),
+}
+
+/// State that outlives a session, kept in a line-based file next to the
+/// config: one `kind<TAB>fields...` record per line, newest first. Only
+/// the changes made by this session are written over whatever is on
+/// disk at exit, so several editors can share the file.
+#[derive(Default)]
+pub struct Session {
+    path: Option<PathBuf>,
+    positions: VecDeque<(String, usize, usize)>,
+    commands: VecDeque<String>,
+    searches: VecDeque<String>,
+    registers: BTreeMap<char, (String, bool)>,
+    marks: BTreeMap<char, Mark>,
+    changes: Vec<Change>,
+}
+
+impl Session {
+    pub fn load(path: PathBuf) -> io::Result<Self> {
+        let mut session = Self::read(&path)?;
+        session.path = Some(path);
+        Ok(session)
+    }
+
+    fn read(path: &Path) -> io::Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            result => result?,
+        };
+        let mut session = Self::default();
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields[..] {
+                ["position", y, x, file] => {
+                    if let (Ok(y), Ok(x)) = (y.parse(), x.parse()) {
+                        session.positions.push_back((unescape(file), y, x));
+                    }
+                }
+                ["command", text] => session.commands.push_back(unescape(text)),
+                ["search", text] => session.searches.push_back(unescape(text)),
+                ["register", name, linewise, text] => {
+                    if let Some(name) = single(name) {
+                        session.registers.insert(name, (unescape(text), linewise == "1"));
+                    }
+                }
+                ["mark", name, y, x, file] => {
+                    if let (Some(name), Ok(y), Ok(x)) = (single(name), y.parse(), x.parse()) {
+                        session.marks.insert(name, Mark { file: unescape(file), y, x });
+                    }
+                }
+                _ => {}
+            }
+        }
+        session.positions.truncate(MAX_POSITIONS);
+        session.commands.truncate(MAX_HISTORY);
+        session.searches.truncate(MAX_HISTORY);
+        Ok(session)
+    }
+
+    pub fn position(&self, file: &str) -> Option<(usize, usize)> {
+        let key = key(file)?;
+        self.positions.iter().find(|(name, _, _)| *name == key).map(|&(_, y, x)| (y, x))
+    }
+
+    pub fn remember(&mut self, file: &str, y: usize, x: usize) {
+        if let Some(key) = key(file) {
+            self.apply(Change::Position(key, y, x));
+        }
+    }
+
+    pub fn commands(&self) -> &VecDeque<String> {
+        &self.commands
+    }
+
+    pub fn add_command(&mut self, command: &str) {
+        self.apply(Change::Command(command.to_string()));
+    }
+
+    pub fn searches(&self) -> &VecDeque<String> {
+        &self.searches
+    }
+
+    pub fn add_search(&mut self, pattern: &str) {
+        self.apply(Change::Search(pattern.to_string()));
+    }
+
+    pub fn registers(&self) -> impl Iterator<Item = (char, &str, bool)> {
+        self.registers.iter().map(|(&name, (text, linewise))| (name, text.as_str(), *linewise))
+    }
+
+    pub fn set_register(&mut self, name: char, text: &str, linewise: bool) {
+        if self.registers.get(&name).is_none_or(|(old, was)| old != text || *was != linewise) {
+            self.apply(Change::Register(name, text.to_string(), linewise));
+        }
+    }
+
+    pub fn marks(&self) -> &BTreeMap<char, Mark> {
+        &self.marks
+    }
+
+    pub fn set_mark(&mut self, name: char, file: &str, y: usize, x: usize) -> bool {
+        let Some(file) = key(file) else {
+            return false;
+        };
+        self.apply(Change::Mark(name, Mark { file, y, x }));
+        true
+    }
+
+    fn apply(&mut self, change: Change) {
+        self.update(change.clone());
+        self.changes.push(change);
+    }
+
+    fn update(&mut self, change: Change) {
+        match change {
+            Change::Position(file, y, x) => {
+                self.positions.retain(|(name, _, _)| *name != file);
+                self.positions.push_front((file, y, x));
+                self.positions.truncate(MAX_POSITIONS);
+            }
+            Change::Command(text) => push_history(&mut self.commands, text),
+            Change::Search(text) => push_history(&mut self.searches, text),
+            Change::Register(name, text, linewise) => {
+                self.registers.insert(name, (text, linewise));
+            }
+            Change::Mark(name, mark) => {
+                self.marks.insert(name, mark);
+            }
+        }
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut merged = Self::read(path)?;
+        for change in &self.changes {
+            merged.update(change.clone());
+        }
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut text = String::new();
+        for (file, y, x) in &merged.positions {
+            text.push_str(&format!("position\t{}\t{}\t{}\n", y, x, escape(file)));
+        }
+        for command in &merged.commands {
+            text.push_str(&format!("command\t{}\n", escape(command)));
+        }
+        for search in &merged.searches {
+            text.push_str(&format!("search\t{}\n", escape(search)));
+        }
+        for (name, (register, linewise)) in merged.registers.iter().filter(|(_, (register, _))| register.len() <= MAX_REGISTER) {
+            text.push_str(&format!("register\t{}\t{}\t{}\n", name, u8::from(*linewise), escape(register)));
+        }
+        for (name, mark) in &merged.marks {
+            text.push_str(&format!("mark\t{}\t{}\t{}\t{}\n", name, mark.y, mark.x, escape(&mark.file)));
+        }
+        let temp = PathBuf::from(save::sibling(&path.to_string_lossy()));
+        let result = write_private(&temp, &text).and_then(|()| fs::rename(&temp, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        result
+    }
+}
+
+// Registers and history can hold anything typed or yanked, so the file is
+// only readable by its owner.
+fn write_private(path: &Path, text: &str) -> io::Result<()> {
+    let _ = fs::remove_file(path);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(text.as_bytes())
+}
+
+/// Steps through a history from newest to oldest, only visiting entries
+/// that start with what was typed before the first step.
+#[derive(Default)]
+pub struct Recall {
+    index: Option<usize>,
+    draft: String,
+}
+
+impl Recall {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn step(&mut self, history: &VecDeque<String>, input: &mut String, older: bool) {
+        if self.index.is_none() {
+            self.draft = input.clone();
+        }
+        let matches = |i: &usize| history[*i].starts_with(&self.draft) && history[*i] != self.draft;
+        let next = match (self.index, older) {
+            (None, true) => (0..history.len()).find(matches),
+            (Some(i), true) => (i + 1..history.len()).find(matches).or(Some(i)),
+            (Some(i), false) => (0..i).rev().find(matches),
+            (None, false) => None,
+        };
+        self.index = next;
+        *input = match next {
+            Some(i) => history[i].clone(),
+            None => self.draft.clone(),
+        };
+    }
+}
+
+pub fn key(file: &str) -> Option<String> {
+    let path = fs::canonicalize(Path::new(file)).ok()?;
+    Some(path.to_string_lossy().into_owned())
+}
+
+fn push_history(history: &mut VecDeque<String>, text: String) {
+    history.retain(|entry| *entry != text);
+    history.push_front(text);
+    history.truncate(MAX_HISTORY);
+}
+
+fn single(field: &str) -> Option<char> {
+    let mut chars = field.chars();
+    chars.next().filter(|_| chars.next().is_none())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\n', "\\n").replace('\t', "\\t")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
@@ -0,0 +1,67 @@
+use std::io::{self, Write};
+use std::process::{Command, Output, Stdio};
+
+pub fn command(line: &str) -> Command {
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    };
+    #[cfg(not(windows))]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(line);
+    command
+}
+
+pub fn quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+pub fn run(line: &str) -> io::Result<Output> {
+    command(line).stdin(Stdio::null()).output()
+}
+
+pub fn filter(line: &str, input: impl AsRef<[u8]>) -> io::Result<Output> {
+    let mut child = command(line)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("piped stdin");
+    let input = input.as_ref().to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+    Ok(output)
+}
+
+pub fn failure(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match stderr.lines().rev().find(|line| !line.trim().is_empty()) {
+        Some(line) => line.trim().to_string(),
+        None => output.status.to_string(),
+    }
+}
+
+pub fn sudo_cached(program: &str) -> bool {
+    run(&format!("{} -n true", program)).is_ok_and(|output| output.status.success())
+}
+
+pub fn sudo_write(program: &str, path: &str, password: Option<&str>, bytes: &[u8]) -> io::Result<()> {
+    let (line, mut input) = match password {
+        Some(password) => (format!("{} -k -S -p '' tee -- {} > /dev/null", program, quote(path)), format!("{}\n", password).into_bytes()),
+        None => (format!("{} -n tee -- {} > /dev/null", program, quote(path)), Vec::new()),
+    };
+    input.extend_from_slice(bytes);
+    let output = filter(&line, input)?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(failure(&output)))
+    }
+}
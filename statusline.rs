@@ -0,0 +1,98 @@
+use crate::text;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The cells a named segment covers in an expanded status line.
+pub type Span = (usize, usize, String);
+
+fn cells(text: &str) -> usize {
+    text::width(text, 1)
+}
+
+/// Expands a `statusline` format: `{segment}` placeholders are replaced
+/// through `segment`, and `%=` splits the line into left, center and right
+/// parts (one `%=` gives left and right). Returns the line and where each
+/// expanded segment ended up.
+pub fn format(spec: &str, width: usize, segment: impl Fn(&str) -> Option<String>) -> (String, Vec<Span>) {
+    let mut parts: Vec<(String, Vec<Span>)> = spec.splitn(3, "%=").map(|part| expand(part, &segment)).collect();
+    let right = if parts.len() > 1 { parts.pop() } else { None }.unwrap_or_default();
+    let center = if parts.len() > 1 { parts.pop() } else { None }.unwrap_or_default();
+    let left = parts.pop().unwrap_or_default();
+    let mut line = String::new();
+    let mut spans = Vec::new();
+    let mut used = 0;
+    for (n, (text, part_spans)) in [left, center, right].into_iter().enumerate() {
+        if text.is_empty() {
+            continue;
+        }
+        let start = match n {
+            0 => 0,
+            1 => (width.saturating_sub(cells(&text)) / 2).max(used + usize::from(used > 0)),
+            _ => width.saturating_sub(cells(&text)).max(used + usize::from(used > 0)),
+        };
+        line.push_str(&" ".repeat(start - used));
+        line.push_str(&text);
+        spans.extend(part_spans.into_iter().map(|(from, to, name)| (start + from, start + to, name)));
+        used = start + cells(&text);
+    }
+    (line, spans)
+}
+
+fn expand(part: &str, segment: &impl Fn(&str) -> Option<String>) -> (String, Vec<Span>) {
+    let mut out = String::new();
+    let mut spans = Vec::new();
+    let mut rest = part;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}') else {
+            rest = &rest[open..];
+            break;
+        };
+        let name = &rest[open + 1..open + close];
+        match segment(name) {
+            Some(value) => {
+                let start = cells(&out);
+                out.push_str(&value);
+                spans.push((start, cells(&out), name.to_string()));
+            }
+            None => out.push_str(&rest[open..=open + close]),
+        }
+        rest = &rest[open + close + 1..];
+    }
+    out.push_str(rest);
+    let lead = cells(&out) - cells(out.trim_start());
+    let text = out.trim().to_string();
+    let end = cells(&text);
+    let spans = spans
+        .into_iter()
+        .map(|(from, to, name)| (from.saturating_sub(lead), to.saturating_sub(lead).min(end), name))
+        .filter(|(from, to, _)| from < to)
+        .collect();
+    (text, spans)
+}
+
+/// The checked-out branch of the repository containing `file`, or the
+/// abbreviated commit when HEAD is detached.
+pub fn branch(file: Option<&str>) -> Option<String> {
+    let start = match file {
+        Some(file) => fs::canonicalize(file).ok()?.parent()?.to_path_buf(),
+        None => std::env::current_dir().ok()?,
+    };
+    let head = start.ancestors().find_map(head_file)?;
+    let head = fs::read_to_string(head).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(reference) => Some(reference.strip_prefix("refs/heads/").unwrap_or(reference).to_string()),
+        None => Some(head.chars().take(7).collect()),
+    }
+}
+
+fn head_file(dir: &Path) -> Option<PathBuf> {
+    let git = dir.join(".git");
+    if git.is_dir() {
+        return Some(git.join("HEAD"));
+    }
+    let link = fs::read_to_string(&git).ok()?;
+    let target = Path::new(link.trim().strip_prefix("gitdir: ")?);
+    Some(dir.join(target).join("HEAD"))
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone)]
+pub enum Address {
+    Line(usize),
+    Pattern(String, bool),
+}
+
+#[derive(Clone)]
+pub struct Tag {
+    pub file: String,
+    pub address: Address,
+    pub kind: String,
+}
+
+impl Tag {
+    pub fn summary(&self) -> String {
+        let at = match &self.address {
+            Address::Line(line) => (line + 1).to_string(),
+            Address::Pattern(pattern, _) => pattern.trim().to_string(),
+        };
+        format!("{} {}: {}", self.kind, self.file, at).trim_start().to_string()
+    }
+}
+
+fn tags_file(start: &Path) -> Option<PathBuf> {
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+    start.ancestors().map(|dir| dir.join("tags")).find(|path| path.is_file())
+}
+
+pub fn find(start: &Path, name: &str) -> Option<Vec<Tag>> {
+    let path = tags_file(start)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let text = fs::read_to_string(&path).ok()?;
+    let tags = text
+        .lines()
+        .filter(|line| !line.starts_with("!_TAG_"))
+        .filter_map(|line| parse(line, name, dir))
+        .collect();
+    Some(tags)
+}
+
+fn parse(line: &str, name: &str, dir: &Path) -> Option<Tag> {
+    let rest = line.strip_prefix(name)?.strip_prefix('\t')?;
+    let (file, rest) = rest.split_once('\t')?;
+    let (address, fields) = match rest.rfind(";\"") {
+        Some(end) => (&rest[..end], &rest[end + 2..]),
+        None => (rest, ""),
+    };
+    let address = match address.parse::<usize>() {
+        Ok(line) => Address::Line(line.saturating_sub(1)),
+        Err(_) => {
+            let pattern = address.strip_prefix(['/', '?'])?;
+            let pattern = pattern.strip_suffix(['/', '?']).unwrap_or(pattern);
+            let (pattern, anchored) = match pattern.strip_prefix('^') {
+                Some(rest) => (rest.strip_suffix('$').unwrap_or(rest), true),
+                None => (pattern, false),
+            };
+            Address::Pattern(pattern.replace("\\/", "/").replace("\\\\", "\\"), anchored)
+        }
+    };
+    let kind = fields.split('\t').map(str::trim).find_map(|field| match field.strip_prefix("kind:") {
+        Some(kind) => Some(kind),
+        None => Some(field).filter(|field| !field.is_empty() && !field.contains(':')),
+    });
+    let file = if Path::new(file).is_absolute() {
+        file.to_string()
+    } else {
+        dir.join(file).to_string_lossy().into_owned()
+    };
+    Some(Tag {
+        file,
+        address,
+        kind: kind.unwrap_or("").to_string(),
+    })
+}
+
+pub fn locate(lines: &[String], address: &Address) -> Option<usize> {
+    match address {
+        Address::Line(line) => Some((*line).min(lines.len().saturating_sub(1))),
+        Address::Pattern(pattern, anchored) => {
+            let exact = lines.iter().position(|line| *anchored && line == pattern);
+            exact.or_else(|| lines.iter().position(|line| line.contains(pattern.as_str())))
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAKEFILES: &[&str] = &["GNUmakefile", "makefile", "Makefile"];
+const CARGO: &[&str] = &["build", "test", "run", "check", "clippy"];
+
+pub fn root(start: &Path) -> PathBuf {
+    let start = start.canonicalize().unwrap_or_else(|_| start.to_path_buf());
+    let marked = |dir: &Path| MAKEFILES.iter().chain(&["Cargo.toml", "package.json"]).any(|name| dir.join(name).is_file());
+    start.ancestors().find(|dir| marked(dir)).unwrap_or(&start).to_path_buf()
+}
+
+pub fn detect(dir: &Path) -> Vec<String> {
+    let mut tasks = Vec::new();
+    if let Some(text) = MAKEFILES.iter().find_map(|name| fs::read_to_string(dir.join(name)).ok()) {
+        tasks.extend(makefile_targets(&text).into_iter().map(|target| format!("make {}", target)));
+    }
+    if dir.join("Cargo.toml").is_file() {
+        tasks.extend(CARGO.iter().map(|command| format!("cargo {}", command)));
+    }
+    if let Ok(text) = fs::read_to_string(dir.join("package.json")) {
+        let runner = if dir.join("pnpm-lock.yaml").is_file() {
+            "pnpm run"
+        } else if dir.join("yarn.lock").is_file() {
+            "yarn run"
+        } else {
+            "npm run"
+        };
+        tasks.extend(package_scripts(&text).into_iter().map(|script| format!("{} {}", runner, script)));
+    }
+    tasks
+}
+
+fn makefile_targets(text: &str) -> Vec<String> {
+    let mut targets = Vec::new();
+    for line in text.lines() {
+        if line.starts_with(['\t', ' ', '#']) {
+            continue;
+        }
+        let Some((names, rest)) = line.split_once(':') else {
+            continue;
+        };
+        if rest.starts_with('=') || names.contains(['=', '$', '%']) {
+            continue;
+        }
+        for name in names.split_whitespace() {
+            if !name.starts_with('.') && !targets.iter().any(|target| target == name) {
+                targets.push(name.to_string());
+            }
+        }
+    }
+    targets
+}
+
+fn package_scripts(text: &str) -> Vec<String> {
+    let mut scripts = Vec::new();
+    let Some(start) = text.find("\"scripts\"") else {
+        return scripts;
+    };
+    let mut chars = text[start + 9..].chars().skip_while(|&c| c != '{').skip(1);
+    let mut depth = 0;
+    let mut expect_key = true;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => string.extend(chars.next()),
+                        '"' => break,
+                        c => string.push(c),
+                    }
+                }
+                if depth == 0 && expect_key {
+                    scripts.push(string);
+                    expect_key = false;
+                }
+            }
+            '{' | '[' => depth += 1,
+            '}' | ']' if depth == 0 => break,
+            '}' | ']' => depth -= 1,
+            ',' if depth == 0 => expect_key = true,
+            _ => {}
+        }
+    }
+    scripts
+}
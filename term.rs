@@ -0,0 +1,366 @@
+use crate::screen::{Cell, Screen, Style};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Color;
+
+enum State {
+    Ground,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+    Charset,
+}
+
+pub struct Terminal {
+    cols: usize,
+    rows: usize,
+    cells: Vec<Cell>,
+    x: usize,
+    y: usize,
+    style: Style,
+    saved: (usize, usize),
+    top: usize,
+    bottom: usize,
+    wrap_pending: bool,
+    state: State,
+    params: String,
+    utf8: Vec<u8>,
+}
+
+impl Terminal {
+    pub fn new(cols: u16, rows: u16) -> Self {
+        let (cols, rows) = (cols.max(1) as usize, rows.max(1) as usize);
+        Self {
+            cols,
+            rows,
+            cells: vec![Cell::default(); cols * rows],
+            x: 0,
+            y: 0,
+            style: Style::default(),
+            saved: (0, 0),
+            top: 0,
+            bottom: rows - 1,
+            wrap_pending: false,
+            state: State::Ground,
+            params: String::new(),
+            utf8: Vec::new(),
+        }
+    }
+
+    pub fn size(&self) -> (u16, u16) {
+        (self.cols as u16, self.rows as u16)
+    }
+
+    pub fn cursor(&self) -> (u16, u16) {
+        (self.x as u16, self.y as u16)
+    }
+
+    pub fn resize(&mut self, cols: u16, rows: u16) {
+        let (cols, rows) = (cols.max(1) as usize, rows.max(1) as usize);
+        let mut cells = vec![Cell::default(); cols * rows];
+        let skip = (self.y + 1).saturating_sub(rows);
+        for y in 0..rows.min(self.rows - skip) {
+            for x in 0..cols.min(self.cols) {
+                cells[y * cols + x] = self.cells[(y + skip) * self.cols + x];
+            }
+        }
+        self.cells = cells;
+        self.cols = cols;
+        self.rows = rows;
+        self.x = self.x.min(cols - 1);
+        self.y = (self.y - skip).min(rows - 1);
+        self.top = 0;
+        self.bottom = rows - 1;
+        self.wrap_pending = false;
+    }
+
+    pub fn draw(&self, screen: &mut Screen, top: u16) {
+        for y in 0..self.rows {
+            for x in 0..self.cols {
+                let cell = self.cells[y * self.cols + x];
+                screen.put(x as u16, top + y as u16, cell.ch, cell.style);
+            }
+        }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.byte(byte);
+        }
+    }
+
+    fn byte(&mut self, byte: u8) {
+        match self.state {
+            State::Ground => self.ground(byte),
+            State::Escape => {
+                self.state = State::Ground;
+                match byte {
+                    b'[' => {
+                        self.params.clear();
+                        self.state = State::Csi;
+                    }
+                    b']' => self.state = State::Osc,
+                    b'(' | b')' => self.state = State::Charset,
+                    b'7' => self.saved = (self.x, self.y),
+                    b'8' => self.goto(self.saved.0, self.saved.1),
+                    b'D' => self.linefeed(),
+                    b'E' => {
+                        self.x = 0;
+                        self.linefeed();
+                    }
+                    b'M' => self.reverse_index(),
+                    b'c' => *self = Self::new(self.cols as u16, self.rows as u16),
+                    _ => {}
+                }
+            }
+            State::Csi => match byte {
+                0x30..=0x3f => self.params.push(byte as char),
+                0x40..=0x7e => {
+                    self.state = State::Ground;
+                    self.csi(byte);
+                }
+                _ => {}
+            },
+            State::Osc => match byte {
+                0x07 => self.state = State::Ground,
+                0x1b => self.state = State::OscEscape,
+                _ => {}
+            },
+            State::OscEscape | State::Charset => self.state = State::Ground,
+        }
+    }
+
+    fn ground(&mut self, byte: u8) {
+        if !self.utf8.is_empty() || byte >= 0x80 {
+            self.utf8.push(byte);
+            let expected = match self.utf8[0] {
+                0xc0..=0xdf => 2,
+                0xe0..=0xef => 3,
+                0xf0..=0xf7 => 4,
+                _ => 1,
+            };
+            if self.utf8.len() >= expected {
+                let c = std::str::from_utf8(&self.utf8).ok().and_then(|s| s.chars().next()).unwrap_or('?');
+                self.utf8.clear();
+                self.put(c);
+            }
+            return;
+        }
+        match byte {
+            0x1b => self.state = State::Escape,
+            b'\r' => self.goto(0, self.y),
+            b'\n' | 0x0b | 0x0c => self.linefeed(),
+            0x08 => self.goto(self.x.saturating_sub(1), self.y),
+            b'\t' => self.goto((self.x / 8 + 1) * 8, self.y),
+            0x20..=0x7e => self.put(byte as char),
+            _ => {}
+        }
+    }
+
+    fn goto(&mut self, x: usize, y: usize) {
+        self.x = x.min(self.cols - 1);
+        self.y = y.min(self.rows - 1);
+        self.wrap_pending = false;
+    }
+
+    fn put(&mut self, c: char) {
+        if self.wrap_pending {
+            self.x = 0;
+            self.linefeed();
+        }
+        self.cells[self.y * self.cols + self.x] = Cell { ch: c, style: self.style };
+        if self.x + 1 == self.cols {
+            self.wrap_pending = true;
+        } else {
+            self.x += 1;
+        }
+    }
+
+    fn linefeed(&mut self) {
+        self.wrap_pending = false;
+        if self.y == self.bottom {
+            self.scroll_up(self.top, 1);
+        } else if self.y + 1 < self.rows {
+            self.y += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        if self.y == self.top {
+            self.scroll_down(self.top, 1);
+        } else {
+            self.y = self.y.saturating_sub(1);
+        }
+    }
+
+    fn scroll_up(&mut self, from: usize, n: usize) {
+        if from > self.bottom {
+            return;
+        }
+        let region = &mut self.cells[from * self.cols..(self.bottom + 1) * self.cols];
+        let n = n.min(self.bottom + 1 - from) * self.cols;
+        region.rotate_left(n);
+        let len = region.len();
+        region[len - n..].fill(Cell::default());
+    }
+
+    fn scroll_down(&mut self, from: usize, n: usize) {
+        if from > self.bottom {
+            return;
+        }
+        let region = &mut self.cells[from * self.cols..(self.bottom + 1) * self.cols];
+        let n = n.min(self.bottom + 1 - from) * self.cols;
+        region.rotate_right(n);
+        region[..n].fill(Cell::default());
+    }
+
+    fn erase(&mut self, from: usize, to: usize) {
+        let to = to.min(self.cells.len());
+        if from < to {
+            self.cells[from..to].fill(Cell::default());
+        }
+    }
+
+    fn csi(&mut self, command: u8) {
+        if self.params.starts_with(['?', '>', '=']) {
+            return;
+        }
+        let params: Vec<usize> = self.params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+        let arg = |i: usize| params.get(i).copied().filter(|&n| n > 0).unwrap_or(1);
+        let here = self.y * self.cols + self.x;
+        let line = self.y * self.cols;
+        match command {
+            b'A' => self.goto(self.x, self.y.saturating_sub(arg(0))),
+            b'B' => self.goto(self.x, self.y + arg(0)),
+            b'C' => self.goto(self.x + arg(0), self.y),
+            b'D' => self.goto(self.x.saturating_sub(arg(0)), self.y),
+            b'E' => self.goto(0, self.y + arg(0)),
+            b'F' => self.goto(0, self.y.saturating_sub(arg(0))),
+            b'G' | b'`' => self.goto(arg(0) - 1, self.y),
+            b'd' => self.goto(self.x, arg(0) - 1),
+            b'H' | b'f' => self.goto(arg(1) - 1, arg(0) - 1),
+            b'J' => match params[0] {
+                0 => self.erase(here, self.cells.len()),
+                1 => self.erase(0, here + 1),
+                _ => self.erase(0, self.cells.len()),
+            },
+            b'K' => match params[0] {
+                0 => self.erase(here, line + self.cols),
+                1 => self.erase(line, here + 1),
+                _ => self.erase(line, line + self.cols),
+            },
+            b'X' => self.erase(here, here + arg(0).min(self.cols - self.x)),
+            b'@' | b'P' => {
+                let row = &mut self.cells[here..line + self.cols];
+                let n = arg(0).min(row.len());
+                if command == b'@' {
+                    row.rotate_right(n);
+                    row[..n].fill(Cell::default());
+                } else {
+                    row.rotate_left(n);
+                    let len = row.len();
+                    row[len - n..].fill(Cell::default());
+                }
+            }
+            b'L' => self.scroll_down(self.y, arg(0)),
+            b'M' => self.scroll_up(self.y, arg(0)),
+            b'S' => self.scroll_up(self.top, arg(0)),
+            b'T' => self.scroll_down(self.top, arg(0)),
+            b'r' => {
+                let top = arg(0) - 1;
+                let bottom = params.get(1).copied().filter(|&n| n > 0).unwrap_or(self.rows) - 1;
+                if top < bottom && bottom < self.rows {
+                    self.top = top;
+                    self.bottom = bottom;
+                }
+                self.goto(0, 0);
+            }
+            b's' => self.saved = (self.x, self.y),
+            b'u' => self.goto(self.saved.0, self.saved.1),
+            b'm' => self.sgr(&params),
+            _ => {}
+        }
+    }
+
+    fn sgr(&mut self, params: &[usize]) {
+        let mut i = 0;
+        while i < params.len() {
+            let color = |i: &mut usize| -> Option<Color> {
+                match params.get(*i + 1) {
+                    Some(5) => {
+                        *i += 2;
+                        params.get(*i).map(|&n| Color::AnsiValue(n as u8))
+                    }
+                    Some(2) => {
+                        *i += 4;
+                        let rgb = params.get(*i - 2..=*i)?;
+                        Some(Color::Rgb {
+                            r: rgb[0] as u8,
+                            g: rgb[1] as u8,
+                            b: rgb[2] as u8,
+                        })
+                    }
+                    _ => None,
+                }
+            };
+            match params[i] {
+                0 => self.style = Style::default(),
+                1 => self.style.bold = true,
+                4 => self.style.underline = true,
+                7 => self.style.reverse = true,
+                22 => self.style.bold = false,
+                24 => self.style.underline = false,
+                27 => self.style.reverse = false,
+                n @ 30..=37 => self.style.fg = Some(Color::AnsiValue((n - 30) as u8)),
+                n @ 90..=97 => self.style.fg = Some(Color::AnsiValue((n - 90 + 8) as u8)),
+                n @ 40..=47 => self.style.bg = Some(Color::AnsiValue((n - 40) as u8)),
+                n @ 100..=107 => self.style.bg = Some(Color::AnsiValue((n - 100 + 8) as u8)),
+                38 => self.style.fg = color(&mut i),
+                48 => self.style.bg = color(&mut i),
+                39 => self.style.fg = None,
+                49 => self.style.bg = None,
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+pub fn key_bytes(key: &KeyEvent) -> Option<Vec<u8>> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let mut bytes = match key.code {
+        KeyCode::Char(c) if ctrl && c.is_ascii_alphabetic() => vec![c.to_ascii_lowercase() as u8 - b'a' + 1],
+        KeyCode::Char(' ' | '@' | '2') if ctrl => vec![0],
+        KeyCode::Char('[' | '3') if ctrl => vec![0x1b],
+        KeyCode::Char('\\' | '4') if ctrl => vec![0x1c],
+        KeyCode::Char(']' | '5') if ctrl => vec![0x1d],
+        KeyCode::Char('^' | '6') if ctrl => vec![0x1e],
+        KeyCode::Char('_' | '7' | '/') if ctrl => vec![0x1f],
+        KeyCode::Char(c) => c.to_string().into_bytes(),
+        KeyCode::Enter => b"\r".to_vec(),
+        KeyCode::Tab => b"\t".to_vec(),
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => b"\x1b[A".to_vec(),
+        KeyCode::Down => b"\x1b[B".to_vec(),
+        KeyCode::Right => b"\x1b[C".to_vec(),
+        KeyCode::Left => b"\x1b[D".to_vec(),
+        KeyCode::Home => b"\x1b[H".to_vec(),
+        KeyCode::End => b"\x1b[F".to_vec(),
+        KeyCode::Insert => b"\x1b[2~".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        KeyCode::F(n @ 1..=4) => vec![0x1b, b'O', b'P' + n - 1],
+        KeyCode::F(n @ 5..=12) => {
+            let code = [15, 17, 18, 19, 20, 21, 23, 24][n as usize - 5];
+            format!("\x1b[{}~", code).into_bytes()
+        }
+        _ => return None,
+    };
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        bytes.insert(0, 0x1b);
+    }
+    Some(bytes)
+}
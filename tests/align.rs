@@ -0,0 +1,31 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn aligns_the_first_delimiter_in_the_paragraph() {
+    Driver::new("x = 1\nlonger_name = 2\n  y=3\n\nz = 4")
+        .keys(":align =<CR>")
+        .assert_text("x           = 1\nlonger_name = 2\n  y         = 3\n\nz = 4");
+}
+
+#[test]
+fn aligns_every_occurrence_in_a_range() {
+    Driver::new("| a | bb |\n| ccc | d |\nplain\n| e |")
+        .keys(":1,3align *|<CR>")
+        .assert_text("| a   | bb |\n| ccc | d  |\nplain\n| e |");
+}
+
+#[test]
+fn leaves_later_delimiters_alone_by_default() {
+    Driver::new("name: aon\nhomepage: http://example.com")
+        .keys(":align :<CR>")
+        .assert_text("name     : aon\nhomepage : http://example.com");
+}
+
+#[test]
+fn requires_a_delimiter() {
+    let mut driver = Driver::new("a = 1");
+    driver.keys(":align<CR>").assert_text("a = 1");
+    assert!(driver.editor.message().unwrap_or_default().contains("align"));
+}
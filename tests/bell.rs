@@ -0,0 +1,36 @@
+mod common;
+
+use common::Driver;
+use std::thread;
+use std::time::Duration;
+
+fn reversed(driver: &mut Driver, x: u16, y: u16) -> bool {
+    let screen = driver.editor.render(40, 10);
+    screen.cell(x, y).is_some_and(|cell| cell.style.reverse)
+}
+
+#[test]
+fn flashes_the_status_bar_on_errors() {
+    let mut driver = Driver::new("text");
+    let status = (0..10).find(|&y| driver.editor.render(40, 10).row(y).starts_with("[")).unwrap();
+    assert!(!reversed(&mut driver, 39, status));
+    driver.keys(":frobnicate<CR>");
+    assert_eq!(driver.editor.message(), Some("Unknown command: frobnicate"));
+    assert!(reversed(&mut driver, 39, status));
+    assert!(!reversed(&mut driver, 0, 0));
+    thread::sleep(Duration::from_millis(160));
+    driver.editor.tick();
+    assert!(!reversed(&mut driver, 39, status));
+}
+
+#[test]
+fn backspace_at_the_start_is_reported() {
+    let mut driver = Driver::with_config("text", "bell = screen");
+    driver.keys("<BS>").assert_text("text");
+    assert_eq!(driver.editor.message(), Some("Already at the start of the buffer"));
+    assert!(reversed(&mut driver, 0, 0));
+    assert!(reversed(&mut driver, 20, 5));
+    let mut driver = Driver::with_config("text", "bell = none");
+    driver.keys("<BS>");
+    assert!(!reversed(&mut driver, 0, 0));
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+
+fn binary_file(name: &str) -> (TempDir, std::path::PathBuf) {
+    let dir = TempDir::new(&format!("binary-{}", name));
+    let path = dir.file("file.bin", b"ELF\0\x01\x02\r\nname=x\n\xff\0end");
+    (dir, path)
+}
+
+#[test]
+fn warns_and_offers_modes_for_binary_files() {
+    let (_dir, path) = binary_file("warn");
+    let mut driver = Driver::open(path.to_str().unwrap());
+    assert_eq!(driver.editor.message(), Some(format!("{} looks like a binary file", path.display()).as_str()));
+    let screen = driver.editor.render(60, 12);
+    let rows: Vec<String> = (0..12).map(|y| screen.row(y)).collect();
+    assert!(rows.iter().any(|row| row.contains("Read-only text")), "{:#?}", rows);
+    assert!(rows.iter().any(|row| row.contains("Hex view")));
+    driver.keys("<Down><CR>");
+    let screen = driver.editor.render(60, 12);
+    assert!(screen.row(0).starts_with("00000000  45 4c 46 00"));
+}
+
+#[test]
+fn read_only_text_shows_control_characters() {
+    let (_dir, path) = binary_file("readonly");
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.keys("<CR>");
+    let screen = driver.editor.render(60, 12);
+    assert_eq!(screen.row(0).trim_end(), "ELF\u{2400}\u{2401}\u{2402}\u{240d}");
+    assert_eq!(screen.row(2).trim_end(), "\u{fffd}\u{2400}end");
+    driver.keys("x");
+    assert_eq!(driver.editor.message(), Some("Buffer is read-only"));
+}
+
+#[test]
+fn forced_text_saves_byte_for_byte() {
+    let (_dir, path) = binary_file("text");
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.keys("<Down><Down><CR><Down><End>y:w<CR>");
+    assert_eq!(fs::read(&path).unwrap(), b"ELF\0\x01\x02\r\nname=xy\n\xff\0end");
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn follows_the_cursor_through_nested_scopes() {
+    let (mut driver, _dir) = Driver::open_temp("a.rs", "mod shapes {\n    impl Point {\n        fn norm(&self) {\n            1\n        }\n    }\n}");
+    driver.keys("<Down><Down><Down>");
+    assert!(driver.status_row().ends_with("| rust | shapes > Point > norm"), "{}", driver.status_row());
+    driver.keys("<Up><Up>");
+    assert!(driver.status_row().ends_with("| rust | shapes > Point"));
+    driver.keys(":set nobreadcrumbs<CR>");
+    assert!(driver.status_row().ends_with("| rust"));
+}
+
+#[test]
+fn shows_the_markdown_heading_chain() {
+    let (mut driver, _dir) = Driver::open_temp("b.md", "# Guide\n## Install\ntext\n## Usage\nmore");
+    driver.keys("<Down><Down>");
+    assert!(driver.status_row().ends_with("| markdown | Guide > Install"));
+    driver.keys("<Down><Down>");
+    assert!(driver.status_row().ends_with("| markdown | Guide > Usage"));
+}
+
+#[test]
+fn follows_edits_to_the_buffer() {
+    let (mut driver, _dir) = Driver::open_temp("c.rs", "fn first() {\n    1\n}");
+    driver.keys("<Down>");
+    assert!(driver.status_row().ends_with("| rust | first"));
+    driver.keys("<Up><End><Left><Left><Left><Left><BS><BS><BS><BS><BS>last<Down>");
+    assert!(driver.status_row().ends_with("| rust | last"), "{}", driver.status_row());
+}
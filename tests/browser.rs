@@ -0,0 +1,57 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+use std::path::PathBuf;
+
+fn setup(name: &str) -> (TempDir, PathBuf) {
+    let temp = TempDir::new(&format!("browser-{}", name));
+    fs::create_dir_all(temp.join("src")).unwrap();
+    temp.file("src/main.rs", "fn main() {}");
+    temp.file("README", "hello");
+    temp.file("Cargo.toml", "[package]");
+    let dir = temp.path().canonicalize().unwrap();
+    (temp, dir)
+}
+
+#[test]
+fn lists_directories_first_and_opens_entries() {
+    let (_temp, dir) = setup("open");
+    let mut driver = Driver::open(dir.to_str().unwrap());
+    driver.assert_text("../\nsrc/\nCargo.toml\nREADME");
+    driver.keys("<Down><CR>").assert_text("../\nmain.rs");
+    driver.keys("<Down><CR>").assert_text("fn main() {}");
+    driver.keys(":bp<CR>-").assert_text("../\nsrc/\nCargo.toml\nREADME").assert_cursor(1, 0);
+}
+
+#[test]
+fn creates_renames_and_deletes_entries() {
+    let (_temp, dir) = setup("edit");
+    let mut driver = Driver::open(dir.to_str().unwrap());
+    driver.keys("dbuild<CR>").assert_text("../\nbuild/\nsrc/\nCargo.toml\nREADME").assert_cursor(1, 0);
+    driver.keys("<Down><Down><Down>R<BS><BS><BS><BS><BS><BS>NOTES<CR>");
+    driver.assert_text("../\nbuild/\nsrc/\nCargo.toml\nNOTES");
+    assert_eq!(fs::read_to_string(dir.join("NOTES")).unwrap(), "hello");
+
+    driver.keys("Dn").assert_text("../\nbuild/\nsrc/\nCargo.toml\nNOTES");
+    driver.keys("Dy").assert_text("../\nbuild/\nsrc/\nCargo.toml").assert_cursor(3, 0);
+    assert!(!dir.join("NOTES").exists());
+
+    driver.keys("%todo.txt<CR>");
+    assert!(dir.join("todo.txt").is_file());
+    driver.keys("first").assert_text("first");
+}
+
+#[test]
+fn reports_failures() {
+    let (_temp, dir) = setup("fail");
+    let mut driver = Driver::open(dir.to_str().unwrap());
+    driver.keys("<Down>Dy");
+    assert!(driver.editor.message().unwrap().starts_with("Could not update src:"));
+    assert!(dir.join("src/main.rs").exists());
+
+    driver.keys("<Down>R<BS><BS><BS><BS><BS><BS><BS><BS><BS><BS>README<CR>");
+    assert!(driver.editor.message().unwrap().starts_with("Could not update Cargo.toml:"));
+    assert_eq!(fs::read_to_string(dir.join("README")).unwrap(), "hello");
+    assert!(dir.join("Cargo.toml").exists());
+}
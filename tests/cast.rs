@@ -0,0 +1,27 @@
+mod common;
+
+use common::{Driver, TempDir};
+
+#[test]
+fn cast_command_exports_frames_and_input() {
+    let dir = TempDir::new("cast");
+    let path = dir.join("session.cast");
+    let path = path.to_str().unwrap();
+    let mut driver = Driver::new("");
+    driver.keys(&format!(":cast {}<CR>", path));
+    assert!(driver.editor.casting());
+    driver.editor.record_output(b"\x1b[2J\"hi\"", 100, 30);
+    driver.keys("ab");
+    driver.editor.record_output(b"ab", 120, 30);
+    driver.keys(":cast<CR>");
+    assert!(!driver.editor.casting());
+
+    let text = std::fs::read_to_string(path).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines[0].starts_with("{\"version\": 2, \"width\": 100, \"height\": 30,"));
+    assert!(lines[1].ends_with(", \"o\", \"\\u001b[2J\\\"hi\\\"\"]"));
+    assert!(lines[2].ends_with(", \"i\", \"a\"]"));
+    assert!(lines[3].ends_with(", \"i\", \"b\"]"));
+    assert!(lines[4].ends_with(", \"r\", \"120x30\"]"));
+    assert!(lines[5].ends_with(", \"o\", \"ab\"]"));
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use aon::Mode;
+use common::Driver;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn runs_two_key_sequences_and_replays_broken_ones() {
+    let mut driver = Driver::with_config("", "map.j k = command-mode");
+    driver.keys("jx").assert_text("jx").assert_mode(Mode::Insert);
+    driver.keys("jk").assert_text("jx").assert_mode(Mode::Command);
+}
+
+#[test]
+fn modifier_chords_keep_the_first_key_when_they_time_out() {
+    let mut driver = Driver::with_config("one", "keymap = vim\nmap.Ctrl+K Ctrl+U = undo\ntimeoutlen = 1");
+    driver.keys("<Esc>x").assert_text("ne");
+    driver.keys("<C-k><C-u>").assert_text("one");
+    driver.keys("i<C-k>");
+    thread::sleep(Duration::from_millis(5));
+    driver.editor.tick();
+    driver.keys("e:<C-k>o:").assert_text("\u{eb}\u{f6}one");
+}
+
+#[test]
+fn escape_cancels_a_pending_sequence() {
+    let mut driver = Driver::with_config("", "map.Ctrl+K Ctrl+U = undo");
+    driver.keys("ab<C-k><Esc>").assert_text("ab").assert_mode(Mode::Insert);
+    driver.keys("c").assert_text("abc");
+}
+
+#[test]
+fn sequences_in_normal_mode_take_a_count() {
+    let mut driver = Driver::with_config("a\nb\nc\nd\ne", "keymap = vim\nmap.g j = move-down");
+    driver.keys("<Esc>3gj").assert_cursor(3, 0);
+    driver.keys("gg").assert_cursor(0, 0);
+    driver.keys("2dd").assert_text("c\nd\ne");
+}
+
+#[test]
+fn alt_digits_repeat_the_next_key() {
+    let mut driver = Driver::new("");
+    driver.keys("<A-3>x<A-1><A-2>y").assert_text(&format!("xxx{}", "y".repeat(12)));
+    driver.keys("<A-2><Esc>z").assert_text(&format!("xxx{}z", "y".repeat(12)));
+}
+
+#[test]
+fn pending_sequences_are_shown_with_their_continuations() {
+    let mut driver = Driver::with_config("", "map.Ctrl+K Ctrl+U = undo\nmap.Ctrl+K u = paste\nwhichkey = 1");
+    driver.keys("<A-2><C-k>");
+    thread::sleep(Duration::from_millis(5));
+    driver.editor.tick();
+    let screen = driver.editor.render(50, 12);
+    let rows: Vec<String> = (0..12).map(|y| screen.row(y).trim_end().to_string()).collect();
+    assert!(rows.iter().any(|row| row.contains(" 2Ctrl+K ")), "{:#?}", rows);
+    assert!(rows.iter().any(|row| row.starts_with("│ Ctrl+U  Undo")), "{:#?}", rows);
+    assert!(rows.iter().any(|row| row.starts_with("│ u       Paste")), "{:#?}", rows);
+    assert!(rows.iter().any(|row| row.trim_end().ends_with("2Ctrl+K")), "{:#?}", rows);
+}
+
+#[test]
+fn map_command_accepts_sequences() {
+    let mut driver = Driver::new("");
+    driver.keys(":map Ctrl+X Ctrl+S undo<CR>");
+    assert_eq!(driver.editor.message(), None);
+    driver.keys(":help<CR>");
+    assert!(driver.text().contains("Ctrl+X Ctrl+S"));
+}
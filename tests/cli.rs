@@ -0,0 +1,77 @@
+mod common;
+
+use aon::cli::{self, Start};
+use aon::Editor;
+use common::{Driver, TempDir};
+use std::fs;
+
+fn parse(args: &[&str]) -> Result<cli::Args, String> {
+    cli::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+fn names(args: &cli::Args) -> Vec<&str> {
+    args.files.iter().map(|(file, _)| file.as_str()).collect()
+}
+
+#[test]
+fn parses_files_positions_and_commands() {
+    let args = parse(&["-R", "+42", "a.rs", "--cmd", "set nowrap", "b.rs", "--cmd", "set number"]).unwrap();
+    assert_eq!(names(&args), ["a.rs", "b.rs"]);
+    assert_eq!(args.start, Some(Start::Line(42)));
+    assert!(args.readonly);
+    assert_eq!(args.commands, ["set nowrap", "set number"]);
+    assert_eq!(parse(&["+/fn main"]).unwrap().start, Some(Start::Pattern("fn main".to_string())));
+    assert_eq!(parse(&["+"]).unwrap().start, Some(Start::Last));
+    assert_eq!(names(&parse(&["--", "-R", "+3"]).unwrap()), ["-R", "+3"]);
+    let args = parse(&["--merge", "l", "b", "r", "m", "other"]).unwrap();
+    assert_eq!(names(&args), ["m", "other"]);
+    assert!(parse(&["-x"]).unwrap_err().starts_with("unknown option: -x"));
+    assert!(parse(&["+4x"]).unwrap_err().starts_with("invalid line number: +4x"));
+    assert!(parse(&["--cmd"]).unwrap_err().starts_with("--cmd needs an argument"));
+}
+
+#[test]
+fn opens_every_file_as_a_buffer() {
+    let dir = TempDir::new("cli");
+    let first = dir.join("first.txt").to_string_lossy().into_owned();
+    let second = dir.join("second.txt").to_string_lossy().into_owned();
+    fs::write(&first, "one\n  two\nthree\n").unwrap();
+    fs::write(&second, "other\n").unwrap();
+    let mut driver = Driver {
+        editor: Editor::new(Some(first.clone())),
+        quit: false,
+    };
+    driver.editor.open_buffers(&[(second.clone(), None)]);
+    driver.editor.set_readonly();
+    driver.editor.start_at(&Start::Line(2));
+    assert_eq!(driver.editor.filename(), Some(first.as_str()));
+    driver.assert_cursor(1, 2);
+    driver.editor.start_at(&Start::Pattern("th".to_string()));
+    driver.assert_cursor(2, 0);
+    driver.keys("x").assert_text("one\n  two\nthree");
+    assert!(!driver.editor.run_command("bn").unwrap());
+    assert_eq!(driver.editor.filename(), Some(second.as_str()));
+    driver.keys("x").assert_text("other");
+}
+
+#[test]
+fn splits_compiler_style_positions() {
+    assert_eq!(cli::split_position("src/main.rs:120:8"), ("src/main.rs".to_string(), Some(Start::Position(120, 8))));
+    assert_eq!(cli::split_position("notes.md:7:"), ("notes.md".to_string(), Some(Start::Line(7))));
+    assert_eq!(cli::split_position("C:/work/a.rs:3"), ("C:/work/a.rs".to_string(), Some(Start::Line(3))));
+    assert_eq!(cli::split_position("a.rs:x"), ("a.rs:x".to_string(), None));
+    assert_eq!(cli::split_position(":12"), (":12".to_string(), None));
+    assert_eq!(cli::split_position("https://host:8080"), ("https://host:8080".to_string(), None));
+    let dir = TempDir::new("cli-position");
+    let name = dir.file("notes:5", "").to_string_lossy().into_owned();
+    assert_eq!(cli::split_position(&name), (name.clone(), None));
+}
+
+#[test]
+fn places_the_cursor_at_a_character_column() {
+    let mut driver = Driver::new("first\nna\u{ef}ve caf\u{e9}");
+    driver.editor.start_at(&Start::Position(2, 5));
+    driver.assert_cursor(1, 5);
+    driver.editor.start_at(&Start::Position(9, 80));
+    driver.assert_cursor(1, 12);
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn yanks_reach_the_terminal_clipboard() {
+    let mut driver = Driver::with_config("hello\nworld", "keymap = vim\nosc52 = always");
+    driver.keys("yy");
+    assert_eq!(driver.editor.render(40, 10).passthrough, b"\x1b]52;c;aGVsbG8=\x07");
+    assert!(driver.editor.render(40, 10).passthrough.is_empty());
+    driver.keys("jyw");
+    assert_eq!(driver.editor.render(40, 10).passthrough, b"\x1b]52;c;d29ybGQ=\x07");
+}
+
+#[test]
+fn respects_the_size_limit_and_toggle() {
+    let mut driver = Driver::with_config("hello", "keymap = vim\nosc52 = always\nosc52limit = 3");
+    driver.keys("yy");
+    assert!(driver.editor.render(40, 10).passthrough.is_empty());
+    assert_eq!(driver.editor.message(), Some("Not copied to the terminal clipboard: 5 bytes exceeds osc52limit (3)"));
+    driver.keys(":set osc52=never<CR>:set osc52limit=100<CR>yy");
+    assert!(driver.editor.render(40, 10).passthrough.is_empty());
+    driver.keys("p").assert_text("hello\nhello");
+}
@@ -0,0 +1,190 @@
+#![allow(dead_code)]
+
+use aon::config::Config;
+use aon::{Editor, Mode};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use std::path::{Path, PathBuf};
+
+pub struct Driver {
+    pub editor: Editor,
+    pub quit: bool,
+}
+
+impl Driver {
+    pub fn new(text: &str) -> Self {
+        Self {
+            editor: Editor::from_text(text),
+            quit: false,
+        }
+    }
+
+    pub fn open(filename: &str) -> Self {
+        Self {
+            editor: Editor::new(Some(filename.to_string())),
+            quit: false,
+        }
+    }
+
+    pub fn vim(text: &str) -> Self {
+        Self::with_config(text, "keymap = vim")
+    }
+
+    /// Opens `name` holding `text` in a fresh directory, which must outlive
+    /// the driver. Names must differ between the tests of one file.
+    pub fn open_temp(name: &str, text: &str) -> (Self, TempDir) {
+        let dir = TempDir::new(&format!("open-{}", name));
+        let path = dir.file(name, text);
+        (Self::open(path.to_str().unwrap()), dir)
+    }
+
+    pub fn with_config(text: &str, config: &str) -> Self {
+        let mut driver = Self::new(text);
+        driver.editor.apply_config(&Config::parse(config));
+        driver
+    }
+
+    pub fn keys(&mut self, script: &str) -> &mut Self {
+        for key in parse(script) {
+            assert!(!self.quit, "key {:?} sent after the editor quit", key.code);
+            self.quit = self.editor.dispatch(Event::Key(key)).expect("key handling failed");
+        }
+        self
+    }
+
+    pub fn mouse(&mut self, kind: MouseEventKind, column: u16, row: u16) -> &mut Self {
+        let event = MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        self.editor.dispatch(Event::Mouse(event)).expect("mouse handling failed");
+        self
+    }
+
+    pub fn text(&self) -> String {
+        self.editor.buffer().join("\n")
+    }
+
+    pub fn assert_text(&mut self, expected: &str) -> &mut Self {
+        assert_eq!(self.text(), expected);
+        self
+    }
+
+    pub fn assert_cursor(&mut self, line: usize, column: usize) -> &mut Self {
+        assert_eq!(self.editor.cursor(), (line, column), "cursor (line, column)");
+        self
+    }
+
+    pub fn wait_until(&mut self, done: impl Fn(&Editor) -> bool) -> &mut Self {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !done(&self.editor) {
+            assert!(std::time::Instant::now() < deadline, "timed out waiting for background work");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            self.editor.tick();
+        }
+        self
+    }
+
+    pub fn wait_for_message(&mut self, start: &str) -> &mut Self {
+        self.wait_until(|editor| editor.message().is_some_and(|message| message.starts_with(start)))
+    }
+
+    pub fn assert_mode(&mut self, mode: Mode) -> &mut Self {
+        assert_eq!(self.editor.mode(), mode);
+        self
+    }
+
+    pub fn status_row(&mut self) -> String {
+        let screen = self.editor.render(120, 12);
+        (0..12).map(|y| screen.row(y)).find(|row| row.contains("Line ")).expect("no status line").trim_end().to_string()
+    }
+}
+
+/// Removed with its contents when dropped, even if the test fails.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("aon-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    pub fn join(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+
+    pub fn file(&self, name: &str, contents: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+pub fn parse(script: &str) -> Vec<KeyEvent> {
+    let mut keys = Vec::new();
+    let mut rest = script;
+    while let Some(c) = rest.chars().next() {
+        let name = if c == '<' { rest[1..].find('>').map(|end| &rest[1..end + 1]) } else { None };
+        match name {
+            Some(name) => {
+                keys.push(named(name));
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                keys.push(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    keys
+}
+
+fn named(name: &str) -> KeyEvent {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut base = name;
+    while let Some((prefix, tail)) = base.split_once('-').filter(|(_, tail)| !tail.is_empty()) {
+        modifiers |= match prefix {
+            "C" => KeyModifiers::CONTROL,
+            "A" | "M" => KeyModifiers::ALT,
+            "S" => KeyModifiers::SHIFT,
+            _ => panic!("unknown modifier in <{}>", name),
+        };
+        base = tail;
+    }
+    let code = match base {
+        "Esc" => KeyCode::Esc,
+        "CR" | "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "BS" => KeyCode::Backspace,
+        "Del" => KeyCode::Delete,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Insert" => KeyCode::Insert,
+        "Space" => KeyCode::Char(' '),
+        "lt" => KeyCode::Char('<'),
+        _ if base.starts_with('F') && base.len() > 1 => KeyCode::F(base[1..].parse().expect("function key number")),
+        "gt" => KeyCode::Char('>'),
+        _ if base.chars().count() == 1 => KeyCode::Char(base.chars().next().unwrap_or(' ')),
+        _ => panic!("unknown key <{}>", name),
+    };
+    KeyEvent::new(code, modifiers)
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::Driver;
+use crossterm::event::Event;
+
+#[test]
+fn composes_hangul_syllables_from_jamo() {
+    let mut driver = Driver::new("");
+    driver.keys("ㅎㅏㄴ").assert_text("").assert_cursor(0, 0);
+    driver.keys("ㄱㅡㄹ").assert_text("한").assert_cursor(0, 3);
+    driver.keys(" ").assert_text("한글 ");
+    driver.keys("ㄷㅏㄹㄱ").assert_text("한글 ");
+    driver.keys("ㅏ").assert_text("한글 달");
+    driver.keys("ㅗㅏ.").assert_text("한글 달가ㅘ.");
+}
+
+#[test]
+fn backspace_edits_the_preedit() {
+    let mut driver = Driver::new("");
+    driver.keys("ㄱㅏㅁ<BS>ㄴ<Left>").assert_text("간").assert_cursor(0, 0);
+    driver.keys("<End>ㅇ<BS><BS>x").assert_text("x");
+}
+
+#[test]
+fn dead_keys_compose_accented_letters() {
+    let mut driver = Driver::new("");
+    driver.keys("\u{b4}e\u{a8}u\u{b8}c").assert_text("\u{e9}\u{fc}\u{e7}");
+    driver.keys("\u{b4} \u{b4}q").assert_text("\u{e9}\u{fc}\u{e7}\u{b4}\u{b4}q");
+}
+
+#[test]
+fn commits_before_other_input() {
+    let mut driver = Driver::with_config("", "keymap = vim");
+    driver.keys("iㅁㅜㄹ<Esc>").assert_text("물");
+    let mut driver = Driver::new("");
+    driver.keys("ㄴㅏ");
+    driver.editor.dispatch(Event::Paste("!".to_string())).unwrap();
+    driver.assert_text("나!");
+    let mut driver = Driver::with_config("", "compose = false");
+    driver.keys("ㅎㅏ\u{b4}e").assert_text("ㅎㅏ\u{b4}e");
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::Driver;
+use crossterm::style::Color;
+
+#[test]
+fn answers_each_match_in_turn() {
+    let mut driver = Driver::new("foo foo\nbar\nfoo foo foo");
+    driver.keys(":%s/foo/x/gc<CR>").assert_cursor(0, 0);
+    driver.keys("y").assert_text("x foo\nbar\nfoo foo foo").assert_cursor(0, 2);
+    driver.keys("n").assert_cursor(2, 0);
+    driver.keys("y").assert_text("x foo\nbar\nx foo foo").assert_cursor(2, 2);
+    driver.keys("l").assert_text("x foo\nbar\nx x foo");
+    assert_eq!(driver.editor.message(), Some("3 substitutions on 2 lines"));
+    driver.keys("<C-z>").assert_text("foo foo\nbar\nfoo foo foo");
+}
+
+#[test]
+fn all_and_quit() {
+    let mut driver = Driver::new("a1 a2\na3");
+    driver.keys(":%s/a(\\d)/<lt>\\1>/gc<CR>na");
+    driver.assert_text("a1 <2>\n<3>");
+    let mut driver = Driver::new("a1 a2\na3");
+    driver.keys(":%s/a/b/c<CR>nq").assert_text("a1 a2\na3");
+    driver.keys("x").assert_text("a1 a2\nxa3");
+    let mut driver = Driver::new("aa\naa");
+    driver.keys(":%s/a/b/c<CR>yy").assert_text("ba\nba");
+}
+
+#[test]
+fn highlights_the_current_match() {
+    let mut driver = Driver::new("one two\ttwo");
+    driver.keys(":s/two/2/gc<CR>y");
+    let screen = driver.editor.render(40, 10);
+    let bg = |x| screen.cell(x, 0).and_then(|cell| cell.style.bg);
+    assert_eq!(bg(5), None);
+    assert_eq!(bg(7), None);
+    assert_eq!(bg(8), Some(Color::AnsiValue(94)));
+    assert_eq!(bg(10), Some(Color::AnsiValue(94)));
+    assert_eq!(bg(11), None);
+    assert!((0..10).any(|y| screen.row(y).contains("Replace with 2 (y/n/a/q/l)?")));
+}
+
+#[test]
+fn reports_missing_pattern() {
+    let mut driver = Driver::new("abc");
+    driver.keys(":s/z/y/c<CR>");
+    assert_eq!(driver.editor.message(), Some("Pattern not found: z"));
+    driver.keys("x").assert_text("xabc");
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::Driver;
+use crossterm::style::Color;
+
+const MERGED: &str = "fn main() {\n<<<<<<< HEAD\n    let x = 1;\n=======\n    let x = 2;\n>>>>>>> feature\n    run(x);\n<<<<<<< HEAD\n    done();\n||||||| base\n    finish();\n=======\n    exit();\n>>>>>>> feature\n}";
+
+#[test]
+fn jumps_between_conflicts() {
+    let mut driver = Driver::new(MERGED);
+    driver.keys("<F7>").assert_cursor(1, 0);
+    assert_eq!(driver.editor.message(), Some("Conflict 1 of 2"));
+    driver.keys("<F7>").assert_cursor(7, 0);
+    driver.keys("<F7>").assert_cursor(1, 0);
+    driver.keys("<S-F7>").assert_cursor(7, 0);
+}
+
+#[test]
+fn keeps_ours_theirs_or_both() {
+    let mut driver = Driver::new(MERGED);
+    driver.keys("<Down><Down>:resolve theirs<CR>");
+    driver.assert_text("fn main() {\n    let x = 2;\n    run(x);\n<<<<<<< HEAD\n    done();\n||||||| base\n    finish();\n=======\n    exit();\n>>>>>>> feature\n}");
+    driver.keys("<F7>:resolve both<CR>");
+    driver.assert_text("fn main() {\n    let x = 2;\n    run(x);\n    done();\n    exit();\n}");
+    driver.keys("<C-z><F7>:resolve ours<CR>");
+    driver.assert_text("fn main() {\n    let x = 2;\n    run(x);\n    done();\n}");
+    driver.keys(":resolve ours<CR>");
+    assert_eq!(driver.editor.message(), Some("The cursor is not inside a merge conflict"));
+}
+
+#[test]
+fn vim_brackets_move_between_conflicts() {
+    let mut driver = Driver::with_config(MERGED, "keymap = vim");
+    driver.keys("]n").assert_cursor(1, 0);
+    driver.keys("]n").assert_cursor(7, 0);
+    driver.keys("[n").assert_cursor(1, 0);
+}
+
+#[test]
+fn tints_each_side() {
+    let mut driver = Driver::new(MERGED);
+    let screen = driver.editor.render(40, 20);
+    let bg = |y| screen.cell(10, y).and_then(|cell| cell.style.bg);
+    assert_eq!(bg(0), None);
+    assert_eq!(bg(1), Some(Color::AnsiValue(239)));
+    assert_eq!(bg(2), Some(Color::AnsiValue(22)));
+    assert_eq!(bg(4), Some(Color::AnsiValue(24)));
+    assert_eq!(bg(10), Some(Color::AnsiValue(58)));
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use aon::config::Config;
+use common::{Driver, TempDir};
+use std::fs;
+
+const GPG: &str = r#"read -r pass
+for arg; do last=$arg; done
+case " $* " in
+*" --decrypt "*)
+    read -r stored < "$last"
+    [ "$stored" = "$pass" ] || { echo "gpg: decryption failed: Bad session key" >&2; exit 2; }
+    tail -n +2 "$last" ;;
+*) { echo "$pass"; cat; } > "$last" ;;
+esac
+"#;
+
+fn prompt(driver: &mut Driver) -> String {
+    let screen = driver.editor.render(120, 12);
+    (0..12).map(|y| screen.row(y)).find(|row| row.starts_with("Passphrase")).unwrap_or_default().trim_end().to_string()
+}
+
+#[cfg(unix)]
+#[test]
+fn decrypts_on_open_and_encrypts_on_save() {
+    let dir = TempDir::new("crypt");
+    dir.file("gpg.sh", GPG);
+    let path = dir.file("notes.md.gpg", "hunter2\n# Secrets\nkeep out");
+
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.editor.apply_config(&Config::parse(&format!("gpg = sh {}", dir.join("gpg.sh").display())));
+    assert!(driver.status_row().ends_with("| markdown | locked"), "{}", driver.status_row());
+    driver.keys("wrong");
+    assert_eq!(prompt(&mut driver), "Passphrase: *****");
+    driver.keys("<CR>");
+    assert!(driver.editor.message().unwrap().ends_with("Bad session key"));
+    driver.assert_text("");
+
+    driver.keys("hunter2<CR>").assert_text("# Secrets\nkeep out");
+    assert!(driver.status_row().ends_with("| markdown | Secrets | encrypted"), "{}", driver.status_row());
+    driver.keys("<Down><End>!:w<CR>");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hunter2\n# Secrets\nkeep out!");
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn registers_yanked_from_encrypted_buffers_stay_out_of_the_session() {
+    let dir = TempDir::new("crypt-session");
+    dir.file("gpg.sh", GPG);
+    let path = dir.file("notes.gpg", "hunter2\nkeep out");
+    let plain = dir.file("plain.txt", "public");
+    let state = dir.join("state");
+
+    let mut driver = Driver::open(path.to_str().unwrap());
+    let config = format!("gpg = sh {}\nkeymap = vim", dir.join("gpg.sh").display());
+    driver.editor.apply_config(&Config::parse(&config));
+    driver.editor.load_session(state.clone());
+    driver.keys("hunter2<CR>").assert_text("keep out");
+    driver.keys("\"ayy\"Ayy");
+    driver.keys(&format!(":e {}<CR>\"byy", plain.display())).assert_text("public");
+    driver.editor.save_session().unwrap();
+    let saved = fs::read_to_string(&state).unwrap();
+    assert!(saved.contains("public"), "{}", saved);
+    assert!(!saved.contains("keep out"), "{}", saved);
+}
+
+#[cfg(unix)]
+#[test]
+fn history_typed_in_encrypted_buffers_stays_out_of_the_session() {
+    let dir = TempDir::new("crypt-history");
+    dir.file("gpg.sh", GPG);
+    let path = dir.file("notes.gpg", "hunter2\nkeep out");
+    let plain = dir.file("plain.txt", "public");
+    let state = dir.join("state");
+
+    let mut driver = Driver::open(path.to_str().unwrap());
+    let config = format!("gpg = sh {}\nkeymap = vim", dir.join("gpg.sh").display());
+    driver.editor.apply_config(&Config::parse(&config));
+    driver.editor.load_session(state.clone());
+    driver.keys("hunter2<CR>/out<CR>:s/keep/hide/<CR>").assert_text("hide out");
+    driver.keys(&format!(":e {}<CR>/pub<CR>:set number<CR>", plain.display())).assert_text("public");
+    driver.editor.save_session().unwrap();
+    let saved = fs::read_to_string(&state).unwrap();
+    assert!(saved.contains("set number") && saved.contains("pub"), "{}", saved);
+    assert!(!saved.contains("out") && !saved.contains("keep"), "{}", saved);
+}
+
+#[test]
+fn refuses_to_save_while_locked() {
+    let dir = TempDir::new("crypt-locked");
+    let path = dir.file("notes.age", "ciphertext");
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.keys("<Esc>text").assert_text("");
+    driver.keys(":w<CR>");
+    assert!(driver.editor.message().unwrap().ends_with("no passphrase or identity is set for this buffer"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "ciphertext");
+}
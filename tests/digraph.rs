@@ -0,0 +1,44 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn inserts_characters_by_codepoint() {
+    let mut driver = Driver::new("");
+    driver.keys("<C-q>u00e7<C-q>U0001F600<C-q>x41").assert_text("\u{e7}\u{1f600}A").assert_cursor(0, 7);
+    driver.keys("<C-q>u3b1<CR><C-q><Tab>").assert_text("\u{e7}\u{1f600}A\u{3b1}\t");
+}
+
+#[test]
+fn inserts_digraphs_in_either_order() {
+    let mut driver = Driver::new("");
+    driver.keys("<C-q>e'<C-q>:a<C-q>Eu<C-q>ss<C-q>c,<C-q>l*").assert_text("\u{e9}\u{e4}\u{20ac}\u{df}\u{e7}\u{3bb}");
+}
+
+#[test]
+fn bypasses_autopair_and_cancels() {
+    let mut driver = Driver::new("");
+    driver.keys("<C-q>x28<C-q>u0<Esc>x").assert_text("(x");
+    driver.keys("<C-q>qq").assert_text("(x");
+    assert_eq!(driver.editor.message(), Some("No character for qq"));
+}
+
+#[test]
+fn vim_insert_mode_uses_ctrl_k() {
+    let mut driver = Driver::with_config("", "keymap = vim");
+    driver.keys("i<C-k>o:<C-k>u00f1<Esc>").assert_text("\u{f6}\u{f1}");
+}
+
+#[test]
+fn describes_the_character_under_the_cursor() {
+    let mut driver = Driver::new("caf\u{e9}\tx");
+    driver.keys(":ascii<CR>");
+    assert_eq!(driver.editor.message(), Some("<c> 99, Hex 0063, Oct 143"));
+    let mut driver = Driver::with_config("caf\u{e9}\te\u{301}", "keymap = vim");
+    driver.keys("3lga");
+    assert_eq!(driver.editor.message(), Some("<\u{e9}> 233, Hex 00e9, Oct 351, Digraph e'"));
+    driver.keys("lga");
+    assert_eq!(driver.editor.message(), Some("<^I> 9, Hex 0009, Oct 11"));
+    driver.keys("lga");
+    assert_eq!(driver.editor.message(), Some("<e> 101, Hex 0065, Oct 145; <\u{301}> 769, Hex 0301, Oct 1401"));
+}
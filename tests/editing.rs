@@ -0,0 +1,186 @@
+mod common;
+
+use aon::Mode;
+use common::Driver;
+
+#[test]
+fn typing_inserts_at_cursor() {
+    Driver::new("").keys("hello").assert_text("hello").assert_cursor(0, 5);
+    Driver::new("world").keys("hello ").assert_text("hello world").assert_cursor(0, 6);
+}
+
+#[test]
+fn typing_an_opening_bracket_inserts_its_pair() {
+    Driver::new("").keys("f(x").assert_text("f(x)").assert_cursor(0, 3);
+    Driver::new("").keys("[\"").assert_text("[\"\"]").assert_cursor(0, 2);
+}
+
+#[test]
+fn enter_splits_the_line() {
+    Driver::new("abcd")
+        .keys("<Right><Right><CR>")
+        .assert_text("ab\ncd")
+        .assert_cursor(1, 0);
+    Driver::new("abc").keys("<End><CR>").assert_text("abc\n").assert_cursor(1, 0);
+    Driver::new("abc").keys("<CR>").assert_text("\nabc").assert_cursor(1, 0);
+}
+
+#[test]
+fn backspace_at_buffer_start_does_nothing() {
+    let mut driver = Driver::new("abc");
+    driver.keys("<BS>").assert_text("abc").assert_cursor(0, 0);
+    assert!(!driver.editor.is_dirty());
+}
+
+#[test]
+fn backspace_at_line_start_joins_lines() {
+    Driver::new("ab\ncd")
+        .keys("<Down><BS>")
+        .assert_text("abcd")
+        .assert_cursor(0, 2);
+}
+
+#[test]
+fn backspace_removes_the_previous_character() {
+    Driver::new("abc").keys("<End><BS><BS>").assert_text("a").assert_cursor(0, 1);
+}
+
+#[test]
+fn undo_reverts_one_edit_at_a_time() {
+    let mut driver = Driver::new("");
+    driver.keys("ab<CR>c");
+    driver.keys("<C-z>").assert_text("ab\n").assert_cursor(1, 0);
+    driver.keys("<C-z>").assert_text("ab").assert_cursor(0, 2);
+    driver.keys("<C-z><C-z>").assert_text("");
+}
+
+#[test]
+fn undo_with_empty_history_does_nothing() {
+    Driver::new("abc").keys("<C-z><C-z>").assert_text("abc").assert_cursor(0, 0);
+}
+
+#[test]
+fn undo_restores_joined_lines() {
+    Driver::new("ab\ncd")
+        .keys("<Down><BS><C-z>")
+        .assert_text("ab\ncd")
+        .assert_cursor(1, 0);
+}
+
+#[test]
+fn overwrite_replaces_characters_and_appends_at_line_end() {
+    Driver::new("abc")
+        .keys("<Insert>xyzw")
+        .assert_text("xyzw")
+        .assert_cursor(0, 4);
+}
+
+#[test]
+fn home_and_end_move_within_the_line() {
+    Driver::new("hello")
+        .keys("<End>")
+        .assert_cursor(0, 5)
+        .keys("<Home>")
+        .assert_cursor(0, 0);
+}
+
+#[test]
+fn escape_quits_in_the_default_keymap() {
+    let mut driver = Driver::new("abc");
+    driver.keys("<Esc>");
+    assert!(driver.quit);
+}
+
+#[test]
+fn kill_line_joins_at_line_end() {
+    Driver::with_config("ab\ncd", "keymap = emacs")
+        .keys("<C-e><C-k>")
+        .assert_text("abcd")
+        .keys("<C-a><C-k>")
+        .assert_text("")
+        .keys("<C-y>")
+        .assert_text("abcd");
+}
+
+#[test]
+fn substitute_is_a_single_undo_step() {
+    Driver::new("foo foo\nbar foo")
+        .keys(":%s/foo/x/g<CR>")
+        .assert_text("x x\nbar x")
+        .keys("<C-z>")
+        .assert_text("foo foo\nbar foo");
+}
+
+#[test]
+fn global_delete_is_a_single_undo_step() {
+    Driver::new("a1\nb\na2\nc")
+        .keys(":g/a/d<CR>")
+        .assert_text("b\nc")
+        .keys("<C-z>")
+        .assert_text("a1\nb\na2\nc");
+}
+
+#[test]
+fn global_skips_marked_lines_an_earlier_command_deleted() {
+    Driver::new("a\na\nb\nc").keys(":g/a/.,+1d<CR>").assert_text("b\nc");
+    Driver::new("a\nx\na\ny").keys(":g/a/.,+1d<CR>").assert_text("");
+    Driver::new("a\na").keys(":g/a/d<CR>").assert_text("");
+    Driver::new("a\na\nb").keys(":g/a/s/a/x/<CR>").assert_text("x\nx\nb");
+}
+
+#[test]
+fn vim_delete_line_and_undo() {
+    Driver::with_config("one\ntwo\nthree", "keymap = vim")
+        .assert_mode(Mode::Normal)
+        .keys("jdd")
+        .assert_text("one\nthree")
+        .assert_cursor(1, 0)
+        .keys("u")
+        .assert_text("one\ntwo\nthree");
+}
+
+#[test]
+fn vim_delete_last_line_moves_cursor_up() {
+    Driver::with_config("one\ntwo", "keymap = vim")
+        .keys("Gdd")
+        .assert_text("one")
+        .assert_cursor(0, 0);
+}
+
+#[test]
+fn vim_counted_delete_stops_at_buffer_end() {
+    Driver::with_config("a\nb\nc", "keymap = vim")
+        .keys("j5dd")
+        .assert_text("a")
+        .assert_cursor(0, 0);
+}
+
+#[test]
+fn vim_insert_and_escape_clamps_cursor() {
+    Driver::with_config("ab", "keymap = vim")
+        .keys("Acd<Esc>")
+        .assert_text("abcd")
+        .assert_mode(Mode::Normal)
+        .assert_cursor(0, 3);
+}
+
+#[test]
+fn vim_delete_char_on_empty_line_does_nothing() {
+    let mut driver = Driver::with_config("", "keymap = vim");
+    driver.keys("x").assert_text("");
+    assert!(!driver.editor.is_dirty());
+}
+
+#[test]
+fn ctrl_home_and_end_jump_to_buffer_edges() {
+    Driver::new("one\ntwo\nthree")
+        .keys("<Down><C-End>")
+        .assert_cursor(2, 5)
+        .keys("<C-Home>")
+        .assert_cursor(0, 0);
+    Driver::with_config("one\ntwo", "keymap = emacs")
+        .keys("<A-gt>")
+        .assert_cursor(1, 3)
+        .keys("<A-lt>")
+        .assert_cursor(0, 0);
+}
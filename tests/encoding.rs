@@ -0,0 +1,83 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+
+#[test]
+fn keeps_invalid_bytes_when_saving() {
+    let dir = TempDir::new("latin1");
+    let original = b"caf\xe9 ol\xe9\nsecond line\nna\xefve \xc3(";
+    let path = dir.file("latin1.txt", original);
+    let mut driver = Driver::open(path.to_str().unwrap());
+    let screen = driver.editor.render(40, 10);
+    assert_eq!(screen.row(0).trim_end(), "caf\u{fffd} ol\u{fffd}");
+    assert_eq!(screen.row(2).trim_end(), "na\u{fffd}ve \u{fffd}(");
+    driver.keys("<Down><End>!:w<CR>");
+    assert_eq!(fs::read(&path).unwrap(), b"caf\xe9 ol\xe9\nsecond line!\nna\xefve \xc3(");
+    driver.keys("<Up><Home><Right><Right><Right>:ascii<CR>");
+    assert_eq!(driver.editor.message(), Some("<e9> 233, Hex 00e9, Oct 351"));
+}
+
+#[test]
+fn keeps_invalid_bytes_when_saving_in_the_background() {
+    let dir = TempDir::new("latin1-large");
+    let path = dir.join("latin1.txt");
+    let mut original = b"caf\xe9\n".to_vec();
+    original.extend("filler line\n".repeat(100_000).bytes());
+    fs::write(&path, &original).unwrap();
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.keys("<End>!:w<CR>");
+    driver.wait_until(|editor| editor.message().is_some_and(|message| message.ends_with("written")));
+    original.splice(4..4, *b"!");
+    let saved = fs::read(&path).unwrap();
+    assert_eq!(saved.len(), original.len());
+    assert!(saved == original, "{:?}", &saved[..16]);
+}
+
+#[test]
+fn keeps_line_endings_and_the_final_newline() {
+    let dir = TempDir::new("newlines");
+    for original in ["one\ntwo\n", "one\r\ntwo\r\n", "one\r\ntwo", "one\ntwo", "one\r\ntwo\n"] {
+        let path = dir.file("lines.txt", original);
+        let mut driver = Driver::open(path.to_str().unwrap());
+        driver.keys(":w<CR>");
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+    }
+    let path = dir.file("crlf.txt", "one\r\ntwo\r\n");
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.assert_text("one\ntwo").keys("<End>!:w<CR>");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one!\r\ntwo\r\n");
+}
+
+#[test]
+fn keeps_crlf_when_saving_in_the_background() {
+    let dir = TempDir::new("crlf-large");
+    let original = "filler line\r\n".repeat(100_000);
+    let path = dir.file("crlf.txt", &original);
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.keys(":w<CR>").wait_until(|editor| editor.message().is_some_and(|message| message.ends_with("written")));
+    assert!(fs::read_to_string(&path).unwrap() == original);
+}
+
+#[test]
+fn mostly_invalid_files_still_open_as_hex() {
+    let dir = TempDir::new("noise");
+    let path = dir.file("noise.bin", b"\xff\xfe\x80\x81ab");
+    let mut driver = Driver::open(path.to_str().unwrap());
+    assert_eq!(driver.editor.contents(), b"\xff\xfe\x80\x81ab");
+    driver.keys("<Esc>");
+    let screen = driver.editor.render(80, 10);
+    assert!(screen.row(0).starts_with("00000000  ff fe 80 81 61 62"));
+}
+
+#[test]
+fn files_holding_the_escape_range_stay_read_only() {
+    let dir = TempDir::new("plane16");
+    let original = "a\u{10ff80}b\nend".as_bytes();
+    let path = dir.file("plane16.txt", original);
+    let mut driver = Driver::open(path.to_str().unwrap());
+    assert_eq!(driver.editor.message(), Some(format!("{} looks like a binary file", path.display()).as_str()));
+    driver.keys("<Down><Down><CR>x");
+    assert_eq!(driver.editor.message(), Some("Buffer is read-only"));
+    assert_eq!(fs::read(&path).unwrap(), original);
+}
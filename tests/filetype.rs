@@ -0,0 +1,57 @@
+mod common;
+
+use aon::filetype::{self, Indent};
+
+fn detect(filename: Option<&str>, text: &str) -> Option<&'static str> {
+    let lines: Vec<String> = text.lines().map(str::to_string).collect();
+    filetype::detect(filename, &lines).map(|filetype| filetype.name)
+}
+
+#[test]
+fn detects_from_extension_and_file_name() {
+    assert_eq!(detect(Some("src/main.rs"), ""), Some("rust"));
+    assert_eq!(detect(Some("notes.MD"), ""), Some("markdown"));
+    assert_eq!(detect(Some("build/Makefile"), ""), Some("make"));
+    assert_eq!(detect(Some("unknown.xyz"), ""), None);
+}
+
+#[test]
+fn detects_from_shebang() {
+    assert_eq!(detect(Some("script"), "#!/bin/bash\necho hi"), Some("sh"));
+    assert_eq!(detect(None, "#!/usr/bin/env -S python3.11 -u\n"), Some("python"));
+    assert_eq!(detect(Some("tool.rs"), "#!/usr/bin/env node"), Some("rust"));
+}
+
+#[test]
+fn modeline_overrides_everything() {
+    assert_eq!(detect(Some("a.txt"), "# vim: set ft=python :\nx = 1"), Some("python"));
+    let mut text = "#!/bin/sh\n".to_string();
+    text.push_str(&"line\n".repeat(20));
+    text.push_str("// vim: filetype=go");
+    assert_eq!(detect(Some("a.sh"), &text), Some("go"));
+    assert_eq!(detect(Some("a.sh"), "index: ft=go"), Some("sh"));
+}
+
+#[test]
+fn filetypes_carry_comment_and_indent_rules() {
+    let go = filetype::find("go").unwrap();
+    assert_eq!((go.comment, go.indent), (Some("//"), Indent::Tabs));
+    assert_eq!(filetype::find("bash").map(|filetype| filetype.name), Some("sh"));
+}
+
+#[test]
+fn filetype_command_overrides_the_buffer() {
+    let mut driver = common::Driver::new("");
+    assert!(driver.editor.filetype().is_none());
+    driver.keys(":ft rust<CR>");
+    assert_eq!(driver.editor.filetype().map(|filetype| filetype.name), Some("rust"));
+}
+
+#[test]
+fn naming_a_buffer_in_the_background_detects_its_filetype() {
+    let dir = common::TempDir::new("filetype-large");
+    let mut driver = common::Driver::new(&"let x = 1;\n".repeat(100_000));
+    driver.keys(&format!(":w {}<CR>", dir.join("large.rs").display()));
+    driver.wait_until(|editor| editor.message().is_some_and(|message| message.ends_with("written")));
+    assert_eq!(driver.editor.filetype().map(|filetype| filetype.name), Some("rust"));
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use common::Driver;
+use crossterm::style::Color;
+use std::thread;
+use std::time::Duration;
+
+fn tinted(driver: &mut Driver, x: u16, y: u16) -> Option<Color> {
+    let screen = driver.editor.render(40, 10);
+    screen.cell(x, y).and_then(|cell| cell.style.bg)
+}
+
+#[test]
+fn flashes_the_yanked_text() {
+    let mut driver = Driver::with_config("alpha beta gamma\nsecond", "keymap = vim\nyankcolor = 90\nyankflash = 50");
+    driver.keys("wyw");
+    assert_eq!(tinted(&mut driver, 5, 0), None);
+    assert_eq!(tinted(&mut driver, 6, 0), Some(Color::AnsiValue(90)));
+    assert_eq!(tinted(&mut driver, 10, 0), Some(Color::AnsiValue(90)));
+    assert_eq!(tinted(&mut driver, 11, 0), None);
+    thread::sleep(Duration::from_millis(60));
+    driver.editor.tick();
+    assert_eq!(tinted(&mut driver, 6, 0), None);
+}
+
+#[test]
+fn flashes_whole_lines_and_clears_on_the_next_key() {
+    let mut driver = Driver::with_config("alpha\nbeta\ngamma", "keymap = vim");
+    driver.keys("yj");
+    assert_eq!(tinted(&mut driver, 0, 1), Some(Color::AnsiValue(24)));
+    assert_eq!(tinted(&mut driver, 4, 0), Some(Color::AnsiValue(24)));
+    assert_eq!(tinted(&mut driver, 0, 2), None);
+    driver.keys("l");
+    assert_eq!(tinted(&mut driver, 0, 1), None);
+    let mut driver = Driver::with_config("alpha", "keymap = vim\nyankflash = 0");
+    driver.keys("yy");
+    assert_eq!(tinted(&mut driver, 0, 0), None);
+}
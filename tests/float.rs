@@ -0,0 +1,51 @@
+mod common;
+
+use aon::config::Config;
+use common::{Driver, TempDir};
+
+fn rows(driver: &mut Driver, width: u16, height: u16) -> Vec<String> {
+    let screen = driver.editor.render(width, height);
+    (0..height).map(|y| screen.row(y)).collect()
+}
+
+#[test]
+fn completion_candidates_float_above_the_status_line_until_the_next_key() {
+    let dir = TempDir::new("float");
+    dir.file("alpha.txt", "");
+    dir.file("alpine.txt", "");
+    let mut driver = Driver::new("one\ntwo");
+    driver.keys(&format!(":e {}/a<Tab>", dir.path().display()));
+    let shown = rows(&mut driver, 40, 12);
+    let top = shown.iter().position(|row| row.starts_with('┌')).expect("no panel");
+    assert_eq!(shown[top + 1].trim_end(), "│ alpha.txt  │");
+    assert_eq!(shown[top + 2].trim_end(), "│ alpine.txt │");
+    assert!(shown[top + 3].starts_with('└'));
+
+    driver.keys("l");
+    let shown = rows(&mut driver, 40, 12);
+    assert!(!shown.iter().any(|row| row.contains("alpine")), "{:#?}", shown);
+}
+
+#[cfg(unix)]
+#[test]
+fn long_diagnostics_hover_under_the_cursor_until_it_moves() {
+    let dir = TempDir::new("float-hover");
+    let path = dir.file("hover.sh", "a\nbcd\nc\nd\ne\nf");
+    let linter = "echo '%:2:2: warning: this message is far too long for the screen'";
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.editor.apply_config(&Config::parse(&format!("lint.sh = {}", linter)));
+    driver
+        .keys(":w<CR>")
+        .wait_until(|editor| editor.message().is_some_and(|message| message.contains("Lint")));
+    rows(&mut driver, 30, 12);
+
+    driver.keys("<F8>").assert_cursor(1, 1);
+    let shown = rows(&mut driver, 30, 12);
+    assert!(shown[2].contains('┌'), "{:#?}", shown);
+    assert!(shown[3].contains("│ this message is far too"), "{:#?}", shown);
+    assert!(shown[4].contains("│ long for the screen"), "{:#?}", shown);
+
+    driver.keys("l");
+    let shown = rows(&mut driver, 30, 12);
+    assert!(!shown.iter().any(|row| row.contains('┌')), "{:#?}", shown);
+}
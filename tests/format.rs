@@ -0,0 +1,41 @@
+mod common;
+
+use aon::config::Config;
+use common::{Driver, TempDir};
+
+fn formatted(name: &str, text: &str, config: &str, script: &str) -> (String, String) {
+    let dir = TempDir::new(&format!("format-{}", name));
+    let path = dir.file("code.py", text);
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.editor.apply_config(&Config::parse(config));
+    driver.keys(script);
+    let buffer = driver.text();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    (buffer, saved)
+}
+
+#[cfg(unix)]
+#[test]
+fn formats_on_write_as_one_undoable_edit() {
+    let (buffer, saved) = formatted("ok", "x=1\ny=2\n", "format.python = tr = :", ":w<CR>");
+    assert_eq!(buffer, "x:1\ny:2");
+    assert_eq!(saved, "x:1\ny:2\n");
+
+    let (buffer, _) = formatted("undo", "x=1\n", "format.python = tr = :", ":w<CR><C-z>");
+    assert_eq!(buffer, "x=1");
+}
+
+#[cfg(unix)]
+#[test]
+fn failing_formatter_leaves_the_buffer_alone() {
+    let (buffer, saved) = formatted("fail", "x=1\n", "format.python = echo broken >&2; exit 3", ":w<CR>");
+    assert_eq!(buffer, "x=1");
+    assert_eq!(saved, "x=1\n");
+}
+
+#[test]
+fn explicit_format_without_a_formatter_warns() {
+    let mut driver = Driver::new("text");
+    driver.keys(":format<CR>").assert_text("text");
+    assert!(driver.editor.message().is_some());
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::Driver;
+
+const DECOMPOSED: &str = "cafe\u{301} ok";
+
+#[test]
+fn backspace_removes_the_whole_cluster() {
+    let mut driver = Driver::new(DECOMPOSED);
+    driver.keys("<End><Left><Left><Left><Left>").assert_cursor(0, 3);
+    driver.keys("<Right>").assert_cursor(0, 6);
+    driver.keys("<BS>").assert_text("caf ok").assert_cursor(0, 3);
+    driver.keys("<End> \u{1f44d}\u{1f3fd}!<Left><BS>").assert_text("caf ok !");
+}
+
+#[test]
+fn vertical_moves_never_split_a_cluster() {
+    let mut driver = Driver::new("abcdef\ncafe\u{301}");
+    driver.keys("<End><Left><Down>").assert_cursor(1, 3);
+    driver.keys("<Up><End><Down>").assert_cursor(1, 6);
+}
+
+#[test]
+fn vim_vertical_moves_never_split_a_character_or_cluster() {
+    let mut driver = Driver::with_config("abcdef\n\u{e7}\u{e7}", "keymap = vim");
+    driver.keys("lllj").assert_cursor(1, 2);
+    driver.keys("x").assert_text("abcdef\n\u{e7}");
+    let mut driver = Driver::with_config("abcdef\ncafe\u{301}x", "keymap = vim");
+    driver.keys("llllj").assert_cursor(1, 3);
+    driver.keys("x").assert_text("abcdef\ncafx");
+    driver.keys("k").assert_cursor(0, 3);
+}
+
+#[test]
+fn vim_motions_step_over_clusters() {
+    let mut driver = Driver::with_config(DECOMPOSED, "keymap = vim");
+    driver.keys("lll").assert_cursor(0, 3);
+    driver.keys("l").assert_cursor(0, 6);
+    driver.keys("hx").assert_text("caf ok");
+    driver.keys("$").assert_cursor(0, 5);
+}
+
+#[test]
+fn renders_a_cluster_in_one_cell() {
+    let mut driver = Driver::new(DECOMPOSED);
+    driver.keys("<End>");
+    let screen = driver.editor.render(20, 6);
+    assert_eq!(screen.row(0).trim_end(), "café ok");
+    assert_eq!(screen.cursor, Some((7, 0)));
+}
+
+#[test]
+fn vim_word_objects_take_combining_marks_with_their_base() {
+    let mut driver = Driver::with_config(DECOMPOSED, "keymap = vim");
+    driver.keys("diw").assert_text(" ok");
+    let mut driver = Driver::with_config(DECOMPOSED, "keymap = vim");
+    driver.keys("llldaw").assert_text("ok");
+}
+
+#[test]
+fn overwrite_replaces_the_whole_cluster() {
+    let mut driver = Driver::new("e\u{301}z");
+    driver.keys("<Insert>x").assert_text("xz").assert_cursor(0, 1);
+}
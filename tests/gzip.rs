@@ -0,0 +1,28 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::process::Command;
+
+#[cfg(unix)]
+#[test]
+fn edits_compressed_files_in_place() {
+    let dir = TempDir::new("gzip");
+    let path = dir.join("app.log.gz");
+    let plain = dir.file("app.log", "started\nfinished");
+    assert!(Command::new("gzip").arg("-f").arg(&plain).status().unwrap().success());
+
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.assert_text("started\nfinished");
+    driver.keys("<Down><End> ok:w<CR>");
+    let output = Command::new("gzip").arg("-dc").arg(&path).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "started\nfinished ok");
+}
+
+#[test]
+fn keeps_corrupt_archives_read_only() {
+    let dir = TempDir::new("gzip-bad");
+    let path = dir.file("bad.gz", "not gzip");
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.keys("x");
+    assert_eq!(driver.editor.message(), Some("Buffer is read-only"));
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn increments_the_number_after_the_cursor() {
+    Driver::vim("width: 41px").keys("<C-a>").assert_text("width: 42px").assert_cursor(0, 8);
+    Driver::vim("x = 7").keys("5<C-x>").assert_text("x = 2");
+    Driver::vim("x = 3").keys("10<C-x>").assert_text("x = -7");
+    Driver::vim("x = -3").keys("<C-a><C-a><C-a><C-a>").assert_text("x = 1");
+}
+
+#[test]
+fn skips_numbers_before_the_cursor() {
+    Driver::vim("1 2 3").keys("2l<C-a>").assert_text("1 3 3");
+    Driver::vim("v1.0").keys("$<C-a>").assert_text("v1.1");
+    Driver::vim("no digits").keys("<C-a>").assert_text("no digits");
+}
+
+#[test]
+fn preserves_leading_zeros_and_hex_case() {
+    Driver::vim("frame_007.png").keys("<C-a>").assert_text("frame_008.png");
+    Driver::vim("id 0099").keys("<C-a>").assert_text("id 0100");
+    Driver::vim("0x0f").keys("<C-a>").assert_text("0x10");
+    Driver::vim("0xFF").keys("<C-x>").assert_text("0xFE");
+    Driver::vim("item-2").keys("<C-a>").assert_text("item-3");
+}
+
+#[test]
+fn default_keymap_bindings() {
+    Driver::new("count = 9").keys("<C-a>").assert_text("count = 10").keys("<C-x><C-x>").assert_text("count = 8");
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use aon::Editor;
+use common::{Driver, TempDir};
+use std::fs;
+
+fn setup(name: &str) -> (TempDir, String) {
+    let dir = TempDir::new(&format!("ioerror-{}", name));
+    let file = dir.file("file.txt", "one\ntwo");
+    (dir, file.to_string_lossy().into_owned())
+}
+
+#[test]
+fn failed_write_is_reported_and_keeps_the_buffer_dirty() {
+    let (_dir, file) = setup("write");
+    let mut driver = Driver::open(&file);
+    driver.keys(&format!("x:w {}/inner<CR>", file));
+    let message = driver.editor.message().unwrap().to_string();
+    assert!(message.contains("could not be saved"), "{}", message);
+    assert!(message.contains(&format!("{}/inner", file)), "{}", message);
+    assert!(driver.editor.is_dirty());
+    assert_eq!(fs::read_to_string(&file).unwrap(), "one\ntwo");
+}
+
+#[test]
+fn unreadable_file_is_reported_and_opened_read_only() {
+    let (_dir, file) = setup("read");
+    let missing = format!("{}/inner", file);
+    let mut driver = Driver::open(&missing);
+    let message = driver.editor.message().unwrap().to_string();
+    assert!(message.contains("could not be read"), "{}", message);
+    driver.keys("abc").assert_text("");
+}
+
+#[test]
+fn missing_file_opens_as_a_new_buffer_without_an_error() {
+    let (dir, _) = setup("new");
+    let driver = Driver::open(&dir.join("new.txt").to_string_lossy());
+    assert_eq!(driver.editor.message(), None);
+}
+
+#[test]
+fn unreadable_state_file_is_reported_and_left_alone() {
+    let (dir, file) = setup("state");
+    let state = dir.join("state");
+    fs::create_dir_all(&state).unwrap();
+    let mut editor = Editor::new(Some(file));
+    editor.load_session(state.clone());
+    let message = editor.message().unwrap().to_string();
+    assert!(message.contains("could not be read"), "{}", message);
+    editor.save_session().unwrap();
+    assert!(state.is_dir());
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use aon::Mode;
+use common::Driver;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+#[test]
+fn unbound_enhanced_keys_act_like_their_legacy_keys() {
+    let mut plain = Driver::new("");
+    plain.keys("a<Tab>b<CR>c");
+    let mut driver = Driver::new("");
+    driver.keys("a<C-i>b<S-CR>c").assert_text(&plain.text());
+}
+
+#[test]
+fn enhanced_keys_can_be_bound_apart_from_their_legacy_keys() {
+    let mut driver = Driver::with_config("", "map.Shift+Enter = command-mode\nmap.Ctrl+I = line-start");
+    let mut plain = Driver::new("");
+    plain.keys("ab<Tab>");
+    driver.keys("ab<Tab>").assert_text(&plain.text());
+    driver.keys("<C-i>").assert_cursor(0, 0).assert_text(&plain.text());
+    driver.keys("<CR>").assert_cursor(1, 0).assert_mode(Mode::Insert);
+    driver.keys("<S-CR>").assert_mode(Mode::Command);
+}
+
+#[test]
+fn key_releases_are_ignored() {
+    let mut driver = Driver::new("");
+    driver.keys("a");
+    let release = KeyEvent::new_with_kind(KeyCode::Char('a'), KeyModifiers::NONE, KeyEventKind::Release);
+    driver.editor.dispatch(Event::Key(release)).unwrap();
+    driver.assert_text("a");
+}
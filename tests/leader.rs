@@ -0,0 +1,34 @@
+mod common;
+
+use aon::Mode;
+use common::Driver;
+
+#[test]
+fn leader_mappings_run_after_the_leader_key() {
+    let mut driver = Driver::with_config("one\ntwo", "keymap = vim\nleader = ,\nmap.<leader>j = move-down\nmap.<leader>ff = command-mode");
+    driver.keys("<Esc>,j").assert_cursor(1, 0);
+    driver.keys(",ff").assert_mode(Mode::Command);
+}
+
+#[test]
+fn default_leader_is_backslash_and_leaves_other_keys_alone() {
+    let mut driver = Driver::with_config("", "map.<leader>w = command-mode");
+    driver.keys("a\\b").assert_text("a\\b").assert_mode(Mode::Insert);
+    driver.keys("\\w").assert_text("a\\b").assert_mode(Mode::Command);
+}
+
+#[test]
+fn map_command_expands_the_current_leader() {
+    let mut driver = Driver::new("");
+    driver.keys(":set leader=Space<CR>:map <lt>leader>q command-mode<CR>");
+    driver.keys("a b").assert_text("a b");
+    driver.keys(" q").assert_mode(Mode::Command);
+}
+
+#[test]
+fn invalid_leader_is_reported() {
+    let mut driver = Driver::new("");
+    driver.keys(":set leader=Ctrl+<CR>:map <lt>leader>w quit<CR>");
+    let message = driver.editor.message().unwrap().to_string();
+    assert!(message.contains("Invalid key"), "{}", message);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use aon::config::Config;
+use common::{Driver, TempDir};
+
+fn lint_file(name: &str, text: &str, linter: &str) -> (Driver, TempDir) {
+    let dir = TempDir::new(&format!("lint-{}", name));
+    let path = dir.file("script.sh", text);
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.editor.apply_config(&Config::parse(&format!("lint.sh = {}", linter)));
+    driver
+        .keys(":w<CR>")
+        .wait_until(|editor| editor.message().is_some_and(|message| message.contains("Lint")));
+    (driver, dir)
+}
+
+#[cfg(unix)]
+#[test]
+fn lints_after_save_and_marks_the_gutter() {
+    let linter = "echo '%:2:3: warning: unused thing'; echo '%:4:1: error: broken'";
+    let (mut driver, _dir) = lint_file("gutter", "a\nb  x\nc\nd", linter);
+    assert!(driver.editor.message().unwrap().contains('2'));
+    let screen = driver.editor.render(20, 10);
+    assert!(screen.row(1).starts_with("●b"));
+    assert!(screen.row(3).starts_with("●d"));
+    assert!(screen.row(0).starts_with(" a"));
+
+    driver.keys("<F8>").assert_cursor(1, 2);
+    assert_eq!(driver.editor.message(), Some("unused thing"));
+    driver.keys("<F8>").assert_cursor(3, 0).keys("<F8>").assert_cursor(1, 2);
+    driver.keys("<S-F8>").assert_cursor(3, 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn understands_cargo_style_reports_and_lists_them() {
+    let linter = "echo 'error[E0425]: cannot find value'; echo '  --> %:3:2'; echo 'error: could not compile'";
+    let (mut driver, _dir) = lint_file("cargo", "one\ntwo\nthree", linter);
+    assert!(driver.editor.message().unwrap().contains('1'));
+    driver.keys(":diagnostics<CR><CR>").assert_cursor(2, 1);
+    assert_eq!(driver.editor.message(), Some("cannot find value"));
+}
+
+#[cfg(unix)]
+#[test]
+fn clean_runs_clear_the_marks() {
+    let (driver, _dir) = lint_file("clean", "fine", "true");
+    assert_eq!(driver.editor.message(), Some("Lint: no problems"));
+}
+
+#[cfg(unix)]
+#[test]
+fn quotes_file_names_with_spaces() {
+    let dir = TempDir::new("lint-spaces");
+    let path = dir.file("my script.sh", "fine");
+    let mut driver = Driver::open(path.to_str().unwrap());
+    driver.editor.apply_config(&Config::parse("lint.sh = test -f %"));
+    driver
+        .keys(":w<CR>")
+        .wait_until(|editor| editor.message().is_some_and(|message| message.contains("Lint")));
+    assert_eq!(driver.editor.message(), Some("Lint: no problems"));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use common::Driver;
+
+fn minified() -> String {
+    (0..200_000).map(|i| format!("{{\"k{}\":{}}},", i, i)).collect()
+}
+
+#[test]
+fn renders_only_the_visible_part_of_a_long_line() {
+    let line = minified();
+    let mut driver = Driver::new(&format!("\tx\n{}\nend", line));
+    driver.keys("<Down><End>");
+    let screen = driver.editor.render(40, 10);
+    assert_eq!(screen.row(1)[..38], line[line.len() - 38..]);
+    driver.keys("<Home><Up>");
+    let screen = driver.editor.render(40, 10);
+    assert_eq!(screen.row(0).trim_end(), "    x");
+    assert_eq!(screen.row(1)[..39], line[..39]);
+}
+
+#[test]
+fn wraps_a_long_line_only_as_far_as_the_screen() {
+    let line = minified();
+    let mut driver = Driver::with_config(&format!("{}\nend", line), "wrap = true");
+    let screen = driver.editor.render(40, 10);
+    for y in 0..5 {
+        assert_eq!(screen.row(y)[..39], line[y as usize * 39..(y as usize + 1) * 39]);
+    }
+    driver.keys("xyz").assert_cursor(0, 3);
+    assert!(driver.text().starts_with("xyz{\"k0\":0}"));
+}
+
+#[test]
+fn substitutes_across_a_long_line_without_running_out_of_stack() {
+    let line = minified();
+    let mut driver = Driver::new(&format!("{}\nend", line));
+    driver.keys(":s/,/;/g<CR>");
+    assert_eq!(driver.text(), format!("{}\nend", line.replace(',', ";")));
+    driver.keys(":s/.*/x/<CR>").assert_text("x\nend");
+}
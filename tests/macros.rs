@@ -0,0 +1,51 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+
+#[test]
+fn records_and_replays_into_a_register() {
+    let mut driver = Driver::with_config("one\ntwo\nthree\nfour", "keymap = vim");
+    driver.keys("qaA!<Esc>jq").assert_text("one!\ntwo\nthree\nfour");
+    assert_eq!(driver.editor.message(), Some("Recorded macro @a"));
+    driver.keys("@a").assert_text("one!\ntwo!\nthree\nfour");
+    driver.keys("@@").assert_text("one!\ntwo!\nthree!\nfour");
+    driver.keys(":@a<CR>").assert_text("one!\ntwo!\nthree!\nfour!");
+}
+
+#[test]
+fn counts_repeat_and_recursion_stops() {
+    let mut driver = Driver::with_config("a\nb\nc\nd", "keymap = vim");
+    driver.keys("qxI-<Esc>jq2@x").assert_text("-a\n-b\n-c\nd");
+    driver.keys("ggqyx@yq@y");
+    assert_eq!(driver.editor.message(), Some("Macro nesting is too deep"));
+}
+
+#[test]
+fn saved_macros_persist_across_sessions() {
+    let dir = TempDir::new("macros");
+    let path = dir.join("macros");
+    let mut driver = Driver::with_config("foo bar", "keymap = vim");
+    driver.editor.load_macros(path.clone());
+    driver.keys("qqcwbaz<Esc>wq:macrosave refactor<CR>");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "refactor = c w b a z Esc w\n");
+    driver.keys(":macros<CR>");
+    assert!(driver.text().contains("*@refactor"));
+
+    let mut driver = Driver::with_config("one two three", "keymap = vim");
+    driver.editor.load_macros(path.clone());
+    driver.keys(":@refactor<CR>").assert_text("baz two three");
+    driver.keys(":macrodelete refactor<CR>");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "");
+    driver.keys(":@refactor<CR>");
+    assert_eq!(driver.editor.message(), Some("No macro named @refactor"));
+}
+
+#[test]
+fn function_keys_record_in_other_presets() {
+    let mut driver = Driver::new("x");
+    driver.keys("<F6><End>yz<F6>");
+    driver.keys("<S-F6><S-F6>").assert_text("xyzyzyz");
+    driver.keys(":macrosave bad.name<CR>");
+    assert_eq!(driver.editor.message(), Some("Invalid macro name: bad.name"));
+}
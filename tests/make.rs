@@ -0,0 +1,34 @@
+mod common;
+
+use common::Driver;
+
+#[cfg(unix)]
+#[test]
+fn streams_build_output_into_a_pane() {
+    let config = "make = echo compiling; echo 'src/lib.rs:3:7: error: oops' >&2; exit 2";
+    let mut driver = Driver::with_config("fn main() {}", config);
+    driver.keys(":make<CR>");
+    driver.wait_for_message("Build");
+    assert_eq!(driver.editor.message(), Some("Build failed with exit 2, 1 location(s), :diagnostics to list"));
+
+    let screen = driver.editor.render(120, 15);
+    let rows: Vec<String> = (0..15).map(|y| screen.row(y)).collect();
+    let title = rows.iter().position(|row| row.contains("(exit 2)")).expect("pane title");
+    assert!(rows[title + 1..].iter().any(|row| row.starts_with("compiling")));
+    assert!(rows[title + 1..].iter().any(|row| row.starts_with("src/lib.rs:3:7: error: oops")));
+
+    driver.keys(":output<CR>");
+    let screen = driver.editor.render(120, 15);
+    assert!((0..15).all(|y| !screen.row(y).contains("(exit 2)")));
+}
+
+#[cfg(unix)]
+#[test]
+fn passes_arguments_and_reports_success() {
+    let mut driver = Driver::with_config("", "make = echo");
+    driver.keys(":make hello world<CR>");
+    driver.wait_for_message("Build");
+    assert_eq!(driver.editor.message(), Some("Build finished, 0 location(s) found"));
+    let screen = driver.editor.render(120, 15);
+    assert!((0..15).any(|y| screen.row(y).starts_with("hello world")));
+}
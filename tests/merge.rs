@@ -0,0 +1,49 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+
+const MERGED: &str = "a\n<<<<<<< HEAD\nlocal\n=======\nremote\n>>>>>>> topic\nz";
+
+fn start(name: &str) -> (TempDir, Driver) {
+    let dir = TempDir::new(&format!("merge-{}", name));
+    dir.file("LOCAL", "a\nlocal\nz\n");
+    dir.file("BASE", "a\nbase\nz\n");
+    dir.file("REMOTE", "a\nremote\nz\n");
+    dir.file("MERGED", MERGED);
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+    let mut driver = Driver::open(&path("MERGED"));
+    driver.editor.start_merge(&path("LOCAL"), &path("BASE"), &path("REMOTE")).unwrap();
+    (dir, driver)
+}
+
+#[test]
+fn shows_three_versions_above_the_merged_buffer() {
+    let (_dir, mut driver) = start("panes");
+    driver.assert_cursor(1, 0);
+    let screen = driver.editor.render(62, 24);
+    assert!(screen.row(0).starts_with(" Local: "), "{}", screen.row(0));
+    assert!(screen.row(0).contains("│ Base: "));
+    assert!(screen.row(0).contains("│ Remote: "));
+    assert_eq!(screen.row(2), format!("{:<20}│{:<20}│{:<20}", "local", "base", "remote"));
+    assert_eq!(screen.row(8), format!("{:<62}", "a"));
+}
+
+#[test]
+fn pulls_a_version_into_the_conflict() {
+    let (dir, mut driver) = start("pull");
+    driver.keys(":pull base<CR>").assert_text("a\nbase\nz");
+    driver.keys("<C-z>:pull remote<CR>").assert_text("a\nremote\nz");
+    driver.keys("<C-z><Down><Down>:pull local<CR>").assert_text("a\nlocal\nz");
+    driver.keys(":pull local<CR>");
+    assert_eq!(driver.editor.message(), Some("The cursor is not inside a merge conflict"));
+    driver.keys(":w<CR>");
+    assert_eq!(fs::read_to_string(dir.join("MERGED")).unwrap(), "a\nlocal\nz");
+}
+
+#[test]
+fn pull_needs_merge_mode() {
+    let mut driver = Driver::new(MERGED);
+    driver.keys("<Down>:pull base<CR>").assert_text(MERGED);
+    assert_eq!(driver.editor.message(), Some("Not in merge mode (start with aon --merge)"));
+}
@@ -0,0 +1,43 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+
+fn python(script: &str) -> Option<String> {
+    let output = Command::new("python3").arg("-c").arg(script).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[test]
+fn restores_the_mode_after_the_file_was_replaced() {
+    let dir = TempDir::new("meta");
+    let path = dir.file("script.sh", "#!/bin/sh\necho hi");
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o750)).unwrap();
+
+    let mut driver = Driver::open(path.to_str().unwrap());
+    fs::remove_file(&path).unwrap();
+    fs::write(&path, "replaced").unwrap();
+    assert_ne!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o750);
+    driver.keys("<Down><End> there:w<CR>");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "#!/bin/sh\necho hi there");
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o750);
+}
+
+#[test]
+fn restores_extended_attributes() {
+    let dir = TempDir::new("meta-xattr");
+    let path = dir.file("text.txt", "text");
+    let set = format!("import os; os.setxattr({:?}, 'user.origin', b'download')", path.display().to_string());
+    if python(&set).is_none() {
+        return;
+    }
+    let mut driver = Driver::open(path.to_str().unwrap());
+    fs::remove_file(&path).unwrap();
+    driver.keys("more :w<CR>");
+    let get = format!("import os; print(os.getxattr({:?}, 'user.origin').decode())", path.display().to_string());
+    assert_eq!(python(&get).as_deref(), Some("download"));
+}
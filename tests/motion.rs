@@ -0,0 +1,51 @@
+mod common;
+
+use common::Driver;
+
+const PROSE: &str = "one\ntwo\n\nthree\nfour\n\n\nfive";
+
+const CODE: &str = "fn main() {\n    let a = 1;\n    if a {\n        go();\n\n        stop();\n    }\n    done();\n}";
+
+#[test]
+fn paragraph_motions_stop_at_blank_lines() {
+    let mut driver = Driver::with_config(PROSE, "keymap = vim");
+    driver.keys("}").assert_cursor(2, 0);
+    driver.keys("}").assert_cursor(5, 0);
+    driver.keys("}").assert_cursor(7, 3);
+    driver.keys("{").assert_cursor(6, 0);
+    driver.keys("2{").assert_cursor(0, 0);
+}
+
+#[test]
+fn deleting_to_the_next_paragraph() {
+    Driver::with_config(PROSE, "keymap = vim")
+        .keys("jd}")
+        .assert_text("one\n\nthree\nfour\n\n\nfive");
+}
+
+#[test]
+fn paragraph_actions_in_the_default_keymap() {
+    Driver::new(PROSE)
+        .keys("<C-Down><C-Down>")
+        .assert_cursor(5, 0)
+        .keys("<C-Up>")
+        .assert_cursor(2, 0);
+}
+
+#[test]
+fn block_motions_follow_indentation() {
+    let mut driver = Driver::with_config(CODE, "keymap = vim");
+    driver.keys("3G").assert_cursor(2, 4);
+    driver.keys("]b").assert_cursor(7, 4);
+    driver.keys("[b").assert_cursor(1, 4);
+    driver.keys("[b").assert_cursor(0, 0);
+    driver.keys("4G]b").assert_cursor(5, 8);
+    driver.keys("]b").assert_cursor(6, 4);
+}
+
+#[test]
+fn deleting_an_indented_block_is_linewise() {
+    Driver::with_config(CODE, "keymap = vim")
+        .keys("4Gd]b")
+        .assert_text("fn main() {\n    let a = 1;\n    if a {\n    }\n    done();\n}");
+}
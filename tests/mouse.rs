@@ -0,0 +1,139 @@
+mod common;
+
+use aon::filetype::FILETYPES;
+use aon::Mode;
+use common::Driver;
+use crossterm::event::{MouseButton, MouseEventKind};
+
+fn click(driver: &mut Driver, column: u16, row: u16) {
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), column, row);
+}
+
+fn find(driver: &mut Driver, text: &str) -> (u16, u16) {
+    let screen = driver.editor.render(40, 10);
+    (0..10)
+        .find_map(|y| Some((screen.row(y).find(text)? as u16, y)))
+        .unwrap_or_else(|| panic!("{} is not on screen", text))
+}
+
+fn top_line(driver: &mut Driver) -> usize {
+    let row = driver.editor.render(40, 15).row(0);
+    row.split_whitespace().next().unwrap().parse().unwrap()
+}
+
+fn numbered(count: usize) -> String {
+    (0..count).map(|n| n.to_string()).collect::<Vec<_>>().join("\n")
+}
+
+#[test]
+fn dragging_selects_text_for_copy() {
+    let mut driver = Driver::new("hello world\nsecond line");
+    driver.editor.render(40, 10);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 0, 0);
+    driver.mouse(MouseEventKind::Drag(MouseButton::Left), 5, 0);
+    driver.assert_cursor(0, 5);
+    let screen = driver.editor.render(40, 10);
+    let bg = |x| screen.cell(x, 0).unwrap().style.bg;
+    assert_ne!(bg(2), bg(7));
+    driver.keys("<C-c>").assert_cursor(0, 0);
+    driver.keys("<C-v>").assert_text("hellohello world\nsecond line");
+}
+
+#[test]
+fn vim_operators_apply_to_the_selection() {
+    let mut driver = Driver::with_config("hello world\nsecond line", "keymap = vim");
+    driver.editor.render(40, 10);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 6, 0);
+    driver.mouse(MouseEventKind::Drag(MouseButton::Left), 3, 1);
+    driver.keys("d").assert_text("hello ond line").assert_cursor(0, 6);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 2, 0);
+    driver.keys("x").assert_text("helo ond line");
+}
+
+#[test]
+fn other_keys_drop_the_selection() {
+    let mut driver = Driver::new("hello world");
+    driver.editor.render(40, 10);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 0, 0);
+    driver.mouse(MouseEventKind::Drag(MouseButton::Left), 5, 0);
+    driver.keys("<Esc><BS>").assert_text("hell world");
+}
+
+#[test]
+fn wheel_scrolls_the_view_without_moving_the_cursor() {
+    let mut driver = Driver::with_config(&numbered(40), "mousescroll = 5");
+    driver.editor.render(40, 15);
+    driver.mouse(MouseEventKind::ScrollDown, 0, 0);
+    driver.mouse(MouseEventKind::ScrollDown, 0, 0);
+    assert_eq!(top_line(&mut driver), 10);
+    driver.assert_cursor(0, 0);
+    driver.mouse(MouseEventKind::ScrollUp, 0, 0);
+    assert_eq!(top_line(&mut driver), 5);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 0, 2);
+    driver.assert_cursor(7, 0);
+    driver.keys("<Down>");
+    assert_eq!(top_line(&mut driver), 5);
+    driver.mouse(MouseEventKind::ScrollDown, 0, 0);
+    driver.keys("<Up>");
+    assert!(top_line(&mut driver) <= 7);
+}
+
+#[test]
+fn double_click_selects_a_word_and_triple_click_a_line() {
+    let mut driver = Driver::new("one two three\nfour");
+    driver.editor.render(40, 10);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 5, 0);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 5, 0);
+    driver.assert_cursor(0, 7);
+    driver.keys("<C-c><End><C-v>").assert_text("one two threetwo\nfour");
+    driver.editor.render(40, 10);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 1, 1);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 1, 1);
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), 1, 1);
+    driver.keys("<BS>").assert_text("one two threetwo");
+}
+
+#[test]
+fn triple_click_then_drag_selects_whole_lines_for_vim_operators() {
+    let mut driver = Driver::with_config("a\nbb\nccc\nd", "keymap = vim");
+    driver.editor.render(40, 10);
+    for _ in 0..3 {
+        driver.mouse(MouseEventKind::Down(MouseButton::Left), 1, 1);
+    }
+    driver.mouse(MouseEventKind::Drag(MouseButton::Left), 0, 2);
+    driver.keys("d").assert_text("a\nd");
+    driver.keys("p").assert_text("a\nd\nbb\nccc");
+}
+
+#[test]
+fn clicking_the_position_opens_the_command_line() {
+    let mut driver = Driver::new("a\nb\nc");
+    let (column, row) = find(&mut driver, "Line 1/3");
+    click(&mut driver, column + 2, row);
+    driver.assert_mode(Mode::Command);
+    driver.keys("3<CR>").assert_cursor(2, 0);
+}
+
+#[test]
+fn clicking_the_default_filetype_opens_a_picker() {
+    let mut driver = Driver::new("");
+    driver.keys(":filetype rust<CR>");
+    let (column, row) = find(&mut driver, "| rust");
+    click(&mut driver, column + 2, row);
+    find(&mut driver, "Filetypes");
+    driver.keys("<CR>");
+    find(&mut driver, &format!("| {}", FILETYPES[0].name));
+}
+
+#[test]
+fn clicking_the_filetype_opens_a_picker() {
+    let mut driver = Driver::with_config("", "statusline = {file} %= {filetype}");
+    driver.keys(":filetype rust<CR>");
+    let (column, row) = find(&mut driver, "rust");
+    click(&mut driver, column, row);
+    find(&mut driver, "Filetypes");
+    driver.keys("<Down><CR>");
+    find(&mut driver, FILETYPES[1].name);
+    click(&mut driver, 0, row);
+    driver.assert_mode(Mode::Insert);
+}
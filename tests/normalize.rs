@@ -0,0 +1,39 @@
+mod common;
+
+use common::Driver;
+use crossterm::event::Event;
+
+#[test]
+fn composes_typed_combining_marks() {
+    let mut driver = Driver::with_config("", "normalize = true");
+    driver.keys("cafe\u{301}!").assert_text("caf\u{e9}!").assert_cursor(0, 6);
+    let mut driver = Driver::new("");
+    driver.keys("cafe\u{301}!").assert_text("cafe\u{301}!").assert_cursor(0, 7);
+}
+
+#[test]
+fn normalizes_pasted_text() {
+    let mut driver = Driver::with_config("", "normalize = true");
+    let pasted = "A\u{30a}ngstro\u{308}m \u{3b1}\u{313}\u{301} a\u{302}\u{323} \u{1112}\u{1161}\u{11ab}";
+    driver.editor.dispatch(Event::Paste(pasted.to_string())).unwrap();
+    driver.assert_text("\u{c5}ngstr\u{f6}m \u{1f04} \u{1ead} \u{d55c}");
+}
+
+#[test]
+fn normalizes_beyond_latin_and_greek() {
+    let mut driver = Driver::with_config("", "normalize = true");
+    let pasted = "\u{418}\u{306} \u{928}\u{93c} \u{915}\u{93c} \u{304b}\u{3099} \u{5d1}\u{5bc}";
+    driver.editor.dispatch(Event::Paste(pasted.to_string())).unwrap();
+    driver.assert_text("\u{419} \u{929} \u{915}\u{93c} \u{304c} \u{5d1}\u{5bc}");
+}
+
+#[test]
+fn patterns_match_either_form() {
+    let text = "cafe\u{301} caf\u{e9} CAF\u{c9}";
+    let mut driver = Driver::with_config(text, "normalize = true");
+    driver.keys(":s/caf\u{e9}/X/g<CR>").assert_text("X X CAF\u{c9}");
+    let mut driver = Driver::with_config(text, "normalize = true\nignorecase = true");
+    driver.keys(":s/cafe\u{301}/X/g<CR>").assert_text("X X X");
+    let mut driver = Driver::new(text);
+    driver.keys(":s/caf\u{e9}/X/g<CR>").assert_text("cafe\u{301} X CAF\u{c9}");
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use common::Driver;
+
+const SOURCE: &str = "use std::fmt;\n\
+\n\
+pub struct Point {\n    x: i32,\n}\n\
+\n\
+impl fmt::Display for Point {\n    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {\n        write!(f, \"{}\", self.x)\n    }\n}\n\
+\n\
+pub(crate) async fn main() {\n    // fn not_a_symbol\n}";
+
+fn panel(driver: &mut Driver) -> Vec<String> {
+    let screen = driver.editor.render(80, 20);
+    (0..20).map(|y| screen.row(y).chars().skip(60).collect::<String>().trim_end().to_string()).collect()
+}
+
+#[test]
+fn lists_symbols_and_jumps_to_the_selection() {
+    let (mut driver, _dir) = Driver::open_temp("a.rs", SOURCE);
+    driver.keys(":outline<CR>");
+    let rows = panel(&mut driver);
+    assert_eq!(rows[0], "│ Outline");
+    assert_eq!(rows[1..5], ["│ struct Point", "│ impl fmt::Display", "│   fn fmt", "│ fn main"]);
+
+    driver.keys("<Down><Down><CR>");
+    driver.assert_cursor(7, 4);
+    driver.keys("<Down><Down><Down><Down><Down><Down>");
+    let screen = driver.editor.render(80, 20);
+    assert!(screen.cell(62, 4).unwrap().style.reverse);
+    assert!(!screen.cell(62, 3).unwrap().style.reverse);
+
+    driver.keys("<F9><F9>");
+    assert!(panel(&mut driver).iter().all(|row| !row.contains("Outline")));
+}
+
+#[test]
+fn markdown_headings_nest_by_level() {
+    let (mut driver, _dir) = Driver::open_temp("notes.md", "# Title\n## Setup\n```\n# not a heading\n```\n### Details\n## Usage");
+    driver.keys("<F9>");
+    let rows = panel(&mut driver);
+    assert_eq!(rows[1..5], ["│ # Title", "│   ## Setup", "│     ### Details", "│   ## Usage"]);
+    driver.keys("<Esc>");
+    driver.assert_cursor(0, 0);
+}
+
+#[test]
+fn picks_up_edits_while_open() {
+    let (mut driver, _dir) = Driver::open_temp("edit.md", "# Title\ntext");
+    driver.keys("<F9><Esc>");
+    assert_eq!(panel(&mut driver)[1], "│ # Title");
+    driver.keys("<End>s<CR>## Added");
+    let rows = panel(&mut driver);
+    assert_eq!(rows[1..3], ["│ # Titles", "│   ## Added"]);
+}
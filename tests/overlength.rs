@@ -0,0 +1,35 @@
+mod common;
+
+use common::Driver;
+use crossterm::style::Color;
+
+#[test]
+fn flags_text_past_textwidth() {
+    let mut driver = Driver::with_config("short\n0123456789abc", "overlength = true\ntextwidth = 10");
+    let screen = driver.editor.render(40, 10);
+    let bg = |x, y| screen.cell(x, y).and_then(|cell| cell.style.bg);
+    assert_eq!(bg(4, 0), None);
+    assert_eq!(bg(9, 1), None);
+    assert_eq!(bg(10, 1), Some(Color::AnsiValue(52)));
+    assert_eq!(bg(12, 1), Some(Color::AnsiValue(52)));
+    assert_eq!(bg(13, 1), None);
+    driver.keys("<End>+++++++");
+    let screen = driver.editor.render(40, 10);
+    assert_eq!(screen.cell(11, 0).and_then(|cell| cell.style.bg), Some(Color::AnsiValue(52)));
+}
+
+#[test]
+fn follows_wrapped_and_scrolled_rows() {
+    let line = "x".repeat(50);
+    let mut driver = Driver::with_config(&line, "overlength = true\ntextwidth = 45\nwrap = true");
+    let screen = driver.editor.render(40, 10);
+    let bg = |x, y| screen.cell(x, y).and_then(|cell| cell.style.bg);
+    assert_eq!(bg(38, 0), None);
+    assert_eq!(bg(5, 1), None);
+    assert_eq!(bg(6, 1), Some(Color::AnsiValue(52)));
+    assert_eq!(bg(10, 1), Some(Color::AnsiValue(52)));
+    assert_eq!(bg(11, 1), None);
+    let mut driver = Driver::with_config(&line, "textwidth = 45");
+    let screen = driver.editor.render(40, 10);
+    assert_eq!(screen.cell(20, 0).and_then(|cell| cell.style.bg), None);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{Driver, TempDir};
+use crossterm::event::Event;
+
+fn paste(driver: &mut Driver, text: &str) {
+    driver.editor.dispatch(Event::Paste(text.to_string())).unwrap();
+}
+
+#[test]
+fn inserts_the_whole_paste_as_one_undo_step() {
+    let mut driver = Driver::new("start end");
+    driver.keys("<Right><Right><Right><Right><Right><Right>");
+    paste(&mut driver, "f(x) {\r\n    [1, \"two\"]\r\n}\n");
+    driver.assert_text("start f(x) {\n    [1, \"two\"]\n}\nend").assert_cursor(3, 0);
+    driver.keys("<C-z>").assert_text("start end");
+}
+
+#[test]
+fn pastes_the_first_line_into_the_command_line() {
+    let mut driver = Driver::new("one");
+    driver.keys(":");
+    paste(&mut driver, "s/one/two/\nignored");
+    driver.keys("<CR>").assert_text("two");
+}
+
+#[test]
+fn readonly_buffers_ignore_pastes() {
+    let dir = TempDir::new("paste");
+    let mut driver = Driver::open(dir.path().to_str().unwrap());
+    let listing = driver.text();
+    paste(&mut driver, "more");
+    driver.assert_text(&listing);
+}
+
+#[test]
+fn paste_mode_suspends_auto_pairing() {
+    let mut driver = Driver::new("");
+    driver.keys(":set paste<CR>(x)");
+    driver.assert_text("(x)");
+    let screen = driver.editor.render(80, 10);
+    assert!((0..10).any(|y| screen.row(y).contains("| PASTE")));
+    driver.keys(":set nopaste<CR>(").assert_text("(x)()");
+    driver.keys(":set noautopair<CR>[").assert_text("(x)([)");
+}
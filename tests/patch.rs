@@ -0,0 +1,46 @@
+mod common;
+
+use common::{Driver, TempDir};
+
+fn rows(driver: &mut Driver) -> Vec<String> {
+    let screen = driver.editor.render(100, 20);
+    (0..20).map(|y| screen.row(y)).collect()
+}
+
+#[test]
+fn applies_chosen_hunks_to_files_and_buffers() {
+    let dir = TempDir::new("patch");
+    let one = dir.file("one.txt", "alpha\nbeta\ngamma\ndelta\nepsilon\nzeta\neta\ntheta\n");
+    let two = dir.file("two.txt", "first\nsecond\n");
+    let diff = format!(
+        "diff --git a/one.txt b/one.txt\n--- {one}\n+++ {one}\n@@ -1,3 +1,3 @@\n alpha\n-beta\n+BETA\n gamma\n@@ -6,3 +6,4 @@ delta\n zeta\n eta\n+eta2\n theta\n--- {two}\t2024-01-01\n+++ {two}\t2024-01-01\n@@ -1,2 +1,2 @@\n-first\n+1st\n second\n",
+        one = one.display(),
+        two = two.display()
+    );
+    dir.file("change.diff", &diff);
+
+    let mut driver = Driver::open(two.to_str().unwrap());
+    driver.keys(&format!(":patch {}<CR>", dir.join("change.diff").display()));
+    let shown = rows(&mut driver);
+    assert!(shown.iter().any(|row| row.contains("Patch: 3 hunks in 2 files")), "{:#?}", shown);
+    assert!(shown.iter().any(|row| row.contains("one.txt @@ -6,3 +6,4 +eta2")), "{:#?}", shown);
+    driver.keys("<Down><Space><CR>");
+    assert_eq!(driver.editor.message(), Some("2 hunks applied in 2 buffers"));
+    driver.assert_text("1st\nsecond");
+    driver.keys(":bn<CR>").assert_text("alpha\nBETA\ngamma\ndelta\nepsilon\nzeta\neta\ntheta");
+    assert!(driver.editor.is_dirty());
+}
+
+#[test]
+fn reports_hunks_whose_context_is_missing() {
+    let mut driver = Driver::new("--- a/notes\n+++ b/notes\n@@ -2,2 +2,2 @@\n keep\n-old\n+new");
+    driver.keys(":patch<CR><CR>");
+    assert_eq!(driver.editor.message(), Some("1 of 1 hunks did not apply (context mismatch)"));
+}
+
+#[test]
+fn needs_a_diff() {
+    let mut driver = Driver::new("just text");
+    driver.keys(":patch<CR>");
+    assert_eq!(driver.editor.message(), Some("No diff hunks found"));
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{Driver, TempDir};
+
+const SOURCE: &str = "# Title\n\nSome **bold** and `code`, a [link](http://x.y) and snake_case.\n\n- [ ] todo\n1. first\n> quoted\n\n```\nlet *x* = 1;\n```\n---";
+
+#[test]
+fn preview_renders_markdown_and_refreshes_on_save() {
+    let dir = TempDir::new("preview");
+    let path = dir.file("notes.md", SOURCE);
+    let path = path.to_str().unwrap();
+
+    let mut driver = Driver::open(path);
+    driver.keys(":preview<CR>");
+    assert_eq!(
+        driver.editor.buffer(),
+        [
+            "Title",
+            "═════",
+            "",
+            "Some bold and code, a link <http://x.y> and snake_case.",
+            "",
+            "  • ☐ todo",
+            "  1. first",
+            "│ quoted",
+            "",
+            "    let *x* = 1;",
+            "",
+            "────────────────────────────────────────",
+        ]
+    );
+
+    driver.keys(":b 1<CR>");
+    driver.keys("#");
+    driver.keys(":w<CR>:b 2<CR>");
+    assert_eq!(driver.editor.buffer()[0], "Title");
+    assert_eq!(driver.editor.buffer()[1], "─────");
+}
+
+#[test]
+fn preview_requires_a_markdown_buffer() {
+    let mut driver = Driver::new("text");
+    driver.keys(":preview<CR>");
+    assert_eq!(driver.editor.buffer(), ["text"]);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+
+fn prompt(driver: &mut Driver) -> Option<String> {
+    let screen = driver.editor.render(60, 12);
+    (0..12).map(|y| screen.row(y).trim_end().to_string()).find(|row| row.starts_with("Save") || row.starts_with("File"))
+}
+
+#[test]
+fn quitting_with_changes_asks_first() {
+    let mut driver = Driver::new("");
+    driver.keys("abc:q<CR>");
+    assert_eq!(prompt(&mut driver).as_deref(), Some("Save changes? (y/n)"));
+    driver.keys("x<Esc>");
+    assert!(!driver.quit);
+    assert_eq!(prompt(&mut driver), None);
+    driver.keys(":q<CR>N");
+    assert!(driver.quit);
+}
+
+#[test]
+fn saving_on_quit_asks_for_a_name_then_quits() {
+    let dir = TempDir::new("prompt-quit");
+    let file = dir.join("saved.txt");
+    let mut driver = Driver::new("");
+    driver.keys("abc:q<CR>y");
+    assert_eq!(prompt(&mut driver).as_deref(), Some("File name:"));
+    driver.keys(&format!("{}<CR>", file.display()));
+    assert!(driver.quit);
+    assert_eq!(fs::read_to_string(&file).unwrap().trim_end(), "abc");
+}
+
+#[test]
+fn writing_an_unnamed_buffer_asks_for_a_name() {
+    let dir = TempDir::new("prompt-write");
+    let file = dir.join("named.txt");
+    let mut driver = Driver::new("");
+    driver.keys("abc:w<CR>");
+    driver.editor.dispatch(crossterm::event::Event::Paste(format!("{}x", file.display()))).unwrap();
+    assert_eq!(prompt(&mut driver), Some(format!("File name: {}x", file.display())));
+    driver.keys("<BS><CR>");
+    assert!(!driver.quit);
+    assert_eq!(prompt(&mut driver), None);
+    assert_eq!(fs::read_to_string(&file).unwrap().trim_end(), "abc");
+    assert_eq!(driver.text(), "abc");
+}
+
+#[test]
+fn searches_take_pastes_and_cancel_on_an_empty_backspace() {
+    let mut driver = Driver::with_config("one two\ntwo", "keymap = vim");
+    driver.keys("/");
+    driver.editor.dispatch(crossterm::event::Event::Paste("tw\nignored".to_string())).unwrap();
+    driver.keys("<CR>").assert_cursor(0, 4);
+    driver.keys("/x<BS><BS>n").assert_cursor(1, 0);
+    driver.assert_mode(aon::Mode::Normal);
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn reflows_the_paragraph_under_the_cursor() {
+    Driver::with_config("one two three four five six\nseven\n\nuntouched text here", "textwidth = 10")
+        .keys(":reflow<CR>")
+        .assert_text("one two\nthree four\nfive six\nseven\n\nuntouched text here");
+}
+
+#[test]
+fn keeps_indentation_and_comment_markers() {
+    Driver::with_config("    // alpha beta gamma delta epsilon\n    // zeta", "textwidth = 25")
+        .keys(":filetype rust<CR>:reflow<CR>")
+        .assert_text("    // alpha beta gamma\n    // delta epsilon zeta");
+    Driver::with_config("  plain words wrap at the width", "textwidth = 12")
+        .keys(":reflow<CR>")
+        .assert_text("  plain\n  words wrap\n  at the\n  width");
+}
+
+#[test]
+fn joins_short_lines_with_gq() {
+    Driver::with_config("a\nb\nc\n\nd", "keymap = vim\ntextwidth = 20")
+        .keys("gq}")
+        .assert_text("a b c\n\nd")
+        .assert_cursor(1, 0);
+    Driver::with_config("# one two three", "keymap = vim\ntextwidth = 9")
+        .keys(":filetype python<CR>gqq")
+        .assert_text("# one two\n# three");
+}
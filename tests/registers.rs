@@ -0,0 +1,33 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn pastes_the_chosen_register() {
+    let mut driver = Driver::with_config("alpha\nbeta", "keymap = vim");
+    driver.keys("\"ayyj\"byy:registers<CR>");
+    let screen = driver.editor.render(60, 12);
+    let rows: Vec<String> = (0..12).map(|y| screen.row(y).to_string()).collect();
+    assert!(rows.iter().any(|row| row.contains("\"a  alpha ")));
+    assert!(rows.iter().any(|row| row.contains("#2  alpha ")));
+    driver.keys("<Down><CR>").assert_text("alpha\nbeta\nalpha");
+}
+
+#[test]
+fn truncates_long_entries_and_pastes_history() {
+    let long = "x".repeat(100);
+    let mut driver = Driver::with_config(&format!("{}\nshort", long), "keymap = vim");
+    driver.keys("yyjyiw:registers<CR>");
+    let screen = driver.editor.render(100, 12);
+    let row = (0..12).map(|y| screen.row(y).to_string()).find(|row| row.contains("#2")).unwrap();
+    assert!(row.contains(&format!("{}…", "x".repeat(60))));
+    assert!(!row.contains(&"x".repeat(61)));
+    driver.keys("<Down><Down><CR>").assert_text(&format!("{}\nshort\n{}", long, long));
+}
+
+#[test]
+fn reports_when_nothing_was_copied() {
+    let mut driver = Driver::new("text");
+    driver.keys(":registers<CR>");
+    assert_eq!(driver.editor.message(), Some("All registers are empty"));
+}
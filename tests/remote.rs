@@ -0,0 +1,117 @@
+mod common;
+
+use aon::config::Config;
+use common::{Driver, TempDir};
+use std::fs;
+
+const SCP: &str = r#"echo "$@" >> "$(dirname "$0")/log"
+for arg; do from=$to; to=$arg; done
+cp "${from#*:}" "${to#*:}" 2>/dev/null || { echo "scp: ${from#*:}: No such file or directory" >&2; exit 1; }
+"#;
+
+const PEEK: &str = r#"for arg; do from=$to; to=$arg; done
+case $to in *:*) local=$from ;; *) local=$to ;; esac
+ls -ld "$(dirname "$local")" >> "$(dirname "$0")/modes"
+cp "${from#*:}" "${to#*:}"
+"#;
+
+fn setup(name: &str) -> (TempDir, String) {
+    let dir = TempDir::new(&format!("remote-test-{}", name));
+    let config = format!("scp = sh {}", dir.file("scp.sh", SCP).display());
+    (dir, config)
+}
+
+#[cfg(unix)]
+#[test]
+fn fetches_edits_and_writes_back() {
+    let (dir, config) = setup("edit");
+    fs::write(dir.join("main.rs"), "fn main() {}").unwrap();
+    let url = format!("ssh://me@example.com{}", dir.join("main.rs").display());
+
+    let mut driver = Driver::open(&url);
+    driver.editor.apply_config(&Config::parse(&config));
+    assert!(driver.status_row().contains("| fetching"));
+    driver.wait_until(|editor| editor.buffer()[0] == "fn main() {}");
+    assert!(!driver.status_row().contains("fetching"));
+
+    driver.keys("<End> // remote:w<CR>");
+    assert_eq!(fs::read_to_string(dir.join("main.rs")).unwrap(), "fn main() {} // remote");
+    let log = fs::read_to_string(dir.join("log")).unwrap();
+    assert!(log.contains("-o ControlMaster=auto -o ControlPersist=600"), "{}", log);
+    assert!(log.lines().next().unwrap().contains(&format!(" -- me@example.com:{} ", dir.join("main.rs").display())));
+    assert!(log.lines().nth(1).unwrap().ends_with(&format!(" me@example.com:{}", dir.join("main.rs").display())));
+}
+
+#[cfg(unix)]
+#[test]
+fn hosts_never_reach_scp_as_options() {
+    let (dir, config) = setup("option");
+    let mut driver = Driver::open("ssh://-oProxyCommand=true/tmp/file");
+    driver.editor.apply_config(&Config::parse(&config));
+    driver.editor.tick();
+    assert!(!driver.status_row().contains("fetching"));
+    assert!(!dir.join("log").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn stays_read_only_while_offline() {
+    let (dir, config) = setup("down");
+    let url = format!("ssh://example.com:2222{}", dir.join("gone.txt").display());
+
+    let mut driver = Driver::open(&url);
+    driver.editor.apply_config(&Config::parse(&config));
+    driver.wait_until(|editor| editor.message().is_some_and(|message| message.contains("could not be fetched")));
+    assert!(driver.editor.message().unwrap().ends_with("No such file or directory"));
+    assert!(driver.status_row().ends_with("| offline"), "{}", driver.status_row());
+    assert!(fs::read_to_string(dir.join("log")).unwrap().contains("-P 2222"));
+
+    driver.keys("text").assert_text("");
+    driver.keys(":w<CR>");
+    assert!(driver.editor.message().unwrap().ends_with("reopen it with :e to retry"));
+
+    fs::write(dir.join("gone.txt"), "back").unwrap();
+    driver.keys(&format!(":e {}<CR>", url));
+    driver.wait_until(|editor| editor.buffer()[0] == "back");
+    assert!(!driver.status_row().contains("offline"), "{}", driver.status_row());
+}
+
+#[cfg(unix)]
+#[test]
+fn downloads_web_files_read_only() {
+    let (dir, _) = setup("web");
+    fs::write(dir.join("curl.sh"), "for arg; do url=$arg; done\ncat \"$(dirname \"$0\")/${url##*/}\"\n").unwrap();
+    fs::write(dir.join("settings.toml"), "[editor]\ntheme = \"dark\"").unwrap();
+    let config = format!("curl = sh {}", dir.join("curl.sh").display());
+
+    let mut driver = Driver::open("https://example.com/gist/settings.toml");
+    driver.editor.apply_config(&Config::parse(&config));
+    driver.wait_until(|editor| editor.buffer()[0] == "[editor]");
+    assert!(driver.status_row().contains("| toml"));
+    driver.keys("x").assert_text("[editor]\ntheme = \"dark\"");
+    driver.keys(":w<CR>");
+    assert!(driver.editor.message().unwrap().ends_with("save a copy with :w <file>"));
+
+    let copy = dir.join("copy.toml");
+    driver.keys(&format!(":w {}<CR>", copy.display()));
+    assert_eq!(fs::read_to_string(&copy).unwrap(), "[editor]\ntheme = \"dark\"");
+    driver.keys("# ").assert_text("# [editor]\ntheme = \"dark\"");
+}
+
+#[cfg(unix)]
+#[test]
+fn copies_through_a_private_directory() {
+    let (dir, _) = setup("private");
+    fs::write(dir.join("peek.sh"), PEEK).unwrap();
+    fs::write(dir.join("notes.txt"), "old").unwrap();
+    let url = format!("ssh://example.com{}", dir.join("notes.txt").display());
+
+    let mut driver = Driver::open(&url);
+    driver.editor.apply_config(&Config::parse(&format!("scp = sh {}", dir.join("peek.sh").display())));
+    driver.wait_until(|editor| editor.buffer()[0] == "old");
+    driver.keys("<End>!:w<CR>");
+    assert_eq!(fs::read_to_string(dir.join("notes.txt")).unwrap(), "old!");
+    let modes = fs::read_to_string(dir.join("modes")).unwrap();
+    assert_eq!(modes.lines().count(), 2);
+    assert!(modes.lines().all(|line| line.starts_with("drwx------")), "{}", modes);
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use aon::config::Config;
+use common::{Driver, TempDir};
+
+const SERVER: &str = r#"
+import json, re, sys
+
+def read():
+    length = None
+    while True:
+        line = sys.stdin.buffer.readline()
+        if not line:
+            sys.exit(0)
+        line = line.strip()
+        if not line:
+            break
+        name, value = line.split(b":", 1)
+        if name.lower() == b"content-length":
+            length = int(value)
+    return json.loads(sys.stdin.buffer.read(length))
+
+def send(message):
+    body = json.dumps(message).encode()
+    sys.stdout.buffer.write(b"Content-Length: %d\r\n\r\n" % len(body) + body)
+    sys.stdout.buffer.flush()
+
+def edits(text, word, new):
+    found = []
+    for y, line in enumerate(text.split("\n")):
+        for m in re.finditer(r"\b%s\b" % word, line):
+            if new == "block":
+                new = "{\n}"
+            found.append({"range": {"start": {"line": y, "character": m.start()}, "end": {"line": y, "character": m.end()}}, "newText": new})
+    return found
+
+documents = {}
+while True:
+    message = read()
+    method = message.get("method")
+    if method == "initialize":
+        send({"jsonrpc": "2.0", "id": message["id"], "result": {"capabilities": {"renameProvider": True}}})
+    elif method == "textDocument/didOpen":
+        document = message["params"]["textDocument"]
+        documents[document["uri"]] = document["text"]
+    elif method == "textDocument/rename":
+        params = message["params"]
+        uri = params["textDocument"]["uri"]
+        line = documents[uri].split("\n")[params["position"]["line"]]
+        column = params["position"]["character"]
+        word = [m.group() for m in re.finditer(r"\w+", line) if m.start() <= column <= m.end()][0]
+        send({"jsonrpc": "2.0", "id": "progress", "method": "window/workDoneProgress/create", "params": {}})
+        read()
+        other = uri.rsplit("/", 1)[0] + "/other.rs"
+        other_text = open(other[len("file://"):]).read()
+        changes = {uri: edits(documents[uri], word, params["newName"]), other: edits(other_text, word, params["newName"])}
+        send({"jsonrpc": "2.0", "id": message["id"], "result": {"changes": changes}})
+"#;
+
+fn python() -> bool {
+    std::process::Command::new("python3").arg("--version").output().is_ok()
+}
+
+fn wait_for_preview(driver: &mut Driver) -> Vec<String> {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    loop {
+        driver.editor.tick();
+        let screen = driver.editor.render(100, 20);
+        let rows: Vec<String> = (0..20).map(|y| screen.row(y)).collect();
+        if rows.iter().any(|row| row.contains("Rename:")) {
+            return rows;
+        }
+        assert!(!driver.editor.message().is_some_and(|message| message.contains("failed")), "{:?}", driver.editor.message());
+        assert!(std::time::Instant::now() < deadline, "timed out waiting for the rename preview");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn previews_and_applies_a_workspace_rename() {
+    if !python() {
+        return;
+    }
+    let dir = TempDir::new("rename");
+    dir.file("server.py", SERVER);
+    dir.file("main.rs", "fn main() {// é count\n    let count = 1;\n}");
+    dir.file("other.rs", "fn f(count: i32) -> i32 {\n    count + 1\n}");
+
+    let mut driver = Driver::open(dir.join("main.rs").to_str().unwrap());
+    let config = format!("lsp.rust = python3 {}", dir.join("server.py").display());
+    driver.editor.apply_config(&Config::parse(&config));
+    driver.keys("<Down><End><Left> + count<Home><Right><Right><Right><Right><Right><Right><Right><Right>");
+    driver.keys(":rename total<CR>");
+    let rows = wait_for_preview(&mut driver);
+    assert!(rows.iter().any(|row| row.contains("Rename: 5 edits in 2 files")), "{:#?}", rows);
+    assert!(rows.iter().any(|row| row.contains("main.rs +:1: fn main() {// é total")));
+    assert!(rows.iter().any(|row| row.contains("other.rs:2: total + 1")));
+
+    driver.keys("<CR>");
+    assert_eq!(driver.editor.message(), Some("5 replacements in 2 buffers"));
+    driver.assert_text("fn main() {// é total\n    let total = 1 + total;\n}");
+    driver.keys("<C-z>").assert_text("fn main() {// é count\n    let count = 1 + count;\n}");
+    driver.keys(":bn<CR>").assert_text("fn f(total: i32) -> i32 {\n    total + 1\n}");
+}
+
+fn wait_for_message(driver: &mut Driver, text: &str) {
+    driver.wait_until(|editor| editor.message().is_some_and(|message| message.contains(text)));
+}
+
+#[cfg(unix)]
+fn rename_setup(name: &str) -> (TempDir, Driver) {
+    let dir = TempDir::new(&format!("rename-{}", name));
+    dir.file("server.py", SERVER);
+    dir.file("main.rs", "let count = 1;");
+    dir.file("other.rs", "count");
+    let mut driver = Driver::open(dir.join("main.rs").to_str().unwrap());
+    let config = format!("lsp.rust = python3 {}", dir.join("server.py").display());
+    driver.editor.apply_config(&Config::parse(&config));
+    driver.keys("<Right><Right><Right><Right><Right>");
+    (dir, driver)
+}
+
+#[cfg(unix)]
+#[test]
+fn refuses_edits_that_span_lines() {
+    if !python() {
+        return;
+    }
+    let (_dir, mut driver) = rename_setup("lines");
+    driver.keys(":rename block<CR>");
+    wait_for_message(&mut driver, "failed");
+    assert_eq!(driver.editor.message(), Some("Rename failed: the language server sent edits across lines"));
+    driver.assert_text("let count = 1;");
+}
+
+#[cfg(unix)]
+#[test]
+fn discards_edits_for_a_buffer_changed_meanwhile() {
+    if !python() {
+        return;
+    }
+    let (_dir, mut driver) = rename_setup("stale");
+    driver.keys(":rename total<CR><Home>x");
+    wait_for_message(&mut driver, "discarded");
+    driver.assert_text("xlet count = 1;");
+}
+
+#[cfg(unix)]
+#[test]
+fn leaves_hex_buffers_out_of_the_plan() {
+    if !python() {
+        return;
+    }
+    let (dir, mut driver) = rename_setup("hex");
+    driver.keys(&format!(":e {}<CR>:hex<CR>:bn<CR>", dir.join("other.rs").display()));
+    driver.keys(":rename total<CR>");
+    let rows = wait_for_preview(&mut driver);
+    assert!(rows.iter().any(|row| row.contains("Rename: 1 edits in 1 files")), "{:#?}", rows);
+    driver.keys("<CR>").assert_text("let total = 1;");
+    assert_eq!(std::fs::read_to_string(dir.join("other.rs")).unwrap(), "count");
+}
+
+#[test]
+fn needs_a_configured_server() {
+    let mut driver = Driver::new("value");
+    driver.keys(":rename other<CR>");
+    assert_eq!(driver.editor.message(), Some("Buffer has no file name"));
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::Driver;
+
+fn tinted(driver: &mut Driver, y: u16) -> Vec<u16> {
+    let screen = driver.editor.render(40, 10);
+    (0..screen.width())
+        .filter(|&x| screen.cell(x, y).is_some_and(|cell| cell.style.bg.is_some()))
+        .collect()
+}
+
+#[test]
+fn color_columns_are_tinted_on_text_rows() {
+    let mut driver = Driver::new("hello\nworld");
+    driver.keys(":set cc=3,10,99<CR>");
+    assert_eq!(tinted(&mut driver, 0), [2, 9]);
+    assert_eq!(tinted(&mut driver, 1), [2, 9]);
+
+    driver.keys(":set nu<CR>");
+    assert_eq!(tinted(&mut driver, 0), [4, 11]);
+}
+
+#[test]
+fn cursor_line_and_column_are_tinted() {
+    let mut driver = Driver::new("abc\ndef\nghi");
+    driver.keys("<Down><Right>:set cul<CR>");
+    assert_eq!(tinted(&mut driver, 0), [] as [u16; 0]);
+    assert_eq!(tinted(&mut driver, 1), (0..39).collect::<Vec<u16>>());
+
+    driver.keys(":set nocul cuc<CR>");
+    for y in 0..3 {
+        assert_eq!(tinted(&mut driver, y), [1]);
+    }
+}
+
+fn top_line(driver: &mut Driver) -> String {
+    driver.editor.render(40, 10).row(0).split_whitespace().next().unwrap_or("").to_string()
+}
+
+#[test]
+fn scrolloff_keeps_context_around_the_cursor() {
+    let text: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+    let mut driver = Driver::new(&text.join("\n"));
+    driver.keys(":set so=2<CR>");
+    assert_eq!(top_line(&mut driver), "1");
+    driver.keys("<Down><Down>");
+    assert_eq!(top_line(&mut driver), "1");
+    driver.keys("<Down>");
+    assert_eq!(top_line(&mut driver), "2");
+    driver.keys(&"<Down>".repeat(16));
+    assert_eq!(driver.editor.cursor(), (19, 0));
+    assert_eq!(top_line(&mut driver), "16");
+    driver.keys(&"<Up>".repeat(2));
+    assert_eq!(top_line(&mut driver), "16");
+    driver.keys("<Up>");
+    assert_eq!(top_line(&mut driver), "15");
+}
+
+#[test]
+fn view_can_be_placed_around_the_cursor_line() {
+    let text: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+    let mut driver = Driver::with_config(&text.join("\n"), "keymap = vim");
+    top_line(&mut driver);
+    driver.keys("10G");
+    assert_eq!(top_line(&mut driver), "6");
+    driver.keys("zt");
+    assert_eq!(top_line(&mut driver), "10");
+    driver.keys("zb");
+    assert_eq!(top_line(&mut driver), "6");
+    driver.keys("zz");
+    assert_eq!(top_line(&mut driver), "8");
+    assert_eq!(driver.editor.cursor(), (9, 0));
+    driver.keys(":top-view<CR>");
+    assert_eq!(top_line(&mut driver), "10");
+}
+
+#[test]
+fn recenter_cycles_between_center_top_and_bottom() {
+    let text: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+    let mut driver = Driver::with_config(&text.join("\n"), "keymap = emacs");
+    top_line(&mut driver);
+    driver.keys(&"<Down>".repeat(9));
+    let tops: Vec<String> = (0..4).map(|_| top_line(driver.keys("<C-l>"))).collect();
+    assert_eq!(tops, ["8", "10", "6", "8"]);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use aon::replay;
+use common::{Driver, TempDir};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+#[test]
+fn events_round_trip_through_the_log_format() {
+    let events = [
+        Event::Key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE)),
+        Event::Key(KeyEvent::new(KeyCode::Char('+'), KeyModifiers::CONTROL)),
+        Event::Key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE)),
+        Event::Key(KeyEvent::new(KeyCode::PageDown, KeyModifiers::CONTROL | KeyModifiers::ALT)),
+        Event::Key(KeyEvent::new(KeyCode::F(5), KeyModifiers::NONE)),
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 12,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        }),
+        Event::Resize(80, 24),
+        Event::Paste(" fn \"main\"() {\r\n\tlet s = '\\\\';\u{7}é\n".to_string()),
+    ];
+    for event in events {
+        let line = replay::format(&event).expect("event is recordable");
+        assert_eq!(replay::parse(&line), Some(event), "{}", line);
+    }
+}
+
+#[test]
+fn replaying_a_log_reproduces_the_edit() {
+    let dir = TempDir::new("replay");
+    let path = dir.file("events.log", "resize 80 24\n# comment\nkey h\nkey i\nkey Enter\nkey Ctrl+Z\nkey !\n");
+    let events = replay::load(path.to_str().unwrap()).unwrap();
+
+    let mut driver = Driver::new("");
+    for event in events {
+        driver.editor.dispatch(event).unwrap();
+    }
+    driver.assert_text("hi!").assert_cursor(0, 3);
+}
+
+#[test]
+fn key_releases_are_not_recorded() {
+    let dir = TempDir::new("replay-release");
+    let path = dir.join("events.log");
+    let mut recorder = replay::Recorder::create(path.to_str().unwrap(), 80, 24).unwrap();
+    for c in ['o', 'k'] {
+        for kind in [KeyEventKind::Press, KeyEventKind::Release] {
+            recorder.record(&Event::Key(KeyEvent::new_with_kind(KeyCode::Char(c), KeyModifiers::NONE, kind))).unwrap();
+        }
+    }
+    drop(recorder);
+    let events = replay::load(path.to_str().unwrap()).unwrap();
+
+    let mut driver = Driver::new("");
+    for event in events {
+        driver.editor.dispatch(event).unwrap();
+    }
+    driver.assert_text("ok");
+}
+
+#[test]
+fn mouse_lines_parse_into_events() {
+    let scroll = MouseEvent {
+        kind: MouseEventKind::ScrollDown,
+        column: 4,
+        row: 7,
+        modifiers: KeyModifiers::NONE,
+    };
+    assert_eq!(replay::parse("mouse scroll down 4 7"), Some(Event::Mouse(scroll)));
+    let drag = MouseEvent {
+        kind: MouseEventKind::Drag(MouseButton::Right),
+        column: 30,
+        row: 2,
+        modifiers: KeyModifiers::NONE,
+    };
+    assert_eq!(replay::parse("mouse drag right 30 2"), Some(Event::Mouse(drag)));
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+    assert_eq!(replay::parse("key Hyper+Q"), None);
+    assert_eq!(replay::parse("mouse down left 3"), None);
+    assert_eq!(replay::parse("resize 80"), None);
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::Driver;
+use crossterm::event::{MouseButton, MouseEventKind};
+
+fn drag(driver: &mut Driver, from: (u16, u16), to: (u16, u16)) {
+    driver.mouse(MouseEventKind::Down(MouseButton::Left), from.0, from.1);
+    driver.mouse(MouseEventKind::Drag(MouseButton::Left), to.0, to.1);
+    driver.mouse(MouseEventKind::Up(MouseButton::Left), to.0, to.1);
+}
+
+fn separator(driver: &mut Driver, width: u16) -> usize {
+    let screen = driver.editor.render(width, 20);
+    screen.row(1).chars().position(|c| c == '│').expect("outline separator")
+}
+
+fn pane_title(driver: &mut Driver, height: u16) -> u16 {
+    let screen = driver.editor.render(60, height);
+    (0..height).find(|&y| screen.row(y).contains("true (done)")).expect("pane title")
+}
+
+#[test]
+fn outline_width_follows_keys_and_drags() {
+    let mut driver = Driver::new("one\ntwo");
+    driver.keys(":outline<CR><Esc>");
+    assert_eq!(separator(&mut driver, 80), 60);
+    driver.keys("<C-w><lt><C-w><lt>");
+    assert_eq!(separator(&mut driver, 80), 58);
+    driver.keys("<C-w>>");
+    assert_eq!(separator(&mut driver, 80), 59);
+    drag(&mut driver, (59, 1), (40, 1));
+    assert_eq!(separator(&mut driver, 80), 40);
+    assert_eq!(separator(&mut driver, 60), 30);
+    drag(&mut driver, (30, 1), (58, 1));
+    assert_eq!(separator(&mut driver, 60), 50);
+    assert_eq!(driver.text(), "one\ntwo");
+}
+
+#[cfg(unix)]
+#[test]
+fn pane_height_follows_keys_and_drags() {
+    let mut driver = Driver::with_config("", "make = true");
+    driver.keys(":make<CR>");
+    driver.wait_for_message("Build");
+    let title = pane_title(&mut driver, 24);
+    driver.keys("<C-w>+");
+    assert_eq!(pane_title(&mut driver, 24), title + 1);
+    driver.keys("<C-w>-<C-w>-");
+    assert_eq!(pane_title(&mut driver, 24), title - 1);
+    drag(&mut driver, (3, title - 1), (3, 8));
+    assert_eq!(pane_title(&mut driver, 24), 8);
+    let small = pane_title(&mut driver, 12);
+    assert!(small < 8, "{}", small);
+    drag(&mut driver, (3, small), (3, 0));
+    assert_eq!(pane_title(&mut driver, 12), 2);
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn shows_match_index_and_total() {
+    let mut driver = Driver::with_config("one foo\nfoo foo\nbar\nfoo", "keymap = vim");
+    assert!(!driver.status_row().contains("[1/"));
+    driver.keys("/foo<CR>").assert_cursor(0, 4);
+    assert!(driver.status_row().contains(" | [1/4]"), "{}", driver.status_row());
+    driver.keys("n").assert_cursor(1, 0);
+    assert!(driver.status_row().contains(" | [2/4]"));
+    driver.keys("nn").assert_cursor(3, 0);
+    assert!(driver.status_row().contains(" | [4/4]"));
+    driver.keys("n").assert_cursor(0, 4);
+    assert_eq!(driver.editor.message(), Some("Search wrapped around the buffer"));
+    assert!(driver.status_row().contains(" | [1/4]"));
+    driver.keys("N").assert_cursor(3, 0);
+    driver.keys("gg");
+    assert!(driver.status_row().contains(" | [0/4]"));
+    driver.keys(":noh<CR>");
+    assert!(!driver.status_row().contains("/4]"));
+}
+
+#[test]
+fn searches_backward_and_from_the_default_keymap() {
+    let mut driver = Driver::with_config("ab\nxab\nab", "keymap = vim");
+    driver.keys("G?a<CR>").assert_cursor(1, 1);
+    driver.keys("n").assert_cursor(0, 0);
+    driver.keys("N").assert_cursor(1, 1);
+    let mut driver = Driver::new("ab\nxab\nab");
+    driver.keys("<C-f>b<CR>").assert_cursor(0, 1);
+    driver.keys("<F3>").assert_cursor(1, 2);
+    driver.keys("<S-F3><S-F3>").assert_cursor(2, 1);
+    driver.keys("<C-f>zz<CR>");
+    assert_eq!(driver.editor.message(), Some("Pattern not found: zz"));
+}
+
+#[test]
+fn match_total_follows_edits_to_the_buffer() {
+    let mut driver = Driver::with_config("foo\nbar", "keymap = vim");
+    driver.keys("/foo<CR>");
+    assert!(driver.status_row().contains(" | [1/1]"));
+    driver.keys("jofoo foo<Esc>");
+    assert!(driver.status_row().contains(" | [3/3]"), "{}", driver.status_row());
+    driver.keys("dd");
+    assert!(driver.status_row().contains(" | [1/1]"), "{}", driver.status_row());
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use aon::config::Config;
+use aon::Editor;
+use common::{Driver, TempDir};
+use std::fs;
+use std::path::Path;
+
+fn setup(name: &str) -> (TempDir, String, String) {
+    let dir = TempDir::new(&format!("session-{}", name));
+    let first = dir.file("first.txt", "one\ntwo\nthree\nfour\n");
+    let second = dir.file("second.txt", "alpha\nbeta\n");
+    (dir, first.to_string_lossy().into_owned(), second.to_string_lossy().into_owned())
+}
+
+fn open(file: &str, state: &Path, config: &str) -> Driver {
+    let mut editor = Editor::new(Some(file.to_string()));
+    editor.apply_config(&Config::parse(config));
+    editor.load_session(state.to_path_buf());
+    Driver { editor, quit: false }
+}
+
+#[test]
+fn reopens_files_where_the_cursor_was() {
+    let (dir, first, second) = setup("restore");
+    let state = dir.join("state");
+    let mut driver = open(&first, &state, "");
+    driver.keys("<Down><Down><Right><Right>").assert_cursor(2, 2);
+    driver.keys(&format!(":e {}<CR><Down><Right>", second)).assert_cursor(1, 1);
+    driver.editor.save_session().unwrap();
+
+    open(&first, &state, "").assert_cursor(2, 2);
+    let mut driver = open(&second, &state, "");
+    driver.assert_cursor(1, 1);
+    driver.keys(&format!(":e {}<CR>", first)).assert_cursor(2, 2);
+
+    fs::write(&first, "short\n").unwrap();
+    open(&first, &state, "").assert_cursor(0, 2);
+}
+
+#[test]
+fn restorecursor_can_be_turned_off() {
+    let (dir, first, _) = setup("off");
+    let state = dir.join("state");
+    let mut driver = open(&first, &state, "");
+    driver.keys("<Down><Down>");
+    driver.editor.save_session().unwrap();
+    open(&first, &state, "restorecursor = false").assert_cursor(0, 0);
+}
+
+#[test]
+fn recalls_command_and_search_history_across_sessions() {
+    let (dir, first, _) = setup("history");
+    let state = dir.join("state");
+    let mut driver = open(&first, &state, "keymap = vim");
+    driver.keys(":set number<CR>:%s/one/uno/<CR>/thr<CR>");
+    driver.editor.save_session().unwrap();
+
+    let mut driver = open(&first, &state, "keymap = vim");
+    driver.keys(":<Up><Up><Down><CR>").assert_text("uno\ntwo\nthree\nfour");
+    driver.keys(":set <Up>");
+    let screen = driver.editor.render(40, 8);
+    assert!((0..8).any(|y| screen.row(y).contains(":set number")));
+    driver.keys("<Esc>gg/<Up><CR>").assert_cursor(2, 0);
+}
+
+#[test]
+fn keeps_named_registers_and_global_marks() {
+    let (dir, first, second) = setup("marks");
+    let state = dir.join("state");
+    let mut driver = open(&first, &state, "keymap = vim");
+    driver.keys("jj\"ayyjlmA");
+    driver.editor.save_session().unwrap();
+
+    let mut driver = open(&second, &state, "keymap = vim");
+    driver.keys("\"ap").assert_text("alpha\nthree\nbeta");
+    driver.keys("'A");
+    assert_eq!(driver.editor.filename(), Some(first.as_str()));
+    driver.assert_cursor(3, 0);
+    driver.keys(&format!(":e {}<CR>`A", second));
+    assert_eq!(driver.editor.filename(), Some(first.as_str()));
+    driver.assert_cursor(3, 1);
+    driver.keys("'B");
+    assert_eq!(driver.editor.message(), Some("Mark not set: B"));
+}
+
+#[test]
+fn merges_with_other_sessions_on_exit() {
+    let (dir, first, _) = setup("merge");
+    let state = dir.join("state");
+    let mut left = open(&first, &state, "");
+    let mut right = open(&first, &state, "");
+    left.keys(":set number<CR>");
+    right.keys(":set wrap<CR>");
+    left.editor.save_session().unwrap();
+    right.editor.save_session().unwrap();
+    let text = fs::read_to_string(&state).unwrap();
+    assert!(text.contains("command\tset wrap\ncommand\tset number\n"));
+}
+
+#[test]
+fn writes_a_private_file_without_huge_registers() {
+    let (dir, first, _) = setup("private");
+    let state = dir.join("state");
+    let big = dir.file("big.txt", "x".repeat(100_000));
+    let mut driver = open(big.to_str().unwrap(), &state, "keymap = vim");
+    driver.keys("\"byy");
+    driver.keys(&format!(":e {}<CR>\"cyy", first));
+    driver.editor.save_session().unwrap();
+    let text = fs::read_to_string(&state).unwrap();
+    assert!(text.contains("register\tc\t1\tone"));
+    assert!(!text.contains("register\tb"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(fs::metadata(&state).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::Driver;
+
+fn corner(driver: &mut Driver) -> String {
+    let screen = driver.editor.render(30, 6);
+    screen.row(3)[19..].trim_end().to_string()
+}
+
+#[test]
+fn shows_the_partially_typed_sequence() {
+    let mut driver = Driver::with_config("one two three\nfour", "keymap = vim");
+    driver.keys("<Esc>");
+    assert_eq!(corner(&mut driver), "");
+    driver.keys("d");
+    assert_eq!(corner(&mut driver), "d");
+    driver.keys("2");
+    assert_eq!(corner(&mut driver), "d2");
+    driver.keys("w").assert_text("three\nfour");
+    assert_eq!(corner(&mut driver), "");
+    driver.keys("\"a");
+    assert_eq!(corner(&mut driver), "");
+    driver.keys("12y");
+    assert_eq!(corner(&mut driver), "12y");
+    driver.keys("<Esc>");
+    assert_eq!(corner(&mut driver), "");
+}
+
+#[test]
+fn keeps_the_last_keys_of_long_counts() {
+    let mut driver = Driver::with_config("x", "keymap = vim");
+    driver.keys("<Esc>123456789012");
+    assert_eq!(corner(&mut driver), "3456789012");
+}
+
+#[test]
+fn can_be_turned_off_or_moved_into_the_status_line() {
+    let mut driver = Driver::with_config("x", "keymap = vim\nshowcmd = false\nstatusline = {mode} %= [{keys}]");
+    driver.keys("<Esc>gz");
+    driver.keys("g");
+    assert_eq!(corner(&mut driver), "");
+    let screen = driver.editor.render(30, 6);
+    assert_eq!(screen.row(2), format!("NORMAL{}[g]", " ".repeat(21)));
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use aon::Mode;
+use common::{Driver, TempDir};
+use std::fs;
+
+fn script(name: &str, text: &str) -> (TempDir, String) {
+    let dir = TempDir::new(&format!("source-{}", name));
+    let path = dir.file(&format!("{}.aon", name), text);
+    (dir, path.to_string_lossy().into_owned())
+}
+
+#[test]
+fn runs_each_line_as_an_ex_command() {
+    let (_dir, path) = script("init", "\" startup\n:set keymap=vim\n\n# aliases\ncommand Shout s/$/!/\nmap Ctrl+G search\n");
+    let mut driver = Driver::new("hello");
+    assert!(!driver.editor.source(&path).unwrap());
+    driver.assert_mode(Mode::Normal);
+    driver.keys(":Shout<CR>").assert_text("hello!");
+    driver.keys("i<C-g>l<CR><Esc>").assert_cursor(0, 2);
+}
+
+#[test]
+fn reports_errors_with_the_line_number() {
+    let (_dir, path) = script("broken", "set number\nfrobnicate\n");
+    let mut driver = Driver::new("");
+    driver.editor.source(&path).unwrap();
+    assert_eq!(driver.editor.message(), Some(format!("{}:2: Unknown command: frobnicate", path).as_str()));
+    driver.keys(":frobnicate<CR>");
+    assert_eq!(driver.editor.message(), Some("Unknown command: frobnicate"));
+}
+
+#[test]
+fn nested_sources_stop_recursing() {
+    let dir = TempDir::new("source-loop");
+    let path = dir.join("loop.aon");
+    let name = path.to_string_lossy().into_owned();
+    fs::write(&path, format!("source {}\n", name)).unwrap();
+    let mut driver = Driver::new("");
+    driver.keys(&format!(":source {}<CR>", name));
+    assert!(driver.editor.message().unwrap().ends_with("Command nesting too deep: source"));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+
+fn status(driver: &mut Driver, width: u16) -> String {
+    let screen = driver.editor.render(width, 6);
+    screen.row(2)
+}
+
+#[test]
+fn aligns_left_center_and_right_parts() {
+    let mut driver = Driver::with_config("héllo\nworld", "statusline = {mode}{modified} %= {filetype}{encoding} %= {position}");
+    assert_eq!(status(&mut driver, 30), format!("INSERT{}utf-8{}1:1", " ".repeat(6), " ".repeat(10)));
+    driver.keys("<Right><Right>x");
+    assert_eq!(status(&mut driver, 30), format!("INSERT[+]{}utf-8{}1:4", " ".repeat(3), " ".repeat(10)));
+}
+
+#[test]
+fn one_separator_splits_left_and_right() {
+    let mut driver = Driver::with_config("", "statusline = {file} %= {position} {scroll}");
+    assert_eq!(status(&mut driver, 24), format!("[No Name]{}1:1 All", " ".repeat(8)));
+}
+
+#[test]
+fn unknown_segments_are_kept_literally() {
+    let mut driver = Driver::with_config("", "statusline = [{nope}] {mode");
+    assert_eq!(status(&mut driver, 20).trim_end(), "[{nope}] {mode");
+}
+
+#[test]
+fn shows_the_git_branch_of_the_file() {
+    let dir = TempDir::new("statusline");
+    fs::create_dir_all(dir.join(".git")).unwrap();
+    fs::create_dir_all(dir.join("src")).unwrap();
+    dir.file(".git/HEAD", "ref: refs/heads/feature/bar\n");
+    let file = dir.file("src/main.rs", "fn main() {}");
+    let mut driver = Driver::open(&file.to_string_lossy());
+    driver.keys(":set statusline={branch}\\ {filetype}<CR>");
+    assert_eq!(status(&mut driver, 30).trim_end(), "feature/bar rust");
+    dir.file(".git/HEAD", "0123456789abcdef\n");
+    assert_eq!(status(&mut driver, 30).trim_end(), "0123456 rust");
+}
+
+#[test]
+fn empty_format_keeps_the_default_status_line() {
+    let mut driver = Driver::with_config("", "statusline = {mode}");
+    driver.keys(":set statusline=<CR>");
+    assert!(status(&mut driver, 60).starts_with("[SAVED]"));
+}
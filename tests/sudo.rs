@@ -0,0 +1,71 @@
+mod common;
+
+use aon::config::Config;
+use common::{Driver, TempDir};
+use std::fs;
+
+const SUDO: &str = r#"if [ "$1" = -n ]; then
+    [ -e "$(dirname "$0")/cached" ] || { echo "sudo: a password is required" >&2; exit 1; }
+    shift
+else
+    shift 4
+    read -r pass
+    [ "$pass" = secret ] || { echo "sudo: 1 incorrect password attempt" >&2; exit 1; }
+fi
+exec "$@"
+"#;
+
+fn setup(name: &str) -> (TempDir, Driver) {
+    let dir = TempDir::new(&format!("sudo-{}", name));
+    let sudo = dir.file("sudo.sh", SUDO);
+    let hosts = dir.file("hosts", "127.0.0.1 localhost");
+    let mut driver = Driver::open(hosts.to_str().unwrap());
+    driver.editor.apply_config(&Config::parse(&format!("sudo = sh {}", sudo.display())));
+    (dir, driver)
+}
+
+fn prompt(driver: &mut Driver) -> Option<String> {
+    let screen = driver.editor.render(80, 12);
+    (0..12).map(|y| screen.row(y)).find(|row| row.starts_with("[sudo]")).map(|row| row.trim_end().to_string())
+}
+
+#[cfg(unix)]
+#[test]
+fn asks_for_the_password_then_writes_through_tee() {
+    let (dir, mut driver) = setup("password");
+    driver.keys("<End> aon:w!!<CR>");
+    assert_eq!(prompt(&mut driver).as_deref(), Some("[sudo] password:"));
+    driver.keys("wrong<CR>");
+    assert!(driver.editor.message().unwrap().ends_with("1 incorrect password attempt"));
+    assert_eq!(fs::read_to_string(dir.join("hosts")).unwrap(), "127.0.0.1 localhost");
+
+    driver.keys(":w!!<CR>secret");
+    assert_eq!(prompt(&mut driver).as_deref(), Some("[sudo] password: ******"));
+    driver.keys("<CR>");
+    assert!(driver.editor.message().unwrap().contains("hosts"));
+    assert_eq!(fs::read_to_string(dir.join("hosts")).unwrap(), "127.0.0.1 localhost aon");
+    assert!(!driver.editor.is_dirty());
+}
+
+#[cfg(unix)]
+#[test]
+fn skips_the_prompt_with_cached_credentials() {
+    let (dir, mut driver) = setup("cached");
+    fs::write(dir.join("cached"), "").unwrap();
+    driver.keys("# :w!!<CR>");
+    assert_eq!(prompt(&mut driver), None);
+    assert_eq!(fs::read_to_string(dir.join("hosts")).unwrap(), "# 127.0.0.1 localhost");
+}
+
+#[cfg(unix)]
+#[test]
+fn suggests_sudo_when_permission_is_denied() {
+    use std::os::unix::fs::PermissionsExt;
+    let (dir, mut driver) = setup("denied");
+    fs::set_permissions(dir.join("hosts"), fs::Permissions::from_mode(0o444)).unwrap();
+    if fs::OpenOptions::new().write(true).open(dir.join("hosts")).is_ok() {
+        return;
+    }
+    driver.keys("x:w<CR>");
+    assert!(driver.editor.message().unwrap().ends_with("use :w!! to save with sudo"));
+}
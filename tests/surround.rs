@@ -0,0 +1,30 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn adding_surroundings() {
+    Driver::vim("call value now").keys("wysiw)").assert_text("call (value) now");
+    Driver::vim("call value now").keys("wysiw(").assert_text("call ( value ) now");
+    Driver::vim("call value now").keys("ys2w\"").assert_text("\"call value \"now");
+    Driver::vim("    return x;").keys("yss]").assert_text("    [return x;]");
+}
+
+#[test]
+fn deleting_surroundings() {
+    Driver::vim("f(\"text\")").keys("3lds\"").assert_text("f(text)");
+    Driver::vim("f(a, [b])").keys("6lds(").assert_text("fa, [b]");
+    Driver::vim("<p><b>bold</b> text</p>").keys("7ldst").assert_text("<p>bold text</p>");
+}
+
+#[test]
+fn changing_surroundings() {
+    Driver::vim("x = 'quoted'").keys("6lcs'\"").assert_text("x = \"quoted\"");
+    Driver::vim("{ a }").keys("2lcs}]").assert_text("[ a ]");
+    Driver::vim("<em>hi</em>").keys("5lcst)").assert_text("(hi)");
+}
+
+#[test]
+fn surround_undoes_in_one_step() {
+    Driver::vim("word").keys("ysiw'").assert_text("'word'").keys("u").assert_text("word");
+}
@@ -0,0 +1,49 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+use std::os::unix::fs::symlink;
+
+fn setup(name: &str) -> (TempDir, Driver) {
+    let dir = TempDir::new(&format!("symlink-{}", name));
+    dir.file("real.conf", "value = 1");
+    symlink("real.conf", dir.join("link.conf")).unwrap();
+    let driver = Driver::open(dir.join("link.conf").to_str().unwrap());
+    (dir, driver)
+}
+
+#[test]
+fn writes_through_the_link_by_default() {
+    let (dir, mut driver) = setup("follow");
+    assert!(driver.status_row().ends_with("| → real.conf"), "{}", driver.status_row());
+    driver.keys("<End>0:w<CR>");
+    assert_eq!(fs::read_to_string(dir.join("real.conf")).unwrap(), "value = 10");
+    assert!(fs::symlink_metadata(dir.join("link.conf")).unwrap().file_type().is_symlink());
+}
+
+#[test]
+fn can_replace_the_link_with_a_file() {
+    let (dir, mut driver) = setup("replace");
+    driver.keys(":set symlinks=replace<CR>");
+    assert!(driver.status_row().ends_with("| replaces link"));
+    driver.keys("<End>0:w<CR>");
+    assert_eq!(fs::read_to_string(dir.join("real.conf")).unwrap(), "value = 1");
+    assert_eq!(fs::read_to_string(dir.join("link.conf")).unwrap(), "value = 10");
+    assert!(!fs::symlink_metadata(dir.join("link.conf")).unwrap().file_type().is_symlink());
+    assert!(!driver.status_row().contains("replaces link"));
+}
+
+#[test]
+fn keeps_the_link_when_replacing_it_fails() {
+    let dir = TempDir::new("symlink-failed");
+    dir.file("real.gpg", "ciphertext");
+    symlink("real.gpg", dir.join("link.gpg")).unwrap();
+    let mut driver = Driver::open(dir.join("link.gpg").to_str().unwrap());
+    driver.keys("<Esc>:set symlinks=replace<CR>:w<CR>");
+    assert!(driver.editor.message().is_some_and(|message| message.contains("could not be saved")));
+    assert!(fs::symlink_metadata(dir.join("link.gpg")).unwrap().file_type().is_symlink());
+    assert_eq!(fs::read_to_string(dir.join("real.gpg")).unwrap(), "ciphertext");
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+}
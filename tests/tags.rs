@@ -0,0 +1,80 @@
+mod common;
+
+use aon::config::Config;
+use common::{Driver, TempDir};
+use std::fs;
+
+const TAGS: &str = "!_TAG_FILE_FORMAT\t2\t/extended format/\n\
+dup\ta.rs\t1;\"\tf\n\
+dup\tsrc/b.rs\t1;\"\tf\n\
+helper\tsrc/b.rs\t/^pub fn helper() {}$/;\"\tf\n\
+main\ta.rs\t1;\"\tkind:function\n";
+
+fn project(name: &str) -> TempDir {
+    let dir = TempDir::new(&format!("tags-{}", name));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    dir.file("tags", TAGS);
+    dir.file("a.rs", "fn main() {\n    helper();\n}");
+    dir.file("src/b.rs", "// moved down\npub fn helper() {}");
+    dir
+}
+
+#[test]
+fn jumps_to_the_tag_under_the_cursor_and_back() {
+    let dir = project("cursor");
+    let mut driver = Driver::open(dir.join("a.rs").to_str().unwrap());
+    driver.keys("<Down><Right><Right><Right><Right><Right><Right><F12>");
+    assert!(driver.editor.filename().unwrap().ends_with("b.rs"));
+    driver.assert_cursor(1, 0);
+    driver.keys("<S-F12>");
+    assert!(driver.editor.filename().unwrap().ends_with("a.rs"));
+    driver.assert_cursor(1, 6);
+    driver.keys("<S-F12>");
+    assert_eq!(driver.editor.message(), Some("Tag stack is empty"));
+}
+
+#[test]
+fn tag_command_and_ambiguous_tags() {
+    let dir = project("command");
+    let mut driver = Driver::open(dir.join("src/b.rs").to_str().unwrap());
+    driver.keys(":tag main<CR>");
+    assert!(driver.editor.filename().unwrap().ends_with("a.rs"));
+    driver.assert_cursor(0, 0);
+    driver.keys(":tag nope<CR>");
+    assert_eq!(driver.editor.message(), Some("Tag not found: nope"));
+
+    driver.keys(":tag dup<CR><Down><CR>");
+    assert!(driver.editor.filename().unwrap().ends_with("b.rs"));
+    driver.keys(":pop<CR>:pop<CR>");
+    assert!(driver.editor.filename().unwrap().ends_with("b.rs"));
+}
+
+#[test]
+fn vim_ctrl_bracket_and_ctrl_t() {
+    let dir = project("vim");
+    let mut driver = Driver::open(dir.join("a.rs").to_str().unwrap());
+    driver.editor.apply_config(&Config::parse("keymap = vim"));
+    driver.keys("jw<C-]>");
+    assert!(driver.editor.filename().unwrap().ends_with("b.rs"));
+    driver.assert_cursor(1, 0);
+    driver.keys("<C-t>");
+    driver.assert_cursor(1, 4);
+}
+
+#[test]
+fn reports_a_missing_tags_file() {
+    let mut driver = Driver::new("word");
+    driver.keys(":tag word<CR>");
+    assert_eq!(driver.editor.message(), Some("No tags file found (run ctags -R)"));
+}
+
+#[test]
+fn popping_back_to_an_edited_line_lands_on_a_character() {
+    let dir = project("edited");
+    let mut driver = Driver::open(dir.join("a.rs").to_str().unwrap());
+    driver.keys("<Down><Right><Right><Right><Right><Right><Right>:tag main<CR>");
+    driver.assert_cursor(0, 0);
+    driver.keys("<Down><End><BS><BS><BS><BS><BS><BS><BS><BS><BS><BS><BS><BS><BS>aaaaa\u{e9}");
+    driver.keys("<S-F12>").assert_cursor(1, 5);
+    driver.keys("!").assert_text("fn main() {\naaaaa!\u{e9}\n}");
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::{Driver, TempDir};
+use std::fs;
+
+const MAKEFILE: &str = "CC := gcc\n.PHONY: build test\nbuild: main.o\n\t@echo building\ntest:\n\t@echo testing $(CC)\n%.o: %.c\n";
+const PACKAGE: &str = r#"{"name": "x", "scripts": {"lint": "eslint .", "start": "node -e \"{}\""}, "dependencies": {"a": "1"}}"#;
+
+fn rows(driver: &mut Driver) -> Vec<String> {
+    let screen = driver.editor.render(80, 20);
+    (0..20).map(|y| screen.row(y)).collect()
+}
+
+#[cfg(unix)]
+#[test]
+fn picks_and_reruns_detected_tasks() {
+    let dir = TempDir::new("tasks");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    dir.file("Makefile", MAKEFILE);
+    dir.file("package.json", PACKAGE);
+    let file = dir.file("src/notes.txt", "notes");
+
+    let mut driver = Driver::open(file.to_str().unwrap());
+    driver.keys(":tasks<CR>");
+    let listed: Vec<String> = rows(&mut driver).into_iter().filter(|row| row.contains(" run ") || row.contains("make ")).collect();
+    assert_eq!(listed.len(), 4, "{:?}", listed);
+    for (row, task) in listed.iter().zip(["make build", "make test", "npm run lint", "npm run start"]) {
+        assert!(row.contains(task), "{:?} should list {}", row, task);
+    }
+
+    driver.keys("<Down><CR>");
+    driver.wait_for_message("Build");
+    assert!(rows(&mut driver).iter().any(|row| row.starts_with("testing gcc")));
+
+    driver.keys(":tasks<CR>");
+    let first = rows(&mut driver).into_iter().position(|row| row.contains("make test"));
+    let second = rows(&mut driver).into_iter().position(|row| row.contains("make build"));
+    assert!(first < second);
+    driver.keys("<Esc>");
+
+    driver.keys("<F5>");
+    driver.wait_for_message("Build");
+    assert!(rows(&mut driver).iter().any(|row| row.contains("make test (done)")));
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::Driver;
+
+fn exited(driver: &mut Driver) {
+    driver.wait_until(|editor| editor.message().is_some_and(|message| message.starts_with("Terminal closed")));
+}
+
+#[cfg(unix)]
+#[test]
+fn runs_a_command_in_a_terminal_pane() {
+    let mut driver = Driver::new("fn main() {}");
+    driver.keys(":term printf 'one\\r\\n\\033[1;31mtwo\\033[0m\\r\\nthree\\033[2D\\033[Kee'<CR>");
+    exited(&mut driver);
+    assert_eq!(driver.editor.message(), Some("Terminal closed: printf 'one\\r\\n\\033[1;31mtwo\\033[0m\\r\\nthree\\033[2D\\033[Kee' exited"));
+
+    let screen = driver.editor.render(60, 18);
+    let rows: Vec<String> = (0..18).map(|y| screen.row(y)).collect();
+    let title = rows.iter().position(|row| row.starts_with("Terminal closed")).expect("pane title");
+    assert!(rows[0].starts_with("fn main() {}"));
+    assert!(rows[title + 1].starts_with("one "));
+    assert!(rows[title + 2].starts_with("two "));
+    assert!(rows[title + 3].starts_with("three "));
+    assert!(screen.cell(0, title as u16 + 2).unwrap().style.bold);
+
+    driver.keys("<C-\\>");
+    let screen = driver.editor.render(60, 18);
+    assert!((0..18).all(|y| !screen.row(y).starts_with("Terminal closed")));
+}
+
+#[cfg(unix)]
+#[test]
+fn forwards_keys_while_focused() {
+    let mut driver = Driver::new("");
+    driver.keys(":term cat<CR>");
+    driver.keys("hi<CR><C-\\>x<C-\\><C-d>");
+    exited(&mut driver);
+    assert_eq!(driver.text(), "x");
+
+    let screen = driver.editor.render(60, 18);
+    let rows: Vec<String> = (0..18).map(|y| screen.row(y)).collect();
+    let title = rows.iter().position(|row| row.starts_with("Terminal closed: cat exited")).expect("pane title");
+    assert!(rows[title + 1].starts_with("hi "));
+    assert!(rows[title + 2].starts_with("hi "));
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn word_objects() {
+    Driver::vim("alpha beta gamma").keys("wdiw").assert_text("alpha  gamma");
+    Driver::vim("alpha beta gamma").keys("wdaw").assert_text("alpha gamma");
+    Driver::vim("alpha beta").keys("wdaw").assert_text("alpha");
+}
+
+#[test]
+fn quote_objects() {
+    Driver::vim(r#"say("hello \"there\"", 'x')"#)
+        .keys("6ldi\"")
+        .assert_text(r#"say("", 'x')"#);
+    Driver::vim("print 'one' + 'two'").keys("da'").assert_text("print  + 'two'");
+    Driver::vim("a = \"old\"").keys("ci\"new<Esc>").assert_text("a = \"new\"").assert_mode(aon::Mode::Normal);
+}
+
+#[test]
+fn bracket_objects_nest() {
+    Driver::vim("f(a, g(b, c))").keys("8ldi(").assert_text("f(a, g())");
+    Driver::vim("f(a, g(b, c))").keys("8ld2i(").assert_text("f()");
+    Driver::vim("x = [1, [2]]").keys("4lda[").assert_text("x = ");
+    Driver::vim("if a {\n    go();\n    stop();\n}")
+        .keys("jdiB")
+        .assert_text("if a {\n}");
+}
+
+#[test]
+fn line_objects() {
+    Driver::vim("    let a = 1;  \nnext").keys("yil$p").assert_text("    let a = 1;  let a = 1;\nnext");
+    Driver::vim("one\ntwo\nthree").keys("jdal").assert_text("one\nthree");
+}
@@ -0,0 +1,23 @@
+mod common;
+
+use common::Driver;
+
+#[test]
+fn follows_the_current_buffer_and_its_changes() {
+    let mut driver = Driver::new("");
+    assert_eq!(driver.editor.window_title().as_deref(), Some("[No Name] \u{2014} aon"));
+    driver.keys("x");
+    assert_eq!(driver.editor.window_title().as_deref(), Some("[No Name] [+] \u{2014} aon"));
+    driver.keys(":messages<CR>");
+    assert_eq!(driver.editor.window_title().as_deref(), Some("[Messages] \u{2014} aon"));
+    driver.keys(":bp<CR>");
+    assert_eq!(driver.editor.window_title().as_deref(), Some("[No Name] [+] \u{2014} aon"));
+}
+
+#[test]
+fn can_be_turned_off() {
+    let mut driver = Driver::with_config("", "title = false");
+    assert_eq!(driver.editor.window_title(), None);
+    driver.keys(":set title<CR>");
+    assert!(driver.editor.window_title().is_some());
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::Driver;
+use std::thread;
+use std::time::Duration;
+
+fn popup(driver: &mut Driver) -> Vec<String> {
+    thread::sleep(Duration::from_millis(5));
+    driver.editor.tick();
+    let screen = driver.editor.render(50, 20);
+    (0..20).map(|y| screen.row(y).trim_end().to_string()).filter(|row| row.starts_with(['┌', '│', '└'])).collect()
+}
+
+#[test]
+fn lists_the_continuations_after_a_pause() {
+    let mut driver = Driver::with_config("one\ntwo", "keymap = vim\nwhichkey = 1");
+    driver.keys("<Esc>");
+    assert!(popup(&mut driver).is_empty());
+    driver.keys("g");
+    let rows = popup(&mut driver);
+    assert_eq!(rows[0], format!("┌{} g {}┐", "─".repeat(22), "─".repeat(23)));
+    assert_eq!(rows[1], format!("│ g  Go to the first line{}│", " ".repeat(24)));
+    assert!(rows[2].starts_with("│ a  Show the codepoint"));
+    assert!(rows[4].starts_with('└'));
+    driver.keys("g");
+    assert!(popup(&mut driver).is_empty());
+}
+
+#[test]
+fn follows_operators_and_their_prefixes() {
+    let mut driver = Driver::with_config("one", "keymap = vim\nwhichkey = 1");
+    driver.keys("<Esc>2d");
+    let rows = popup(&mut driver);
+    assert!(rows[0].contains(" 2d "));
+    assert!(rows[1].starts_with("│ d        Whole lines"));
+    assert!(rows.iter().any(|row| row.starts_with("│ s        Surroundings")));
+    driver.keys("i");
+    let rows = popup(&mut driver);
+    assert!(rows[0].contains(" 2di "));
+    assert!(rows[1].starts_with("│ w") && rows[1].contains("Word"));
+    driver.keys("<Esc>");
+    assert!(popup(&mut driver).is_empty());
+}
+
+#[test]
+fn waits_for_the_delay_and_can_be_disabled() {
+    let mut driver = Driver::with_config("one", "keymap = vim\nwhichkey = 60000");
+    driver.keys("<Esc>z");
+    assert!(popup(&mut driver).is_empty());
+    let mut driver = Driver::with_config("one", "keymap = vim\nwhichkey = 0");
+    driver.keys("<Esc>[");
+    assert!(popup(&mut driver).is_empty());
+}
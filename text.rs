@@ -0,0 +1,318 @@
+use crate::unicode;
+use crate::utf8;
+
+pub fn expand_tabs(line: &str, tabwidth: usize) -> Vec<char> {
+    let tabwidth = tabwidth.max(1);
+    let mut cells = Vec::with_capacity(line.len());
+    for c in line.chars() {
+        if c == '\t' {
+            let pad = tabwidth - cells.len() % tabwidth;
+            cells.resize(cells.len() + pad, ' ');
+        } else {
+            cells.push(c);
+        }
+    }
+    cells
+}
+
+fn plain(line: &str) -> bool {
+    line.bytes().all(|b| (b' '..0x7f).contains(&b))
+}
+
+pub fn graphemes(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        let rest = &line[offset..];
+        let mut chars = rest.char_indices();
+        let (_, mut prev) = chars.next()?;
+        let mut end = rest.len();
+        for (i, c) in chars {
+            if !unicode::extends(prev, c) {
+                end = i;
+                break;
+            }
+            prev = c;
+        }
+        let start = offset;
+        offset += end;
+        Some((start, &rest[..end]))
+    })
+}
+
+pub fn next_boundary(line: &str, x: usize) -> usize {
+    graphemes(&line[x..]).next().map_or(x, |(_, cluster)| x + cluster.len())
+}
+
+pub fn prev_boundary(line: &str, x: usize) -> usize {
+    let mut chars = line[..x].char_indices().rev().peekable();
+    while let Some((i, c)) = chars.next() {
+        match chars.peek() {
+            Some(&(_, prev)) if unicode::extends(prev, c) => {}
+            _ => return i,
+        }
+    }
+    0
+}
+
+pub fn snap(line: &str, x: usize) -> usize {
+    let mut x = x.min(line.len());
+    while !line.is_char_boundary(x) {
+        x -= 1;
+    }
+    match (line[..x].chars().next_back(), line[x..].chars().next()) {
+        (Some(prev), Some(c)) if unicode::extends(prev, c) => prev_boundary(line, x),
+        _ => x,
+    }
+}
+
+fn shown(cluster: &str) -> char {
+    let mut chars = cluster.chars();
+    let first = chars.next().unwrap_or(' ');
+    if utf8::raw_byte(first).is_some() {
+        return '\u{fffd}';
+    }
+    if first.is_ascii_control() {
+        return char::from_u32(0x2400 + (first as u32).min(0x21)).unwrap_or(first);
+    }
+    if chars.next().is_none() {
+        return first;
+    }
+    let composed = unicode::nfc(cluster);
+    let mut chars = composed.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => c,
+        _ => first,
+    }
+}
+
+pub fn width(line: &str, tabwidth: usize) -> usize {
+    if plain(line) {
+        return line.len();
+    }
+    let tabwidth = tabwidth.max(1);
+    graphemes(line).fold(0, |cells, (_, cluster)| if cluster.starts_with('\t') { cells + tabwidth - cells % tabwidth } else { cells + 1 })
+}
+
+pub fn window(line: &str, tabwidth: usize, start: usize, len: usize) -> Vec<char> {
+    let tabwidth = tabwidth.max(1);
+    let end = start.saturating_add(len);
+    if plain(line) {
+        return line.get(start.min(line.len())..end.min(line.len())).unwrap_or_default().chars().collect();
+    }
+    let mut cells = Vec::with_capacity(len.min(line.len()));
+    let mut column = 0;
+    for (_, cluster) in graphemes(line) {
+        if column >= end {
+            break;
+        }
+        let (c, n) = if cluster.starts_with('\t') { (' ', tabwidth - column % tabwidth) } else { (shown(cluster), 1) };
+        for cell in column..column + n {
+            if (start..end).contains(&cell) {
+                cells.push(c);
+            }
+        }
+        column += n;
+    }
+    cells
+}
+
+pub fn column(line: &str, x: usize, tabwidth: usize) -> usize {
+    let prefix = line.get(..x.min(line.len())).unwrap_or(line);
+    width(prefix, tabwidth)
+}
+
+/// The byte index of the grapheme drawn at `column`, or the end of the line
+/// when `column` is past it.
+pub fn index(line: &str, column: usize, tabwidth: usize) -> usize {
+    let tabwidth = tabwidth.max(1);
+    let mut cells = 0;
+    for (i, cluster) in graphemes(line) {
+        cells += if cluster.starts_with('\t') { tabwidth - cells % tabwidth } else { 1 };
+        if cells > column {
+            return i;
+        }
+    }
+    line.len()
+}
+
+pub fn increment(line: &str, x: usize, delta: i64) -> Option<(String, usize)> {
+    let bytes = line.as_bytes();
+    let run_end = |from: usize, digit: fn(&u8) -> bool| {
+        (from..bytes.len()).find(|&j| !digit(&bytes[j])).unwrap_or(bytes.len())
+    };
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes[i] == b'0'
+            && matches!(bytes.get(i + 1), Some(b'x' | b'X'))
+            && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit);
+        let (start, digits, end) = if hex {
+            (i, i + 2, run_end(i + 2, u8::is_ascii_hexdigit))
+        } else if bytes[i].is_ascii_digit() {
+            let signed = i > 0 && bytes[i - 1] == b'-' && !(i > 1 && bytes[i - 2].is_ascii_alphanumeric());
+            (if signed { i - 1 } else { i }, i, run_end(i, u8::is_ascii_digit))
+        } else {
+            i += 1;
+            continue;
+        };
+        if end <= x {
+            i = end;
+            continue;
+        }
+        let text = &line[digits..end];
+        let number = if hex {
+            let value = u64::from_str_radix(text, 16).ok()?.wrapping_add(delta as u64);
+            let digits = format!("{:0width$x}", value, width = text.len());
+            let upper = text.bytes().any(|b| b.is_ascii_uppercase());
+            format!("{}{}", &line[start..i + 2], if upper { digits.to_uppercase() } else { digits })
+        } else {
+            let magnitude: i128 = text.parse().ok()?;
+            let value = if start < digits { -magnitude } else { magnitude } + delta as i128;
+            let width = if text.len() > 1 && text.starts_with('0') { text.len() } else { 0 };
+            let sign = if value < 0 { "-" } else { "" };
+            format!("{}{:0width$}", sign, value.unsigned_abs(), width = width)
+        };
+        let cursor = start + number.len() - 1;
+        return Some((format!("{}{}{}", &line[..start], number, &line[end..]), cursor));
+    }
+    None
+}
+
+pub fn align(lines: &mut [String], delimiter: &str, all: bool) {
+    let limit = if all { usize::MAX } else { 2 };
+    let rows: Vec<Option<Vec<&str>>> = lines
+        .iter()
+        .map(|line| {
+            line.contains(delimiter).then(|| {
+                line.splitn(limit, delimiter)
+                    .enumerate()
+                    .map(|(k, cell)| if k == 0 { cell.trim_end() } else { cell.trim() })
+                    .collect()
+            })
+        })
+        .collect();
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows.iter().flatten() {
+        for (k, cell) in row[..row.len() - 1].iter().enumerate() {
+            let width = cell.chars().count();
+            match widths.get_mut(k) {
+                Some(max) => *max = (*max).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+    let aligned: Vec<Option<String>> = rows
+        .iter()
+        .map(|row| {
+            let row = row.as_ref()?;
+            let mut out = String::new();
+            for (k, cell) in row.iter().enumerate() {
+                if k > 0 {
+                    if !out.is_empty() {
+                        out.push(' ');
+                    }
+                    out.push_str(delimiter);
+                    out.push(' ');
+                }
+                out.push_str(cell);
+                if k + 1 < row.len() {
+                    out.extend(std::iter::repeat_n(' ', widths[k] - cell.chars().count()));
+                }
+            }
+            Some(out.trim_end().to_string())
+        })
+        .collect();
+    for (line, aligned) in lines.iter_mut().zip(aligned) {
+        if let Some(aligned) = aligned {
+            *line = aligned;
+        }
+    }
+}
+
+fn line_prefix<'a>(line: &'a str, comment: Option<&str>) -> &'a str {
+    let indent = line.len() - line.trim_start().len();
+    let Some(marker) = comment.filter(|marker| line[indent..].starts_with(marker)) else {
+        return &line[..indent];
+    };
+    let after = &line[indent + marker.len()..];
+    let body = after.trim_start_matches(|c| marker.contains(c) || c == '!');
+    let spaces = body.len() - body.trim_start().len();
+    &line[..line.len() - body.len() + spaces]
+}
+
+fn fill(out: &mut Vec<String>, prefix: &str, words: &[&str], width: usize) {
+    let indent = prefix.chars().count();
+    let mut line = String::new();
+    for word in words {
+        if !line.is_empty() && indent + line.chars().count() + 1 + word.chars().count() > width {
+            out.push(format!("{}{}", prefix, line));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        out.push(format!("{}{}", prefix, line));
+    }
+}
+
+pub fn reflow(lines: &[String], width: usize, comment: Option<&str>) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut prefix = "";
+    let mut words = Vec::new();
+    for line in lines {
+        let line_prefix = line_prefix(line, comment);
+        let body = &line[line_prefix.len()..];
+        if body.trim().is_empty() {
+            fill(&mut out, prefix, &words, width);
+            words.clear();
+            out.push(line.trim_end().to_string());
+            continue;
+        }
+        if words.is_empty() {
+            prefix = line_prefix;
+        }
+        words.extend(body.split_whitespace());
+    }
+    fill(&mut out, prefix, &words, width);
+    out
+}
+
+/// How a file ends its lines, so it is written back the way it was read.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct Newlines {
+    pub crlf: bool,
+    pub last: bool,
+}
+
+impl Newlines {
+    /// Splits on CRLF when every line ends that way and on LF otherwise,
+    /// leaving any other CR in the text.
+    pub fn split(text: &str) -> (Vec<String>, Self) {
+        let crlf = text.contains('\n') && text.split('\n').rev().skip(1).all(|line| line.ends_with('\r'));
+        let newlines = Self {
+            crlf,
+            last: text.ends_with('\n'),
+        };
+        let body = text.strip_suffix(newlines.separator()).unwrap_or(text);
+        let lines = body.split(newlines.separator()).map(str::to_string).collect();
+        (lines, newlines)
+    }
+
+    pub fn separator(self) -> &'static str {
+        if self.crlf {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+
+    pub fn join(self, lines: &[String]) -> String {
+        let mut text = lines.join(self.separator());
+        if self.last {
+            text.push_str(self.separator());
+        }
+        text
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Timer {
+    Autosave,
+    Blink,
+    Preview,
+    Flash,
+    Bell,
+    WhichKey,
+    Chord,
+}
+
+#[derive(Default)]
+pub struct Timers {
+    deadlines: Vec<(Timer, Instant)>,
+}
+
+impl Timers {
+    pub fn schedule(&mut self, timer: Timer, after: Duration) {
+        self.schedule_at(timer, Instant::now() + after);
+    }
+
+    pub fn schedule_at(&mut self, timer: Timer, deadline: Instant) {
+        self.cancel(timer);
+        self.deadlines.push((timer, deadline));
+    }
+
+    pub fn cancel(&mut self, timer: Timer) {
+        self.deadlines.retain(|&(t, _)| t != timer);
+    }
+
+    pub fn is_scheduled(&self, timer: Timer) -> bool {
+        self.deadlines.iter().any(|&(t, _)| t == timer)
+    }
+
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        self.deadlines
+            .iter()
+            .map(|&(_, deadline)| deadline.saturating_duration_since(now))
+            .min()
+    }
+
+    pub fn take_due(&mut self, now: Instant) -> Vec<Timer> {
+        let due = self
+            .deadlines
+            .iter()
+            .filter(|&&(_, deadline)| deadline <= now)
+            .map(|&(timer, _)| timer)
+            .collect();
+        self.deadlines.retain(|&(_, deadline)| deadline > now);
+        due
+    }
+}
@@ -0,0 +1,40 @@
+// Bytes that are not valid UTF-8 are carried through the buffer as the
+// last 128 code points of plane 16, so they are written back unchanged.
+// Files that already hold those code points can't round-trip as text.
+const RAW_BASE: u32 = 0x10ff00;
+
+pub fn raw_byte(c: char) -> Option<u8> {
+    let code = c as u32;
+    (RAW_BASE + 0x80..=RAW_BASE + 0xff).contains(&code).then(|| (code - RAW_BASE) as u8)
+}
+
+pub fn collides(bytes: &[u8]) -> bool {
+    bytes.utf8_chunks().any(|chunk| chunk.valid().chars().any(|c| raw_byte(c).is_some()))
+}
+
+pub fn invalid_bytes(bytes: &[u8]) -> usize {
+    bytes.utf8_chunks().map(|chunk| chunk.invalid().len()).sum()
+}
+
+pub fn decode(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len());
+    for chunk in bytes.utf8_chunks() {
+        text.push_str(chunk.valid());
+        text.extend(chunk.invalid().iter().filter_map(|&b| char::from_u32(RAW_BASE + b as u32)));
+    }
+    text
+}
+
+pub fn encode(text: String) -> Vec<u8> {
+    if !text.chars().any(|c| raw_byte(c).is_some()) {
+        return text.into_bytes();
+    }
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match raw_byte(c) {
+            Some(b) => bytes.push(b),
+            None => bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+    bytes
+}
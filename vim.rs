@@ -0,0 +1,483 @@
+use crate::i18n::Text;
+use crate::motion::{Motion, Object};
+use crate::registers::Registers;
+use crossterm::event::KeyCode;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Operator {
+    Delete,
+    Yank,
+    Change,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InsertAt {
+    Cursor,
+    After,
+    LineStart,
+    LineEnd,
+    Below,
+    Above,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ViewAt {
+    Center,
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Target {
+    Motion(Motion, Option<usize>),
+    Object(Object, bool, Option<usize>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    Move(Motion, Option<usize>),
+    Operate(Operator, Motion, Option<usize>),
+    OperateObject(Operator, Object, bool, Option<usize>),
+    Surround(Target, char),
+    DeleteSurround(char),
+    ChangeSurround(char, char),
+    Increment(i64),
+    Tag,
+    PopTag,
+    Reflow(Target),
+    Diagnostic(bool),
+    Conflict(bool),
+    Ascii,
+    Search(bool),
+    SearchNext(bool),
+    Insert(InsertAt),
+    DeleteChar(usize),
+    Paste { before: bool, count: usize },
+    Undo,
+    Register(char),
+    Record(Option<char>),
+    SetMark(char),
+    JumpMark(char, bool),
+    Play(char, usize),
+    View(ViewAt),
+    Ex,
+}
+
+#[derive(Clone, Copy)]
+enum Surround {
+    Add,
+    Wrap(Target),
+    Delete,
+    Change(Option<char>),
+}
+
+#[derive(Default)]
+pub struct Normal {
+    count: Option<usize>,
+    operator: Option<(Operator, Option<usize>)>,
+    prefix_g: bool,
+    prefix_z: bool,
+    prefix_bracket: Option<char>,
+    prefix_register: bool,
+    prefix_macro: Option<char>,
+    prefix_mark: Option<char>,
+    prefix_object: Option<bool>,
+    surround: Option<Surround>,
+    reflow: bool,
+    typed: String,
+    pub recording: bool,
+}
+
+impl Normal {
+    pub fn reset(&mut self) {
+        *self = Self {
+            recording: self.recording,
+            typed: std::mem::take(&mut self.typed),
+            ..Self::default()
+        };
+    }
+
+    /// The keys of a sequence that is still waiting for more input.
+    pub fn pending(&self) -> Option<&str> {
+        let waiting = self.count.is_some()
+            || self.operator.is_some()
+            || self.prefix_g
+            || self.prefix_z
+            || self.prefix_bracket.is_some()
+            || self.prefix_register
+            || self.prefix_macro.is_some()
+            || self.prefix_mark.is_some()
+            || self.prefix_object.is_some()
+            || self.surround.is_some();
+        waiting.then_some(self.typed.as_str())
+    }
+
+    /// Takes a typed count when nothing but the count is pending.
+    pub fn take_count(&mut self) -> Option<usize> {
+        let count = self.count.take();
+        if self.pending().is_some() {
+            self.count = count;
+            return None;
+        }
+        count
+    }
+
+    /// The keys that can follow the pending sequence, for the which-key
+    /// popup.
+    pub fn continuations(&self) -> Vec<(String, Text)> {
+        let keys = |list: &[(&str, Text)]| list.iter().map(|&(key, text)| (key.to_string(), text)).collect();
+        if self.prefix_g {
+            return match self.operator {
+                Some(_) => keys(&[("g", Text::KeyFileStart)]),
+                None => keys(&[("g", Text::KeyFileStart), ("a", Text::CmdAscii), ("q", Text::CmdReflow)]),
+            };
+        }
+        if self.prefix_z {
+            return keys(&[("z", Text::CmdCenterView), ("t", Text::CmdTopView), ("b", Text::CmdBottomView)]);
+        }
+        if let Some(bracket) = self.prefix_bracket {
+            let (block, diagnostic, conflict) = match bracket {
+                '[' => (Text::KeyBlockStart, Text::KeyPrevDiagnostic, Text::KeyPrevConflict),
+                _ => (Text::KeyBlockEnd, Text::KeyNextDiagnostic, Text::KeyNextConflict),
+            };
+            return match self.operator {
+                Some(_) => keys(&[("b", block)]),
+                None => keys(&[("b", block), ("d", diagnostic), ("n", conflict)]),
+            };
+        }
+        if self.prefix_register {
+            return keys(&[("a-z", Text::KeyRegister), ("A-Z", Text::KeyAppendRegister)]);
+        }
+        match self.prefix_macro {
+            Some('q') => return keys(&[("a-z 0-9", Text::KeyRecord)]),
+            Some(_) => return keys(&[("a-z 0-9", Text::KeyPlay), ("@", Text::KeyPlayLast)]),
+            None => {}
+        }
+        match self.prefix_mark {
+            Some('m') => return keys(&[("A-Z", Text::CmdMark)]),
+            Some('\'') => return keys(&[("A-Z", Text::KeyJumpMarkLine)]),
+            Some(_) => return keys(&[("A-Z", Text::KeyJumpMark)]),
+            None => {}
+        }
+        if self.prefix_object.is_some() {
+            return keys(&[
+                ("w", Text::KeyObjectWord),
+                ("l", Text::KeyObjectLine),
+                ("t", Text::KeyObjectTag),
+                ("( [ { < \" ' `", Text::KeyObjectPair),
+            ]);
+        }
+        if self.surround.is_some() {
+            return Vec::new();
+        }
+        match self.operator {
+            Some((operator, _)) => {
+                let line = match operator {
+                    Operator::Delete => "d",
+                    Operator::Yank if self.reflow => "q",
+                    Operator::Yank => "y",
+                    Operator::Change => "c",
+                };
+                let mut list = keys(&[
+                    (line, Text::KeyLines),
+                    ("h j k l", Text::KeyMotionChar),
+                    ("w b e", Text::KeyMotionWord),
+                    ("0 ^ $", Text::KeyMotionLine),
+                    ("{ }", Text::KeyMotionParagraph),
+                    ("gg G", Text::KeyMotionFile),
+                    ("i a", Text::KeyObject),
+                ]);
+                if !self.reflow {
+                    list.push(("s".to_string(), Text::KeySurround));
+                }
+                list
+            }
+            None => Vec::new(),
+        }
+    }
+
+    pub fn feed_ctrl(&mut self, code: KeyCode) -> Option<Command> {
+        let count = i64::try_from(self.count.unwrap_or(1)).unwrap_or(i64::MAX);
+        let pending = self.operator.is_some() || self.surround.is_some();
+        self.reset();
+        match code {
+            KeyCode::Char('a') if !pending => Some(Command::Increment(count)),
+            KeyCode::Char('x') if !pending => Some(Command::Increment(-count)),
+            KeyCode::Char(']') if !pending => Some(Command::Tag),
+            KeyCode::Char('t') if !pending => Some(Command::PopTag),
+            _ => None,
+        }
+    }
+
+    pub fn feed(&mut self, code: KeyCode) -> Option<Command> {
+        let c = match code {
+            KeyCode::Char(c) => c,
+            KeyCode::Left => 'h',
+            KeyCode::Right => 'l',
+            KeyCode::Up => 'k',
+            KeyCode::Down => 'j',
+            KeyCode::Home => '0',
+            KeyCode::End => '$',
+            _ => {
+                self.reset();
+                return None;
+            }
+        };
+        if self.pending().is_none() {
+            self.typed.clear();
+        }
+        self.typed.push(c);
+        if std::mem::take(&mut self.prefix_register) {
+            self.reset();
+            return Registers::is_valid(c).then_some(Command::Register(c));
+        }
+        if let Some(prefix) = self.prefix_mark.take() {
+            self.reset();
+            return c.is_ascii_uppercase().then_some(match prefix {
+                'm' => Command::SetMark(c),
+                _ => Command::JumpMark(c, prefix == '`'),
+            });
+        }
+        if let Some(prefix) = self.prefix_macro.take() {
+            let count = self.count.unwrap_or(1);
+            self.reset();
+            return match prefix {
+                'q' => c.is_ascii_alphanumeric().then_some(Command::Record(Some(c))),
+                _ => (c.is_ascii_alphanumeric() || c == '@').then_some(Command::Play(c, count)),
+            };
+        }
+        let command = match self.surround {
+            Some(Surround::Wrap(target)) => Some(Command::Surround(target, c)),
+            Some(Surround::Delete) => Some(Command::DeleteSurround(c)),
+            Some(Surround::Change(Some(old))) => Some(Command::ChangeSurround(old, c)),
+            Some(Surround::Change(None)) => {
+                self.surround = Some(Surround::Change(Some(c)));
+                return None;
+            }
+            Some(Surround::Add) | None => None,
+        };
+        if command.is_some() {
+            self.reset();
+            return command;
+        }
+        if let Some(around) = self.prefix_object.take() {
+            return match Object::from_char(c) {
+                Some(object) => self.target(Target::Object(object, around, None)),
+                None => {
+                    self.reset();
+                    None
+                }
+            };
+        }
+        if let Some(digit) = c.to_digit(10) {
+            if digit != 0 || self.count.is_some() {
+                let count = self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit as usize);
+                self.count = Some(count);
+                return None;
+            }
+        }
+        if std::mem::take(&mut self.prefix_z) {
+            let at = match c {
+                'z' | '.' => Some(ViewAt::Center),
+                't' => Some(ViewAt::Top),
+                'b' | '-' => Some(ViewAt::Bottom),
+                _ => None,
+            };
+            self.reset();
+            return at.map(Command::View);
+        }
+        if let Some(bracket) = self.prefix_bracket.take() {
+            return match (bracket, c) {
+                ('[', 'b') => self.motion(Motion::BlockStart),
+                (']', 'b') => self.motion(Motion::BlockEnd),
+                ('[' | ']', 'd') if self.operator.is_none() => {
+                    self.reset();
+                    Some(Command::Diagnostic(bracket == ']'))
+                }
+                ('[' | ']', 'n') if self.operator.is_none() => {
+                    self.reset();
+                    Some(Command::Conflict(bracket == ']'))
+                }
+                _ => {
+                    self.reset();
+                    None
+                }
+            };
+        }
+        if std::mem::take(&mut self.prefix_g) {
+            return match c {
+                'g' => self.motion(Motion::FileStart),
+                'a' if self.operator.is_none() => {
+                    self.reset();
+                    Some(Command::Ascii)
+                }
+                'q' if self.operator.is_none() => {
+                    self.operator = Some((Operator::Yank, self.count.take()));
+                    self.reflow = true;
+                    None
+                }
+                _ => {
+                    self.reset();
+                    None
+                }
+            };
+        }
+        let motion = match c {
+            'h' => Motion::Left,
+            'l' | ' ' => Motion::Right,
+            'j' => Motion::Down,
+            'k' => Motion::Up,
+            '0' => Motion::LineStart,
+            '^' => Motion::FirstNonBlank,
+            '$' => Motion::LineEnd,
+            'w' => Motion::WordForward,
+            'b' => Motion::WordBackward,
+            'e' => Motion::WordEnd,
+            'G' => Motion::FileEnd,
+            'q' if self.reflow => Motion::Lines,
+            '}' => Motion::ParagraphForward,
+            '{' => Motion::ParagraphBackward,
+            '[' | ']' => {
+                self.prefix_bracket = Some(c);
+                return None;
+            }
+            '"' if self.operator.is_none() => {
+                self.prefix_register = true;
+                return None;
+            }
+            's' if self.operator.is_some() && !self.reflow => return self.surround(),
+            'i' | 'a' if self.operator.is_some() => {
+                self.prefix_object = Some(c == 'a');
+                return None;
+            }
+            'g' => {
+                self.prefix_g = true;
+                return None;
+            }
+            'z' if self.operator.is_none() => {
+                self.prefix_z = true;
+                return None;
+            }
+            'q' if self.operator.is_none() && self.recording => {
+                self.reset();
+                return Some(Command::Record(None));
+            }
+            'q' | '@' if self.operator.is_none() => {
+                self.prefix_macro = Some(c);
+                return None;
+            }
+            'm' | '\'' | '`' if self.operator.is_none() => {
+                self.prefix_mark = Some(c);
+                return None;
+            }
+            _ => return self.command(c),
+        };
+        self.motion(motion)
+    }
+
+    fn motion(&mut self, motion: Motion) -> Option<Command> {
+        match self.operator {
+            Some(_) => self.target(Target::Motion(motion, None)),
+            None => {
+                let command = Command::Move(motion, self.count.take());
+                self.reset();
+                Some(command)
+            }
+        }
+    }
+
+    fn target(&mut self, target: Target) -> Option<Command> {
+        let count = self.count.take();
+        let Some((operator, first)) = self.operator.take() else {
+            self.reset();
+            return None;
+        };
+        let count = multiply(first, count);
+        let (target, command) = match target {
+            Target::Motion(motion, _) => (
+                Target::Motion(motion, count),
+                Command::Operate(operator, motion, count),
+            ),
+            Target::Object(object, around, _) => (
+                Target::Object(object, around, count),
+                Command::OperateObject(operator, object, around, count),
+            ),
+        };
+        if let Some(Surround::Add) = self.surround {
+            self.reset();
+            self.surround = Some(Surround::Wrap(target));
+            return None;
+        }
+        let command = if self.reflow { Command::Reflow(target) } else { command };
+        self.reset();
+        Some(command)
+    }
+
+    fn surround(&mut self) -> Option<Command> {
+        let surround = match (self.surround, self.operator) {
+            (Some(Surround::Add), _) => return self.motion(Motion::Lines),
+            (_, Some((Operator::Yank, _))) => {
+                self.surround = Some(Surround::Add);
+                return None;
+            }
+            (_, Some((Operator::Delete, _))) => Surround::Delete,
+            (_, Some((Operator::Change, _))) => Surround::Change(None),
+            (_, None) => return None,
+        };
+        self.reset();
+        self.surround = Some(surround);
+        None
+    }
+
+    fn command(&mut self, c: char) -> Option<Command> {
+        let operator = match c {
+            'd' => Some(Operator::Delete),
+            'y' => Some(Operator::Yank),
+            'c' => Some(Operator::Change),
+            _ => None,
+        };
+        if let Some(operator) = operator {
+            return match self.operator {
+                Some((pending, _)) if pending == operator => self.motion(Motion::Lines),
+                Some(_) => {
+                    self.reset();
+                    None
+                }
+                None => {
+                    self.operator = Some((operator, self.count.take()));
+                    None
+                }
+            };
+        }
+        if self.operator.is_some() {
+            self.reset();
+            return None;
+        }
+        let count = self.count.unwrap_or(1);
+        let command = match c {
+            'i' => Some(Command::Insert(InsertAt::Cursor)),
+            'a' => Some(Command::Insert(InsertAt::After)),
+            'I' => Some(Command::Insert(InsertAt::LineStart)),
+            'A' => Some(Command::Insert(InsertAt::LineEnd)),
+            'o' => Some(Command::Insert(InsertAt::Below)),
+            'O' => Some(Command::Insert(InsertAt::Above)),
+            'x' => Some(Command::DeleteChar(count)),
+            'p' => Some(Command::Paste { before: false, count }),
+            'P' => Some(Command::Paste { before: true, count }),
+            'u' => Some(Command::Undo),
+            ':' => Some(Command::Ex),
+            '/' | '?' => Some(Command::Search(c == '/')),
+            'n' | 'N' => Some(Command::SearchNext(c == 'N')),
+            _ => None,
+        };
+        self.reset();
+        command
+    }
+}
+
+fn multiply(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.saturating_mul(b)),
+        (a, b) => a.or(b),
+    }
+}